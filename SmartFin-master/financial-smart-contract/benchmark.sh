@@ -0,0 +1,38 @@
+#!/bin/bash
+
+# Measures the gas used by a catalogue of contract definitions on the development blockchain, and writes the report to
+# client/benchmark/gas-report.json (or the path given by GAS_REPORT)
+
+cd "$(dirname "$0")"
+
+NC_BENCHMARK=0
+
+while test $# -gt 0
+do
+    case "$1" in
+        -nc) NC_BENCHMARK=1
+            ;;
+    esac
+    shift
+done
+
+# Start blockchain node if required
+if [ $NC_BENCHMARK -eq 0 ]
+then
+    "./run-node.sh" &
+    sleep 3
+fi
+
+# Build the contract, and run the benchmark
+"./build.sh"
+cd client
+yarn benchmark
+cd -
+
+# Kill blockchain node if required
+if [ $NC_BENCHMARK -eq 0 ]
+then
+    kill -9 $(lsof -ti :8545)
+fi
+
+cd -
@@ -5,7 +5,7 @@ cd "$(dirname "$0")"
 cd contract
 
 # Generate new build cargo manifest (just in case)
-source ./generate-cargo.sh cdylib
+source ./generate-cargo.sh
 
 # Build
 cargo build --release --target wasm32-unknown-unknown
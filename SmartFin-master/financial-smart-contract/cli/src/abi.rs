@@ -0,0 +1,456 @@
+// Encoding and decoding of calls to the financial smart contract's ABI, laid out as pwasm-abi (and Solidity) lay them out: a
+// 4-byte function selector followed by 32-byte words, with dynamic values (bytes and arrays) placed after the static ones
+use pwasm_std::types::Address;
+use serde_json::Value;
+use tiny_keccak::keccak256;
+
+// The types of the parameters and return values of the ABI
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum ParamType {
+    Address,
+    Uint64,
+    Int64,
+    Bool,
+    Bytes,
+    Array(&'static ParamType)
+}
+
+// A parameter or return value of the ABI
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Token {
+    Address(Address),
+    Uint(u64),
+    Int(i64),
+    Bool(bool),
+    Bytes(Vec<u8>),
+    Array(Vec<Token>)
+}
+
+// A function of the financial smart contract's ABI
+pub struct Function {
+    // The name of the function
+    pub name: &'static str,
+
+    // The types of the function's parameters
+    pub inputs: &'static [ParamType],
+
+    // The types of the function's return values
+    pub outputs: &'static [ParamType],
+
+    // Whether or not the function only reads the contract (and so is called without a transaction)
+    pub constant: bool
+}
+
+// The types of the constructor's parameters: the contract definition, holder, whether or not Ether payments forward the gas
+// stipend and the gas stipend
+pub static CONSTRUCTOR_INPUTS: &[ParamType] = &[ParamType::Array(&ParamType::Int64), ParamType::Address, ParamType::Bool, ParamType::Uint64];
+
+// The functions of the FinancialScInterface ABI
+pub static FUNCTIONS: &[Function] = &[
+    Function { name: "get_holder", inputs: &[], outputs: &[ParamType::Address], constant: true },
+    Function { name: "get_counter_party", inputs: &[], outputs: &[ParamType::Address], constant: true },
+    Function { name: "get_contract_definition", inputs: &[], outputs: &[ParamType::Array(&ParamType::Int64)], constant: true },
+    Function { name: "get_balance", inputs: &[ParamType::Bool], outputs: &[ParamType::Int64], constant: true },
+    Function { name: "get_asset_balance", inputs: &[ParamType::Bool, ParamType::Address], outputs: &[ParamType::Int64], constant: true },
+    Function { name: "get_assets", inputs: &[], outputs: &[ParamType::Array(&ParamType::Address)], constant: true },
+    Function { name: "get_concluded", inputs: &[], outputs: &[ParamType::Bool], constant: true },
+    Function { name: "get_use_gas", inputs: &[], outputs: &[ParamType::Bool], constant: true },
+    Function { name: "get_gas_stipend", inputs: &[], outputs: &[ParamType::Uint64], constant: true },
+    Function { name: "get_last_updated", inputs: &[], outputs: &[ParamType::Int64], constant: true },
+    Function { name: "get_projected_balances", inputs: &[ParamType::Uint64], outputs: &[ParamType::Array(&ParamType::Int64)], constant: true },
+    Function { name: "get_acquisition_times", inputs: &[], outputs: &[ParamType::Array(&ParamType::Int64)], constant: true },
+    Function { name: "get_or_choices", inputs: &[], outputs: &[ParamType::Bytes], constant: true },
+    Function { name: "get_obs_entries", inputs: &[], outputs: &[ParamType::Array(&ParamType::Int64)], constant: true },
+    Function { name: "get_obs_submissions", inputs: &[ParamType::Uint64], outputs: &[ParamType::Array(&ParamType::Int64)], constant: true },
+    Function { name: "get_obs_dispute", inputs: &[ParamType::Uint64], outputs: &[ParamType::Array(&ParamType::Int64)], constant: true },
+    Function { name: "get_state_tree", inputs: &[], outputs: &[ParamType::Array(&ParamType::Int64)], constant: true },
+    Function { name: "get_operator_permissions", inputs: &[ParamType::Address, ParamType::Address], outputs: &[ParamType::Uint64], constant: true },
+    Function { name: "set_or_choice", inputs: &[ParamType::Uint64, ParamType::Bool], outputs: &[], constant: false },
+    Function { name: "set_obs_value", inputs: &[ParamType::Uint64, ParamType::Int64], outputs: &[], constant: false },
+    Function {
+        name: "set_or_choices",
+        inputs: &[ParamType::Array(&ParamType::Uint64), ParamType::Array(&ParamType::Bool), ParamType::Bool],
+        outputs: &[],
+        constant: false
+    },
+    Function {
+        name: "set_obs_values",
+        inputs: &[ParamType::Array(&ParamType::Uint64), ParamType::Array(&ParamType::Int64), ParamType::Bool],
+        outputs: &[],
+        constant: false
+    },
+    Function {
+        name: "submit_obs_report",
+        inputs: &[ParamType::Uint64, ParamType::Int64, ParamType::Uint64, ParamType::Bytes],
+        outputs: &[],
+        constant: false
+    },
+    Function { name: "flag_obs_value", inputs: &[ParamType::Uint64], outputs: &[], constant: false },
+    Function { name: "resolve_obs_value", inputs: &[ParamType::Uint64, ParamType::Int64], outputs: &[], constant: false },
+    Function { name: "acquire", inputs: &[], outputs: &[], constant: false },
+    Function { name: "update", inputs: &[], outputs: &[], constant: false },
+    Function { name: "acquire_anytime_sub_contract", inputs: &[ParamType::Uint64], outputs: &[], constant: false },
+    Function { name: "stake", inputs: &[], outputs: &[ParamType::Int64], constant: false },
+    Function { name: "withdraw", inputs: &[ParamType::Uint64], outputs: &[], constant: false },
+    Function { name: "withdraw_to", inputs: &[ParamType::Address, ParamType::Address, ParamType::Uint64], outputs: &[], constant: false },
+    Function { name: "stake_asset", inputs: &[ParamType::Address, ParamType::Uint64], outputs: &[ParamType::Int64], constant: false },
+    Function { name: "withdraw_asset", inputs: &[ParamType::Address, ParamType::Uint64], outputs: &[], constant: false },
+    Function { name: "approve_operator", inputs: &[ParamType::Address, ParamType::Uint64], outputs: &[], constant: false },
+    Function { name: "revoke_operator", inputs: &[ParamType::Address], outputs: &[], constant: false }
+];
+
+// Method implementation of the ABI types
+impl ParamType {
+    // Gets the name of the type in function signatures
+    pub fn name(&self) -> String {
+        match self {
+            ParamType::Address => "address".to_string(),
+            ParamType::Uint64 => "uint64".to_string(),
+            ParamType::Int64 => "int64".to_string(),
+            ParamType::Bool => "bool".to_string(),
+            ParamType::Bytes => "bytes".to_string(),
+            ParamType::Array(element_type) => element_type.name() + "[]"
+        }
+    }
+
+    // Returns whether or not values of the type are placed after the static values (with their offset in place)
+    fn is_dynamic(&self) -> bool {
+        match self {
+            ParamType::Bytes | ParamType::Array(_) => true,
+            _ => false
+        }
+    }
+}
+
+// Method implementation of the ABI functions
+impl Function {
+    // Finds the function with the given name
+    pub fn find(name: &str) -> Result<&'static Function, String> {
+        match FUNCTIONS.iter().find(|function| function.name == name) {
+            Some(function) => Ok(function),
+            None => Err(format!("Unknown function: {}.", name))
+        }
+    }
+
+    // Gets the signature of the function, e.g. set_or_choice(uint64,bool)
+    pub fn signature(&self) -> String {
+        let inputs: Vec<String> = self.inputs.iter().map(|input| input.name()).collect();
+        format!("{}({})", self.name, inputs.join(","))
+    }
+
+    // Gets the selector of the function, the first 4 bytes of the hash of its signature
+    pub fn selector(&self) -> [u8; 4] {
+        let hash = keccak256(self.signature().as_bytes());
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    // Encodes a call of the function with the given arguments
+    pub fn encode_call(&self, args: &[Token]) -> Result<Vec<u8>, String> {
+        check_types(self.inputs, args)?;
+        let mut call = self.selector().to_vec();
+        call.extend(encode(args));
+        Ok(call)
+    }
+
+    // Decodes the values returned by the function
+    pub fn decode_output(&self, output: &[u8]) -> Result<Vec<Token>, String> {
+        decode(self.outputs, output)
+    }
+}
+
+// Method implementation of the ABI values
+impl Token {
+    // Parses a value of the given type from text: numbers, true or false, hex addresses and bytes (0x-prefixed), and
+    // comma-separated elements for arrays
+    pub fn parse(param_type: &ParamType, text: &str) -> Result<Token, String> {
+        let text = text.trim();
+        let invalid = || format!("Expected {}, found: '{}'.", param_type.name(), text);
+        match param_type {
+            ParamType::Address => parse_address(text).map(Token::Address),
+            ParamType::Uint64 => text.parse().map(Token::Uint).map_err(|_| invalid()),
+            ParamType::Int64 => text.parse().map(Token::Int).map_err(|_| invalid()),
+            ParamType::Bool => text.parse().map(Token::Bool).map_err(|_| invalid()),
+            ParamType::Bytes => from_hex(text).map(Token::Bytes),
+            ParamType::Array(element_type) => {
+                let elements = text.trim_start_matches('[').trim_end_matches(']');
+                if elements.trim().is_empty() {
+                    return Ok(Token::Array(Vec::new()));
+                }
+                let tokens: Result<Vec<Token>, String> = elements.split(',').map(|element| Token::parse(element_type, element)).collect();
+                tokens.map(Token::Array)
+            }
+        }
+    }
+
+    // Returns whether or not the value is of the given type
+    pub fn is_type(&self, param_type: &ParamType) -> bool {
+        match (self, param_type) {
+            (Token::Address(_), ParamType::Address)
+                | (Token::Uint(_), ParamType::Uint64)
+                | (Token::Int(_), ParamType::Int64)
+                | (Token::Bool(_), ParamType::Bool)
+                | (Token::Bytes(_), ParamType::Bytes) => true,
+            (Token::Array(elements), ParamType::Array(element_type)) => elements.iter().all(|element| element.is_type(element_type)),
+            _ => false
+        }
+    }
+
+    // Converts the value to JSON, with addresses and bytes as 0x-prefixed hex strings
+    pub fn to_json(&self) -> Value {
+        match self {
+            Token::Address(address) => Value::from(to_hex(&address.0)),
+            Token::Uint(value) => Value::from(*value),
+            Token::Int(value) => Value::from(*value),
+            Token::Bool(value) => Value::from(*value),
+            Token::Bytes(bytes) => Value::from(to_hex(bytes)),
+            Token::Array(elements) => Value::Array(elements.iter().map(|element| element.to_json()).collect())
+        }
+    }
+}
+
+// Checks that the given values are of the given types
+pub fn check_types(types: &[ParamType], tokens: &[Token]) -> Result<(), String> {
+    if types.len() != tokens.len() {
+        return Err(format!("Expected {} arguments, found {}.", types.len(), tokens.len()));
+    }
+    match types.iter().zip(tokens).find(|(param_type, token)| !token.is_type(param_type)) {
+        Some((param_type, token)) => Err(format!("Expected {}, found: {:?}.", param_type.name(), token)),
+        None => Ok(())
+    }
+}
+
+// Encodes the given values: a word for each static value, or the offset of each dynamic value, followed by the dynamic values
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let mut head: Vec<u8> = Vec::new();
+    let mut tail: Vec<u8> = Vec::new();
+    let head_length = 32 * tokens.len();
+
+    for token in tokens.iter() {
+        match token {
+            Token::Address(address) => {
+                head.extend_from_slice(&[0; 12]);
+                head.extend_from_slice(&address.0);
+            },
+            Token::Uint(value) => head.extend(encode_word(*value, 0)),
+            Token::Int(value) => head.extend(encode_word(*value as u64, if *value < 0 { 0xff } else { 0 })),
+            Token::Bool(value) => head.extend(encode_word(*value as u64, 0)),
+            Token::Bytes(bytes) => {
+                head.extend(encode_word((head_length + tail.len()) as u64, 0));
+                tail.extend(encode_word(bytes.len() as u64, 0));
+                tail.extend_from_slice(bytes);
+                let padding = (32 - bytes.len() % 32) % 32;
+                tail.extend(vec![0; padding]);
+            },
+            Token::Array(elements) => {
+                head.extend(encode_word((head_length + tail.len()) as u64, 0));
+                tail.extend(encode_word(elements.len() as u64, 0));
+                tail.extend(encode(elements));
+            }
+        }
+    }
+
+    head.extend(tail);
+    head
+}
+
+// Decodes values of the given types, encoded as encode does
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, String> {
+    let mut tokens: Vec<Token> = Vec::new();
+    for (i, param_type) in types.iter().enumerate() {
+        let word = read_word(data, 32 * i)?;
+        let token = if param_type.is_dynamic() {
+            let offset = decode_uint(&word)? as usize;
+            let length = decode_uint(&read_word(data, offset)?)? as usize;
+            let start = offset + 32;
+            match param_type {
+                ParamType::Array(element_type) => {
+                    let element_types = vec![**element_type; length];
+                    Token::Array(decode(&element_types, data.get(start..).unwrap_or(&[]))?)
+                },
+                _ => match data.get(start..(start + length)) {
+                    Some(bytes) => Token::Bytes(bytes.to_vec()),
+                    None => return Err("ABI data is too short.".to_string())
+                }
+            }
+        } else {
+            match param_type {
+                ParamType::Address => {
+                    if word[0..12].iter().any(|byte| *byte != 0) {
+                        return Err("ABI address is out of range.".to_string());
+                    }
+                    Token::Address(Address::from_slice(&word[12..32]))
+                },
+                ParamType::Uint64 => Token::Uint(decode_uint(&word)?),
+                ParamType::Int64 => Token::Int(decode_int(&word)?),
+                _ => match decode_uint(&word)? {
+                    0 => Token::Bool(false),
+                    1 => Token::Bool(true),
+                    _ => return Err("ABI bool is out of range.".to_string())
+                }
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+// Encodes a 64-bit value as a word, padded with the given byte
+fn encode_word(value: u64, padding: u8) -> Vec<u8> {
+    let mut word = vec![padding; 24];
+    word.extend((0..8).map(|i| (value >> (56 - 8 * i)) as u8));
+    word
+}
+
+// Reads the word at the given position
+fn read_word(data: &[u8], position: usize) -> Result<[u8; 32], String> {
+    match data.get(position..(position + 32)) {
+        Some(bytes) => {
+            let mut word = [0; 32];
+            word.copy_from_slice(bytes);
+            Ok(word)
+        },
+        None => Err("ABI data is too short.".to_string())
+    }
+}
+
+// Decodes a word holding an unsigned 64-bit value
+fn decode_uint(word: &[u8; 32]) -> Result<u64, String> {
+    if word[0..24].iter().any(|byte| *byte != 0) {
+        return Err("ABI integer is out of range.".to_string());
+    }
+    Ok(word[24..32].iter().fold(0, |value, byte| (value << 8) | u64::from(*byte)))
+}
+
+// Decodes a word holding a signed 64-bit value (sign-extended to 256 bits)
+fn decode_int(word: &[u8; 32]) -> Result<i64, String> {
+    let value = word[24..32].iter().fold(0, |value, byte| (value << 8) | u64::from(*byte)) as i64;
+    let padding = if value < 0 { 0xff } else { 0 };
+    if word[0..24].iter().any(|byte| *byte != padding) {
+        return Err("ABI integer is out of range.".to_string());
+    }
+    Ok(value)
+}
+
+// Parses a 0x-prefixed hex address
+pub fn parse_address(text: &str) -> Result<Address, String> {
+    let bytes = from_hex(text)?;
+    if bytes.len() != 20 {
+        return Err(format!("Expected address, found: '{}'.", text));
+    }
+    Ok(Address::from_slice(&bytes))
+}
+
+// Formats bytes as 0x-prefixed hex
+pub fn to_hex(bytes: &[u8]) -> String {
+    let digits: Vec<String> = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("0x{}", digits.concat())
+}
+
+// Parses 0x-prefixed (or unprefixed) hex into bytes
+pub fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits = text.trim_start_matches("0x");
+    if digits.len() % 2 != 0 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Expected hex, found: '{}'.", text));
+    }
+    Ok((0..digits.len()).step_by(2).map(|i| u8::from_str_radix(&digits[i..(i + 2)], 16).unwrap()).collect())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function selectors are the first 4 bytes of the hash of the function signature
+    #[test]
+    fn selectors_correct() {
+        let balance_of = Function { name: "balanceOf", inputs: &[ParamType::Address], outputs: &[ParamType::Uint64], constant: true };
+        assert_eq!(balance_of.signature(), "balanceOf(address)");
+        assert_eq!(balance_of.selector(), [0x70, 0xa0, 0x82, 0x31]);
+
+        assert_eq!(Function::find("acquire").unwrap().signature(), "acquire()");
+        assert_eq!(Function::find("set_or_choices").unwrap().signature(), "set_or_choices(uint64[],bool[],bool)");
+        assert_eq!(Function::find("get_or_choices").unwrap().outputs, &[ParamType::Bytes]);
+    }
+
+    // Static values take a word each, negative integers being sign-extended
+    #[test]
+    fn static_values_encoded_correctly() {
+        let address = Address::from_slice(&[0x11; 20]);
+        let encoded = encode(&[Token::Int(-2), Token::Bool(true), Token::Address(address)]);
+
+        assert_eq!(encoded.len(), 96);
+        assert_eq!(&encoded[0..31], &[0xff; 31][..]);
+        assert_eq!(encoded[31], 0xfe);
+        assert_eq!(encoded[63], 1);
+        assert_eq!(&encoded[64..76], &[0; 12][..]);
+        assert_eq!(&encoded[76..96], &[0x11; 20][..]);
+    }
+
+    // Dynamic values are placed after the static values, with their offset in place
+    #[test]
+    fn dynamic_values_encoded_correctly() {
+        let encoded = encode(&[Token::Array(vec![Token::Int(5), Token::Int(1)]), Token::Bytes(vec![0xab])]);
+
+        let expected_words: Vec<u64> = vec![64, 160, 2, 5, 1, 1];
+        for (i, expected) in expected_words.iter().enumerate() {
+            assert_eq!(decode_uint(&read_word(&encoded, 32 * i).unwrap()).unwrap(), *expected);
+        }
+        assert_eq!(encoded[192], 0xab);
+        assert_eq!(encoded.len(), 224);
+    }
+
+    // Decoding encoded values gives the original values
+    #[test]
+    fn decoding_correct() {
+        let types = [ParamType::Array(&ParamType::Int64), ParamType::Address, ParamType::Bool, ParamType::Uint64, ParamType::Bytes];
+        let tokens = vec![
+            Token::Array(vec![Token::Int(-1), Token::Int(i64::max_value())]),
+            Token::Address(Address::from_slice(&[0xab; 20])),
+            Token::Bool(false),
+            Token::Uint(u64::max_value()),
+            Token::Bytes(vec![1, 2, 3])
+        ];
+
+        assert_eq!(decode(&types, &encode(&tokens)).unwrap(), tokens);
+    }
+
+    // Values are parsed from text by type
+    #[test]
+    fn parsing_correct() {
+        assert_eq!(Token::parse(&ParamType::Int64, "-3").unwrap(), Token::Int(-3));
+        assert_eq!(Token::parse(&ParamType::Bool, "true").unwrap(), Token::Bool(true));
+        assert_eq!(Token::parse(&ParamType::Bytes, "0x01ff").unwrap(), Token::Bytes(vec![1, 255]));
+        assert_eq!(Token::parse(&ParamType::Array(&ParamType::Uint64), "[1, 2]").unwrap(), Token::Array(vec![Token::Uint(1), Token::Uint(2)]));
+        assert_eq!(Token::parse(&ParamType::Array(&ParamType::Bool), "").unwrap(), Token::Array(vec![]));
+        assert!(Token::parse(&ParamType::Uint64, "-1").is_err());
+        assert!(Token::parse(&ParamType::Address, "0x1234").is_err());
+    }
+
+    // Calls with arguments of the wrong types are not encoded
+    #[test]
+    fn call_arguments_checked() {
+        let set_obs_value = Function::find("set_obs_value").unwrap();
+
+        assert!(set_obs_value.encode_call(&[Token::Uint(0), Token::Int(5)]).is_ok());
+        assert!(set_obs_value.encode_call(&[Token::Uint(0), Token::Bool(true)]).is_err());
+        assert!(set_obs_value.encode_call(&[Token::Uint(0)]).is_err());
+        assert!(Function::find("set_obs").is_err());
+    }
+
+    // Words outside the range of their type are not decoded
+    #[test]
+    fn out_of_range_words_not_decoded() {
+        let mut word = encode(&[Token::Int(-1)]);
+        word[0] = 0;
+
+        assert!(decode(&[ParamType::Int64], &word).is_err());
+        assert!(decode(&[ParamType::Uint64], &encode(&[Token::Int(-1)])).is_err());
+        assert!(decode(&[ParamType::Bool], &encode(&[Token::Uint(2)])).is_err());
+        assert!(decode(&[ParamType::Uint64], &[0; 31]).is_err());
+    }
+}
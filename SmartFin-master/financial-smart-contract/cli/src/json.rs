@@ -0,0 +1,516 @@
+// JSON representations of contract definitions and their runtime state, for exchanging contracts with other systems.
+//
+// A definition is a tree of combinators, each an object naming its combinator, its fields and its sub-combinators:
+//
+//     { "combinator": "truncate", "horizon": 1546300800, "sub_combinators": [
+//         { "combinator": "scale", "scale_value": { "type": "observable", "arbiter": "0x…", "name": "price" },
+//           "sub_combinators": [{ "combinator": "one" }] }
+//     ] }
+//
+// The fields of each combinator are:
+//     zero, one, and, or, give, then, get, anytime: none
+//     one_asset: asset (address)
+//     truncate: horizon
+//     scale: scale_value
+//     schedule: start, period, count
+//     truncate_relative: offset
+//     schedule_relative: start_offset, period, count
+//
+// Scale values have a type, and are either provided ({ "type": "provided", "value": -5 }), an observable (arbiter and name),
+// a quorum observable (arbiters, threshold and name), or a disputable observable (window, resolver, and the observable or quorum
+// observable as observable). Names are strings, or arrays of character codes if they are not valid text.
+//
+// The runtime state of a contract is its live state tree (as returned by get_state_tree), with each node naming its combinator,
+// its index (or null), acquisition time (or null), horizon (or null), whether it is fully updated, the value it has accrued per
+// asset and its sub-combinators, followed by the or choices (true, false or null) and observable values (or null):
+//
+//     { "state_tree": { "combinator": "one", "index": null, "acquisition_time": 1546300800, "horizon": null,
+//                       "fully_updated": true, "accrued": [1], "sub_combinators": [] },
+//       "or_choices": [], "obs_values": [] }
+//
+// Conversions are lossless: converting a vector to JSON and back gives the original vector, and vectors which could not be
+// given back exactly are rejected.
+use abi::{ parse_address, to_hex };
+use financial_smart_contract::Combinator;
+use financial_smart_contract::builder::{ Definition, Dispute, Observable, Quorum, ScaleValue };
+use financial_smart_contract::state_tree::{ StateNode, serialize_state_tree, try_deserialize_state_tree };
+use pwasm_std::types::Address;
+use serde_json::{ Map, Value };
+
+// The runtime state of a contract, as returned by its getters
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct ContractState {
+    // The serialized state tree (get_state_tree)
+    pub state_tree: Vec<i64>,
+
+    // The or choices, 0 (false), 1 (true) or 2 (not set) each (get_or_choices)
+    pub or_choices: Vec<u8>,
+
+    // The observable values (the values of get_obs_entries)
+    pub obs_values: Vec<Option<i64>>
+}
+
+// The names of the built-in combinators in JSON
+const COMBINATOR_NAMES: &[(Combinator, &str)] = &[
+    (Combinator::ZERO, "zero"),
+    (Combinator::ONE, "one"),
+    (Combinator::AND, "and"),
+    (Combinator::OR, "or"),
+    (Combinator::TRUNCATE, "truncate"),
+    (Combinator::SCALE, "scale"),
+    (Combinator::GIVE, "give"),
+    (Combinator::THEN, "then"),
+    (Combinator::GET, "get"),
+    (Combinator::ANYTIME, "anytime"),
+    (Combinator::SCHEDULE, "schedule"),
+    (Combinator::TRUNCATE_RELATIVE, "truncate_relative"),
+    (Combinator::SCHEDULE_RELATIVE, "schedule_relative"),
+    (Combinator::ONE_ASSET, "one_asset")
+];
+
+// Converts a definition vector (as taken by the contract constructor) to JSON
+pub fn definition_to_json(serialized: &Vec<i64>) -> Result<Value, String> {
+    let definition = Definition::try_deserialize(serialized).map_err(|err| err.to_string())?;
+    if definition.serialize() != *serialized {
+        return Err("Definition has addresses which are not valid.".to_string());
+    }
+    Ok(definition_node_to_json(&definition))
+}
+
+// Converts JSON to a definition vector
+pub fn definition_from_json(json: &Value) -> Result<Vec<i64>, String> {
+    Ok(definition_node_from_json(json)?.serialize())
+}
+
+// Converts the runtime state of a contract to JSON
+pub fn state_to_json(state: &ContractState) -> Result<Value, String> {
+    let state_tree = try_deserialize_state_tree(&state.state_tree).map_err(|err| err.to_string())?;
+    if serialize_state_tree(&state_tree) != state.state_tree {
+        return Err("State tree has fields which are not valid.".to_string());
+    }
+
+    let or_choices: Result<Vec<Value>, String> = state.or_choices.iter().map(|choice| match choice {
+        0 => Ok(Value::from(false)),
+        1 => Ok(Value::from(true)),
+        2 => Ok(Value::Null),
+        _ => Err(format!("Expected or choice, found: {}.", choice))
+    }).collect();
+
+    Ok(json!({
+        "state_tree": state_node_to_json(&state_tree),
+        "or_choices": or_choices?,
+        "obs_values": state.obs_values
+    }))
+}
+
+// Converts JSON to the runtime state of a contract
+pub fn state_from_json(json: &Value) -> Result<ContractState, String> {
+    let or_choices: Result<Vec<u8>, String> = array_field(json, "or_choices")?.iter().map(|choice| match choice {
+        Value::Bool(false) => Ok(0),
+        Value::Bool(true) => Ok(1),
+        Value::Null => Ok(2),
+        _ => Err(format!("Expected or choice, found: {}.", choice))
+    }).collect();
+    let obs_values: Result<Vec<Option<i64>>, String> = array_field(json, "obs_values")?.iter().map(|value| match value {
+        Value::Null => Ok(None),
+        _ => value.as_i64().map(Some).ok_or_else(|| format!("Expected observable value, found: {}.", value))
+    }).collect();
+
+    Ok(ContractState {
+        state_tree: serialize_state_tree(&state_node_from_json(field(json, "state_tree")?)?),
+        or_choices: or_choices?,
+        obs_values: obs_values?
+    })
+}
+
+// Converts a definition to JSON
+fn definition_node_to_json(definition: &Definition) -> Value {
+    let mut node = Map::new();
+    let (combinator, sub_combinators): (Combinator, Vec<&Definition>) = match definition {
+        Definition::Zero => (Combinator::ZERO, vec![]),
+        Definition::One => (Combinator::ONE, vec![]),
+        Definition::OneAsset(asset) => {
+            node.insert("asset".to_string(), address_to_json(asset));
+            (Combinator::ONE_ASSET, vec![])
+        },
+        Definition::And(sub_combinator0, sub_combinator1) => (Combinator::AND, vec![sub_combinator0, sub_combinator1]),
+        Definition::Or(sub_combinator0, sub_combinator1) => (Combinator::OR, vec![sub_combinator0, sub_combinator1]),
+        Definition::Truncate(horizon, sub_combinator) => {
+            node.insert("horizon".to_string(), Value::from(*horizon));
+            (Combinator::TRUNCATE, vec![sub_combinator])
+        },
+        Definition::Scale(scale_value, sub_combinator) => {
+            node.insert("scale_value".to_string(), scale_value_to_json(scale_value));
+            (Combinator::SCALE, vec![sub_combinator])
+        },
+        Definition::Give(sub_combinator) => (Combinator::GIVE, vec![sub_combinator]),
+        Definition::Then(sub_combinator0, sub_combinator1) => (Combinator::THEN, vec![sub_combinator0, sub_combinator1]),
+        Definition::Get(sub_combinator) => (Combinator::GET, vec![sub_combinator]),
+        Definition::Anytime(sub_combinator) => (Combinator::ANYTIME, vec![sub_combinator]),
+        Definition::Schedule(start, period, count, sub_combinator) => {
+            node.insert("start".to_string(), Value::from(*start));
+            node.insert("period".to_string(), Value::from(*period));
+            node.insert("count".to_string(), Value::from(*count));
+            (Combinator::SCHEDULE, vec![sub_combinator])
+        },
+        Definition::TruncateRelative(offset, sub_combinator) => {
+            node.insert("offset".to_string(), Value::from(*offset));
+            (Combinator::TRUNCATE_RELATIVE, vec![sub_combinator])
+        },
+        Definition::ScheduleRelative(start_offset, period, count, sub_combinator) => {
+            node.insert("start_offset".to_string(), Value::from(*start_offset));
+            node.insert("period".to_string(), Value::from(*period));
+            node.insert("count".to_string(), Value::from(*count));
+            (Combinator::SCHEDULE_RELATIVE, vec![sub_combinator])
+        }
+    };
+
+    node.insert("combinator".to_string(), combinator_to_json(combinator));
+    if !sub_combinators.is_empty() {
+        let sub_combinators: Vec<Value> = sub_combinators.into_iter().map(definition_node_to_json).collect();
+        node.insert("sub_combinators".to_string(), Value::Array(sub_combinators));
+    }
+    Value::Object(node)
+}
+
+// Converts JSON to a definition
+fn definition_node_from_json(json: &Value) -> Result<Definition, String> {
+    let combinator = combinator_from_json(field(json, "combinator")?)?;
+    let sub_combinator_count = match combinator {
+        Combinator::ZERO | Combinator::ONE | Combinator::ONE_ASSET => 0,
+        Combinator::AND | Combinator::OR | Combinator::THEN => 2,
+        Combinator::CUSTOM(tag) => return Err(format!("Definitions only describe the built-in combinators, found: {}.", tag)),
+        _ => 1
+    };
+
+    let mut sub_combinators: Vec<Box<Definition>> = Vec::new();
+    if sub_combinator_count > 0 {
+        for sub_combinator in array_field(json, "sub_combinators")?.iter() {
+            sub_combinators.push(Box::new(definition_node_from_json(sub_combinator)?));
+        }
+    }
+    if sub_combinators.len() != sub_combinator_count {
+        return Err(format!("Expected {} sub-combinators, found: {}.", sub_combinator_count, json));
+    }
+    let mut sub_combinators = sub_combinators.into_iter();
+    let mut sub_combinator = || sub_combinators.next().unwrap();
+
+    Ok(match combinator {
+        Combinator::ZERO => Definition::Zero,
+        Combinator::ONE => Definition::One,
+        Combinator::ONE_ASSET => Definition::OneAsset(address_from_json(field(json, "asset")?)?),
+        Combinator::AND => Definition::And(sub_combinator(), sub_combinator()),
+        Combinator::OR => Definition::Or(sub_combinator(), sub_combinator()),
+        Combinator::TRUNCATE => Definition::Truncate(u64_field(json, "horizon")?, sub_combinator()),
+        Combinator::SCALE => Definition::Scale(scale_value_from_json(field(json, "scale_value")?)?, sub_combinator()),
+        Combinator::GIVE => Definition::Give(sub_combinator()),
+        Combinator::THEN => Definition::Then(sub_combinator(), sub_combinator()),
+        Combinator::GET => Definition::Get(sub_combinator()),
+        Combinator::ANYTIME => Definition::Anytime(sub_combinator()),
+        Combinator::SCHEDULE => Definition::Schedule(
+            u64_field(json, "start")?, u64_field(json, "period")?, u64_field(json, "count")?, sub_combinator()
+        ),
+        Combinator::TRUNCATE_RELATIVE => Definition::TruncateRelative(u64_field(json, "offset")?, sub_combinator()),
+        Combinator::SCHEDULE_RELATIVE => Definition::ScheduleRelative(
+            u64_field(json, "start_offset")?, u64_field(json, "period")?, u64_field(json, "count")?, sub_combinator()
+        ),
+        Combinator::CUSTOM(_) => unreachable!()
+    })
+}
+
+// Converts a scale value to JSON
+fn scale_value_to_json(scale_value: &ScaleValue) -> Value {
+    match scale_value {
+        ScaleValue::Provided(value) => json!({ "type": "provided", "value": value }),
+        ScaleValue::Observable(obs) => json!({ "type": "observable", "arbiter": address_to_json(&obs.arbiter), "name": name_to_json(&obs.name) }),
+        ScaleValue::Quorum(obs) => json!({
+            "type": "quorum",
+            "arbiters": obs.arbiters.iter().map(address_to_json).collect::<Vec<Value>>(),
+            "threshold": obs.threshold,
+            "name": name_to_json(&obs.name)
+        }),
+        ScaleValue::Disputable(dispute, obs) => json!({
+            "type": "disputable",
+            "window": dispute.window,
+            "resolver": address_to_json(&dispute.resolver),
+            "observable": scale_value_to_json(obs)
+        })
+    }
+}
+
+// Converts JSON to a scale value
+fn scale_value_from_json(json: &Value) -> Result<ScaleValue, String> {
+    match field(json, "type")?.as_str() {
+        Some("provided") => Ok(ScaleValue::Provided(i64_field(json, "value")?)),
+        Some("observable") => Ok(ScaleValue::Observable(Observable {
+            arbiter: address_from_json(field(json, "arbiter")?)?,
+            name: name_from_json(field(json, "name")?)?
+        })),
+        Some("quorum") => {
+            let arbiters: Result<Vec<Address>, String> = array_field(json, "arbiters")?.iter().map(address_from_json).collect();
+            Ok(ScaleValue::Quorum(Quorum {
+                arbiters: arbiters?,
+                threshold: u64_field(json, "threshold")?,
+                name: name_from_json(field(json, "name")?)?
+            }))
+        },
+        Some("disputable") => {
+            let dispute = Dispute { window: u64_field(json, "window")?, resolver: address_from_json(field(json, "resolver")?)? };
+            match scale_value_from_json(field(json, "observable")?)? {
+                obs @ ScaleValue::Observable(_) | obs @ ScaleValue::Quorum(_) => Ok(ScaleValue::Disputable(dispute, Box::new(obs))),
+                _ => Err("Only observables and quorum observables can be disputed.".to_string())
+            }
+        },
+        _ => Err(format!("Expected scale value type, found: {}.", json))
+    }
+}
+
+// Converts a state tree node to JSON
+fn state_node_to_json(node: &StateNode) -> Value {
+    let mut json = json!({
+        "combinator": combinator_to_json(node.combinator),
+        "index": node.index,
+        "acquisition_time": node.acquisition_time,
+        "horizon": node.horizon,
+        "fully_updated": node.fully_updated,
+        "accrued": node.accrued,
+        "sub_combinators": node.sub_nodes.iter().map(state_node_to_json).collect::<Vec<Value>>()
+    });
+    if let Combinator::CUSTOM(tag) = node.combinator {
+        json["tag"] = Value::from(tag);
+    }
+    json
+}
+
+// Converts JSON to a state tree node
+fn state_node_from_json(json: &Value) -> Result<StateNode, String> {
+    let accrued: Result<Vec<i64>, String> = array_field(json, "accrued")?.iter().map(|value|
+        value.as_i64().ok_or_else(|| format!("Expected accrued value, found: {}.", value))
+    ).collect();
+    let sub_nodes: Result<Vec<StateNode>, String> = array_field(json, "sub_combinators")?.iter().map(state_node_from_json).collect();
+
+    // Custom combinators are identified by their tag
+    let combinator = match json.get("tag") {
+        Some(_) => Combinator::from(i64_field(json, "tag")?),
+        None => combinator_from_json(field(json, "combinator")?)?
+    };
+
+    Ok(StateNode {
+        combinator,
+        index: optional_u64_field(json, "index")?.map(|index| index as usize),
+        acquisition_time: optional_u64_field(json, "acquisition_time")?,
+        horizon: optional_u64_field(json, "horizon")?,
+        fully_updated: field(json, "fully_updated")?.as_bool().ok_or_else(|| format!("Expected fully_updated, found: {}.", json))?,
+        accrued: accrued?,
+        sub_nodes: sub_nodes?
+    })
+}
+
+// Converts a combinator to its name
+fn combinator_to_json(combinator: Combinator) -> Value {
+    match COMBINATOR_NAMES.iter().find(|(named, _)| *named == combinator) {
+        Some((_, name)) => Value::from(*name),
+        None => Value::from("custom")
+    }
+}
+
+// Converts a name to the built-in combinator it names
+fn combinator_from_json(json: &Value) -> Result<Combinator, String> {
+    match COMBINATOR_NAMES.iter().find(|(_, name)| json.as_str() == Some(*name)) {
+        Some((combinator, _)) => Ok(*combinator),
+        None => Err(format!("Unrecognised combinator: {}.", json))
+    }
+}
+
+// Converts an observable name to a string, or an array of character codes if it is not valid text
+fn name_to_json(name: &[i64]) -> Value {
+    let characters: Option<String> = name.iter().map(|code| if *code >= 0 && *code <= i64::from(u32::max_value()) {
+        ::std::char::from_u32(*code as u32)
+    } else {
+        None
+    }).collect();
+    match characters {
+        Some(text) => Value::from(text),
+        None => Value::from(name.to_vec())
+    }
+}
+
+// Converts a string or array of character codes to an observable name
+fn name_from_json(json: &Value) -> Result<Vec<i64>, String> {
+    match json {
+        Value::String(text) => Ok(text.chars().map(|c| c as i64).collect()),
+        Value::Array(codes) => codes.iter().map(|code| code.as_i64().ok_or_else(|| format!("Expected character code, found: {}.", code))).collect(),
+        _ => Err(format!("Expected observable name, found: {}.", json))
+    }
+}
+
+// Converts an address to a hex string
+fn address_to_json(address: &Address) -> Value {
+    Value::from(to_hex(&address.0))
+}
+
+// Converts a hex string to an address
+fn address_from_json(json: &Value) -> Result<Address, String> {
+    match json.as_str() {
+        Some(text) => parse_address(text),
+        None => Err(format!("Expected address, found: {}.", json))
+    }
+}
+
+// Gets the given field of an object
+fn field<'a>(json: &'a Value, name: &str) -> Result<&'a Value, String> {
+    json.get(name).ok_or_else(|| format!("Expected field {} in: {}.", name, json))
+}
+
+// Gets the given array field of an object
+fn array_field<'a>(json: &'a Value, name: &str) -> Result<&'a Vec<Value>, String> {
+    field(json, name)?.as_array().ok_or_else(|| format!("Expected field {} to be an array in: {}.", name, json))
+}
+
+// Gets the given unsigned integer field of an object
+fn u64_field(json: &Value, name: &str) -> Result<u64, String> {
+    field(json, name)?.as_u64().ok_or_else(|| format!("Expected field {} to be an unsigned integer in: {}.", name, json))
+}
+
+// Gets the given integer field of an object
+fn i64_field(json: &Value, name: &str) -> Result<i64, String> {
+    field(json, name)?.as_i64().ok_or_else(|| format!("Expected field {} to be an integer in: {}.", name, json))
+}
+
+// Gets the given unsigned integer field of an object, which is null if not set
+fn optional_u64_field(json: &Value, name: &str) -> Result<Option<u64>, String> {
+    match field(json, name)? {
+        Value::Null => Ok(None),
+        _ => u64_field(json, name).map(Some)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use financial_smart_contract::builder::{ and, dispute, give, observable, one, one_of, or, quorum, scale, scale_by,
+        scale_by_disputable, scale_by_quorum, schedule, schedule_relative, then, truncate, truncate_relative, zero, anytime, get };
+
+    // An arbiter address for observables
+    fn arbiter() -> Address {
+        parse_address("0x3D04E16e08E4c1c7fa8fC5A386237669341EaAcE").unwrap()
+    }
+
+    // Definitions are converted to combinator trees
+    #[test]
+    fn definition_to_json_correct() {
+        let definition = truncate(10, or(scale_by(observable(arbiter(), "price"), one()), scale(-3, zero())));
+
+        assert_eq!(definition_to_json(&definition.serialize()).unwrap(), json!({
+            "combinator": "truncate",
+            "horizon": 10,
+            "sub_combinators": [{
+                "combinator": "or",
+                "sub_combinators": [{
+                    "combinator": "scale",
+                    "scale_value": { "type": "observable", "arbiter": "0x3d04e16e08e4c1c7fa8fc5a386237669341eaace", "name": "price" },
+                    "sub_combinators": [{ "combinator": "one" }]
+                }, {
+                    "combinator": "scale",
+                    "scale_value": { "type": "provided", "value": -3 },
+                    "sub_combinators": [{ "combinator": "zero" }]
+                }]
+            }]
+        }));
+    }
+
+    // Converting a definition vector to JSON and back gives the original vector
+    #[test]
+    fn definition_round_trip_lossless() {
+        let resolver = parse_address("0x0e6D2ABbe3C0d1e0C2f4Bb1aa4B9bC8b5D1f4a2E").unwrap();
+        let definitions = [
+            and(give(one_of(arbiter())), then(get(anytime(one())), zero())),
+            schedule(100, 10, 3, truncate_relative(5, schedule_relative(0, 5, 2, one()))),
+            scale_by_quorum(quorum(vec![arbiter(), resolver], 2, "rate"), one()),
+            scale_by_disputable(dispute(60, resolver), ScaleValue::Observable(observable(arbiter(), "price")), one()),
+            scale_by(Observable { arbiter: arbiter(), name: vec![-1, 0xd800, 97] }, one()),
+            truncate(u64::max_value(), scale(i64::min_value(), one()))
+        ];
+
+        for definition in definitions.iter() {
+            let serialized = definition.serialize();
+            assert_eq!(definition_from_json(&definition_to_json(&serialized).unwrap()).unwrap(), serialized);
+        }
+    }
+
+    // Names which are not valid text are kept as character codes
+    #[test]
+    fn invalid_names_kept_as_codes() {
+        let definition = scale_by(Observable { arbiter: arbiter(), name: vec![-1, 97] }, one());
+
+        let json = definition_to_json(&definition.serialize()).unwrap();
+        assert_eq!(json["scale_value"]["name"], json!([-1, 97]));
+    }
+
+    // Definition vectors which are not valid definitions, or could not be given back exactly, are rejected
+    #[test]
+    fn invalid_definitions_rejected() {
+        assert_eq!(definition_to_json(&vec![2, 1]), Err("Provided combinator contract not valid.".to_string()));
+        assert_eq!(definition_to_json(&vec![1, -1, -1]), Err("Provided combinator contract not valid.".to_string()));
+        assert!(definition_to_json(&vec![13, -1, -1, -1, -1]).is_err());
+    }
+
+    // JSON which does not describe a definition is rejected
+    #[test]
+    fn invalid_json_rejected() {
+        assert!(definition_from_json(&json!({ "combinator": "two" })).is_err());
+        assert!(definition_from_json(&json!({ "combinator": "give" })).is_err());
+        assert!(definition_from_json(&json!({ "combinator": "and", "sub_combinators": [{ "combinator": "one" }] })).is_err());
+        assert!(definition_from_json(&json!({ "combinator": "truncate", "horizon": -1, "sub_combinators": [{ "combinator": "one" }] })).is_err());
+        assert!(definition_from_json(&json!({
+            "combinator": "scale",
+            "scale_value": { "type": "disputable", "window": 1, "resolver": to_hex(&arbiter().0), "observable": { "type": "provided", "value": 1 } },
+            "sub_combinators": [{ "combinator": "one" }]
+        })).is_err());
+    }
+
+    // Runtime state is converted to its state tree, or choices and observable values, and back
+    #[test]
+    fn state_round_trip_lossless() {
+        let state = ContractState {
+            state_tree: vec![3, 0, 5, -1, 1, 2, 1, 1, 0, -1, -1, -1, 0, 0, 0, 1, -1, 5, -1, 1, 0, 1, 1],
+            or_choices: vec![0, 2],
+            obs_values: vec![Some(-4), None]
+        };
+
+        let json = state_to_json(&state).unwrap();
+        assert_eq!(json["state_tree"]["combinator"], json!("or"));
+        assert_eq!(json["state_tree"]["index"], json!(0));
+        assert_eq!(json["state_tree"]["sub_combinators"][0]["acquisition_time"], Value::Null);
+        assert_eq!(json["state_tree"]["sub_combinators"][1]["accrued"], json!([1]));
+        assert_eq!(json["or_choices"], json!([false, null]));
+        assert_eq!(json["obs_values"], json!([-4, null]));
+        assert_eq!(state_from_json(&json).unwrap(), state);
+    }
+
+    // Custom combinators in the state tree keep their tag
+    #[test]
+    fn custom_state_nodes_keep_tag() {
+        let state = ContractState { state_tree: vec![100, -1, -1, -1, 0, 0, 0], or_choices: vec![], obs_values: vec![] };
+
+        let json = state_to_json(&state).unwrap();
+        assert_eq!(json["state_tree"]["combinator"], json!("custom"));
+        assert_eq!(json["state_tree"]["tag"], json!(100));
+        assert_eq!(state_from_json(&json).unwrap(), state);
+    }
+
+    // State which could not be given back exactly is rejected
+    #[test]
+    fn invalid_state_rejected() {
+        let state = ContractState { state_tree: vec![1, -2, -1, -1, 0, 0, 0], or_choices: vec![], obs_values: vec![] };
+        assert!(state_to_json(&state).is_err());
+
+        let state = ContractState { state_tree: vec![1, -1, -1, -1, 0, 0, 0], or_choices: vec![3], obs_values: vec![] };
+        assert!(state_to_json(&state).is_err());
+
+        assert!(state_from_json(&json!({ "state_tree": {}, "or_choices": [], "obs_values": [] })).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+// A client for deploying and driving financial smart contracts on a node over JSON-RPC, with the encoding of calls to the
+// contract's ABI
+extern crate financial_smart_contract;
+extern crate pwasm_std;
+#[macro_use]
+extern crate serde_json;
+extern crate tiny_keccak;
+
+pub mod abi;
+pub mod client;
+pub mod json;
+pub mod rpc;
+
+pub use client::{ FinancialScClient, Receipt };
@@ -0,0 +1,194 @@
+// Command line interface for deploying and driving financial smart contracts on a node
+extern crate financial_smart_contract_cli;
+#[macro_use]
+extern crate serde_json;
+
+use financial_smart_contract_cli::abi::{ self, Function, ParamType, Token, FUNCTIONS };
+use financial_smart_contract_cli::FinancialScClient;
+use financial_smart_contract_cli::client::strip_definition_padding;
+use financial_smart_contract_cli::json::{ definition_from_json, definition_to_json, state_to_json };
+use std::env;
+use std::fs;
+use std::process;
+
+// The usage of the command line interface
+const USAGE: &str = "Usage: smartfin [--url URL] [--from ADDRESS] [--password PASSWORD] [--value WEI] COMMAND [ARGS...]
+
+Commands:
+    deploy WASM DEFINITION HOLDER [USE_GAS] [GAS_STIPEND]
+                                Deploys the contract code in WASM with the given comma-separated definition (or the
+                                definition in the given .json file)
+    export CONTRACT             Prints the definition and runtime state of the contract at CONTRACT as JSON
+    to-json DEFINITION          Prints the given comma-separated definition as JSON
+    from-json FILE              Prints the definition in the given .json file as comma-separated integers
+    functions                   Lists the functions of the contract ABI
+    FUNCTION CONTRACT [ARGS...] Calls the given function of the contract at CONTRACT, in a transaction unless the
+                                function is constant
+
+Options:
+    --url URL                   The JSON-RPC URL of the node (default: http://localhost:8545)
+    --from ADDRESS              The account to send transactions and calls from
+    --password PASSWORD         The password to unlock the --from account with before sending transactions
+    --value WEI                 The value to send with transactions (e.g. for stake)";
+
+// The default JSON-RPC URL of the node
+const DEFAULT_URL: &str = "http://localhost:8545";
+
+// The default gas stipend of withdrawals
+const DEFAULT_GAS_STIPEND: &str = "2300";
+
+// The options given on the command line
+struct Options {
+    url: String,
+    from: Option<String>,
+    password: Option<String>,
+    value: u64
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(args) {
+        Ok(output) => println!("{}", output),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
+
+// Runs the given command, returning its output
+fn run(args: Vec<String>) -> Result<String, String> {
+    let (options, args) = parse_options(args)?;
+    let command = match args.first() {
+        Some(command) => command.as_str(),
+        None => return Err(USAGE.to_string())
+    };
+
+    if command == "functions" {
+        let signatures: Vec<String> = FUNCTIONS.iter().map(|function| {
+            let outputs: Vec<String> = function.outputs.iter().map(|output| output.name()).collect();
+            let constant = if function.constant { " constant" } else { "" };
+            format!("{}{} returns ({})", function.signature(), constant, outputs.join(","))
+        }).collect();
+        return Ok(signatures.join("\n"));
+    }
+
+    if command == "to-json" || command == "from-json" {
+        if args.len() != 2 {
+            return Err(USAGE.to_string());
+        }
+        return if command == "to-json" {
+            Ok(definition_to_json(&parse_definition(&args[1])?)?.to_string())
+        } else {
+            let definition: Vec<String> = read_definition_json(&args[1])?.iter().map(|value| value.to_string()).collect();
+            Ok(definition.join(","))
+        };
+    }
+
+    let client = FinancialScClient::new(&options.url)?;
+    let from = match options.from {
+        Some(ref from) => abi::parse_address(from)?,
+        None => return Err("An account must be given with --from.".to_string())
+    };
+    if let Some(ref password) = options.password {
+        client.unlock_account(&from, password)?;
+    }
+
+    if command == "deploy" {
+        if args.len() < 4 || args.len() > 6 {
+            return Err(USAGE.to_string());
+        }
+        let code = fs::read(&args[1]).map_err(|err| format!("Could not read {}: {}.", args[1], err))?;
+        let definition = if args[2].ends_with(".json") { read_definition_json(&args[2])? } else { parse_definition(&args[2])? };
+        let holder = abi::parse_address(&args[3])?;
+        let use_gas = args.get(4).map(|arg| arg.as_str()).unwrap_or("true");
+        let use_gas: bool = use_gas.parse().map_err(|_| format!("Expected bool, found: '{}'.", use_gas))?;
+        let gas_stipend = args.get(5).map(|arg| arg.as_str()).unwrap_or(DEFAULT_GAS_STIPEND);
+        let gas_stipend: u64 = gas_stipend.parse().map_err(|_| format!("Expected uint64, found: '{}'.", gas_stipend))?;
+
+        let (address, receipt) = client.deploy(&from, &code, &definition, &holder, use_gas, gas_stipend)?;
+        return Ok(json!({
+            "address": abi::to_hex(&address.0),
+            "transactionHash": receipt.transaction_hash,
+            "gasUsed": receipt.gas_used
+        }).to_string());
+    }
+
+    if command == "export" {
+        if args.len() != 2 {
+            return Err(USAGE.to_string());
+        }
+        let contract = abi::parse_address(&args[1])?;
+        let definition = strip_definition_padding(&client.get_contract_definition(&contract, &from)?);
+        return Ok(json!({
+            "definition": definition_to_json(&definition)?,
+            "state": state_to_json(&client.get_state(&contract, &from)?)?
+        }).to_string());
+    }
+
+    let function = Function::find(command)?;
+    if args.len() != function.inputs.len() + 2 {
+        let inputs: Vec<String> = function.inputs.iter().map(|input| input.name().to_uppercase()).collect();
+        return Err(format!("Usage: smartfin {} CONTRACT {}", function.name, inputs.join(" ")));
+    }
+    let contract = abi::parse_address(&args[1])?;
+    let inputs: Result<Vec<Token>, String> = function.inputs.iter().zip(&args[2..]).map(|(input, arg)| Token::parse(input, arg)).collect();
+    let inputs = inputs?;
+
+    if function.constant {
+        let outputs = client.call(&contract, &from, function.name, &inputs)?;
+        match outputs.len() {
+            1 => Ok(outputs[0].to_json().to_string()),
+            _ => Ok(serde_json::Value::Array(outputs.iter().map(|output| output.to_json()).collect()).to_string())
+        }
+    } else {
+        let receipt = client.transact(&contract, &from, function.name, &inputs, options.value)?;
+        Ok(json!({ "transactionHash": receipt.transaction_hash, "gasUsed": receipt.gas_used }).to_string())
+    }
+}
+
+// Parses a comma-separated definition
+fn parse_definition(text: &str) -> Result<Vec<i64>, String> {
+    match Token::parse(&ParamType::Array(&ParamType::Int64), text)? {
+        Token::Array(elements) => Ok(elements.iter().map(|element| match element {
+            Token::Int(value) => *value,
+            _ => unreachable!()
+        }).collect()),
+        _ => unreachable!()
+    }
+}
+
+// Reads the definition in the given .json file
+fn read_definition_json(path: &str) -> Result<Vec<i64>, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("Could not read {}: {}.", path, err))?;
+    let json = serde_json::from_str(&text).map_err(|err| format!("Invalid JSON in {}: {}.", path, err))?;
+    definition_from_json(&json)
+}
+
+// Splits the options from the command and its arguments
+fn parse_options(args: Vec<String>) -> Result<(Options, Vec<String>), String> {
+    let mut options = Options { url: DEFAULT_URL.to_string(), from: None, password: None, value: 0 };
+    let mut args = args.into_iter();
+    let mut rest = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if !rest.is_empty() || !arg.starts_with("--") {
+            rest.push(arg);
+            continue;
+        }
+        let value = match args.next() {
+            Some(value) => value,
+            None => return Err(format!("Expected a value for {}.", arg))
+        };
+        match arg.as_str() {
+            "--url" => options.url = value,
+            "--from" => options.from = Some(value),
+            "--password" => options.password = Some(value),
+            "--value" => options.value = value.parse().map_err(|_| format!("Expected a value in wei, found: '{}'.", value))?,
+            _ => return Err(format!("Unknown option: {}.\n\n{}", arg, USAGE))
+        }
+    }
+
+    Ok((options, rest))
+}
+
@@ -0,0 +1,133 @@
+// A minimal JSON-RPC client, which sends each request in its own HTTP/1.1 POST to the node
+use serde_json::Value;
+use std::io::{ Read, Write };
+use std::net::TcpStream;
+
+// A JSON-RPC connection to a node
+pub struct Rpc {
+    // The host (and port) of the node, e.g. localhost:8545
+    host: String,
+
+    // The path of the node's JSON-RPC endpoint
+    path: String
+}
+
+// Method implementation of the JSON-RPC connection
+impl Rpc {
+    // Creates a connection to the node at the given URL, e.g. http://localhost:8545
+    pub fn new(url: &str) -> Result<Rpc, String> {
+        if !url.starts_with("http://") {
+            return Err(format!("Expected an http:// URL, found: '{}'.", url));
+        }
+        let address = &url["http://".len()..];
+        let (host, path) = match address.find('/') {
+            Some(i) => (&address[..i], &address[i..]),
+            None => (address, "/")
+        };
+        if host.is_empty() {
+            return Err(format!("Expected an http:// URL, found: '{}'.", url));
+        }
+        let host = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+        Ok(Rpc { host, path: path.to_string() })
+    }
+
+    // Sends a request to the node, returning its result (or its error message)
+    pub fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        let body = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 }).to_string();
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path, self.host, body.len(), body
+        );
+
+        let mut stream = TcpStream::connect(&self.host).map_err(|err| format!("Could not connect to {}: {}.", self.host, err))?;
+        stream.write_all(request.as_bytes()).map_err(|err| format!("Could not send request: {}.", err))?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).map_err(|err| format!("Could not read response: {}.", err))?;
+
+        let body = parse_response(&response)?;
+        let response: Value = serde_json::from_slice(&body).map_err(|err| format!("Invalid JSON-RPC response: {}.", err))?;
+        match response.get("error") {
+            Some(error) => Err(format!("{} failed: {}", method, error.get("message").and_then(|message| message.as_str()).unwrap_or("unknown error"))),
+            None => response.get("result").cloned().ok_or_else(|| format!("{} failed: no result.", method))
+        }
+    }
+}
+
+// Gets the body of an HTTP response, which is either of the given length or chunked
+fn parse_response(response: &[u8]) -> Result<Vec<u8>, String> {
+    let header_end = match response.windows(4).position(|window| window == b"\r\n\r\n") {
+        Some(i) => i,
+        None => return Err("Invalid HTTP response.".to_string())
+    };
+    let header = String::from_utf8_lossy(&response[..header_end]).to_lowercase();
+    let body = &response[(header_end + 4)..];
+
+    let status = header.split_whitespace().nth(1).unwrap_or("");
+    if status != "200" {
+        return Err(format!("HTTP request failed with status {}.", status));
+    }
+
+    if header.lines().any(|line| line.starts_with("transfer-encoding:") && line.contains("chunked")) {
+        dechunk(body)
+    } else {
+        Ok(body.to_vec())
+    }
+}
+
+// Joins the chunks of a chunked HTTP body
+fn dechunk(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut joined = Vec::new();
+    loop {
+        let line_end = match body.windows(2).position(|window| window == b"\r\n") {
+            Some(i) => i,
+            None => return Err("Invalid chunked HTTP response.".to_string())
+        };
+        let size_text = String::from_utf8_lossy(&body[..line_end]).to_string();
+        let size = match usize::from_str_radix(size_text.split(';').next().unwrap_or("").trim(), 16) {
+            Ok(size) => size,
+            Err(_) => return Err("Invalid chunked HTTP response.".to_string())
+        };
+        if size == 0 {
+            return Ok(joined);
+        }
+        match body.get((line_end + 2)..(line_end + 2 + size)) {
+            Some(chunk) => joined.extend_from_slice(chunk),
+            None => return Err("Invalid chunked HTTP response.".to_string())
+        }
+        body = body.get((line_end + 4 + size)..).unwrap_or(&[]);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Node URLs are split into the host and path
+    #[test]
+    fn urls_parsed_correctly() {
+        let rpc = Rpc::new("http://localhost:8545").unwrap();
+        assert_eq!(rpc.host, "localhost:8545");
+        assert_eq!(rpc.path, "/");
+
+        let rpc = Rpc::new("http://node/rpc").unwrap();
+        assert_eq!(rpc.host, "node:80");
+        assert_eq!(rpc.path, "/rpc");
+
+        assert!(Rpc::new("https://localhost:8545").is_err());
+        assert!(Rpc::new("http://").is_err());
+    }
+
+    // Bodies are read by length or joined from chunks
+    #[test]
+    fn responses_parsed_correctly() {
+        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\ntrue";
+        assert_eq!(parse_response(response).unwrap(), b"true");
+
+        let response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\n{\"a\r\n4\r\n\": 1\r\n1\r\n}\r\n0\r\n\r\n";
+        assert_eq!(parse_response(response).unwrap(), b"{\"a\": 1}");
+
+        assert!(parse_response(b"HTTP/1.1 500 Internal Server Error\r\n\r\n").is_err());
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n").is_err());
+    }
+}
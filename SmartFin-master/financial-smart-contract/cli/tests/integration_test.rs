@@ -0,0 +1,173 @@
+// Integration tests against the development blockchain (run-node.sh), which must be reachable at localhost:8545 and have the
+// financial smart contract built (build.sh). Run with cargo test -- --ignored.
+extern crate financial_smart_contract;
+extern crate financial_smart_contract_cli;
+extern crate pwasm_std;
+
+use std::env;
+use std::fs;
+use std::time::{ SystemTime, UNIX_EPOCH };
+use pwasm_std::types::Address;
+use financial_smart_contract::builder::{ Definition, observable, one, or, scale_by, zero };
+use financial_smart_contract_cli::FinancialScClient;
+use financial_smart_contract_cli::json::{ definition_to_json, state_from_json, state_to_json };
+use financial_smart_contract_cli::abi::{ parse_address, ParamType, Token };
+
+// The JSON-RPC URL of the development blockchain
+const URL: &str = "http://localhost:8545";
+
+// The password of the development blockchain's accounts
+const PASSWORD: &str = "test";
+
+// The accounts of the development blockchain (the same as the JS client's tests)
+const HOLDER: &str = "0x057E231DaB35A789F5999056c8Ec775512609CBb";
+const COUNTER_PARTY: &str = "0x1e00c1c4f7c9C878e863E9B2acC374F0C2a0F742";
+const UNINVOLVED: &str = "0x37aC31b396F68051e2a5D148CaF2198Af45ac918";
+
+// A deployed contract and the accounts involved
+struct Deployment {
+    client: FinancialScClient,
+    contract: Address,
+    holder: Address,
+    counter_party: Address,
+    uninvolved: Address
+}
+
+// Unlocks the accounts and deploys the given definition from the counter-party
+fn deploy(definition: Definition) -> Deployment {
+    let client = FinancialScClient::new(URL).unwrap();
+    let holder = parse_address(HOLDER).unwrap();
+    let counter_party = parse_address(COUNTER_PARTY).unwrap();
+    let uninvolved = parse_address(UNINVOLVED).unwrap();
+    for account in [holder, counter_party, uninvolved].iter() {
+        client.unlock_account(account, PASSWORD).unwrap();
+    }
+
+    let wasm = env::var("CONTRACT_WASM").unwrap_or_else(|_| "../contract/target/financial_smart_contract.wasm".to_string());
+    let code = fs::read(&wasm).expect("Contract must be built with build.sh.");
+
+    // First deployment may fail
+    let serialized = definition.serialize();
+    let contract = match client.deploy(&counter_party, &code, &serialized, &holder, true, 2300) {
+        Ok((contract, _)) => contract,
+        Err(_) => client.deploy(&counter_party, &code, &serialized, &holder, true, 2300).unwrap().0
+    };
+
+    Deployment { client, contract, holder, counter_party, uninvolved }
+}
+
+// Gets the current UNIX time
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+// The constructor arguments are returned by the getters
+#[test]
+#[ignore]
+fn getters_return_constructor_arguments() {
+    let definition = one();
+    let d = deploy(definition.clone());
+
+    assert_eq!(d.client.get_holder(&d.contract, &d.uninvolved).unwrap(), d.holder);
+    assert_eq!(d.client.get_counter_party(&d.contract, &d.uninvolved).unwrap(), d.counter_party);
+    assert_eq!(d.client.get_contract_definition(&d.contract, &d.uninvolved).unwrap(), definition.serialize());
+    assert!(d.client.get_use_gas(&d.contract, &d.uninvolved).unwrap());
+    assert_eq!(d.client.get_gas_stipend(&d.contract, &d.uninvolved).unwrap(), 2300);
+    assert!(!d.client.get_concluded(&d.contract, &d.uninvolved).unwrap());
+}
+
+// Acquiring a one contract gives the holder 1 Wei
+#[test]
+#[ignore]
+fn acquiring_one_pays_holder() {
+    let d = deploy(one());
+    d.client.acquire(&d.contract, &d.holder).unwrap();
+
+    assert_eq!(d.client.get_balance(&d.contract, &d.holder, true).unwrap(), 1);
+    assert_eq!(d.client.get_balance(&d.contract, &d.counter_party, false).unwrap(), -1);
+    assert!(d.client.get_acquisition_times(&d.contract, &d.holder).unwrap()[0] as u64 <= unix_time());
+}
+
+// Or choices made by the holder select the sub-combinator acquired
+#[test]
+#[ignore]
+fn or_choice_selects_sub_combinator() {
+    let d = deploy(or(zero(), one()));
+    d.client.set_or_choice(&d.contract, &d.holder, 0, false).unwrap();
+
+    assert_eq!(d.client.get_or_choices(&d.contract, &d.holder).unwrap(), vec![0]);
+    d.client.acquire(&d.contract, &d.holder).unwrap();
+    assert_eq!(d.client.get_balance(&d.contract, &d.holder, true).unwrap(), 1);
+}
+
+// Observable values set by the arbiter scale the contract
+#[test]
+#[ignore]
+fn observable_value_scales_contract() {
+    let counter_party = parse_address(COUNTER_PARTY).unwrap();
+    let d = deploy(scale_by(observable(counter_party, "price"), one()));
+
+    assert!(d.client.set_obs_value(&d.contract, &d.uninvolved, 0, 5).is_err());
+    d.client.set_obs_values(&d.contract, &d.counter_party, &[0], &[5], false).unwrap();
+    d.client.acquire(&d.contract, &d.holder).unwrap();
+    assert_eq!(d.client.get_balance(&d.contract, &d.holder, true).unwrap(), 5);
+}
+
+// Stakes are added to the staker's balance, and withdrawals taken from it
+#[test]
+#[ignore]
+fn stake_and_withdraw_update_balance() {
+    let d = deploy(one());
+    d.client.stake(&d.contract, &d.holder, 1000).unwrap();
+    assert_eq!(d.client.get_balance(&d.contract, &d.holder, true).unwrap(), 1000);
+
+    d.client.withdraw(&d.contract, &d.holder, 400).unwrap();
+    assert_eq!(d.client.get_balance(&d.contract, &d.holder, true).unwrap(), 600);
+    assert!(d.client.withdraw(&d.contract, &d.holder, 1000).is_err());
+}
+
+// Projected balances reflect acquiring the contract, without changing its state
+#[test]
+#[ignore]
+fn projected_balances_do_not_change_state() {
+    let d = deploy(one());
+    d.client.acquire(&d.contract, &d.holder).unwrap();
+
+    let projected = d.client.get_projected_balances(&d.contract, &d.holder, unix_time() + 60).unwrap();
+    assert_eq!(projected[0..3].to_vec(), vec![1, 1, -1]);
+    assert!(d.client.get_last_updated(&d.contract, &d.holder).unwrap() as u64 <= unix_time());
+}
+
+// Functions are called by name with parsed arguments, as the command line interface calls them
+#[test]
+#[ignore]
+fn functions_called_by_name() {
+    let d = deploy(one());
+    let args = [Token::parse(&ParamType::Bool, "true").unwrap()];
+
+    d.client.transact(&d.contract, &d.holder, "stake", &[], 10).unwrap();
+    assert_eq!(d.client.call(&d.contract, &d.holder, "get_balance", &args).unwrap(), vec![Token::Int(10)]);
+    assert!(d.client.call(&d.contract, &d.holder, "get_balance", &[]).is_err());
+}
+
+// The definition and runtime state of a deployed contract are exported to JSON losslessly
+#[test]
+#[ignore]
+fn definition_and_state_exported_to_json() {
+    let counter_party = parse_address(COUNTER_PARTY).unwrap();
+    let definition = or(zero(), scale_by(observable(counter_party, "price"), one()));
+    let d = deploy(definition.clone());
+    d.client.set_or_choice(&d.contract, &d.holder, 0, false).unwrap();
+    d.client.set_obs_value(&d.contract, &d.counter_party, 0, 3).unwrap();
+    d.client.acquire(&d.contract, &d.holder).unwrap();
+
+    let exported = definition_to_json(&d.client.get_contract_definition(&d.contract, &d.holder).unwrap()).unwrap();
+    assert_eq!(exported["sub_combinators"][1]["scale_value"]["name"], "price");
+
+    let state = d.client.get_state(&d.contract, &d.holder).unwrap();
+    assert_eq!(state.or_choices, vec![0]);
+    assert_eq!(state.obs_values, vec![Some(3)]);
+    let json = state_to_json(&state).unwrap();
+    assert_eq!(json["state_tree"]["combinator"], "or");
+    assert_eq!(state_from_json(&json).unwrap(), state);
+}
@@ -0,0 +1,133 @@
+import fs from "fs";
+import path from "path";
+import moment from "moment";
+
+import { serializeCombinatorContract } from "../src/js/contract-utils.mjs";
+import { uninvolved, holder, counterParty, getUnixTime, deploy } from "../test/common";
+
+// Where the report is written, can be overridden with the GAS_REPORT environment variable
+const REPORT_PATH = process.env.GAS_REPORT || path.join(__dirname, "gas-report.json");
+
+// How long after acquisition truncated sub-contracts become payable, in seconds
+const MATURITY = 5;
+
+// The amount staked by each party, so withdrawals can be made
+const STAKE = 1000000;
+
+// Formats a UNIX time as a date string of the contract definition syntax
+function date(time) {
+    return "<" + moment.unix(time).utc().format("DD/MM/YYYY HH:mm:ss") + ">";
+}
+
+// The catalogue of benchmarked definitions: one per combinator, and the definitions of gas_cost_comparisons.txt. Each
+// definition is built from the deployment time, and lists the or-choices and observables to set before acquiring.
+const catalogue = [
+    { name: "zero", definition: now => "zero" },
+    { name: "one", definition: now => "one" },
+    { name: "and", definition: now => "and one one" },
+    { name: "or", definition: now => "or one zero", orChoices: [true] },
+    { name: "truncate", definition: now => "truncate " + date(now + 1000) + " one" },
+    { name: "scale", definition: now => "scale 2 one" },
+    { name: "scale (observable)", definition: now => "scale price " + uninvolved.address + " one", observables: 1 },
+    { name: "give", definition: now => "give one" },
+    { name: "then", definition: now => "then truncate " + date(now + MATURITY) + " one one" },
+    { name: "get", definition: now => "get truncate " + date(now + MATURITY) + " one" },
+    { name: "anytime", definition: now => "anytime truncate " + date(now + 1000) + " one" },
+    { name: "schedule", definition: now => "schedule " + date(now) + " " + MATURITY + " 3 one" },
+    { name: "truncate_relative", definition: now => "truncate_relative 1000 one" },
+    { name: "schedule_relative", definition: now => "schedule_relative 0 " + MATURITY + " 3 one" },
+
+    // The token is never called, as only Ether is withdrawn
+    { name: "one_asset", definition: now => "one_asset " + uninvolved.address },
+    {
+        name: "European option",
+        definition: now => "get truncate " + date(now + MATURITY) + " or one zero",
+        orChoices: [true]
+    },
+    {
+        name: "European call (observable)",
+        definition: now => "get truncate " + date(now + MATURITY) + " or scale price " + uninvolved.address + " one zero",
+        orChoices: [true],
+        observables: 1
+    },
+    {
+        name: "Loan with variable repayment",
+        definition: now => "truncate " + date(now + 1000) + " and one anytime then truncate " + date(now + 2000) +
+            " give scale 2 one truncate " + date(now + 3000) + " give scale 3 one"
+    }
+];
+
+// Waits for the given number of seconds
+function wait(seconds) {
+    return new Promise(resolve => setTimeout(resolve, seconds * 1000));
+}
+
+// Returns the gas used by a transaction
+async function gasUsed(transaction) {
+    var receipt = await transaction;
+    return receipt.gasUsed;
+}
+
+// Deploys the given catalogue entry and makes each benchmarked call, returns the gas used by each (null if not applicable)
+async function benchmark(entry) {
+    var definition = entry.definition(getUnixTime());
+    var gas = { constructor: null, acquire: null, update: null, set_obs_value: null, withdraw: null };
+
+    var contract = await deploy(definition);
+    gas.constructor = contract.deploymentReceipt.gasUsed;
+
+    // Fund both parties, so the contract can pay out
+    await contract.methods.stake().send({ from: holder.address, value: STAKE });
+    await contract.methods.stake().send({ from: counterParty.address, value: STAKE });
+
+    // Set the or-choices and observables before acquiring
+    var orChoices = entry.orChoices || [];
+    for (var i = 0; i < orChoices.length; i++) {
+        await contract.methods.set_or_choice(i, orChoices[i]).send({ from: holder.address });
+    }
+    for (var j = 0; j < (entry.observables || 0); j++) {
+        var setObsGas = await gasUsed(contract.methods.set_obs_value(j, 5).send({ from: uninvolved.address }));
+        if (j == 0) {
+            gas.set_obs_value = setObsGas;
+        }
+    }
+
+    gas.acquire = await gasUsed(contract.methods.acquire().send({ from: holder.address }));
+
+    // Update once any truncated sub-contracts have matured, if anything is left to update
+    await wait(MATURITY + 1);
+    var concluded = await contract.methods.get_concluded().call({ from: holder.address });
+    if (!concluded.returnValue0) {
+        gas.update = await gasUsed(contract.methods.update().send({ from: holder.address }));
+    }
+
+    gas.withdraw = await gasUsed(contract.methods.withdraw(STAKE / 10).send({ from: holder.address }));
+
+    return {
+        name: entry.name,
+        definition: definition,
+        serialized: serializeCombinatorContract(definition),
+        gas: gas
+    };
+}
+
+describe('Gas benchmark', function() {
+    var results = [];
+
+    for (let entry of catalogue) {
+        it('Measures the gas used by ' + entry.name, async function() {
+            results.push(await benchmark(entry));
+        });
+    }
+
+    // Write the machine-readable report, so runs before and after a change can be compared
+    after(function() {
+        var report = {
+            chain: "wasm-dev-chain.json",
+            time: moment().utc().format(),
+            results: results
+        };
+        fs.writeFileSync(REPORT_PATH, JSON.stringify(report, null, 4) + "\n");
+        console.log("Gas report written to " + REPORT_PATH);
+    });
+});
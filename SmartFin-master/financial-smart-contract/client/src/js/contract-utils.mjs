@@ -30,7 +30,11 @@ const combinatorDict = {
     "give": 6,
     "then": 7,
     "get": 8,
-    "anytime": 9
+    "anytime": 9,
+    "schedule": 10,
+    "truncate_relative": 11,
+    "schedule_relative": 12,
+    "one_asset": 13
 };
 
 const serializedCombinatorDict = invert(combinatorDict);
@@ -39,6 +43,12 @@ export const DATE_STRING_NO_ZONE_FORMAT = "DD/MM/YYYY HH:mm:ss";
 export const DATE_STRING_FORMAT = "DD/MM/YYYY HH:mm:ss ZZ";
 export const UNIX_FORMAT = "X";
 
+// The operator permissions, combined as bit flags
+export const OPERATOR_CHOICES = 1;
+export const OPERATOR_ACQUIRE = 2;
+export const OPERATOR_WITHDRAW = 4;
+export const OPERATOR_DISPUTE = 8;
+
 // The Option class
 export class Option {
     // Initialises a new object of this class
@@ -178,7 +188,7 @@ export async function unlockAccount(address, password) {
 }
 
 // Loads and deploys the contract (from a fixed contract for this test), returns the contract object
-export function loadAndDeployContract(contractBytes, contractHolder, sender, useGas) {
+export function loadAndDeployContract(contractBytes, contractHolder, sender, useGas, gasStipend = 2300) {
     if (!contractBytes || !contractHolder || !sender) {
         return Promise.reject("Expected arguments are contractBytes, contractHolder, and sender. At least one argument was not supplied!");
     }
@@ -199,7 +209,7 @@ export function loadAndDeployContract(contractBytes, contractHolder, sender, use
     var contractPreDeploy = new web3.eth.Contract(ABI);
     
     // Construct a deployment transaction
-    var contractDeployTransaction = contractPreDeploy.deploy({ data: web3.utils.toHex(CODE_HEX), from: sender, arguments: [contractBytes, contractHolder, useGas] });
+    var contractDeployTransaction = contractPreDeploy.deploy({ data: web3.utils.toHex(CODE_HEX), from: sender, arguments: [contractBytes, contractHolder, useGas, gasStipend] });
     
     return new Promise(function(resolve, reject) {
         // Attempt to estimate the cost of the deployment transaction
@@ -207,7 +217,12 @@ export function loadAndDeployContract(contractBytes, contractHolder, sender, use
             if (gas) {
                 gas = Math.round(gas * 1.2);
                 // Commit the deployment transaction with some extra gas
-                contractDeployTransaction.send({ gas: web3.utils.toHex(gas), from: sender }).then(contract => {
+                var receipt;
+                contractDeployTransaction.send({ gas: web3.utils.toHex(gas), from: sender }).on('receipt', res => {
+                    receipt = res;
+                }).then(contract => {
+                    // Keep the deployment receipt (e.g. for the gas used by the constructor)
+                    contract.deploymentReceipt = receipt;
                     resolve(contract);
                 },
                 err => {
@@ -239,23 +254,19 @@ export function serializeCombinatorContract(combinatorContract) {
         switch (combinators[i].toLowerCase()) {
             case "truncate": {
                 result.push(combinator);
-                var date;
-                if (isNaN(combinators[i + 1]) && combinators[i + 1].indexOf("<") != -1) {
-                    // Time is pretty date string, find closing bracket
-                    var closeIndex = combinators.slice(i + 1).findIndex(elem => elem.indexOf(">") != -1) + i + 1;
-
-                    // Format time
-                    var time = combinators.slice(i + 1, closeIndex + 1).join(" ");
-                    time = time.slice(1, -1);
-                    date = moment.utc(time, [DATE_STRING_FORMAT, DATE_STRING_NO_ZONE_FORMAT], true);
-
-                    // Convert time and push
-                    i = closeIndex;
-                } else {
-                    date = moment.utc(combinators[i + 1], UNIX_FORMAT, true);
-                    i++;
-                }
-                result.push(date.unix());
+                var time = serializeTime(combinators, i + 1);
+                result.push(time.time);
+                i = time.endIndex;
+                break;
+            }
+            case "schedule": {
+                // Start time, then period and count of periods
+                result.push(combinator);
+                var time = serializeTime(combinators, i + 1);
+                result.push(time.time);
+                result.push(parseInt(combinators[time.endIndex + 1]));
+                result.push(parseInt(combinators[time.endIndex + 2]));
+                i = time.endIndex + 2;
                 break;
             }
             case "scale": {
@@ -269,13 +280,38 @@ export function serializeCombinatorContract(combinatorContract) {
 
                     i += 1;
                 } else {
-                    // Observable, push 0
-                    result.push(0);
+                    var arbiter = combinators[i + 2];
+                    if (isDisputable(arbiter)) {
+                        // Disputable observable, push 3 then the dispute window and resolver address before the observable
+                        var dispute = parseDisputable(arbiter);
+                        result.push(3);
+                        result.push(dispute.window);
+                        for (let part of serializeAddress(dispute.resolver)) {
+                            result.push(part.toString());
+                        }
+                        arbiter = dispute.arbiter;
+                    }
 
-                    // Push address
-                    var addressSerialized = serializeAddress(combinators[i + 2]);
-                    for (let part of addressSerialized) {
-                        result.push(part.toString());
+                    if (isQuorum(arbiter)) {
+                        // Quorum observable, push 2 then the threshold and arbiter addresses
+                        var quorum = parseQuorum(arbiter);
+                        result.push(2);
+                        result.push(quorum.threshold);
+                        result.push(quorum.arbiters.length);
+                        for (let quorumArbiter of quorum.arbiters) {
+                            for (let part of serializeAddress(quorumArbiter)) {
+                                result.push(part.toString());
+                            }
+                        }
+                    } else {
+                        // Observable, push 0
+                        result.push(0);
+
+                        // Push address
+                        var addressSerialized = serializeAddress(arbiter);
+                        for (let part of addressSerialized) {
+                            result.push(part.toString());
+                        }
                     }
 
                     // Push observable name
@@ -288,6 +324,31 @@ export function serializeCombinatorContract(combinatorContract) {
                 }
                 break;
             }
+            case "truncate_relative": {
+                // Offset from the acquisition time
+                result.push(combinator);
+                result.push(parseInt(combinators[i + 1]));
+                i++;
+                break;
+            }
+            case "schedule_relative": {
+                // Start offset from the acquisition time, then period and count of periods
+                result.push(combinator);
+                result.push(parseInt(combinators[i + 1]));
+                result.push(parseInt(combinators[i + 2]));
+                result.push(parseInt(combinators[i + 3]));
+                i += 3;
+                break;
+            }
+            case "one_asset": {
+                // Token address
+                result.push(combinator);
+                for (let part of serializeAddress(combinators[i + 1])) {
+                    result.push(part.toString());
+                }
+                i++;
+                break;
+            }
             default:
                 result.push(combinator);
                 break;
@@ -298,6 +359,29 @@ export function serializeCombinatorContract(combinatorContract) {
     return result;
 }
 
+// Serializes the time at the given index in the given list of combinator atoms, which is either a unix timestamp or a date
+// in angle-brackets, and returns it with the index of its last atom
+function serializeTime(combinators, i) {
+    var date;
+    var endIndex = i;
+    if (isNaN(combinators[i]) && combinators[i].indexOf("<") != -1) {
+        // Time is pretty date string, find closing bracket
+        endIndex = combinators.slice(i).findIndex(elem => elem.indexOf(">") != -1) + i;
+
+        // Format time
+        var time = combinators.slice(i, endIndex + 1).join(" ");
+        time = time.slice(1, -1);
+        date = moment.utc(time, [DATE_STRING_FORMAT, DATE_STRING_NO_ZONE_FORMAT], true);
+    } else {
+        date = moment.utc(combinators[i], UNIX_FORMAT, true);
+    }
+
+    return {
+        time: date.unix(),
+        endIndex: endIndex
+    };
+}
+
 // Verifies a combinator contract, and returns an error message and description if it is ill-formed.
 export function verifyContract(contract) {
     if (!contract || contract.length == 0) {
@@ -351,6 +435,18 @@ function verifyCombinator(combinators, seenObservables, i) {
                 endIndex: i
             };
         
+        case "one_asset":
+            // Token address, check it and return index of termination
+            if (combinators.length <= i + 1) {
+                return new VerificationError("Expected token address, found end of contract.", errDesc(i));
+            }
+            if (!web3.utils.isAddress(combinators[i + 1])) {
+                return new VerificationError("Expected a valid address, found: '" + combinators[i + 1] + "'.", errDesc(i));
+            }
+            return {
+                endIndex: i + 1
+            };
+
         case "give":
         case "get":
         case "anytime":
@@ -381,47 +477,70 @@ function verifyCombinator(combinators, seenObservables, i) {
 
         case "truncate":
             // Unix time and sub-combinator, check them and return index
-            if (combinators.length <= i + 1) {
-                return new VerificationError("Expected a unix timestamp, found end of contract.",errDesc(i));
+            var time = verifyTime(combinators, i + 1, errDesc(i));
+            if (time.error) {
+                return time;
             }
 
+            // Check sub-combinator
+            var res = verifyCombinator(combinators, seenObservables, time.endIndex + 1);
+            if (res.error) {
+                return addToErrStack(res, i);
+            } else {
+                return res;
+            }
 
-            // Check timestamp
-            var time = parseInt(combinators[i + 1]);
-            var nextCombinator = i + 2;
+        case "schedule":
+            // Unix start time, period, count and sub-combinator, check them and return index
+            var time = verifyTime(combinators, i + 1, errDesc(i));
+            if (time.error) {
+                return time;
+            }
 
-            if (isNaN(time)) {
-                // Date provided in < [date] > format
-                if (combinators[i + 1].indexOf("<") != -1) {
-                    // Time is pretty date string, find closing bracket
-                    var closeIndex = combinators.slice(i + 1).findIndex(elem => elem.indexOf(">") != -1) + i + 1;
+            var period = verifyInteger(combinators, time.endIndex + 1, 0, "period", errDesc(i));
+            if (period.error) {
+                return period;
+            }
+            var count = verifyInteger(combinators, time.endIndex + 2, 1, "count of periods", errDesc(i));
+            if (count.error) {
+                return count;
+            }
 
-                    if (closeIndex < i + 1) {
-                        return new VerificationError("Angle-brackets surrounding date not closed.", errDesc(i));
-                    }
+            // Check sub-combinator
+            var res = verifyCombinator(combinators, seenObservables, time.endIndex + 3);
+            if (res.error) {
+                return addToErrStack(res, i);
+            } else {
+                return res;
+            }
 
-                    var dateString = combinators.slice(i + 1, closeIndex + 1).join(" ").slice(1, -1);
-                    var date = moment.utc(dateString, [DATE_STRING_FORMAT, DATE_STRING_NO_ZONE_FORMAT], true);
-                    
-                    if (!date.isValid()) {
-                        // Date is invalid
-                        return new VerificationError("Expected date in the form of a UNIX Epoch timestamp, or a date in the format <" + DATE_STRING_FORMAT + "> or <" + DATE_STRING_NO_ZONE_FORMAT + "> (ZZ is a timezone offset), found: '" + dateString + "'.", errDesc(i));
-                    }
+        case "truncate_relative":
+            // Offset from the acquisition time and sub-combinator, check them and return index
+            var offset = verifyInteger(combinators, i + 1, 0, "offset", errDesc(i));
+            if (offset.error) {
+                return offset;
+            }
 
-                    time = date.unix();
-                    nextCombinator = closeIndex + 1;
-                } else {
-                    return new VerificationError("Expected date in the form of a UNIX Epoch timestamp, or a date in the format <" + DATE_STRING_FORMAT + "> or <" + DATE_STRING_NO_ZONE_FORMAT + "> (ZZ is a timezone offset), found: '" + time + "'.", errDesc(i));
-                }
+            // Check sub-combinator
+            var res = verifyCombinator(combinators, seenObservables, i + 2);
+            if (res.error) {
+                return addToErrStack(res, i);
+            } else {
+                return res;
             }
 
-            if (time < 0 || time > Math.pow(2, 32) - 1) {
-                // Timestamp is outside of u32 range
-                return new VerificationError("Expected unsigned 32-bit unix timestamp, found: '" + time + "'.", errDesc(i));
+        case "schedule_relative":
+            // Start offset, period, count and sub-combinator, check them and return index
+            var checks = [[0, "start offset"], [0, "period"], [1, "count of periods"]];
+            for (var j = 0; j < checks.length; j++) {
+                var checked = verifyInteger(combinators, i + 1 + j, checks[j][0], checks[j][1], errDesc(i));
+                if (checked.error) {
+                    return checked;
+                }
             }
 
             // Check sub-combinator
-            var res = verifyCombinator(combinators, seenObservables, nextCombinator);
+            var res = verifyCombinator(combinators, seenObservables, i + 4);
             if (res.error) {
                 return addToErrStack(res, i);
             } else {
@@ -447,8 +566,34 @@ function verifyCombinator(combinators, seenObservables, i) {
 
                 var name = combinators[i + 1];
                 var address = combinators[i + 2];
-                if (!web3.utils.isAddress(address)) {
-                    return new VerificationError("Expected a valid address, found: '" + address + "'.", errDesc(i));
+                var arbiter = address;
+                if (isDisputable(address)) {
+                    // Disputable observable, check dispute window and resolver address
+                    var dispute = parseDisputable(address);
+                    if (!Number.isInteger(dispute.window) || dispute.window < 0) {
+                        return new VerificationError("Expected a non-negative dispute window, found: '" + address + "'.", errDesc(i));
+                    }
+                    if (!web3.utils.isAddress(dispute.resolver)) {
+                        return new VerificationError("Expected a valid address, found: '" + dispute.resolver + "'.", errDesc(i));
+                    }
+                    arbiter = dispute.arbiter;
+                }
+
+                if (isQuorum(arbiter)) {
+                    // Quorum observable, check threshold and arbiter addresses
+                    var quorum = parseQuorum(arbiter);
+                    var invalidArbiter = quorum.arbiters.find(arbiter => !web3.utils.isAddress(arbiter));
+                    if (invalidArbiter !== undefined) {
+                        return new VerificationError("Expected a valid address, found: '" + invalidArbiter + "'.", errDesc(i));
+                    }
+                    if (new Set(quorum.arbiters.map(arbiter => arbiter.toLowerCase())).size != quorum.arbiters.length) {
+                        return new VerificationError("Expected distinct quorum arbiter addresses, found: '" + address + "'.", errDesc(i));
+                    }
+                    if (!Number.isInteger(quorum.threshold) || quorum.threshold < 1 || quorum.threshold > quorum.arbiters.length) {
+                        return new VerificationError("Expected a quorum threshold between one and the number of arbiters, found: '" + address + "'.", errDesc(i));
+                    }
+                } else if (!web3.utils.isAddress(arbiter)) {
+                    return new VerificationError("Expected a valid address, found: '" + arbiter + "'.", errDesc(i));
                 }
 
 
@@ -471,6 +616,69 @@ function verifyCombinator(combinators, seenObservables, i) {
     }
 }
 
+// Verifies the time at the given index in the given list of combinator atoms, which is either a unix timestamp or a date in
+// angle-brackets, and returns it with the index of its last atom.
+function verifyTime(combinators, i, description) {
+    if (combinators.length <= i) {
+        return new VerificationError("Expected a unix timestamp, found end of contract.", description);
+    }
+
+    // Check timestamp
+    var time = parseInt(combinators[i]);
+    var endIndex = i;
+
+    if (isNaN(time)) {
+        // Date provided in < [date] > format
+        if (combinators[i].indexOf("<") != -1) {
+            // Time is pretty date string, find closing bracket
+            var closeIndex = combinators.slice(i).findIndex(elem => elem.indexOf(">") != -1) + i;
+
+            if (closeIndex < i) {
+                return new VerificationError("Angle-brackets surrounding date not closed.", description);
+            }
+
+            var dateString = combinators.slice(i, closeIndex + 1).join(" ").slice(1, -1);
+            var date = moment.utc(dateString, [DATE_STRING_FORMAT, DATE_STRING_NO_ZONE_FORMAT], true);
+            
+            if (!date.isValid()) {
+                // Date is invalid
+                return new VerificationError("Expected date in the form of a UNIX Epoch timestamp, or a date in the format <" + DATE_STRING_FORMAT + "> or <" + DATE_STRING_NO_ZONE_FORMAT + "> (ZZ is a timezone offset), found: '" + dateString + "'.", description);
+            }
+
+            time = date.unix();
+            endIndex = closeIndex;
+        } else {
+            return new VerificationError("Expected date in the form of a UNIX Epoch timestamp, or a date in the format <" + DATE_STRING_FORMAT + "> or <" + DATE_STRING_NO_ZONE_FORMAT + "> (ZZ is a timezone offset), found: '" + time + "'.", description);
+        }
+    }
+
+    if (time < 0 || time > Math.pow(2, 32) - 1) {
+        // Timestamp is outside of u32 range
+        return new VerificationError("Expected unsigned 32-bit unix timestamp, found: '" + time + "'.", description);
+    }
+
+    return {
+        time: time,
+        endIndex: endIndex
+    };
+}
+
+// Verifies the integer at the given index in the given list of combinator atoms is at least the given minimum.
+function verifyInteger(combinators, i, minimum, name, description) {
+    if (combinators.length <= i) {
+        return new VerificationError("Expected " + name + ", found end of contract.", description);
+    }
+
+    var value = Number(combinators[i]);
+    if (!Number.isSafeInteger(value) || value < minimum) {
+        return new VerificationError("Expected " + name + " of at least " + minimum + ", found: '" + combinators[i] + "'.", description);
+    }
+
+    return {
+        value: value
+    };
+}
+
 // Serializes an address into 4 integers
 export function serializeAddress(address) {
     if (!web3) {
@@ -573,6 +781,71 @@ export function deserializeObsEntries(obsEntries) {
     return res;
 }
 
+// Deserializes the submissions of an observable into its threshold, and the arbiter and Optional value of each submission
+export function deserializeObsSubmissions(obsSubmissions) {
+    var res = { threshold: parseInt(obsSubmissions[0]), submissions: [] };
+
+    for (var i = 2; i < obsSubmissions.length; i += 5) {
+        var address = deserializeAddress(obsSubmissions.slice(i, i + 4));
+
+        var value = undefined;
+        if (obsSubmissions[i + 4] != -1) {
+            value = obsSubmissions[i + 5];
+            i++;
+        }
+
+        res.submissions.push({ arbiter: address, value: new Option(value) });
+    }
+
+    return res;
+}
+
+// Deserializes the dispute window of an observable into its window, resolver address and the status, time set and Optional
+// value of its value (undefined if the observable is not disputable)
+export function deserializeObsDispute(obsDispute) {
+    if (obsDispute.length == 0) {
+        return undefined;
+    }
+
+    var res = {
+        window: parseInt(obsDispute[0]),
+        resolver: deserializeAddress(obsDispute.slice(1, 5)),
+        status: parseInt(obsDispute[5]),
+        setTime: undefined,
+        value: new Option(undefined)
+    };
+    if (res.status != -1) {
+        res.setTime = parseInt(obsDispute[6]);
+        res.value = new Option(parseInt(obsDispute[7]));
+    }
+
+    return res;
+}
+
+// Returns true if the given arbiter of an observable is a quorum, written as the threshold followed by the arbiter addresses,
+// separated by colons (e.g. 2:0x...:0x...:0x...)
+export function isQuorum(arbiter) {
+    return arbiter.indexOf(":") != -1;
+}
+
+// Parses a quorum of arbiters into its threshold and arbiter addresses
+export function parseQuorum(quorum) {
+    var parts = quorum.split(":");
+    return { threshold: Number(parts[0]), arbiters: parts.slice(1) };
+}
+
+// Returns true if the given arbiter of an observable is disputable, written as the arbiter (or quorum) followed by the dispute
+// window in seconds and the resolver address, separated by slashes (e.g. 0x.../3600/0x...)
+export function isDisputable(arbiter) {
+    return arbiter.indexOf("/") != -1;
+}
+
+// Parses a disputable arbiter into its arbiter (or quorum), dispute window and resolver address
+export function parseDisputable(arbiter) {
+    var parts = arbiter.split("/");
+    return { arbiter: parts[0], window: Number(parts[1]), resolver: parts[2] };
+}
+
 // Converts a name string into an array ([N, char0, char1..., charN])
 export function serializeName(name) {
     var res = Array.from(name).map(c => c.charCodeAt(0));
@@ -669,6 +942,19 @@ export async function getUseGas(contract, caller) {
     });
 }
 
+// Gets the gas stipend forwarded with Ether payments.
+export async function getGasStipend(contract, caller) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    return contract.methods.get_gas_stipend().call({ from: caller }).then(res => {
+        return res.returnValue0;
+    }, err => {
+        return Promise.reject("Getting gas stipend failed: " + err.toString());
+    });
+}
+
 // Gets the last-updated time.
 export async function getLastUpdated(contract, caller) {
     if (!web3) {
@@ -708,6 +994,32 @@ export async function getObsEntries(contract, caller) {
     });
 }
 
+// Gets the threshold and submissions of the given observable of the contract.
+export async function getObsSubmissions(contract, caller, index) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    return contract.methods.get_obs_submissions(index).call({ from: caller }).then(res => {
+        return deserializeObsSubmissions(res.returnValue0);
+    }, err => {
+        return Promise.reject("Getting observable-submissions failed: " + err.toString());
+    });
+}
+
+// Gets the dispute window and the status of the value of the given observable of the given contract.
+export async function getObsDispute(contract, caller, index) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    return contract.methods.get_obs_dispute(index).call({ from: caller }).then(res => {
+        return deserializeObsDispute(res.returnValue0);
+    }, err => {
+        return Promise.reject("Getting observable-dispute failed: " + err.toString());
+    });
+}
+
 // Gets the acquisition times of the given contract.
 export async function getAcquisitionTimes(contract, caller) {
     if (!web3) {
@@ -747,6 +1059,78 @@ export async function setObsValue(contract, caller, index, value) {
     });
 }
 
+// Hashes an observable report as the arbiter signs it, which can be submitted by anyone within an hour of the timestamp.
+// The observable index is signed, so the report can only be submitted for that observable.
+export function hashObsReport(index, name, value, timestamp, contractAddress) {
+    if (!web3) {
+        throw "Web3 connection not initialised.";
+    }
+
+    return web3.utils.soliditySha3(
+        { type: "string", value: name },
+        { type: "uint64", value: index.toString() },
+        { type: "int64", value: value.toString() },
+        { type: "uint64", value: timestamp.toString() },
+        { type: "address", value: contractAddress }
+    );
+}
+
+// Signs an observable report as the given arbiter, with eth_sign.
+export async function signObsReport(arbiter, index, name, value, timestamp, contractAddress) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    if (!isValidScaleValue(value)) {
+        return Promise.reject("The given value is not a valid 64-bit signed integer.")
+    }
+
+    return web3.eth.sign(hashObsReport(index, name, value, timestamp, contractAddress), arbiter).catch(err => {
+        return Promise.reject("Signing observable report failed: " + err.toString());
+    });
+}
+
+// Submits an observable report signed by the observable's arbiter, on their behalf.
+export async function submitObsReport(contract, caller, index, value, timestamp, signature) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    if (!isValidScaleValue(value)) {
+        return Promise.reject("The given value is not a valid 64-bit signed integer.")
+    }
+
+    return contract.methods.submit_obs_report(index, value, timestamp, signature).send({ from: caller }).catch(err => {
+        return Promise.reject("Submitting observable report failed: " + err.toString());
+    });
+}
+
+// Flags the value of a disputable observable on the given contract, during its dispute window.
+export async function flagObsValue(contract, caller, index) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    return contract.methods.flag_obs_value(index).send({ from: caller }).catch(err => {
+        return Promise.reject("Flagging observable-value failed: " + err.toString());
+    });
+}
+
+// Confirms or overrides the flagged value of a disputable observable on the given contract.
+export async function resolveObsValue(contract, caller, index, value) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    if (!isValidScaleValue(value)) {
+        return Promise.reject("The given value is not a valid 64-bit signed integer.")
+    }
+
+    return contract.methods.resolve_obs_value(index, value).send({ from: caller }).catch(err => {
+        return Promise.reject("Resolving observable-value failed: " + err.toString());
+    });
+}
+
 // Acquires the contract.
 export async function acquireContract(contract, caller) {
     if (!web3) {
@@ -815,6 +1199,52 @@ export async function withdraw(contract, caller, amount) {
     });
 }
 
+// Withdraws the given amount of the given asset (the zero address is Ether) from the given contract, paying it to the recipient.
+export async function withdrawTo(contract, caller, asset, recipient, amount) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    return contract.methods.withdraw_to(asset, recipient, amount).send({ from: caller }).catch(err => {
+        return Promise.reject("Withdrawing failed: " + err.toString());
+    });
+}
+
+// Gets the permissions the given party has approved for the given operator (0 if none).
+export async function getOperatorPermissions(contract, caller, party, operator) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    return contract.methods.get_operator_permissions(party, operator).call({ from: caller }).then(res => {
+        return res.returnValue0;
+    }, err => {
+        return Promise.reject("Getting operator permissions failed: " + err.toString());
+    });
+}
+
+// Approves the given operator to act for the caller with the given permissions (a combination of the OPERATOR_ flags).
+export async function approveOperator(contract, caller, operator, permissions) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    return contract.methods.approve_operator(operator, permissions).send({ from: caller }).catch(err => {
+        return Promise.reject("Approving operator failed: " + err.toString());
+    });
+}
+
+// Revokes the given operator's approval to act for the caller.
+export async function revokeOperator(contract, caller, operator) {
+    if (!web3) {
+        return Promise.reject("Web3 connection not initialised.");
+    }
+
+    return contract.methods.revoke_operator(operator).send({ from: caller }).catch(err => {
+        return Promise.reject("Revoking operator failed: " + err.toString());
+    });
+}
+
 // Deserializes the serialized combinator contract definition.
 export function deserializeCombinatorContract(i, serializedCombinatorContract) {
     if (!serializedCombinatorContract || serializedCombinatorContract.length == 0) {
@@ -829,6 +1259,11 @@ export function deserializeCombinatorContract(i, serializedCombinatorContract) {
             return new DeserializeResult(combinator, i);
         }
 
+        case "one_asset": {
+            var token = deserializeAddress(serializedCombinatorContract.slice(i + 1, i + 5));
+            return new DeserializeResult(combinator + " <" + token + ">", i + 4);
+        }
+
         case "and":
         case "or":
         case "then": {
@@ -862,18 +1297,69 @@ export function deserializeCombinatorContract(i, serializedCombinatorContract) {
             return new DeserializeResult(contract, subRes.getEndIndex());
         }
 
+        case "schedule": {
+            let contract = combinator + " <";
+            contract += moment.utc(serializedCombinatorContract[i + 1], UNIX_FORMAT, true).format(DATE_STRING_FORMAT) + "> ";
+            contract += serializedCombinatorContract[i + 2] + " " + serializedCombinatorContract[i + 3] + " ";
+
+            let subRes = deserializeCombinatorContract(i + 4, serializedCombinatorContract);
+
+            contract += subRes.getContract();
+            return new DeserializeResult(contract, subRes.getEndIndex());
+        }
+
+        case "truncate_relative": {
+            let contract = combinator + " " + serializedCombinatorContract[i + 1] + " ";
+
+            let subRes = deserializeCombinatorContract(i + 2, serializedCombinatorContract);
+
+            contract += subRes.getContract();
+            return new DeserializeResult(contract, subRes.getEndIndex());
+        }
+
+        case "schedule_relative": {
+            let contract = combinator + " " + serializedCombinatorContract.slice(i + 1, i + 4).join(" ") + " ";
+
+            let subRes = deserializeCombinatorContract(i + 4, serializedCombinatorContract);
+
+            contract += subRes.getContract();
+            return new DeserializeResult(contract, subRes.getEndIndex());
+        }
+
         case "scale": {
             let contract = combinator + " ";
             var nextIndex;
 
+            // A disputable observable's dispute window and resolver address precede the observable
+            var disputeSuffix = "";
+            if (serializedCombinatorContract[i + 1] == 3) {
+                var resolver = deserializeAddress(serializedCombinatorContract.slice(i + 3, i + 7));
+                disputeSuffix = "/" + serializedCombinatorContract[i + 2] + "/" + resolver;
+                i += 6;
+            }
+
             if (serializedCombinatorContract[i + 1] == 0) {
                 var address = deserializeAddress(serializedCombinatorContract.slice(i + 2, i + 6));
 
                 var nameLen = parseInt(serializedCombinatorContract[i + 6]);
                 var name = deserializeName(serializedCombinatorContract.slice(i + 7, i + 7 + nameLen));
 
-                contract += name + " <" + address + "> ";
+                contract += name + " <" + address + disputeSuffix + "> ";
                 nextIndex = i + 7 + nameLen;
+            } else if (serializedCombinatorContract[i + 1] == 2) {
+                var threshold = parseInt(serializedCombinatorContract[i + 2]);
+                var arbiterCount = parseInt(serializedCombinatorContract[i + 3]);
+                var arbiters = [];
+                for (var j = 0; j < arbiterCount; j++) {
+                    arbiters.push(deserializeAddress(serializedCombinatorContract.slice(i + 4 + 4 * j, i + 8 + 4 * j)));
+                }
+
+                var nameIndex = i + 4 + 4 * arbiterCount;
+                var nameLen = parseInt(serializedCombinatorContract[nameIndex]);
+                var name = deserializeName(serializedCombinatorContract.slice(nameIndex + 1, nameIndex + 1 + nameLen));
+
+                contract += name + " <" + [threshold].concat(arbiters).join(":") + disputeSuffix + "> ";
+                nextIndex = nameIndex + 1 + nameLen;
             } else if (serializedCombinatorContract[i + 1] == 1) {
                 contract += serializedCombinatorContract[i + 2] + " ";
                 nextIndex = i + 3;
@@ -1,6 +1,6 @@
 import assert from "assert";
 
-import { serializeCombinatorContract, ObservableEntry, Option, deserializeAcquisitionTimes, deserializeOrChoices, deserializeObsEntries, deserializeAddress, deserializeName } from "../src/js/contract-utils.mjs";
+import { serializeCombinatorContract, ObservableEntry, Option, deserializeAcquisitionTimes, deserializeOrChoices, deserializeObsEntries, deserializeAddress, deserializeName, OPERATOR_CHOICES, OPERATOR_ACQUIRE, signObsReport, deserializeObsSubmissions, deserializeObsDispute } from "../src/js/contract-utils.mjs";
 import { uninvolved, holder, counterParty, web3, getUnixTime, deploy } from "./common";
 
 describe('Contract integration tests', function() {
@@ -95,7 +95,7 @@ describe('Contract integration tests', function() {
                 return contract.methods.get_balance(true).call({ from: holder.address }).then(function(balance) {
                     return contract.methods.withdraw(withdrawal).send({ from: holder.address }).then(function() {
                         return contract.methods.get_balance(true).call({ from: holder.address }).then(function(newBalance) {    
-                            assert.equal(newBalance.returnValue0, balance.returnValue0 - withdrawal - 2300);
+                            assert.equal(newBalance.returnValue0, balance.returnValue0 - withdrawal);
                         });
                     });
                 });
@@ -110,7 +110,7 @@ describe('Contract integration tests', function() {
                 return contract.methods.get_balance(false).call({ from: counterParty.address }).then(function(balance) {
                     return contract.methods.withdraw(withdrawal).send({ from: counterParty.address }).then(function() {
                         return contract.methods.get_balance(false).call({ from: counterParty.address }).then(function(newBalance) {    
-                            assert.equal(newBalance.returnValue0, balance.returnValue0 - withdrawal - 2300);
+                            assert.equal(newBalance.returnValue0, balance.returnValue0 - withdrawal);
                         });
                     });
                 });
@@ -372,4 +372,144 @@ describe('Contract integration tests', function() {
             });
         }).timeout(5000);
     });
-});
\ No newline at end of file
+
+    // Tests for operators acting on behalf of the holder or counter-party
+    describe('Operator tests', function() {
+        it('Lets an approved operator set or choices and acquire for the holder', function() {
+            return deploy("or zero one").then(function(contract) {
+                return contract.methods.approve_operator(uninvolved.address, OPERATOR_CHOICES | OPERATOR_ACQUIRE).send({ from: holder.address }).then(function(receipt) {
+                    assert.equal(receipt.events.OperatorApproved.returnValues.permissions, OPERATOR_CHOICES | OPERATOR_ACQUIRE);
+
+                    return contract.methods.set_or_choice(0, false).send({ from: uninvolved.address }).then(function() {
+                        return contract.methods.acquire().send({ from: uninvolved.address }).then(function() {
+                            return contract.methods.get_balance(true).call({ from: holder.address }).then(function(res) {
+                                assert.equal(res.returnValue0, 1);
+                            });
+                        });
+                    });
+                });
+            });
+        });
+
+        it('Does not let a revoked operator acquire', function() {
+            return deploy("one").then(function(contract) {
+                return contract.methods.approve_operator(uninvolved.address, OPERATOR_ACQUIRE).send({ from: holder.address }).then(function() {
+                    return contract.methods.revoke_operator(uninvolved.address).send({ from: holder.address }).then(function() {
+                        return contract.methods.get_operator_permissions(holder.address, uninvolved.address).call({ from: holder.address }).then(function(res) {
+                            assert.equal(res.returnValue0, 0);
+
+                            return contract.methods.acquire().send({ from: uninvolved.address }).then(function() {
+                                assert.fail("Revoked operator acquired the contract.");
+                            }, function() {});
+                        });
+                    });
+                });
+            });
+        });
+    });
+
+    describe('Observable report tests', function() {
+        it('Lets anyone submit a report signed by the arbiter', function() {
+            return deploy("scale price " + counterParty.address + " one").then(function(contract) {
+                var timestamp = getUnixTime() - 10;
+                return signObsReport(counterParty.address, 0, "price", 5, timestamp, contract.options.address).then(function(signature) {
+                    return contract.methods.submit_obs_report(0, 5, timestamp, signature).send({ from: uninvolved.address }).then(function() {
+                        return contract.methods.get_obs_entries().call({ from: holder.address }).then(function(res) {
+                            assert.deepEqual(deserializeObsEntries(res.returnValue0), [new ObservableEntry(counterParty.address, 5, "price", 0)]);
+                        });
+                    });
+                });
+            });
+        });
+
+        it('Does not accept a report signed by someone other than the arbiter', function() {
+            return deploy("scale price " + counterParty.address + " one").then(function(contract) {
+                var timestamp = getUnixTime() - 10;
+                return signObsReport(holder.address, 0, "price", 5, timestamp, contract.options.address).then(function(signature) {
+                    return contract.methods.submit_obs_report(0, 5, timestamp, signature).send({ from: uninvolved.address }).then(function() {
+                        assert.fail("Report not signed by the arbiter was accepted.");
+                    }, function() {});
+                });
+            });
+        });
+
+        it('Does not accept a report for another observable of the same name and arbiter', function() {
+            var observable = "scale price " + counterParty.address + " one";
+            return deploy("and " + observable + " " + observable).then(function(contract) {
+                var timestamp = getUnixTime() - 10;
+                return signObsReport(counterParty.address, 0, "price", 5, timestamp, contract.options.address).then(function(signature) {
+                    return contract.methods.submit_obs_report(1, 5, timestamp, signature).send({ from: uninvolved.address }).then(function() {
+                        assert.fail("Report for another observable was accepted.");
+                    }, function() {});
+                });
+            });
+        });
+    });
+
+    describe('Quorum observable tests', function() {
+        it('Resolves to the median once the threshold is reached, and returns each submission', function() {
+            var quorum = ["2", uninvolved.address, counterParty.address, holder.address].join(":");
+            return deploy("scale price " + quorum + " one").then(function(contract) {
+                return contract.methods.set_obs_value(0, 3).send({ from: uninvolved.address }).then(function() {
+                    return contract.methods.set_obs_value(0, 8).send({ from: holder.address }).then(function() {
+                        return contract.methods.get_obs_submissions(0).call({ from: holder.address }).then(function(res) {
+                            assert.deepEqual(deserializeObsSubmissions(res.returnValue0), {
+                                threshold: 2,
+                                submissions: [
+                                    { arbiter: uninvolved.address, value: new Option(3) },
+                                    { arbiter: counterParty.address, value: new Option(undefined) },
+                                    { arbiter: holder.address, value: new Option(8) }
+                                ]
+                            });
+
+                            return contract.methods.get_obs_entries().call({ from: holder.address }).then(function(res) {
+                                var entries = deserializeObsEntries(res.returnValue0);
+                                assert.equal(entries[0].getValue().getValue(), 5);
+                            });
+                        });
+                    });
+                });
+            });
+        });
+    });
+
+    describe('Disputable observable tests', function() {
+        it('Holds a flagged value until the resolver overrides it', function() {
+            return deploy("scale price " + counterParty.address + "/3600/" + uninvolved.address + " one").then(function(contract) {
+                return contract.methods.acquire().send({ from: holder.address }).then(function() {
+                    return contract.methods.set_obs_value(0, 5).send({ from: counterParty.address }).then(function() {
+                        return contract.methods.flag_obs_value(0).send({ from: holder.address }).then(function() {
+                            return contract.methods.get_obs_dispute(0).call({ from: holder.address }).then(function(res) {
+                                var dispute = deserializeObsDispute(res.returnValue0);
+                                assert.equal(dispute.window, 3600);
+                                assert.equal(dispute.resolver, uninvolved.address);
+                                assert.equal(dispute.status, 1);
+                                assert.equal(dispute.value.getValue(), 5);
+
+                                return contract.methods.resolve_obs_value(0, 8).send({ from: uninvolved.address }).then(function() {
+                                    return contract.methods.update().send({ from: holder.address }).then(function() {
+                                        return contract.methods.get_balance(true).call({ from: holder.address }).then(function(res) {
+                                            assert.equal(res.returnValue0, 8);
+                                        });
+                                    });
+                                });
+                            });
+                        });
+                    });
+                });
+            });
+        });
+
+        it('Does not pay out a value during its dispute window', function() {
+            return deploy("scale price " + counterParty.address + "/3600/" + uninvolved.address + " one").then(function(contract) {
+                return contract.methods.acquire().send({ from: holder.address }).then(function() {
+                    return contract.methods.set_obs_values([0], [5], true).send({ from: counterParty.address }).then(function() {
+                        return contract.methods.get_balance(true).call({ from: holder.address }).then(function(res) {
+                            assert.equal(res.returnValue0, 0);
+                        });
+                    });
+                });
+            });
+        });
+    });
+});
@@ -154,6 +154,33 @@ describe('Contract utility tests', function() {
             assert.deepEqual(contractSerialized, expectedSerialized);
         });
 
+        it('Correctly serializes a schedule combinator', function() {
+            var contractSerialized = Utils.serializeCombinatorContract("schedule 123456789 3600 12 one");
+            var expectedSerialized = [10, 123456789, 3600, 12, 1];
+            assert.deepEqual(contractSerialized, expectedSerialized);
+        });
+
+        it('Correctly serializes a schedule combinator with a shorthand date', function() {
+            var time = "01/02/2003 12:34:56";
+            var contractSerialized = Utils.serializeCombinatorContract("schedule <" + time + "> 60 2 one");
+            var expectedSerialized = [10, moment.utc(time, Utils.DATE_STRING_NO_ZONE_FORMAT, true).unix(), 60, 2, 1];
+            assert.deepEqual(contractSerialized, expectedSerialized);
+        });
+
+        it('Correctly serializes a relative truncate combinator', function() {
+            assert.deepEqual(Utils.serializeCombinatorContract("truncate_relative 3600 one"), [11, 3600, 1]);
+        });
+
+        it('Correctly serializes a relative schedule combinator', function() {
+            assert.deepEqual(Utils.serializeCombinatorContract("schedule_relative 60 3600 12 one"), [12, 60, 3600, 12, 1]);
+        });
+
+        it('Correctly serializes a one-asset combinator', function() {
+            var contractSerialized = Utils.serializeCombinatorContract("one_asset " + uninvolved.address);
+            var expectedSerialized = [13, ...Utils.serializeAddress(uninvolved.address)];
+            assert.deepEqual(contractSerialized, expectedSerialized);
+        });
+
         it('Correctly serializes an anytime combinator', function() {
             assert.deepEqual(Utils.serializeCombinatorContract("anytime one"), [9, 1]);
         });
@@ -205,6 +232,27 @@ describe('Contract utility tests', function() {
             assert.equal(Utils.deserializeCombinatorContract(0, serialized).getContract(), "scale namE013 <" + uninvolved.address + "> one");
         });
 
+        it('Correctly deserializes a scale combinator with a quorum observable', function() {
+            var quorum = ["2", uninvolved.address, holder.address, counterParty.address].join(":");
+            var serialized = Utils.serializeCombinatorContract("scale price " + quorum + " one");
+
+            assert.equal(Utils.deserializeCombinatorContract(0, serialized).getContract(), "scale price <" + quorum + "> one");
+        });
+
+        it('Correctly deserializes a scale combinator with a disputable observable', function() {
+            var arbiter = counterParty.address + "/3600/" + uninvolved.address;
+            var serialized = Utils.serializeCombinatorContract("scale price " + arbiter + " one");
+
+            assert.equal(Utils.deserializeCombinatorContract(0, serialized).getContract(), "scale price <" + arbiter + "> one");
+        });
+
+        it('Correctly deserializes a scale combinator with a disputable quorum observable', function() {
+            var arbiter = ["2", uninvolved.address, holder.address, counterParty.address].join(":") + "/60/" + uninvolved.address;
+            var serialized = Utils.serializeCombinatorContract("scale price " + arbiter + " one");
+
+            assert.equal(Utils.deserializeCombinatorContract(0, serialized).getContract(), "scale price <" + arbiter + "> one");
+        });
+
         it('Correctly deserializes a truncate combinator', function() {
             var contract = "truncate 123456789 one";
             var serialized = Utils.serializeCombinatorContract(contract);
@@ -215,6 +263,36 @@ describe('Contract utility tests', function() {
             );
         });
 
+        it('Correctly deserializes a schedule combinator', function() {
+            var contract = "schedule 123456789 3600 12 one";
+            var serialized = Utils.serializeCombinatorContract(contract);
+
+            assert.equal(
+                Utils.deserializeCombinatorContract(0, serialized).getContract(),
+                "schedule <" + moment.utc(123456789, Utils.UNIX_FORMAT, true).format(Utils.DATE_STRING_FORMAT) + "> 3600 12 one"
+            );
+        });
+
+        it('Correctly deserializes a relative truncate combinator', function() {
+            var contract = "truncate_relative 3600 one";
+            var serialized = Utils.serializeCombinatorContract(contract);
+
+            assert.equal(Utils.deserializeCombinatorContract(0, serialized).getContract(), contract);
+        });
+
+        it('Correctly deserializes a relative schedule combinator', function() {
+            var contract = "schedule_relative 60 3600 12 one";
+            var serialized = Utils.serializeCombinatorContract(contract);
+
+            assert.equal(Utils.deserializeCombinatorContract(0, serialized).getContract(), contract);
+        });
+
+        it('Correctly deserializes a one-asset combinator', function() {
+            var serialized = Utils.serializeCombinatorContract("and one_asset " + uninvolved.address + " one");
+
+            assert.equal(Utils.deserializeCombinatorContract(0, serialized).getContract(), "and one_asset <" + uninvolved.address + "> one");
+        });
+
         it('Correctly deserializes an anytime combinator', function() {
             var contract = "anytime one";
             var serialized = Utils.serializeCombinatorContract(contract);
@@ -299,6 +377,36 @@ describe('Contract utility tests', function() {
             assert.notEqual(res.error, undefined);
         });
 
+        it('Verifies a contract with a schedule combinator', function() {
+            var res = Utils.verifyContract("schedule <01/02/2003 12:34:56> 3600 12 one");
+
+            assert.equal(res.error, undefined);
+        });
+
+        it('Does not verify a schedule combinator with no periods', function() {
+            var res = Utils.verifyContract("schedule 123456789 3600 0 one");
+
+            assert.notEqual(res.error, undefined);
+        });
+
+        it('Verifies a contract with relative truncate and schedule combinators', function() {
+            var res = Utils.verifyContract("truncate_relative 86400 schedule_relative 0 3600 12 one");
+
+            assert.equal(res.error, undefined);
+        });
+
+        it('Does not verify a relative truncate combinator with a negative offset', function() {
+            var res = Utils.verifyContract("truncate_relative -1 one");
+
+            assert.notEqual(res.error, undefined);
+        });
+
+        it('Does not verify a one-asset combinator with an invalid token address', function() {
+            var res = Utils.verifyContract("one_asset 0x123");
+
+            assert.notEqual(res.error, undefined);
+        });
+
         it('Does not verify a contract with an invalid arbiter address', function() {
             var res = Utils.verifyContract("scale name " + uninvolved.address + "123 one");
 
@@ -322,6 +430,42 @@ describe('Contract utility tests', function() {
 
             assert.equal(res.error, undefined);
         });
+
+        it('Verifies a contract with a quorum observable', function() {
+            var res = Utils.verifyContract("scale name 2:" + counterParty.address + ":" + uninvolved.address + " one");
+
+            assert.equal(res.error, undefined);
+        });
+
+        it('Does not verify a contract with a quorum threshold above its number of arbiters', function() {
+            var res = Utils.verifyContract("scale name 3:" + counterParty.address + ":" + uninvolved.address + " one");
+
+            assert.notEqual(res.error, undefined);
+        });
+
+        it('Does not verify a contract with a repeated quorum arbiter', function() {
+            var res = Utils.verifyContract("scale name 1:" + counterParty.address + ":" + counterParty.address + " one");
+
+            assert.notEqual(res.error, undefined);
+        });
+
+        it('Verifies a contract with a disputable observable', function() {
+            var res = Utils.verifyContract("scale name " + counterParty.address + "/3600/" + uninvolved.address + " one");
+
+            assert.equal(res.error, undefined);
+        });
+
+        it('Does not verify a contract with a negative dispute window', function() {
+            var res = Utils.verifyContract("scale name " + counterParty.address + "/-1/" + uninvolved.address + " one");
+
+            assert.notEqual(res.error, undefined);
+        });
+
+        it('Does not verify a contract with an invalid resolver address', function() {
+            var res = Utils.verifyContract("scale name " + counterParty.address + "/3600/0x1234 one");
+
+            assert.notEqual(res.error, undefined);
+        });
     });
 
     describe('Contract interaction tests', function() {
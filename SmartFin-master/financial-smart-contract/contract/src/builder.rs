@@ -0,0 +1,602 @@
+extern crate pwasm_std;
+
+use pwasm_std::{ Box, Vec, types::{ Address } };
+use combinators::Combinator;
+use storage::{ address_to_i64, i64_to_address };
+
+// The reason a definition vector is rejected when it is truncated, has trailing values or has invalid fields
+const INVALID_DEFINITION: &str = "Provided combinator contract not valid.";
+
+// A combinator contract definition, built with the functions below (e.g. truncate(date, or(one(), zero())))
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Definition {
+    Zero,
+    One,
+    OneAsset(Address),
+    And(Box<Definition>, Box<Definition>),
+    Or(Box<Definition>, Box<Definition>),
+    Truncate(u64, Box<Definition>),
+    Scale(ScaleValue, Box<Definition>),
+    Give(Box<Definition>),
+    Then(Box<Definition>, Box<Definition>),
+    Get(Box<Definition>),
+    Anytime(Box<Definition>),
+    // First period date, period length, period count, sub-definition
+    Schedule(u64, u64, u64, Box<Definition>),
+    // Horizon offset from the acquisition time, sub-definition
+    TruncateRelative(u64, Box<Definition>),
+    // First period offset from the acquisition time, period length, period count, sub-definition
+    ScheduleRelative(u64, u64, u64, Box<Definition>)
+}
+
+// The scale value of a scale combinator, either provided in the definition or by an observable
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum ScaleValue {
+    Provided(i64),
+    Observable(Observable),
+    Quorum(Quorum),
+    // An observable or quorum observable whose values can be disputed
+    Disputable(Dispute, Box<ScaleValue>)
+}
+
+// An observable, whose value is provided by the arbiter when the contract is acquired or updated
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Observable {
+    // The address allowed to provide the observable's value
+    pub arbiter: Address,
+
+    // The name of the observable, serialized as one character code per element
+    pub name: Vec<i64>
+}
+
+// Declares an observable with the given arbiter and name
+pub fn observable(arbiter: Address, name: &str) -> Observable {
+    Observable {
+        arbiter,
+        name: name.chars().map(|c| c as i64).collect()
+    }
+}
+
+// A quorum observable, whose value is the median of the values provided by the first of its arbiters to reach the threshold
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Quorum {
+    // The addresses allowed to provide a value for the observable
+    pub arbiters: Vec<Address>,
+
+    // The number of values provided before the observable's value is resolved
+    pub threshold: u64,
+
+    // The name of the observable, serialized as one character code per element
+    pub name: Vec<i64>
+}
+
+// Declares a quorum observable with the given arbiters, threshold and name
+pub fn quorum(arbiters: Vec<Address>, threshold: u64, name: &str) -> Quorum {
+    Quorum {
+        arbiters,
+        threshold,
+        name: name.chars().map(|c| c as i64).collect()
+    }
+}
+
+// The dispute window of an observable, during which either party can flag its value until the resolver confirms or overrides it
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Dispute {
+    // How long after being set the observable's value can be flagged, in seconds
+    pub window: u64,
+
+    // The address allowed to confirm or override a flagged value
+    pub resolver: Address
+}
+
+// Declares a dispute window of the given length and resolver
+pub fn dispute(window: u64, resolver: Address) -> Dispute {
+    Dispute {
+        window,
+        resolver
+    }
+}
+
+// The zero combinator
+pub fn zero() -> Definition {
+    Definition::Zero
+}
+
+// The one combinator (one Wei)
+pub fn one() -> Definition {
+    Definition::One
+}
+
+// The one combinator of the given ERC-20 token asset (the zero address is Ether)
+pub fn one_of(asset: Address) -> Definition {
+    Definition::OneAsset(asset)
+}
+
+// The and combinator
+pub fn and(sub_combinator0: Definition, sub_combinator1: Definition) -> Definition {
+    Definition::And(Box::new(sub_combinator0), Box::new(sub_combinator1))
+}
+
+// The or combinator
+pub fn or(sub_combinator0: Definition, sub_combinator1: Definition) -> Definition {
+    Definition::Or(Box::new(sub_combinator0), Box::new(sub_combinator1))
+}
+
+// The truncate combinator, with the given horizon
+pub fn truncate(time: u64, sub_combinator: Definition) -> Definition {
+    Definition::Truncate(time, Box::new(sub_combinator))
+}
+
+// The scale combinator, with the given scale value
+pub fn scale(scale_value: i64, sub_combinator: Definition) -> Definition {
+    Definition::Scale(ScaleValue::Provided(scale_value), Box::new(sub_combinator))
+}
+
+// The scale combinator, scaled by the given observable's value
+pub fn scale_by(obs: Observable, sub_combinator: Definition) -> Definition {
+    Definition::Scale(ScaleValue::Observable(obs), Box::new(sub_combinator))
+}
+
+// The scale combinator, scaled by the given quorum observable's value
+pub fn scale_by_quorum(obs: Quorum, sub_combinator: Definition) -> Definition {
+    Definition::Scale(ScaleValue::Quorum(obs), Box::new(sub_combinator))
+}
+
+// The scale combinator, scaled by the given observable or quorum observable's value once its dispute window has passed
+pub fn scale_by_disputable(dispute: Dispute, scale_value: ScaleValue, sub_combinator: Definition) -> Definition {
+    Definition::Scale(ScaleValue::Disputable(dispute, Box::new(scale_value)), Box::new(sub_combinator))
+}
+
+// The give combinator
+pub fn give(sub_combinator: Definition) -> Definition {
+    Definition::Give(Box::new(sub_combinator))
+}
+
+// The then combinator
+pub fn then(sub_combinator0: Definition, sub_combinator1: Definition) -> Definition {
+    Definition::Then(Box::new(sub_combinator0), Box::new(sub_combinator1))
+}
+
+// The get combinator
+pub fn get(sub_combinator: Definition) -> Definition {
+    Definition::Get(Box::new(sub_combinator))
+}
+
+// The anytime combinator
+pub fn anytime(sub_combinator: Definition) -> Definition {
+    Definition::Anytime(Box::new(sub_combinator))
+}
+
+// The schedule combinator, acquiring the sub-combinator at the start of each of count periods from the given start date
+pub fn schedule(start: u64, period: u64, count: u64, sub_combinator: Definition) -> Definition {
+    Definition::Schedule(start, period, count, Box::new(sub_combinator))
+}
+
+// The truncate combinator, with a horizon of the given offset from the acquisition time
+pub fn truncate_relative(offset: u64, sub_combinator: Definition) -> Definition {
+    Definition::TruncateRelative(offset, Box::new(sub_combinator))
+}
+
+// The schedule combinator, with a first period starting at the given offset from the acquisition time
+pub fn schedule_relative(start_offset: u64, period: u64, count: u64, sub_combinator: Definition) -> Definition {
+    Definition::ScheduleRelative(start_offset, period, count, Box::new(sub_combinator))
+}
+
+// Method implementation of contract definitions
+impl Definition {
+    // Serializes the definition into the definition vector taken by the contract constructor
+    pub fn serialize(&self) -> Vec<i64> {
+        let mut serialized: Vec<i64> = Vec::new();
+        self.serialize_into(&mut serialized);
+        serialized
+    }
+
+    // Appends the serialized definition to the given vector
+    fn serialize_into(&self, serialized: &mut Vec<i64>) {
+        match self {
+            Definition::Zero => serialized.push(i64::from(Combinator::ZERO)),
+            Definition::One => serialized.push(i64::from(Combinator::ONE)),
+            Definition::OneAsset(asset) => {
+                serialized.push(i64::from(Combinator::ONE_ASSET));
+                serialized.extend_from_slice(&address_to_i64(*asset));
+            },
+            Definition::And(sub_combinator0, sub_combinator1) => {
+                serialized.push(i64::from(Combinator::AND));
+                sub_combinator0.serialize_into(serialized);
+                sub_combinator1.serialize_into(serialized);
+            },
+            Definition::Or(sub_combinator0, sub_combinator1) => {
+                serialized.push(i64::from(Combinator::OR));
+                sub_combinator0.serialize_into(serialized);
+                sub_combinator1.serialize_into(serialized);
+            },
+            Definition::Truncate(time, sub_combinator) => {
+                serialized.push(i64::from(Combinator::TRUNCATE));
+                serialized.push(*time as i64);
+                sub_combinator.serialize_into(serialized);
+            },
+            Definition::Scale(scale_value, sub_combinator) => {
+                serialized.push(i64::from(Combinator::SCALE));
+                scale_value.serialize_into(serialized);
+                sub_combinator.serialize_into(serialized);
+            },
+            Definition::Give(sub_combinator) => {
+                serialized.push(i64::from(Combinator::GIVE));
+                sub_combinator.serialize_into(serialized);
+            },
+            Definition::Then(sub_combinator0, sub_combinator1) => {
+                serialized.push(i64::from(Combinator::THEN));
+                sub_combinator0.serialize_into(serialized);
+                sub_combinator1.serialize_into(serialized);
+            },
+            Definition::Get(sub_combinator) => {
+                serialized.push(i64::from(Combinator::GET));
+                sub_combinator.serialize_into(serialized);
+            },
+            Definition::Anytime(sub_combinator) => {
+                serialized.push(i64::from(Combinator::ANYTIME));
+                sub_combinator.serialize_into(serialized);
+            },
+            Definition::Schedule(start, period, count, sub_combinator) => {
+                serialized.push(i64::from(Combinator::SCHEDULE));
+                serialized.push(*start as i64);
+                serialized.push(*period as i64);
+                serialized.push(*count as i64);
+                sub_combinator.serialize_into(serialized);
+            },
+            Definition::TruncateRelative(offset, sub_combinator) => {
+                serialized.push(i64::from(Combinator::TRUNCATE_RELATIVE));
+                serialized.push(*offset as i64);
+                sub_combinator.serialize_into(serialized);
+            },
+            Definition::ScheduleRelative(start_offset, period, count, sub_combinator) => {
+                serialized.push(i64::from(Combinator::SCHEDULE_RELATIVE));
+                serialized.push(*start_offset as i64);
+                serialized.push(*period as i64);
+                serialized.push(*count as i64);
+                sub_combinator.serialize_into(serialized);
+            }
+        }
+    }
+
+    // Deserializes a definition vector (as taken by the contract constructor)
+    pub fn deserialize(serialized: &Vec<i64>) -> Definition {
+        match Definition::try_deserialize(serialized) {
+            Ok(definition) => definition,
+            Err(message) => panic!("{}", message)
+        }
+    }
+
+    // Deserializes a definition vector, returns the reason the vector is not a valid definition instead of panicking
+    pub fn try_deserialize(serialized: &Vec<i64>) -> Result<Definition, &'static str> {
+        let (index, definition) = Definition::deserialize_at(0, serialized)?;
+        if index != serialized.len() {
+            return Err(INVALID_DEFINITION);
+        }
+        Ok(definition)
+    }
+
+    // Deserializes the definition at the given index, returns the following index and the definition
+    fn deserialize_at(i: usize, serialized: &Vec<i64>) -> Result<(usize, Definition), &'static str> {
+        Definition::check_length(i + 1, serialized)?;
+
+        Ok(match Combinator::from(serialized[i]) {
+            Combinator::ZERO => (i + 1, Definition::Zero),
+            Combinator::ONE => (i + 1, Definition::One),
+            Combinator::ONE_ASSET => (i + 5, Definition::OneAsset(Definition::deserialize_address(i + 1, serialized)?)),
+            Combinator::AND => {
+                let (i0, sub_combinator0) = Definition::deserialize_at(i + 1, serialized)?;
+                let (i1, sub_combinator1) = Definition::deserialize_at(i0, serialized)?;
+                (i1, and(sub_combinator0, sub_combinator1))
+            },
+            Combinator::OR => {
+                let (i0, sub_combinator0) = Definition::deserialize_at(i + 1, serialized)?;
+                let (i1, sub_combinator1) = Definition::deserialize_at(i0, serialized)?;
+                (i1, or(sub_combinator0, sub_combinator1))
+            },
+            Combinator::TRUNCATE => {
+                Definition::check_length(i + 2, serialized)?;
+                let (i0, sub_combinator) = Definition::deserialize_at(i + 2, serialized)?;
+                (i0, truncate(serialized[i + 1] as u64, sub_combinator))
+            },
+            Combinator::SCALE => {
+                let (i0, scale_value) = ScaleValue::deserialize_at(i + 1, serialized)?;
+                let (i1, sub_combinator) = Definition::deserialize_at(i0, serialized)?;
+                (i1, Definition::Scale(scale_value, Box::new(sub_combinator)))
+            },
+            Combinator::GIVE => {
+                let (i0, sub_combinator) = Definition::deserialize_at(i + 1, serialized)?;
+                (i0, give(sub_combinator))
+            },
+            Combinator::THEN => {
+                let (i0, sub_combinator0) = Definition::deserialize_at(i + 1, serialized)?;
+                let (i1, sub_combinator1) = Definition::deserialize_at(i0, serialized)?;
+                (i1, then(sub_combinator0, sub_combinator1))
+            },
+            Combinator::GET => {
+                let (i0, sub_combinator) = Definition::deserialize_at(i + 1, serialized)?;
+                (i0, get(sub_combinator))
+            },
+            Combinator::ANYTIME => {
+                let (i0, sub_combinator) = Definition::deserialize_at(i + 1, serialized)?;
+                (i0, anytime(sub_combinator))
+            },
+            Combinator::SCHEDULE => {
+                Definition::check_length(i + 4, serialized)?;
+                let (i0, sub_combinator) = Definition::deserialize_at(i + 4, serialized)?;
+                (i0, schedule(serialized[i + 1] as u64, serialized[i + 2] as u64, serialized[i + 3] as u64, sub_combinator))
+            },
+            Combinator::TRUNCATE_RELATIVE => {
+                Definition::check_length(i + 2, serialized)?;
+                let (i0, sub_combinator) = Definition::deserialize_at(i + 2, serialized)?;
+                (i0, truncate_relative(serialized[i + 1] as u64, sub_combinator))
+            },
+            Combinator::SCHEDULE_RELATIVE => {
+                Definition::check_length(i + 4, serialized)?;
+                let (i0, sub_combinator) = Definition::deserialize_at(i + 4, serialized)?;
+                (i0, schedule_relative(serialized[i + 1] as u64, serialized[i + 2] as u64, serialized[i + 3] as u64, sub_combinator))
+            },
+            // Definitions only describe the built-in combinators
+            Combinator::CUSTOM(_) => return Err("Unrecognised combinator.")
+        })
+    }
+
+    // Deserializes the address at the given index
+    fn deserialize_address(i: usize, serialized: &Vec<i64>) -> Result<Address, &'static str> {
+        Definition::check_length(i + 4, serialized)?;
+        let mut serialized_address: [i64; 4] = [0; 4];
+        serialized_address.copy_from_slice(&serialized[i..(i + 4)]);
+        Ok(i64_to_address(serialized_address))
+    }
+
+    // Deserializes the observable name (its length, then one character code per element) at the given index, returns the
+    // following index and the name
+    fn deserialize_name(i: usize, serialized: &Vec<i64>) -> Result<(usize, Vec<i64>), &'static str> {
+        Definition::check_length(i + 1, serialized)?;
+        let name_len = serialized[i];
+        if name_len < 0 {
+            return Err(INVALID_DEFINITION);
+        }
+        Definition::check_length(i + 1 + name_len as usize, serialized)?;
+
+        let mut name: Vec<i64> = Vec::new();
+        name.extend_from_slice(&serialized[(i + 1)..(i + 1 + name_len as usize)]);
+        Ok((i + 1 + name_len as usize, name))
+    }
+
+    // Checks that the definition vector has at least the given length
+    fn check_length(length: usize, serialized: &Vec<i64>) -> Result<(), &'static str> {
+        if serialized.len() < length {
+            return Err(INVALID_DEFINITION);
+        }
+        Ok(())
+    }
+}
+
+// Method implementation of scale values
+impl ScaleValue {
+    // Appends the serialized scale value (a marker followed by its fields) to the given vector
+    fn serialize_into(&self, serialized: &mut Vec<i64>) {
+        match self {
+            ScaleValue::Provided(value) => {
+                serialized.push(1);
+                serialized.push(*value);
+            },
+            ScaleValue::Observable(obs) => {
+                serialized.push(-1);
+                serialized.extend_from_slice(&address_to_i64(obs.arbiter));
+                serialized.push(obs.name.len() as i64);
+                serialized.extend_from_slice(&obs.name);
+            },
+            ScaleValue::Quorum(obs) => {
+                serialized.push(2);
+                serialized.push(obs.threshold as i64);
+                serialized.push(obs.arbiters.len() as i64);
+                for arbiter in obs.arbiters.iter() {
+                    serialized.extend_from_slice(&address_to_i64(*arbiter));
+                }
+                serialized.push(obs.name.len() as i64);
+                serialized.extend_from_slice(&obs.name);
+            },
+            ScaleValue::Disputable(dispute, obs) => {
+                serialized.push(3);
+                serialized.push(dispute.window as i64);
+                serialized.extend_from_slice(&address_to_i64(dispute.resolver));
+                obs.serialize_into(serialized);
+            }
+        }
+    }
+
+    // Deserializes the scale value at the given index, returns the following index and the scale value
+    fn deserialize_at(i: usize, serialized: &Vec<i64>) -> Result<(usize, ScaleValue), &'static str> {
+        Definition::check_length(i + 2, serialized)?;
+        let mut i0 = i + 1;
+        Ok(match serialized[i] {
+            1 => (i0 + 1, ScaleValue::Provided(serialized[i0])),
+            2 => {
+                Definition::check_length(i0 + 2, serialized)?;
+                let threshold = serialized[i0];
+                let arbiter_count = serialized[i0 + 1];
+                if arbiter_count < 0 {
+                    return Err(INVALID_DEFINITION);
+                }
+                if threshold < 1 || threshold > arbiter_count {
+                    return Err("Quorum threshold must be between one and the number of arbiters.");
+                }
+                i0 += 2;
+
+                let mut arbiters: Vec<Address> = Vec::new();
+                for _ in 0..arbiter_count {
+                    arbiters.push(Definition::deserialize_address(i0, serialized)?);
+                    i0 += 4;
+                }
+                let (i1, name) = Definition::deserialize_name(i0, serialized)?;
+                (i1, ScaleValue::Quorum(Quorum { arbiters, threshold: threshold as u64, name }))
+            },
+            3 => {
+                let window = serialized[i0] as u64;
+                let resolver = Definition::deserialize_address(i0 + 1, serialized)?;
+                let (i1, obs) = ScaleValue::deserialize_at(i0 + 5, serialized)?;
+
+                // Only observables and quorum observables can be disputed
+                match obs {
+                    ScaleValue::Observable(_) | ScaleValue::Quorum(_) => (i1, ScaleValue::Disputable(dispute(window, resolver), Box::new(obs))),
+                    _ => return Err(INVALID_DEFINITION)
+                }
+            },
+            _ => {
+                let arbiter = Definition::deserialize_address(i0, serialized)?;
+                let (i1, name) = Definition::deserialize_name(i0 + 4, serialized)?;
+                (i1, ScaleValue::Observable(Observable { arbiter, name }))
+            }
+        })
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pwasm_std::{ vec };
+
+    // Building a contract produces the same definition vector as writing it by hand
+    #[test]
+    fn serialization_correct() {
+        assert_eq!(zero().serialize(), vec![0]);
+        assert_eq!(truncate(1, one()).serialize(), vec![4, 1, 1]);
+        assert_eq!(or(zero(), one()).serialize(), vec![3, 0, 1]);
+        assert_eq!(truncate(10, or(one(), zero())).serialize(), vec![4, 10, 3, 1, 0]);
+        assert_eq!(and(give(scale(5, one())), get(truncate(100, one()))).serialize(), vec![2, 6, 5, 1, 5, 1, 8, 4, 100, 1]);
+        assert_eq!(then(anytime(one()), zero()).serialize(), vec![7, 9, 1, 0]);
+        assert_eq!(schedule(100, 10, 3, one()).serialize(), vec![10, 100, 10, 3, 1]);
+        assert_eq!(truncate_relative(10, schedule_relative(0, 5, 2, one())).serialize(), vec![11, 10, 12, 0, 5, 2, 1]);
+    }
+
+    // Observables serialize as their arbiter address followed by their name
+    #[test]
+    fn observable_serialization_correct() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let arbiter_serialized: [i64; 4] = address_to_i64(arbiter);
+
+        assert_eq!(scale_by(observable(arbiter, "ab"), one()).serialize(), vec![
+            5, -1, arbiter_serialized[0], arbiter_serialized[1], arbiter_serialized[2], arbiter_serialized[3], 2, 97, 98, 1
+        ]);
+    }
+
+    // Quorum observables serialize as their threshold and arbiter addresses followed by their name
+    #[test]
+    fn quorum_serialization_correct() {
+        let arbiter0: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let arbiter1: Address = "0e6D2ABbe3C0d1e0C2f4Bb1aa4B9bC8b5D1f4a2E".parse().unwrap();
+        let arbiter0_serialized: [i64; 4] = address_to_i64(arbiter0);
+        let arbiter1_serialized: [i64; 4] = address_to_i64(arbiter1);
+
+        let definition = scale_by_quorum(quorum(vec![arbiter0, arbiter1], 1, "ab"), one());
+        assert_eq!(definition.serialize(), vec![
+            5, 2, 1, 2,
+            arbiter0_serialized[0], arbiter0_serialized[1], arbiter0_serialized[2], arbiter0_serialized[3],
+            arbiter1_serialized[0], arbiter1_serialized[1], arbiter1_serialized[2], arbiter1_serialized[3],
+            2, 97, 98, 1
+        ]);
+        assert_eq!(Definition::deserialize(&definition.serialize()), definition);
+    }
+
+    // Quorum observables are rejected unless their threshold is between one and the number of arbiters, as by the contract
+    #[test]
+    fn quorum_threshold_validated() {
+        let arbiter0: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let arbiter1: Address = "0e6D2ABbe3C0d1e0C2f4Bb1aa4B9bC8b5D1f4a2E".parse().unwrap();
+        let error = Err("Quorum threshold must be between one and the number of arbiters.");
+
+        assert_eq!(Definition::try_deserialize(&scale_by_quorum(quorum(vec![arbiter0, arbiter1], 0, "ab"), one()).serialize()), error);
+        assert_eq!(Definition::try_deserialize(&scale_by_quorum(quorum(vec![arbiter0, arbiter1], 3, "ab"), one()).serialize()), error);
+        assert_eq!(Definition::try_deserialize(&scale_by_quorum(quorum(vec![], 1, "ab"), one()).serialize()), error);
+
+        let definition = scale_by_quorum(quorum(vec![arbiter0, arbiter1], 2, "ab"), one());
+        assert_eq!(Definition::try_deserialize(&definition.serialize()), Ok(definition));
+    }
+
+    // Attempting to deserialize a quorum observable with a negative threshold is not allowed
+    #[test]
+    #[should_panic(expected = "Quorum threshold must be between one and the number of arbiters.")]
+    fn should_panic_if_deserializing_negative_quorum_threshold() {
+        Definition::deserialize(&vec![5, 2, -1, 0, 0, 1]);
+    }
+
+    // Disputable observables serialize as their dispute window and resolver address followed by the observable
+    #[test]
+    fn disputable_serialization_correct() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let resolver: Address = "0e6D2ABbe3C0d1e0C2f4Bb1aa4B9bC8b5D1f4a2E".parse().unwrap();
+        let arbiter_serialized: [i64; 4] = address_to_i64(arbiter);
+        let resolver_serialized: [i64; 4] = address_to_i64(resolver);
+
+        let definition = scale_by_disputable(dispute(60, resolver), ScaleValue::Observable(observable(arbiter, "ab")), one());
+        assert_eq!(definition.serialize(), vec![
+            5, 3, 60, resolver_serialized[0], resolver_serialized[1], resolver_serialized[2], resolver_serialized[3],
+            -1, arbiter_serialized[0], arbiter_serialized[1], arbiter_serialized[2], arbiter_serialized[3], 2, 97, 98, 1
+        ]);
+        assert_eq!(Definition::deserialize(&definition.serialize()), definition);
+    }
+
+    // Attempting to deserialize a disputable provided scale value is not allowed
+    #[test]
+    #[should_panic(expected = "Provided combinator contract not valid.")]
+    fn should_panic_if_deserializing_disputable_provided_value() {
+        Definition::deserialize(&vec![5, 3, 60, 0, 0, 0, 0, 1, 2, 1]);
+    }
+
+    // Token assets serialize as their address
+    #[test]
+    fn asset_serialization_correct() {
+        let token: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let token_serialized: [i64; 4] = address_to_i64(token);
+
+        assert_eq!(one_of(token).serialize(), vec![13, token_serialized[0], token_serialized[1], token_serialized[2], token_serialized[3]]);
+    }
+
+    // Deserializing a serialized definition gives the original definition
+    #[test]
+    fn deserialization_correct() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let definition = and(
+            truncate(10, or(scale_by(observable(arbiter, "price"), one_of(arbiter)), scale(-3, one()))),
+            then(schedule(0, 5, 2, give(one())), anytime(truncate_relative(4, schedule_relative(1, 2, 3, get(zero())))))
+        );
+
+        assert_eq!(Definition::deserialize(&definition.serialize()), definition);
+    }
+
+    // Invalid definition vectors are rejected with the reason instead of panicking
+    #[test]
+    fn try_deserialize_correct() {
+        assert_eq!(Definition::try_deserialize(&vec![4, 10, 3, 1, 0]), Ok(truncate(10, or(one(), zero()))));
+        assert_eq!(Definition::try_deserialize(&vec![2, 1]), Err("Provided combinator contract not valid."));
+        assert_eq!(Definition::try_deserialize(&vec![1, 1]), Err("Provided combinator contract not valid."));
+        assert_eq!(Definition::try_deserialize(&vec![6, 100]), Err("Unrecognised combinator."));
+    }
+
+    // Attempting to deserialize a truncated definition is not allowed
+    #[test]
+    #[should_panic(expected = "Provided combinator contract not valid.")]
+    fn should_panic_if_deserializing_truncated_definition() {
+        Definition::deserialize(&vec![2, 1]);
+    }
+
+    // Attempting to deserialize a definition with trailing values is not allowed
+    #[test]
+    #[should_panic(expected = "Provided combinator contract not valid.")]
+    fn should_panic_if_deserializing_trailing_values() {
+        Definition::deserialize(&vec![1, 1]);
+    }
+}
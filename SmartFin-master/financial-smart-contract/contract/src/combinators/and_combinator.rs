@@ -1,4 +1,5 @@
-use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, latest_time, deserialize_combinator, Box, Vec };
+use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, latest_time, deserialize_combinator, Box, Vec, vec };
+use super::asset_amounts::AssetAmounts;
 use storage::Storage;
 
 // The and combinator
@@ -49,16 +50,33 @@ impl ContractCombinator for AndCombinator {
     }
 
     // Returns the latest of the two sub-horizons
-    fn get_horizon(&self) -> Option<u32> {
+    fn get_horizon(&self) -> Option<u64> {
         latest_time(self.sub_combinator0.get_horizon(), self.sub_combinator1.get_horizon())
     }
 
+    // Resolves any relative times in both sub-combinators
+    fn resolve_relative_times(&mut self, base_time: u64) {
+        self.sub_combinator0.resolve_relative_times(base_time);
+        self.sub_combinator1.resolve_relative_times(base_time);
+    }
+
+    // Resets the anytime acquisition times in the sub-combinators
+    fn reset_anytime_acquisition_times(&self, storage: &mut Storage) {
+        self.sub_combinator0.reset_anytime_acquisition_times(storage);
+        self.sub_combinator1.reset_anytime_acquisition_times(storage);
+    }
+
     fn get_combinator_details(&self) -> &CombinatorDetails {
         &self.combinator_details
     }
 
+    // Returns both sub-combinators
+    fn get_sub_combinators(&self) -> Vec<&Box<ContractCombinator>> {
+        vec![&self.sub_combinator0, &self.sub_combinator1]
+    }
+
     // Acquires the combinator and acquirable sub-combinators
-    fn acquire(&mut self, time: u32, storage: &mut Storage) {
+    fn acquire(&mut self, time: u64, storage: &mut Storage) {
         if self.past_horizon(time) {
             panic!("Cannot acquire an expired contract.");
         }
@@ -76,12 +94,12 @@ impl ContractCombinator for AndCombinator {
     }
 
     // Updates the combinator, returning the current balance to be paid from the holder to the counter-party
-    fn update(&mut self, time: u32, storage: &mut Storage) -> i64 {
+    fn update(&mut self, time: u64, storage: &mut Storage) -> AssetAmounts {
         // If not acquired yet or fully updated (no more pending balance), return 0
         if self.combinator_details.acquisition_time == None
             || self.combinator_details.acquisition_time.unwrap() > time
             || self.combinator_details.fully_updated {
-            return 0;
+            return AssetAmounts::zero();
         }
 
         let sub_value0 = self.sub_combinator0.update(time, storage);
@@ -168,7 +186,7 @@ mod tests {
         );
 
         // Acquire and check details
-        let time: u32 = 5;
+        let time: u64 = 5;
         combinator.acquire(time, &mut Storage::new());
         let combinator_details = combinator.get_combinator_details();
 
@@ -214,7 +232,7 @@ mod tests {
         );
 
         // Acquire and check details
-        let time: u32 = 5;
+        let time: u64 = 5;
         combinator.acquire(time, &mut Storage::new());
     }
 
@@ -1,4 +1,5 @@
-use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, deserialize_combinator, Box, Vec };
+use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, deserialize_combinator, Box, Vec, vec };
+use super::asset_amounts::AssetAmounts;
 use { anytime_acquisition_times_key };
 use storage::*;
 
@@ -49,16 +50,37 @@ impl ContractCombinator for AnytimeCombinator {
     }
 
     // Returns the sub-horizon
-    fn get_horizon(&self) -> Option<u32> {
+    fn get_horizon(&self) -> Option<u64> {
         self.sub_combinator.get_horizon()
     }
 
+    // Resolves any relative times in the sub-combinator
+    fn resolve_relative_times(&mut self, base_time: u64) {
+        self.sub_combinator.resolve_relative_times(base_time);
+    }
+
+    // Resets the acquisition time of this combinator and the anytime acquisition times in the sub-combinator
+    fn reset_anytime_acquisition_times(&self, storage: &mut Storage) {
+        StoresFixedVec::<(bool, Option<u64>)>::set(storage, &anytime_acquisition_times_key(), self.anytime_index, (false, None));
+        self.sub_combinator.reset_anytime_acquisition_times(storage);
+    }
+
     fn get_combinator_details(&self) -> &CombinatorDetails {
         &self.combinator_details
     }
 
+    // Returns the anytime index
+    fn get_index(&self) -> Option<usize> {
+        Some(self.anytime_index)
+    }
+
+    // Returns the sub-combinator
+    fn get_sub_combinators(&self) -> Vec<&Box<ContractCombinator>> {
+        vec![&self.sub_combinator]
+    }
+
     // Acquires the combinator and acquirable sub-combinators
-    fn acquire(&mut self, time: u32, storage: &mut Storage) {
+    fn acquire(&mut self, time: u64, storage: &mut Storage) {
         if self.past_horizon(time) {
             panic!("Cannot acquire an expired contract.");
         }
@@ -71,16 +93,16 @@ impl ContractCombinator for AnytimeCombinator {
     }
 
     // Updates the combinator, setting the acquisition time, and returning the current balance to be paid from the holder to the counter-party
-    fn update(&mut self, time: u32, storage: &mut Storage) -> i64 {
+    fn update(&mut self, time: u64, storage: &mut Storage) -> AssetAmounts {
         // If not acquired yet or fully updated (no more pending balance), return 0
         if self.combinator_details.acquisition_time == None
             || self.combinator_details.acquisition_time.unwrap() > time
             || self.combinator_details.fully_updated {
-            return 0;
+            return AssetAmounts::zero();
         }
 
         // If acquisition time not passed previously, set it as passed
-        let acquisition_details: (bool, Option<u32>) = storage.get(&anytime_acquisition_times_key(), self.anytime_index);
+        let acquisition_details: (bool, Option<u64>) = storage.get(&anytime_acquisition_times_key(), self.anytime_index);
         if !acquisition_details.0 {
             storage.set(&anytime_acquisition_times_key(), self.anytime_index, (true, self.sub_combinator.get_horizon()));
         }
@@ -89,7 +111,7 @@ impl ContractCombinator for AnytimeCombinator {
         let mut acquisition_time = self.sub_combinator.get_combinator_details().acquisition_time;
 
         // If not, check if provided
-        let anytime_acquisition_time: (bool, Option<u32>) = storage.get(&anytime_acquisition_times_key(), self.anytime_index);
+        let anytime_acquisition_time: (bool, Option<u64>) = storage.get(&anytime_acquisition_times_key(), self.anytime_index);
         if acquisition_time == None && anytime_acquisition_time.1 != None {
             acquisition_time = anytime_acquisition_time.1;
 
@@ -131,7 +153,7 @@ mod tests {
     use storage::*;
 
     // Sets up the storage struct
-    fn setup_storage(anytime_acquisition_times: &Vec<(bool, Option<u32>)>) -> Storage {
+    fn setup_storage(anytime_acquisition_times: &Vec<(bool, Option<u64>)>) -> Storage {
         let mut storage = Storage::new();
         storage.write_vec(&anytime_acquisition_times_key(), anytime_acquisition_times);
         storage
@@ -179,7 +201,7 @@ mod tests {
         );
 
         // Acquire and check details
-        let time: u32 = 1;
+        let time: u64 = 1;
         let acquisition_times = &vec![(false, None)];
         let mut storage = setup_storage(acquisition_times);
         combinator.acquire(time, &mut storage);
@@ -235,7 +257,7 @@ mod tests {
         let acquisition_times = &vec![(false, None)];
         let mut storage = setup_storage(acquisition_times);
         combinator.acquire(0, &mut storage);
-        assert_eq!(StoresFixedVec::<(bool, Option<u32>)>::get(&mut storage, &anytime_acquisition_times_key(), 0).1, Some(1));
+        assert_eq!(StoresFixedVec::<(bool, Option<u64>)>::get(&mut storage, &anytime_acquisition_times_key(), 0).1, Some(1));
         let value = combinator.update(2, &mut storage);
 
         assert_eq!(
@@ -262,7 +284,7 @@ mod tests {
         let acquisition_times = &vec![(false, None)];
         let mut storage = setup_storage(acquisition_times);
         combinator.acquire(0, &mut storage);
-        storage.set(&anytime_acquisition_times_key(), 0, (true, Some(3 as u32)));
+        storage.set(&anytime_acquisition_times_key(), 0, (true, Some(3 as u64)));
         let value = combinator.update(2, &mut storage);
 
         assert_eq!(
@@ -289,7 +311,7 @@ mod tests {
         let acquisition_times = &vec![(false, None)];
         let mut storage = setup_storage(acquisition_times);
         combinator.acquire(0, &mut storage);
-        storage.set(&anytime_acquisition_times_key(), 0, (true, Some(1 as u32)));
+        storage.set(&anytime_acquisition_times_key(), 0, (true, Some(1 as u64)));
         combinator.update(0, &mut storage);
         let fully_updated = combinator.get_combinator_details().fully_updated;
 
@@ -317,7 +339,7 @@ mod tests {
         let mut storage = setup_storage(acquisition_times);
 
         combinator.acquire(0, &mut storage);
-        storage.set(&anytime_acquisition_times_key(), 0, (true, Some(0 as u32)));
+        storage.set(&anytime_acquisition_times_key(), 0, (true, Some(0 as u64)));
         combinator.update(1, &mut storage);
 
         let fully_updated = combinator.get_combinator_details().fully_updated;
@@ -373,7 +395,7 @@ mod tests {
         let acquisition_times = &vec![(false, None)];
         let mut storage = setup_storage(acquisition_times);
         combinator.acquire(0, &mut storage);
-        storage.set(&anytime_acquisition_times_key(), 0, (true, Some(1 as u32)));
+        storage.set(&anytime_acquisition_times_key(), 0, (true, Some(1 as u64)));
         let value = combinator.update(1, &mut storage);
 
         assert_eq!(
@@ -545,7 +567,7 @@ mod tests {
         let mut storage = setup_storage(acquisition_times);
         combinator.acquire(2, &mut storage);
         combinator.update(0, &mut storage);
-        let mut acquisition_details: (bool, Option<u32>) = storage.get(&anytime_acquisition_times_key(), 0);
+        let mut acquisition_details: (bool, Option<u64>) = storage.get(&anytime_acquisition_times_key(), 0);
         assert!(
             !acquisition_details.0,
             "anytime combinator is acquirable before its acquisition time."
@@ -632,7 +654,7 @@ mod tests {
         let acquisition_times = &vec![(false, None)];
         let mut storage = setup_storage(acquisition_times);
         combinator.acquire(1, &mut storage);
-        storage.set(&anytime_acquisition_times_key(), 0, (true, Some(0 as u32)));
+        storage.set(&anytime_acquisition_times_key(), 0, (true, Some(0 as u64)));
         combinator.update(2, &mut storage);
     }
 }
\ No newline at end of file
@@ -0,0 +1,213 @@
+use super::contract_combinator::Vec;
+use core::{ fmt, ops::{ Add, AddAssign, Mul, Neg } };
+
+// The asset index of Ether, all other assets are indexed from 1 in the order they appear in the contract definition
+pub const ETHER_ASSET: usize = 0;
+
+// The amounts of each asset to be paid from the counter-party to the holder, indexed by asset index
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct AssetAmounts {
+    amounts: Vec<i64>
+}
+
+// Method implementation of asset amounts
+impl AssetAmounts {
+    // Constructor for no amount of any asset
+    pub fn zero() -> AssetAmounts {
+        AssetAmounts {
+            amounts: Vec::new()
+        }
+    }
+
+    // Constructor for the given amount of a single asset
+    pub fn new(asset: usize, amount: i64) -> AssetAmounts {
+        let mut amounts: Vec<i64> = Vec::new();
+        amounts.resize(asset + 1, 0);
+        amounts[asset] = amount;
+
+        AssetAmounts {
+            amounts
+        }
+    }
+
+    // Gets the amount of the given asset
+    pub fn get(&self, asset: usize) -> i64 {
+        if asset < self.amounts.len() {
+            self.amounts[asset]
+        } else {
+            0
+        }
+    }
+
+    // Gets the amounts of every asset up to the highest asset index with an amount, indexed by asset index
+    pub fn get_amounts(&self) -> &Vec<i64> {
+        &self.amounts
+    }
+
+    // Returns whether or not the amount of every asset is zero
+    pub fn is_zero(&self) -> bool {
+        self.amounts.iter().all(|amount| *amount == 0)
+    }
+
+    // Serializes the amounts as the number of amounts followed by the amounts
+    pub fn serialize(&self) -> Vec<i64> {
+        let mut serialized: Vec<i64> = Vec::new();
+        serialized.push(self.amounts.len() as i64);
+        serialized.extend_from_slice(&self.amounts);
+        serialized
+    }
+
+    // Deserializes amounts at the given index, returns the following index and the amounts
+    pub fn deserialize(index: usize, serialized: &Vec<i64>) -> (usize, AssetAmounts) {
+        if index >= serialized.len() || serialized[index] < 0 || index + 1 + serialized[index] as usize > serialized.len() {
+            panic!("Attempted to deserialize ill-formed serialized AssetAmounts.");
+        }
+        let length = serialized[index] as usize;
+
+        let mut amounts: Vec<i64> = Vec::new();
+        amounts.extend_from_slice(&serialized[(index + 1)..(index + 1 + length)]);
+        (index + 1 + length, AssetAmounts { amounts })
+    }
+}
+
+// Amounts are equal if every asset has the same amount
+impl PartialEq for AssetAmounts {
+    fn eq(&self, other: &AssetAmounts) -> bool {
+        let length = if self.amounts.len() > other.amounts.len() { self.amounts.len() } else { other.amounts.len() };
+        (0..length).all(|asset| self.get(asset) == other.get(asset))
+    }
+}
+
+// Amounts are equal to an i64 if they consist only of that amount of Ether
+impl PartialEq<i64> for AssetAmounts {
+    fn eq(&self, other: &i64) -> bool {
+        *self == AssetAmounts::new(ETHER_ASSET, *other)
+    }
+}
+
+// Adds the amounts of each asset
+impl Add for AssetAmounts {
+    type Output = AssetAmounts;
+
+    fn add(mut self, other: AssetAmounts) -> AssetAmounts {
+        self += other;
+        self
+    }
+}
+
+// Adds the amounts of each asset in place
+impl AddAssign for AssetAmounts {
+    fn add_assign(&mut self, other: AssetAmounts) {
+        if self.amounts.len() < other.amounts.len() {
+            self.amounts.resize(other.amounts.len(), 0);
+        }
+        for (asset, amount) in other.amounts.into_iter().enumerate() {
+            self.amounts[asset] += amount;
+        }
+    }
+}
+
+// Scales the amount of each asset
+impl Mul<i64> for AssetAmounts {
+    type Output = AssetAmounts;
+
+    fn mul(self, scale: i64) -> AssetAmounts {
+        AssetAmounts {
+            amounts: self.amounts.into_iter().map(|amount| amount * scale).collect()
+        }
+    }
+}
+
+// Negates the amount of each asset
+impl Neg for AssetAmounts {
+    type Output = AssetAmounts;
+
+    fn neg(self) -> AssetAmounts {
+        self * -1
+    }
+}
+
+// Displays Ether-only amounts as a single number, otherwise the amounts indexed by asset index
+impl fmt::Display for AssetAmounts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.amounts.iter().skip(1).all(|amount| *amount == 0) {
+            write!(f, "{}", self.get(ETHER_ASSET))
+        } else {
+            write!(f, "{:?}", self.amounts)
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::{ AssetAmounts, ETHER_ASSET };
+    use pwasm_std::{ vec };
+
+    // Amounts of a single asset are stored at the asset index
+    #[test]
+    fn new_amounts_correct() {
+        let amounts = AssetAmounts::new(2, 5);
+        assert_eq!(amounts.get(ETHER_ASSET), 0);
+        assert_eq!(amounts.get(1), 0);
+        assert_eq!(amounts.get(2), 5);
+        assert_eq!(amounts.get(3), 0);
+    }
+
+    // Zero amounts are equal to zero, even when stored with trailing zero amounts
+    #[test]
+    fn zero_amounts_equal_zero() {
+        assert_eq!(AssetAmounts::zero(), 0);
+        assert_eq!(AssetAmounts::new(3, 0), 0);
+        assert_eq!(AssetAmounts::new(3, 0), AssetAmounts::zero());
+        assert!(AssetAmounts::new(3, 0).is_zero());
+    }
+
+    // Amounts of assets other than Ether are not equal to a number
+    #[test]
+    fn non_ether_amounts_not_equal_number() {
+        assert!(AssetAmounts::new(1, 5) != 5);
+        assert!(AssetAmounts::new(1, 5) != 0);
+        assert!(AssetAmounts::new(ETHER_ASSET, 5) == 5);
+    }
+
+    // Adding amounts adds the amount of each asset
+    #[test]
+    fn addition_correct() {
+        let mut amounts = AssetAmounts::new(ETHER_ASSET, 1) + AssetAmounts::new(2, 3);
+        amounts += AssetAmounts::new(2, 4);
+
+        assert_eq!(amounts.get(ETHER_ASSET), 1);
+        assert_eq!(amounts.get(1), 0);
+        assert_eq!(amounts.get(2), 7);
+    }
+
+    // Scaling and negating amounts scales the amount of each asset
+    #[test]
+    fn scaling_and_negation_correct() {
+        let amounts = AssetAmounts::new(ETHER_ASSET, 1) + AssetAmounts::new(1, -2);
+
+        assert_eq!(amounts.clone() * 3, AssetAmounts::new(ETHER_ASSET, 3) + AssetAmounts::new(1, -6));
+        assert_eq!(-amounts, AssetAmounts::new(ETHER_ASSET, -1) + AssetAmounts::new(1, 2));
+    }
+
+    // Deserializing serialized amounts is correct
+    #[test]
+    fn deserialization_correct() {
+        let amounts = AssetAmounts::new(ETHER_ASSET, 3) + AssetAmounts::new(2, -4);
+        let serialized = amounts.serialize();
+        assert_eq!(serialized, vec![3, 3, 0, -4]);
+
+        let (index, deserialized) = AssetAmounts::deserialize(0, &serialized);
+        assert_eq!(index, serialized.len());
+        assert_eq!(deserialized, amounts);
+    }
+
+    // Attempting to deserialize amounts longer than the serialized vector is not allowed
+    #[test]
+    #[should_panic(expected = "Attempted to deserialize ill-formed serialized AssetAmounts.")]
+    fn should_panic_if_deserializing_truncated_amounts() {
+        AssetAmounts::deserialize(0, &vec![3, 1, 2]);
+    }
+}
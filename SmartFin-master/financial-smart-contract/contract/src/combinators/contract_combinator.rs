@@ -2,22 +2,17 @@ extern crate pwasm_std;
 
 pub use self::pwasm_std::{ Box, Vec, vec, types::{ Address } };
 use storage::*;
+use super::asset_amounts::AssetAmounts;
 
-use ZeroCombinator;
-use OneCombinator;
-use AndCombinator;
-use OrCombinator;
-use TruncateCombinator;
-use ScaleCombinator;
-use GiveCombinator;
-use ThenCombinator;
-use GetCombinator;
-use AnytimeCombinator;
+use registry::get_combinator_entry;
 
 // The types of combinators
+#[derive(Clone)]
+#[derive(Copy)]
 #[derive(Debug)]
 #[derive(PartialEq)]
 #[derive(Eq)]
+#[allow(non_camel_case_types)]
 pub enum Combinator {
     ZERO,
     ONE,
@@ -28,7 +23,14 @@ pub enum Combinator {
     GIVE,
     THEN,
     GET,
-    ANYTIME
+    ANYTIME,
+    SCHEDULE,
+    TRUNCATE_RELATIVE,
+    SCHEDULE_RELATIVE,
+    ONE_ASSET,
+
+    // A combinator registered by another crate, with its tag
+    CUSTOM(i64)
 }
 
 // Conversion from i64 to Combinator
@@ -45,7 +47,11 @@ impl From<i64> for Combinator {
             7 => Combinator::THEN,
             8 => Combinator::GET,
             9 => Combinator::ANYTIME,
-            _ => panic!("Unrecognised combinator.")
+            10 => Combinator::SCHEDULE,
+            11 => Combinator::TRUNCATE_RELATIVE,
+            12 => Combinator::SCHEDULE_RELATIVE,
+            13 => Combinator::ONE_ASSET,
+            _ => Combinator::CUSTOM(val)
         }
     }
 }
@@ -63,7 +69,12 @@ impl From<Combinator> for i64 {
             Combinator::GIVE => 6,
             Combinator::THEN => 7,
             Combinator::GET => 8,
-            Combinator::ANYTIME => 9
+            Combinator::ANYTIME => 9,
+            Combinator::SCHEDULE => 10,
+            Combinator::TRUNCATE_RELATIVE => 11,
+            Combinator::SCHEDULE_RELATIVE => 12,
+            Combinator::ONE_ASSET => 13,
+            Combinator::CUSTOM(tag) => tag
         }
     }
 }
@@ -71,7 +82,7 @@ impl From<Combinator> for i64 {
 // The details shared by all combinators
 pub struct CombinatorDetails {
     // The acquisition time of the combinator
-    pub acquisition_time: Option<u32>,
+    pub acquisition_time: Option<u64>,
 
     // Whether or not the combinator is fully updated
     pub fully_updated: bool
@@ -90,7 +101,7 @@ impl CombinatorDetails {
     // Converts a serialized combinator details array to CombinatorDetails
     pub fn deserialize(details_serialized: [i64; 2]) -> CombinatorDetails {
         CombinatorDetails {
-            acquisition_time: if details_serialized[0] >= 0 { Some(details_serialized[0] as u32) } else { None },
+            acquisition_time: if details_serialized[0] >= 0 { Some(details_serialized[0] as u64) } else { None },
             fully_updated: details_serialized[1] == 1
         }
     }
@@ -99,12 +110,46 @@ impl CombinatorDetails {
 // API for combinators
 pub trait ContractCombinator {
     // Returns the horizon of the combinator, or -1 if none exists
-    fn get_horizon(&self) -> Option<u32> {
+    fn get_horizon(&self) -> Option<u64> {
         None
     }
 
+    // Resolves times relative to the contract acquisition time (the base time) in this combinator and its sub-combinators.
+    // Until then, relative horizons are unknown and treated as None.
+    fn resolve_relative_times(&mut self, _base_time: u64) { }
+
+    // Resets the anytime acquisition times in this combinator and its sub-combinators to their state before acquisition, as
+    // each period of a schedule combinator reuses those of the previous period
+    fn reset_anytime_acquisition_times(&self, _storage: &mut Storage) { }
+
+    // Returns the or, anytime or observable index of the combinator, or None if it has none
+    fn get_index(&self) -> Option<usize> {
+        None
+    }
+
+    // Returns the combinator's own parameters: the asset index (one), the truncated horizon (truncate), the provided scale value
+    // (scale, none for observables) or the first period date, period length and period count (schedule). Times are -1 until
+    // relative times are resolved.
+    fn get_parameters(&self) -> Vec<i64> {
+        Vec::new()
+    }
+
+    // Returns the sub-combinators which make up the live contract
+    fn get_sub_combinators(&self) -> Vec<&Box<ContractCombinator>> {
+        Vec::new()
+    }
+
+    // Returns the value paid out by the combinator so far, by default the total paid out by its sub-combinators
+    fn get_accrued(&self, storage: &mut Storage) -> AssetAmounts {
+        let mut accrued = AssetAmounts::zero();
+        for sub_combinator in self.get_sub_combinators() {
+            accrued += sub_combinator.get_accrued(storage);
+        }
+        accrued
+    }
+
     // Returns whether the given time is beyond the combinator's horizon or not
-    fn past_horizon(&self, time: u32) -> bool {
+    fn past_horizon(&self, time: u64) -> bool {
         match self.get_horizon() {
             Some(t) => t < time,
             None => false
@@ -114,7 +159,7 @@ pub trait ContractCombinator {
     // Serializes a combinator's number and details
     fn serialize_details(&self) -> Vec<i64> {
         let mut serialized: Vec<i64> = Vec::new();
-        serialized.push(self.get_combinator_number() as i64);
+        serialized.push(i64::from(self.get_combinator_number()));
 
         let details = self.get_combinator_details();
         match details.acquisition_time {
@@ -130,10 +175,10 @@ pub trait ContractCombinator {
     fn get_combinator_details(&self) -> &CombinatorDetails;
 
     // Acquires the combinator, setting the acquisition time in the combinator details
-    fn acquire(&mut self, time: u32, storage: &mut Storage);
+    fn acquire(&mut self, time: u64, storage: &mut Storage);
 
-    // Updates the combinator, returning the current balance to be paid from the holder to the counter-party
-    fn update(&mut self, time: u32, storage: &mut Storage) -> i64;
+    // Updates the combinator, returning the current balance of each asset to be paid from the holder to the counter-party
+    fn update(&mut self, time: u64, storage: &mut Storage) -> AssetAmounts;
 
     // Gets the combinator number
     fn get_combinator_number(&self) -> Combinator;
@@ -142,10 +187,40 @@ pub trait ContractCombinator {
     fn serialize(&self) -> Vec<i64> {
         self.serialize_details()
     }
+
+    // Serializes the live state of this combinator and its sub-combinators in pre-order, each combinator as
+    // [number, index or -1, acquisition time or -1, horizon or -1, fully updated, sub-combinator count, accrued amounts...]
+    fn serialize_state(&self, storage: &mut Storage) -> Vec<i64> {
+        let details = self.get_combinator_details();
+        let sub_combinators = self.get_sub_combinators();
+
+        let mut serialized: Vec<i64> = Vec::new();
+        serialized.push(i64::from(self.get_combinator_number()));
+        serialized.push(match self.get_index() {
+            Some(index) => index as i64,
+            None => -1
+        });
+        serialized.push(match details.acquisition_time {
+            Some(time) => time as i64,
+            None => -1
+        });
+        serialized.push(match self.get_horizon() {
+            Some(time) => time as i64,
+            None => -1
+        });
+        serialized.push(if details.fully_updated { 1 } else { 0 });
+        serialized.push(sub_combinators.len() as i64);
+        serialized.extend_from_slice(&self.get_accrued(storage).serialize());
+
+        for sub_combinator in sub_combinators {
+            serialized.extend_from_slice(&sub_combinator.serialize_state(storage));
+        }
+        serialized
+    }
 }
 
 // Returns the earliest of the given horizons
-pub fn earliest_time(horizon0: Option<u32>, horizon1: Option<u32>) -> Option<u32> {
+pub fn earliest_time(horizon0: Option<u64>, horizon1: Option<u64>) -> Option<u64> {
     match horizon0 {
         Some(h0) => match horizon1 {
             Some(h1) => if h0 < h1 {
@@ -160,7 +235,7 @@ pub fn earliest_time(horizon0: Option<u32>, horizon1: Option<u32>) -> Option<u32
 }
 
 // Returns the latest of the given horizons
-pub fn latest_time(horizon0: Option<u32>, horizon1: Option<u32>) -> Option<u32> {
+pub fn latest_time(horizon0: Option<u64>, horizon1: Option<u64>) -> Option<u64> {
     match horizon0 {
         Some(h0) => match horizon1 {
             Some(h1) => if h0 > h1 {
@@ -174,23 +249,13 @@ pub fn latest_time(horizon0: Option<u32>, horizon1: Option<u32>) -> Option<u32>
     }
 }
 
-// Deserializes a ContractCombinator
+// Deserializes a ContractCombinator with the state deserializer registered for its tag
 pub fn deserialize_combinator(index: usize, serialized_combinator: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
     if index >= serialized_combinator.len() {
         panic!("Attempted to deserialize ill-formed serialized ContractCombinator.");
     }
-    match Combinator::from(serialized_combinator[index]) {
-        Combinator::ZERO => ZeroCombinator::deserialize(index + 1, serialized_combinator),
-        Combinator::ONE => OneCombinator::deserialize(index + 1, serialized_combinator),
-        Combinator::AND => AndCombinator::deserialize(index + 1, serialized_combinator),
-        Combinator::OR => OrCombinator::deserialize(index + 1, serialized_combinator),
-        Combinator::TRUNCATE => TruncateCombinator::deserialize(index + 1, serialized_combinator),
-        Combinator::SCALE => ScaleCombinator::deserialize(index + 1, serialized_combinator),
-        Combinator::GIVE => GiveCombinator::deserialize(index + 1, serialized_combinator),
-        Combinator::THEN => ThenCombinator::deserialize(index + 1, serialized_combinator),
-        Combinator::GET => GetCombinator::deserialize(index + 1, serialized_combinator),
-        Combinator::ANYTIME => AnytimeCombinator::deserialize(index + 1, serialized_combinator)
-    }
+    let entry = get_combinator_entry(serialized_combinator[index]);
+    (entry.deserialize_state)(index + 1, serialized_combinator)
 }
 
 // Unit tests
@@ -199,6 +264,7 @@ pub fn deserialize_combinator(index: usize, serialized_combinator: &Vec<i64>) ->
 mod tests {
     use super::*;
     use { anytime_acquisition_times_key };
+    use combinators::*;
 
     // Dummy combinator
     pub struct DummyCombinator {
@@ -227,14 +293,14 @@ mod tests {
         }
 
         // Acquires the combinator and acquirable sub-combinators
-        fn acquire(&mut self, time: u32, _: &mut Storage) {
+        fn acquire(&mut self, time: u64, _: &mut Storage) {
             self.combinator_details.acquisition_time = Some(time);
         }
 
         // Updates the combinator, returning the current balance to be paid from the holder to the counter-party
-        fn update(&mut self, _time: u32, _: &mut Storage) -> i64 {
+        fn update(&mut self, _time: u64, _: &mut Storage) -> AssetAmounts {
             self.combinator_details.fully_updated = true;
-            0
+            AssetAmounts::zero()
         }
     }
 
@@ -277,11 +343,13 @@ mod tests {
     // Combinator to/from i64 converts correctly
     #[test]
     fn combinator_conversion_correct() {
-        for i in 0..10 {
+        for i in 0..14 {
             let combinator = Combinator::from(i);
             let val = i64::from(combinator);
             assert_eq!(i, val);
         }
+        assert_eq!(Combinator::from(100), Combinator::CUSTOM(100));
+        assert_eq!(i64::from(Combinator::CUSTOM(100)), 100);
     }
 
     // Combinator details serialization is correct
@@ -300,7 +368,7 @@ mod tests {
         serialized_details = combinator.serialize_details();
         
         assert_eq!(Combinator::from(serialized_details[0]), combinator.get_combinator_number());
-        assert_eq!(serialized_details[1] as u32, combinator.get_combinator_details().acquisition_time.unwrap());
+        assert_eq!(serialized_details[1] as u64, combinator.get_combinator_details().acquisition_time.unwrap());
         assert_eq!(serialized_details[2], 1);
     }
 
@@ -348,7 +416,7 @@ mod tests {
             0
         );
         let mut storage = Storage::new();
-        storage.write_vec(&anytime_acquisition_times_key(), &vec![(false, Some(20 as u32))]);
+        storage.write_vec(&anytime_acquisition_times_key(), &vec![(false, Some(20 as u64))]);
         combinator.acquire(10, &mut storage);
         combinator.update(11, &mut storage);
         let serialized = combinator.serialize();
@@ -1,4 +1,5 @@
-use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, deserialize_combinator, Box, Vec };
+use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, deserialize_combinator, Box, Vec, vec };
+use super::asset_amounts::AssetAmounts;
 use storage::Storage;
 
 // The get combinator
@@ -43,16 +44,31 @@ impl ContractCombinator for GetCombinator {
     }
 
     // Returns the sub-horizon
-    fn get_horizon(&self) -> Option<u32> {
+    fn get_horizon(&self) -> Option<u64> {
         self.sub_combinator.get_horizon()
     }
 
+    // Resolves any relative times in the sub-combinator
+    fn resolve_relative_times(&mut self, base_time: u64) {
+        self.sub_combinator.resolve_relative_times(base_time);
+    }
+
+    // Resets the anytime acquisition times in the sub-combinator
+    fn reset_anytime_acquisition_times(&self, storage: &mut Storage) {
+        self.sub_combinator.reset_anytime_acquisition_times(storage);
+    }
+
     fn get_combinator_details(&self) -> &CombinatorDetails {
         &self.combinator_details
     }
 
+    // Returns the sub-combinator
+    fn get_sub_combinators(&self) -> Vec<&Box<ContractCombinator>> {
+        vec![&self.sub_combinator]
+    }
+
     // Acquires the combinator and acquirable sub-combinators
-    fn acquire(&mut self, time: u32, storage: &mut Storage) {
+    fn acquire(&mut self, time: u64, storage: &mut Storage) {
         if self.past_horizon(time) {
             panic!("Cannot acquire an expired contract.");
         }
@@ -70,12 +86,12 @@ impl ContractCombinator for GetCombinator {
     }
 
     // Updates the combinator, returning the current balance to be paid from the holder to the counter-party
-    fn update(&mut self, time: u32, storage: &mut Storage) -> i64 {
+    fn update(&mut self, time: u64, storage: &mut Storage) -> AssetAmounts {
         // If not acquired yet or fully updated (no more pending balance), return 0
         if self.combinator_details.acquisition_time == None
             || self.combinator_details.acquisition_time.unwrap() > time
             || self.combinator_details.fully_updated {
-            return 0;
+            return AssetAmounts::zero();
         }
 
         let sub_value = self.sub_combinator.update(time, storage);
@@ -138,7 +154,7 @@ mod tests {
         );
 
         // Acquire and check details
-        let time: u32 = 1;
+        let time: u64 = 1;
         combinator.acquire(time, &mut Storage::new());
         let combinator_details = combinator.get_combinator_details();
 
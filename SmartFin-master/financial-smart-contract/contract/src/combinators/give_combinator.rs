@@ -1,4 +1,5 @@
-use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, deserialize_combinator, Box, Vec };
+use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, deserialize_combinator, Box, Vec, vec };
+use super::asset_amounts::AssetAmounts;
 use storage::Storage;
 
 // The give combinator
@@ -42,16 +43,36 @@ impl ContractCombinator for GiveCombinator {
         Combinator::GIVE
     }
 
-    fn get_horizon(&self) -> Option<u32> {
+    fn get_horizon(&self) -> Option<u64> {
         self.sub_combinator.get_horizon()
     }
 
+    // Resolves any relative times in the sub-combinator
+    fn resolve_relative_times(&mut self, base_time: u64) {
+        self.sub_combinator.resolve_relative_times(base_time);
+    }
+
+    // Resets the anytime acquisition times in the sub-combinator
+    fn reset_anytime_acquisition_times(&self, storage: &mut Storage) {
+        self.sub_combinator.reset_anytime_acquisition_times(storage);
+    }
+
     fn get_combinator_details(&self) -> &CombinatorDetails {
         &self.combinator_details
     }
 
+    // Returns the sub-combinator
+    fn get_sub_combinators(&self) -> Vec<&Box<ContractCombinator>> {
+        vec![&self.sub_combinator]
+    }
+
+    // Returns the value paid out so far, the negation of the sub-combinator's
+    fn get_accrued(&self, storage: &mut Storage) -> AssetAmounts {
+        -self.sub_combinator.get_accrued(storage)
+    }
+
     // Acquires the combinator and acquirable sub-combinators
-    fn acquire(&mut self, time: u32, storage: &mut Storage) {
+    fn acquire(&mut self, time: u64, storage: &mut Storage) {
         if self.past_horizon(time) {
             panic!("Cannot acquire an expired contract.");
         }
@@ -64,17 +85,17 @@ impl ContractCombinator for GiveCombinator {
     }
 
     // Updates the combinator, returning the current balance to be paid from the holder to the counter-party
-    fn update(&mut self, time: u32, storage: &mut Storage) -> i64 {
+    fn update(&mut self, time: u64, storage: &mut Storage) -> AssetAmounts {
         // If not acquired yet or fully updated (no more pending balance), return 0
         if self.combinator_details.acquisition_time == None
             || self.combinator_details.acquisition_time.unwrap() > time
             || self.combinator_details.fully_updated {
-            return 0;
+            return AssetAmounts::zero();
         }
 
         let sub_value = self.sub_combinator.update(time, storage);
         self.combinator_details.fully_updated = self.sub_combinator.get_combinator_details().fully_updated;
-        -1 * sub_value
+        -sub_value
     }
 
     // Serializes this combinator
@@ -127,7 +148,7 @@ mod tests {
         let mut combinator = GiveCombinator::new(Box::new(OneCombinator::new()));
 
         // Acquire and check details
-        let time: u32 = 5;
+        let time: u64 = 5;
         combinator.acquire(time, &mut Storage::new());
         let combinator_details = combinator.get_combinator_details();
 
@@ -1,5 +1,6 @@
 // Import combinators
 mod contract_combinator;
+mod asset_amounts;
 mod zero_combinator;
 mod one_combinator;
 mod and_combinator;
@@ -10,11 +11,14 @@ mod give_combinator;
 mod then_combinator;
 mod get_combinator;
 mod anytime_combinator;
+mod schedule_combinator;
 
 // Re-export combinators
 pub use self::contract_combinator::ContractCombinator;
 pub use self::contract_combinator::deserialize_combinator;
 pub use self::contract_combinator::Combinator;
+pub use self::contract_combinator::CombinatorDetails;
+pub use self::asset_amounts::{ AssetAmounts, ETHER_ASSET };
 pub use self::zero_combinator::ZeroCombinator;
 pub use self::one_combinator::OneCombinator;
 pub use self::and_combinator::AndCombinator;
@@ -24,4 +28,5 @@ pub use self::scale_combinator::ScaleCombinator;
 pub use self::give_combinator::GiveCombinator;
 pub use self::then_combinator::ThenCombinator;
 pub use self::get_combinator::GetCombinator;
-pub use self::anytime_combinator::AnytimeCombinator;
\ No newline at end of file
+pub use self::anytime_combinator::AnytimeCombinator;
+pub use self::schedule_combinator::ScheduleCombinator;
\ No newline at end of file
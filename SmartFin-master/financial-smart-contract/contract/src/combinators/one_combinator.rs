@@ -1,17 +1,27 @@
-use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, Vec, Box };
+use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, Vec, Box, vec };
+use super::asset_amounts::{ AssetAmounts, ETHER_ASSET };
 use storage::Storage;
 
-// The one combinator
+// The one combinator, one unit of the given asset (one wei for Ether)
 pub struct OneCombinator {
+    // The index of the asset paid
+    asset: usize,
+
     // The common combinator details
     combinator_details: CombinatorDetails
 }
 
 // Method implementation of the one combinator
 impl OneCombinator {
-    // Constructor
+    // Constructor (one wei)
     pub fn new() -> OneCombinator {
+        OneCombinator::new_asset(ETHER_ASSET)
+    }
+
+    // Constructor for one unit of the asset with the given asset index
+    pub fn new_asset(asset: usize) -> OneCombinator {
         OneCombinator {
+            asset,
             combinator_details: CombinatorDetails::new()
         }
     }
@@ -24,6 +34,21 @@ impl OneCombinator {
         (
             index + 2,
             Box::new(OneCombinator {
+                asset: ETHER_ASSET,
+                combinator_details: CombinatorDetails::deserialize([serialized_combinator[index], serialized_combinator[index + 1]])
+            })
+        )
+    }
+
+    // Deserialize a one combinator with a serialized asset index
+    pub fn deserialize_asset(index: usize, serialized_combinator: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        if index + 2 >= serialized_combinator.len() {
+            panic!("Attempted to deserialize ill-formed serialized OneCombinator.")
+        }
+        (
+            index + 3,
+            Box::new(OneCombinator {
+                asset: serialized_combinator[index + 2] as usize,
                 combinator_details: CombinatorDetails::deserialize([serialized_combinator[index], serialized_combinator[index + 1]])
             })
         )
@@ -33,15 +58,33 @@ impl OneCombinator {
 // Contract combinator implementation of the one combinator
 impl ContractCombinator for OneCombinator {
     fn get_combinator_number(&self) -> Combinator {
-        Combinator::ONE
+        if self.asset == ETHER_ASSET {
+            Combinator::ONE
+        } else {
+            Combinator::ONE_ASSET
+        }
     }
 
     fn get_combinator_details(&self) -> &CombinatorDetails {
         &self.combinator_details
     }
 
+    // Returns the asset index
+    fn get_parameters(&self) -> Vec<i64> {
+        vec![self.asset as i64]
+    }
+
+    // Returns the value paid out so far, one unit of the asset once fully updated
+    fn get_accrued(&self, _: &mut Storage) -> AssetAmounts {
+        if self.combinator_details.fully_updated {
+            AssetAmounts::new(self.asset, 1)
+        } else {
+            AssetAmounts::zero()
+        }
+    }
+
     // Acquires the combinator and acquirable sub-combinators
-    fn acquire(&mut self, time: u32, _: &mut Storage) {
+    fn acquire(&mut self, time: u64, _: &mut Storage) {
         if self.combinator_details.acquisition_time != None {
             panic!("Acquiring a previously-acquired one combinator is not allowed.");
         }
@@ -50,23 +93,33 @@ impl ContractCombinator for OneCombinator {
     }
 
     // Updates the combinator, returning the current balance to be paid from the holder to the counter-party
-    fn update(&mut self, time: u32, _: &mut Storage) -> i64 {
+    fn update(&mut self, time: u64, _: &mut Storage) -> AssetAmounts {
         // If not acquired yet or fully updated (no more pending balance), return 0
         if self.combinator_details.acquisition_time == None
             || self.combinator_details.acquisition_time.unwrap() > time
             || self.combinator_details.fully_updated {
-            return 0;
+            return AssetAmounts::zero();
         }
 
         self.combinator_details.fully_updated = true;
-        1
+        AssetAmounts::new(self.asset, 1)
+    }
+
+    // Serializes this combinator
+    fn serialize(&self) -> Vec<i64> {
+        let mut serialized = self.serialize_details();
+        if self.asset != ETHER_ASSET {
+            serialized.push(self.asset as i64);
+        }
+        serialized
     }
 }
 
 // Unit tests
 #[cfg(test)]
 mod tests {
-    use super::super::{ ContractCombinator, Combinator, OneCombinator };
+    use super::super::{ ContractCombinator, Combinator, OneCombinator, AssetAmounts };
+    use super::super::contract_combinator::{ vec };
     use storage::Storage;
 
     // Combinator number is correct
@@ -76,6 +129,20 @@ mod tests {
         assert_eq!(combinator.get_combinator_number(), Combinator::ONE);
     }
 
+    // Combinator number of a non-Ether one combinator is correct
+    #[test]
+    fn correct_asset_combinator_number() {
+        let combinator = OneCombinator::new_asset(1);
+        assert_eq!(combinator.get_combinator_number(), Combinator::ONE_ASSET);
+    }
+
+    // Parameters are the asset index
+    #[test]
+    fn correct_parameters() {
+        assert_eq!(OneCombinator::new().get_parameters(), vec![0]);
+        assert_eq!(OneCombinator::new_asset(2).get_parameters(), vec![2]);
+    }
+
     // Horizon is None
     #[test]
     fn correct_horizon() {
@@ -99,7 +166,7 @@ mod tests {
         let mut combinator = OneCombinator::new();
 
         // Acquire and check details
-        let time: u32 = 5;
+        let time: u64 = 5;
         combinator.acquire(time, &mut Storage::new());
         let combinator_details = combinator.get_combinator_details();
 
@@ -118,7 +185,7 @@ mod tests {
         let mut combinator = OneCombinator::new();
 
         // Acquire and check details
-        let time: u32 = 5;
+        let time: u64 = 5;
         combinator.acquire(time, &mut Storage::new());
         combinator.update(time, &mut Storage::new());
         let combinator_details = combinator.get_combinator_details();
@@ -148,6 +215,24 @@ mod tests {
         );
     }
 
+    // Acquiring and updating a non-Ether combinator returns one unit of the asset
+    #[test]
+    fn acquiring_and_updating_asset_returns_correct_value() {
+        // Create combinator one of asset 2
+        let mut combinator = OneCombinator::new_asset(2);
+
+        // Acquire and check value
+        combinator.acquire(0, &mut Storage::new());
+        let value = combinator.update(0, &mut Storage::new());
+
+        assert_eq!(
+            value,
+            AssetAmounts::new(2, 1),
+            "Acquisition value of one combinator is not equal to one of asset 2: {}",
+            value
+        );
+    }
+
     // Acquiring and updating combinator twice returns correct value
     #[test]
     fn acquiring_and_updating_twice_returns_correct_value() {
@@ -231,6 +316,21 @@ mod tests {
         assert_eq!(deserialized.serialize(), serialized)
     }
 
+    // Deserializing a non-Ether one-combinator is correct
+    #[test]
+    fn asset_deserialization_correct() {
+        let mut combinator = OneCombinator::new_asset(3);
+        let mut serialized = combinator.serialize();
+        let mut deserialized = OneCombinator::deserialize_asset(1, &serialized).1;
+        assert_eq!(deserialized.serialize(), serialized);
+
+        combinator.acquire(1, &mut Storage::new());
+        combinator.update(2, &mut Storage::new());
+        serialized = combinator.serialize();
+        deserialized = OneCombinator::deserialize_asset(1, &serialized).1;
+        assert_eq!(deserialized.serialize(), serialized)
+    }
+
     // Acquiring one-combinator twice is not allowed
     #[test]
     #[should_panic(expected = "Acquiring a previously-acquired one combinator is not allowed.")]
@@ -1,4 +1,5 @@
-use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, latest_time, deserialize_combinator, Box, Vec };
+use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, latest_time, deserialize_combinator, Box, Vec, vec };
+use super::asset_amounts::AssetAmounts;
 use { or_choices_key };
 use storage::*;
 
@@ -29,7 +30,7 @@ impl OrCombinator {
     }
 
     // Returns whether the current or-choice is the first sub-combinator
-    fn get_or_choice(&self, time: u32, storage: &mut Storage) -> Option<bool> {
+    fn get_or_choice(&self, time: u64, storage: &mut Storage) -> Option<bool> {
         if self.sub_combinator0.past_horizon(time) {
             Some(false)
         } else if self.sub_combinator1.past_horizon(time) {
@@ -66,16 +67,38 @@ impl ContractCombinator for OrCombinator {
     }
 
     // Returns the latest of the two sub-horizons
-    fn get_horizon(&self) -> Option<u32> {
+    fn get_horizon(&self) -> Option<u64> {
         latest_time(self.sub_combinator0.get_horizon(), self.sub_combinator1.get_horizon())
     }
 
+    // Resolves any relative times in both sub-combinators
+    fn resolve_relative_times(&mut self, base_time: u64) {
+        self.sub_combinator0.resolve_relative_times(base_time);
+        self.sub_combinator1.resolve_relative_times(base_time);
+    }
+
+    // Resets the anytime acquisition times in the sub-combinators
+    fn reset_anytime_acquisition_times(&self, storage: &mut Storage) {
+        self.sub_combinator0.reset_anytime_acquisition_times(storage);
+        self.sub_combinator1.reset_anytime_acquisition_times(storage);
+    }
+
     fn get_combinator_details(&self) -> &CombinatorDetails {
         &self.combinator_details
     }
 
+    // Returns the or index
+    fn get_index(&self) -> Option<usize> {
+        Some(self.or_index)
+    }
+
+    // Returns both sub-combinators
+    fn get_sub_combinators(&self) -> Vec<&Box<ContractCombinator>> {
+        vec![&self.sub_combinator0, &self.sub_combinator1]
+    }
+
     // Acquires the combinator and acquirable sub-combinators
-    fn acquire(&mut self, time: u32, storage: &mut Storage) {
+    fn acquire(&mut self, time: u64, storage: &mut Storage) {
         if self.past_horizon(time) {
             panic!("Cannot acquire an expired contract.");
         }
@@ -94,12 +117,12 @@ impl ContractCombinator for OrCombinator {
     }
 
     // Updates the combinator, returning the current balance to be paid from the holder to the counter-party
-    fn update(&mut self, time: u32, storage: &mut Storage) -> i64 {
+    fn update(&mut self, time: u64, storage: &mut Storage) -> AssetAmounts {
         // If not acquired yet or fully updated (no more pending balance), return 0
         if self.combinator_details.acquisition_time == None
             || self.combinator_details.acquisition_time.unwrap() > time
             || self.combinator_details.fully_updated {
-            return 0;
+            return AssetAmounts::zero();
         }
 
         let or_choice = self.get_or_choice(self.combinator_details.acquisition_time.unwrap(), storage);
@@ -108,7 +131,7 @@ impl ContractCombinator for OrCombinator {
         match or_choice {
             Some(true) => sub_combinator = &mut self.sub_combinator0,
             Some(false) => sub_combinator = &mut self.sub_combinator1,
-            None => return 0
+            None => return AssetAmounts::zero()
         }
 
         if sub_combinator.get_combinator_details().acquisition_time == None {
@@ -209,7 +232,7 @@ mod tests {
         );
 
         // Acquire and check details
-        let time: u32 = 5;
+        let time: u64 = 5;
         let mut storage = setup_storage(&vec![Some(true)]);
         combinator.acquire(time, &mut storage);
         let combinator_details = combinator.get_combinator_details();
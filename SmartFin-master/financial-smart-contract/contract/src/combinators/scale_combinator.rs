@@ -1,4 +1,5 @@
-use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, deserialize_combinator, Box, Vec, Address };
+use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, deserialize_combinator, Box, Vec, vec, Address };
+use super::asset_amounts::AssetAmounts;
 use storage::*;
 use { obs_values_key };
 
@@ -79,16 +80,52 @@ impl ContractCombinator for ScaleCombinator {
         Combinator::SCALE
     }
 
-    fn get_horizon(&self) -> Option<u32> {
+    fn get_horizon(&self) -> Option<u64> {
         self.sub_combinator.get_horizon()
     }
 
+    // Resolves any relative times in the sub-combinator
+    fn resolve_relative_times(&mut self, base_time: u64) {
+        self.sub_combinator.resolve_relative_times(base_time);
+    }
+
+    // Resets the anytime acquisition times in the sub-combinator
+    fn reset_anytime_acquisition_times(&self, storage: &mut Storage) {
+        self.sub_combinator.reset_anytime_acquisition_times(storage);
+    }
+
     fn get_combinator_details(&self) -> &CombinatorDetails {
         &self.combinator_details
     }
 
+    // Returns the observable index, if the scale value is an observable
+    fn get_index(&self) -> Option<usize> {
+        self.obs_index
+    }
+
+    // Returns the provided scale value, if the scale value is not an observable
+    fn get_parameters(&self) -> Vec<i64> {
+        match self.obs_index {
+            None => vec![self.scale_value.unwrap()],
+            Some(_) => Vec::new()
+        }
+    }
+
+    // Returns the sub-combinator
+    fn get_sub_combinators(&self) -> Vec<&Box<ContractCombinator>> {
+        vec![&self.sub_combinator]
+    }
+
+    // Returns the value paid out so far, the sub-combinator's scaled by the scale value once known
+    fn get_accrued(&self, storage: &mut Storage) -> AssetAmounts {
+        match self.get_scale_value(storage) {
+            Some(scale_value) => self.sub_combinator.get_accrued(storage) * scale_value,
+            None => AssetAmounts::zero()
+        }
+    }
+
     // Acquires the combinator and acquirable sub-combinators
-    fn acquire(&mut self, time: u32, storage: &mut Storage) {
+    fn acquire(&mut self, time: u64, storage: &mut Storage) {
         if self.past_horizon(time) {
             panic!("Cannot acquire an expired contract.");
         }
@@ -101,7 +138,7 @@ impl ContractCombinator for ScaleCombinator {
     }
 
     // Updates the combinator, returning the current balance to be paid from the holder to the counter-party
-    fn update(&mut self, time: u32, storage: &mut Storage) -> i64 {
+    fn update(&mut self, time: u64, storage: &mut Storage) -> AssetAmounts {
         let scale_value = self.get_scale_value(storage);
 
         // If not acquired yet or fully updated (no more pending balance), return 0
@@ -110,12 +147,12 @@ impl ContractCombinator for ScaleCombinator {
             || self.combinator_details.fully_updated
             // If no scale value or obs value, don't update
             || scale_value == None {
-            return 0;
+            return AssetAmounts::zero();
         }
 
         let sub_value = self.sub_combinator.update(time, storage);
         self.combinator_details.fully_updated = self.sub_combinator.get_combinator_details().fully_updated;
-        scale_value.unwrap() * sub_value
+        sub_value * scale_value.unwrap()
     }
 
     // Serializes this combinator
@@ -161,6 +198,13 @@ mod tests {
         assert_eq!(combinator.get_combinator_number(), Combinator::SCALE);
     }
 
+    // Parameters are the provided scale value, or nothing for observables
+    #[test]
+    fn correct_parameters() {
+        assert_eq!(ScaleCombinator::new(Box::new(OneCombinator::new()), None, Some(-3)).get_parameters(), vec![-3]);
+        assert_eq!(ScaleCombinator::new(Box::new(OneCombinator::new()), Some(0), None).get_parameters(), vec![]);
+    }
+
     // Horizon is equal to sub-combinator's horizon
     #[test]
     fn horizon_equals_sub_combinator_horizon() {
@@ -191,7 +235,7 @@ mod tests {
         let mut combinator = ScaleCombinator::new(Box::new(OneCombinator::new()), None, Some(5));
 
         // Acquire and check details
-        let time: u32 = 5;
+        let time: u64 = 5;
         let mut storage = setup_storage(&vec![]);
         combinator.acquire(time, &mut storage);
         let combinator_details = combinator.get_combinator_details();
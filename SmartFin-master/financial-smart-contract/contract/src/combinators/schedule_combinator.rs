@@ -0,0 +1,628 @@
+use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, deserialize_combinator, Box, Vec, vec };
+use super::asset_amounts::AssetAmounts;
+use storage::Storage;
+
+// The schedule combinator, acquires a copy of the sub-combinator at each of count dates (start, start + period, ...)
+pub struct ScheduleCombinator {
+    // The sub-combinator template, never acquired, copied at the start of each period
+    sub_combinator_template: Box<ContractCombinator>,
+
+    // The sub-combinator of the current period
+    sub_combinator: Box<ContractCombinator>,
+
+    // The date of the first period (an offset from the contract acquisition time if relative)
+    start: u64,
+
+    // The time between two periods
+    period: u64,
+
+    // The number of periods
+    count: u64,
+
+    // The index of the current period (count once all periods have been paid out)
+    current_period: u64,
+
+    // Whether the first period date is relative to the contract acquisition time
+    relative: bool,
+
+    // The contract acquisition time, once known (only used if relative)
+    base_time: Option<u64>,
+
+    // The value paid out over all periods so far
+    accrued: AssetAmounts,
+
+    // The common combinator details
+    combinator_details: CombinatorDetails
+}
+
+// Method implementation for the schedule combinator
+impl ScheduleCombinator {
+    pub fn new(sub_combinator: Box<ContractCombinator>, start: u64, period: u64, count: u64) -> ScheduleCombinator {
+        ScheduleCombinator::new_schedule(sub_combinator, start, period, count, false)
+    }
+
+    // Constructor for a schedule combinator whose first period is the given offset from the contract acquisition time
+    pub fn new_relative(sub_combinator: Box<ContractCombinator>, start_offset: u64, period: u64, count: u64) -> ScheduleCombinator {
+        ScheduleCombinator::new_schedule(sub_combinator, start_offset, period, count, true)
+    }
+
+    fn new_schedule(sub_combinator: Box<ContractCombinator>, start: u64, period: u64, count: u64, relative: bool) -> ScheduleCombinator {
+        if count == 0 {
+            panic!("Schedule combinator must have at least one period.");
+        }
+
+        ScheduleCombinator {
+            sub_combinator: ScheduleCombinator::copy_sub_combinator(&sub_combinator),
+            sub_combinator_template: sub_combinator,
+            start,
+            period,
+            count,
+            current_period: 0,
+            relative,
+            base_time: None,
+            accrued: AssetAmounts::zero(),
+            combinator_details: CombinatorDetails::new()
+        }
+    }
+
+    // Deserialize
+    pub fn deserialize(index: usize, serialized_combinator: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        if index + 5 >= serialized_combinator.len() {
+            panic!("Attempted to deserialize ill-formed serialized ScheduleCombinator.")
+        }
+        let (index0, accrued) = AssetAmounts::deserialize(index + 6, serialized_combinator);
+        let (index1, sub_combinator_template) = deserialize_combinator(index0, serialized_combinator);
+        let (index2, sub_combinator) = deserialize_combinator(index1, serialized_combinator);
+
+        (
+            index2,
+            Box::new(ScheduleCombinator {
+                sub_combinator_template,
+                sub_combinator,
+                start: serialized_combinator[index + 2] as u64,
+                period: serialized_combinator[index + 3] as u64,
+                count: serialized_combinator[index + 4] as u64,
+                current_period: serialized_combinator[index + 5] as u64,
+                relative: false,
+                base_time: None,
+                accrued,
+                combinator_details: CombinatorDetails::deserialize([serialized_combinator[index], serialized_combinator[index + 1]])
+            })
+        )
+    }
+
+    // Deserialize a relative schedule combinator
+    pub fn deserialize_relative(index: usize, serialized_combinator: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        if index + 6 >= serialized_combinator.len() {
+            panic!("Attempted to deserialize ill-formed serialized ScheduleCombinator.")
+        }
+        let (index0, accrued) = AssetAmounts::deserialize(index + 7, serialized_combinator);
+        let (index1, sub_combinator_template) = deserialize_combinator(index0, serialized_combinator);
+        let (index2, sub_combinator) = deserialize_combinator(index1, serialized_combinator);
+        let base_time = serialized_combinator[index + 6];
+
+        (
+            index2,
+            Box::new(ScheduleCombinator {
+                sub_combinator_template,
+                sub_combinator,
+                start: serialized_combinator[index + 2] as u64,
+                period: serialized_combinator[index + 3] as u64,
+                count: serialized_combinator[index + 4] as u64,
+                current_period: serialized_combinator[index + 5] as u64,
+                relative: true,
+                base_time: if base_time >= 0 { Some(base_time as u64) } else { None },
+                accrued,
+                combinator_details: CombinatorDetails::deserialize([serialized_combinator[index], serialized_combinator[index + 1]])
+            })
+        )
+    }
+
+    // Creates an unacquired copy of the given sub-combinator
+    fn copy_sub_combinator(sub_combinator: &Box<ContractCombinator>) -> Box<ContractCombinator> {
+        deserialize_combinator(0, &sub_combinator.serialize()).1
+    }
+
+    // Returns the date of the first period, or None if it is relative to a contract acquisition time which is not yet known
+    fn get_start(&self) -> Option<u64> {
+        if self.relative {
+            self.base_time.map(|base_time| base_time.saturating_add(self.start))
+        } else {
+            Some(self.start)
+        }
+    }
+
+    // Returns the date of the given period, or None if it is not yet known
+    fn get_period_date(&self, period_index: u64) -> Option<u64> {
+        self.get_start().map(|start| start.saturating_add(self.period.saturating_mul(period_index)))
+    }
+
+    // Returns the date of the last period at which the sub-combinator can still be acquired, or None if not yet known
+    fn get_last_period_date(&self) -> Option<u64> {
+        let start = self.get_start()?;
+        let last_date = self.get_period_date(self.count - 1)?;
+        match self.sub_combinator_template.get_horizon() {
+            None => Some(last_date),
+            Some(horizon) => if horizon >= last_date {
+                Some(last_date)
+            } else if horizon < start || self.period == 0 {
+                // No period can acquire the sub-combinator, the horizon is the sub-horizon
+                Some(horizon)
+            } else {
+                self.get_period_date((horizon - start) / self.period)
+            }
+        }
+    }
+
+    // Moves on to the next period, replacing the current sub-combinator with a fresh copy of the template. Any anytime
+    // acquisition times it shares with the previous period are reset, those of the last period are kept.
+    fn next_period(&mut self, storage: &mut Storage) {
+        self.current_period += 1;
+        self.sub_combinator = ScheduleCombinator::copy_sub_combinator(&self.sub_combinator_template);
+        if self.current_period < self.count {
+            self.sub_combinator.reset_anytime_acquisition_times(storage);
+        }
+    }
+}
+
+// Contract combinator implementation for the schedule combinator
+impl ContractCombinator for ScheduleCombinator {
+    fn get_combinator_number(&self) -> Combinator {
+        if self.relative { Combinator::SCHEDULE_RELATIVE } else { Combinator::SCHEDULE }
+    }
+
+    // Returns the date of the last period whose sub-combinator can still be acquired
+    fn get_horizon(&self) -> Option<u64> {
+        self.get_last_period_date()
+    }
+
+    // Resolves the first period date (if relative) and any relative times in the sub-combinators
+    fn resolve_relative_times(&mut self, base_time: u64) {
+        if self.relative {
+            self.base_time = Some(base_time);
+        }
+        self.sub_combinator_template.resolve_relative_times(base_time);
+        self.sub_combinator.resolve_relative_times(base_time);
+    }
+
+    // Resets the anytime acquisition times in the sub-combinator of the current period
+    fn reset_anytime_acquisition_times(&self, storage: &mut Storage) {
+        self.sub_combinator.reset_anytime_acquisition_times(storage);
+    }
+
+    fn get_combinator_details(&self) -> &CombinatorDetails {
+        &self.combinator_details
+    }
+
+    // Returns the first period date (-1 if not yet known), the period length and the period count
+    fn get_parameters(&self) -> Vec<i64> {
+        vec![self.get_start().map(|start| start as i64).unwrap_or(-1), self.period as i64, self.count as i64]
+    }
+
+    // Returns the sub-combinator of the current period
+    fn get_sub_combinators(&self) -> Vec<&Box<ContractCombinator>> {
+        vec![&self.sub_combinator]
+    }
+
+    // Returns the value paid out over all periods so far
+    fn get_accrued(&self, _: &mut Storage) -> AssetAmounts {
+        self.accrued.clone()
+    }
+
+    // Acquires the combinator, skipping any periods which started before the acquisition time
+    fn acquire(&mut self, time: u64, _: &mut Storage) {
+        if self.get_start() == None {
+            panic!("Relative combinator cannot be acquired before the contract acquisition time is known.");
+        }
+        if self.past_horizon(time) {
+            panic!("Cannot acquire an expired contract.");
+        }
+        if self.combinator_details.acquisition_time != None {
+            panic!("Acquiring a previously-acquired schedule combinator is not allowed.");
+        }
+
+        while self.current_period < self.count && self.get_period_date(self.current_period).unwrap() < time {
+            self.current_period += 1;
+        }
+
+        self.combinator_details.acquisition_time = Some(time);
+    }
+
+    // Updates the combinator, returning the current balance to be paid from the holder to the counter-party.
+    // Periods are paid out in order, a period's sub-combinator is only acquired once the previous one is fully updated.
+    fn update(&mut self, time: u64, storage: &mut Storage) -> AssetAmounts {
+        // If not acquired yet or fully updated (no more pending balance), return 0
+        if self.combinator_details.acquisition_time == None
+            || self.combinator_details.acquisition_time.unwrap() > time
+            || self.combinator_details.fully_updated {
+            return AssetAmounts::zero();
+        }
+
+        let mut value = AssetAmounts::zero();
+        while self.current_period < self.count {
+            let date = self.get_period_date(self.current_period).unwrap();
+            if date > time {
+                break;
+            }
+
+            // Acquire the current period's sub-combinator at the period date
+            if self.sub_combinator.get_combinator_details().acquisition_time == None {
+                if self.sub_combinator.past_horizon(date) {
+                    // Later periods start even later, so no more sub-combinators can be acquired
+                    self.current_period = self.count;
+                    break;
+                }
+                self.sub_combinator.acquire(date, storage);
+            }
+
+            value += self.sub_combinator.update(time, storage);
+            if !self.sub_combinator.get_combinator_details().fully_updated {
+                break;
+            }
+
+            self.next_period(storage);
+        }
+
+        self.combinator_details.fully_updated = self.current_period >= self.count;
+        self.accrued += value.clone();
+        value
+    }
+
+    // Serializes this combinator
+    fn serialize(&self) -> Vec<i64> {
+        let mut serialized = self.serialize_details();
+        serialized.push(self.start as i64);
+        serialized.push(self.period as i64);
+        serialized.push(self.count as i64);
+        serialized.push(self.current_period as i64);
+        if self.relative {
+            serialized.push(match self.base_time {
+                Some(time) => time as i64,
+                None => -1
+            });
+        }
+        serialized.extend_from_slice(&self.accrued.serialize());
+        serialized.extend_from_slice(&self.sub_combinator_template.serialize());
+        serialized.extend_from_slice(&self.sub_combinator.serialize());
+        serialized
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::super::{ ContractCombinator, Combinator, ScheduleCombinator, OneCombinator, TruncateCombinator };
+    use super::super::contract_combinator::{ Box, vec };
+    use storage::Storage;
+
+    // Combinator number is correct
+    #[test]
+    fn correct_combinator_number() {
+        let combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 0, 1, 1);
+        assert_eq!(combinator.get_combinator_number(), Combinator::SCHEDULE);
+    }
+
+    // Parameters are the first period date, period length and period count, the date only once relative times are resolved
+    #[test]
+    fn correct_parameters() {
+        assert_eq!(ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 3).get_parameters(), vec![10, 5, 3]);
+
+        let mut combinator = ScheduleCombinator::new_relative(Box::new(OneCombinator::new()), 10, 5, 3);
+        assert_eq!(combinator.get_parameters(), vec![-1, 5, 3]);
+        combinator.resolve_relative_times(100);
+        assert_eq!(combinator.get_parameters(), vec![110, 5, 3]);
+    }
+
+    // Horizon is the date of the last period
+    #[test]
+    fn correct_horizon() {
+        // Create schedule 10 5 3 one
+        let combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 3);
+
+        // Check horizon = 20
+        let horizon = combinator.get_horizon();
+        assert_eq!(
+            horizon,
+            Some(20),
+            "Horizon of 'schedule 10 5 3 one' contract is not equal to Some(20): {:?}",
+            horizon
+        );
+    }
+
+    // Horizon is the last period date before the sub-combinator expires
+    #[test]
+    fn correct_horizon_sub_combinator_expires_first() {
+        // Create schedule 10 5 3 truncate 17 one
+        let combinator = ScheduleCombinator::new(
+            Box::new(TruncateCombinator::new(Box::new(OneCombinator::new()), 17)),
+            10,
+            5,
+            3
+        );
+
+        // Check horizon = 15
+        let horizon = combinator.get_horizon();
+        assert_eq!(
+            horizon,
+            Some(15),
+            "Horizon of 'schedule 10 5 3 truncate 17 one' contract is not equal to Some(15): {:?}",
+            horizon
+        );
+    }
+
+    // Acquiring combinator sets combinator details correctly
+    #[test]
+    fn acquiring_sets_combinator_details() {
+        // Create schedule 10 5 3 one
+        let mut combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 3);
+
+        // Acquire and check details
+        let time: u64 = 1;
+        combinator.acquire(time, &mut Storage::new());
+        let combinator_details = combinator.get_combinator_details();
+
+        assert_eq!(
+            combinator_details.acquisition_time,
+            Some(time),
+            "Acquisition time of combinator is not equal to Some(1): {:?}",
+            combinator_details.acquisition_time
+        );
+    }
+
+    // Updating before the first period returns 0
+    #[test]
+    fn updating_before_first_period_returns_correct_value() {
+        // Create schedule 10 5 3 one
+        let mut combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 3);
+
+        // Acquire and check value
+        combinator.acquire(0, &mut Storage::new());
+        let value = combinator.update(9, &mut Storage::new());
+
+        assert_eq!(
+            value,
+            0,
+            "Update value of schedule 10 5 3 one at time = 9 is not equal to 0: {}",
+            value
+        );
+    }
+
+    // Updating pays out every period which has started
+    #[test]
+    fn updating_pays_out_started_periods() {
+        // Create schedule 10 5 3 one
+        let mut combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 3);
+
+        // Acquire and check values
+        combinator.acquire(0, &mut Storage::new());
+        let value0 = combinator.update(15, &mut Storage::new());
+        let value1 = combinator.update(16, &mut Storage::new());
+        let value2 = combinator.update(20, &mut Storage::new());
+
+        assert_eq!(value0, 2, "Update value of schedule 10 5 3 one at time = 15 is not equal to 2: {}", value0);
+        assert_eq!(value1, 0, "Update value of schedule 10 5 3 one at time = 16 is not equal to 0: {}", value1);
+        assert_eq!(value2, 1, "Update value of schedule 10 5 3 one at time = 20 is not equal to 1: {}", value2);
+    }
+
+    // The accrued value is the total paid out over all periods so far, and survives serialization
+    #[test]
+    fn accrued_value_is_total_paid_out() {
+        // Create schedule 10 5 3 one
+        let mut combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 3);
+
+        // Acquire, update and check accrued value
+        combinator.acquire(0, &mut Storage::new());
+        combinator.update(15, &mut Storage::new());
+        let deserialized = ScheduleCombinator::deserialize(1, &combinator.serialize()).1;
+
+        let accrued = deserialized.get_accrued(&mut Storage::new());
+        assert_eq!(accrued, 2, "Accrued value of schedule 10 5 3 one at time = 15 is not equal to 2: {}", accrued);
+    }
+
+    // Acquiring after some periods have started skips those periods
+    #[test]
+    fn acquiring_skips_started_periods() {
+        // Create schedule 10 5 3 one
+        let mut combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 3);
+
+        // Acquire after the first period and check value
+        combinator.acquire(11, &mut Storage::new());
+        let value = combinator.update(30, &mut Storage::new());
+
+        assert_eq!(
+            value,
+            2,
+            "Update value of schedule 10 5 3 one acquired at time = 11 is not equal to 2: {}",
+            value
+        );
+    }
+
+    // Updating after the last period sets fully updated
+    #[test]
+    fn updating_after_last_period_sets_fully_updated() {
+        // Create schedule 10 5 3 one
+        let mut combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 3);
+
+        combinator.acquire(0, &mut Storage::new());
+        combinator.update(15, &mut Storage::new());
+        assert!(!combinator.get_combinator_details().fully_updated, "fully_updated is not false after 2 of 3 periods");
+
+        combinator.update(20, &mut Storage::new());
+        let fully_updated = combinator.get_combinator_details().fully_updated;
+        assert!(
+            fully_updated,
+            "fully_updated is not true: {}",
+            fully_updated
+        );
+    }
+
+    // Periods after the sub-combinator's horizon are not paid out
+    #[test]
+    fn updating_skips_periods_after_sub_horizon() {
+        // Create schedule 10 5 3 truncate 17 one
+        let mut combinator = ScheduleCombinator::new(
+            Box::new(TruncateCombinator::new(Box::new(OneCombinator::new()), 17)),
+            10,
+            5,
+            3
+        );
+
+        combinator.acquire(0, &mut Storage::new());
+        let value = combinator.update(30, &mut Storage::new());
+
+        assert_eq!(value, 2, "Update value of schedule 10 5 3 truncate 17 one is not equal to 2: {}", value);
+        assert!(combinator.get_combinator_details().fully_updated, "fully_updated is not true");
+    }
+
+    // Updating before acquiring does not set fully updated, and returns correct value
+    #[test]
+    fn updating_before_acquiring_does_nothing() {
+        // Create schedule 0 1 1 one
+        let mut combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 0, 1, 1);
+
+        // Update check details
+        let value = combinator.update(0, &mut Storage::new());
+        let combinator_details = combinator.get_combinator_details();
+
+        assert!(
+            !combinator_details.fully_updated,
+            "fully_updated != false: {}",
+            combinator_details.fully_updated
+        );
+
+        assert_eq!(
+            value,
+            0,
+            "Value of updating before acquiring != 0: {}",
+            value
+        )
+    }
+
+    // Serialized size does not depend on the number of periods
+    #[test]
+    fn serialization_size_independent_of_count() {
+        let combinator0 = ScheduleCombinator::new(Box::new(OneCombinator::new()), 0, 1, 1);
+        let combinator1 = ScheduleCombinator::new(Box::new(OneCombinator::new()), 0, 1, 1000);
+        assert_eq!(combinator0.serialize().len(), combinator1.serialize().len());
+    }
+
+    // Deserializing schedule-combinator is correct
+    #[test]
+    fn deserialization_correct() {
+        let mut combinator = ScheduleCombinator::new(
+            Box::new(TruncateCombinator::new(Box::new(OneCombinator::new()), 100)),
+            10,
+            5,
+            3
+        );
+        combinator.acquire(1, &mut Storage::new());
+        combinator.update(15, &mut Storage::new());
+
+        let serialized = combinator.serialize();
+        let deserialized = ScheduleCombinator::deserialize(1, &serialized).1;
+        assert_eq!(deserialized.serialize(), serialized);
+    }
+
+    // Acquiring combinator twice is not allowed
+    #[test]
+    #[should_panic(expected = "Acquiring a previously-acquired schedule combinator is not allowed.")]
+    fn should_panic_when_acquiring_combinator_twice() {
+        // Create schedule 10 5 3 one
+        let mut combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 3);
+
+        // Acquire twice
+        combinator.acquire(0, &mut Storage::new());
+        combinator.acquire(0, &mut Storage::new());
+    }
+
+    // Acquiring combinator after the last period is not allowed
+    #[test]
+    #[should_panic(expected = "Cannot acquire an expired contract.")]
+    fn should_panic_when_acquiring_post_expiry() {
+        // Create schedule 10 5 3 one
+        let mut combinator = ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 3);
+
+        // Acquire at time = 21
+        combinator.acquire(21, &mut Storage::new());
+    }
+
+    // A schedule without periods is not allowed
+    #[test]
+    #[should_panic(expected = "Schedule combinator must have at least one period.")]
+    fn should_panic_when_count_is_zero() {
+        ScheduleCombinator::new(Box::new(OneCombinator::new()), 10, 5, 0);
+    }
+
+    // Relative combinator number is correct
+    #[test]
+    fn correct_relative_combinator_number() {
+        let combinator = ScheduleCombinator::new_relative(Box::new(OneCombinator::new()), 0, 1, 1);
+        assert_eq!(combinator.get_combinator_number(), Combinator::SCHEDULE_RELATIVE);
+    }
+
+    // Relative horizon is None before the contract acquisition time is known, and offset from it afterwards
+    #[test]
+    fn correct_relative_horizon() {
+        // Create schedule +10 5 3 one
+        let mut combinator = ScheduleCombinator::new_relative(Box::new(OneCombinator::new()), 10, 5, 3);
+
+        let mut horizon = combinator.get_horizon();
+        assert_eq!(
+            horizon,
+            None,
+            "Horizon of unresolved 'schedule +10 5 3 one' contract is not equal to None: {:?}",
+            horizon
+        );
+
+        // Resolve at time = 100, check horizon = 120
+        combinator.resolve_relative_times(100);
+        horizon = combinator.get_horizon();
+        assert_eq!(
+            horizon,
+            Some(120),
+            "Horizon of 'schedule +10 5 3 one' contract resolved at 100 is not equal to Some(120): {:?}",
+            horizon
+        );
+    }
+
+    // Updating a relative schedule pays out periods relative to the contract acquisition time
+    #[test]
+    fn updating_relative_schedule_pays_out_started_periods() {
+        // Create schedule +10 5 3 one, acquired at time = 100
+        let mut combinator = ScheduleCombinator::new_relative(Box::new(OneCombinator::new()), 10, 5, 3);
+        combinator.resolve_relative_times(100);
+        combinator.acquire(100, &mut Storage::new());
+
+        let value0 = combinator.update(109, &mut Storage::new());
+        let value1 = combinator.update(115, &mut Storage::new());
+
+        assert_eq!(value0, 0, "Update value of schedule +10 5 3 one at time = 109 is not equal to 0: {}", value0);
+        assert_eq!(value1, 2, "Update value of schedule +10 5 3 one at time = 115 is not equal to 2: {}", value1);
+    }
+
+    // Deserializing relative schedule-combinator is correct
+    #[test]
+    fn relative_deserialization_correct() {
+        let mut combinator = ScheduleCombinator::new_relative(
+            Box::new(TruncateCombinator::new_relative(Box::new(OneCombinator::new()), 100)),
+            10,
+            5,
+            3
+        );
+        combinator.resolve_relative_times(1);
+        combinator.acquire(1, &mut Storage::new());
+        combinator.update(16, &mut Storage::new());
+
+        let serialized = combinator.serialize();
+        let deserialized = ScheduleCombinator::deserialize_relative(1, &serialized).1;
+        assert_eq!(deserialized.serialize(), serialized);
+    }
+
+    // Acquiring an unresolved relative combinator is not allowed
+    #[test]
+    #[should_panic(expected = "Relative combinator cannot be acquired before the contract acquisition time is known.")]
+    fn should_panic_when_acquiring_unresolved_relative() {
+        let mut combinator = ScheduleCombinator::new_relative(Box::new(OneCombinator::new()), 10, 5, 3);
+        combinator.acquire(0, &mut Storage::new());
+    }
+}
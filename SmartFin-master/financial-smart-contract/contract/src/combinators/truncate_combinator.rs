@@ -1,4 +1,5 @@
-use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, earliest_time, deserialize_combinator, Box, Vec };
+use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, earliest_time, deserialize_combinator, Box, Vec, vec };
+use super::asset_amounts::AssetAmounts;
 use storage::Storage;
 
 // The truncate combinator
@@ -6,8 +7,14 @@ pub struct TruncateCombinator {
     // The sub-combinator
     sub_combinator: Box<ContractCombinator>,
 
-    // The truncated horizon
-    truncated_horizon: u32,
+    // The truncated horizon (an offset from the contract acquisition time if relative)
+    truncated_horizon: u64,
+
+    // Whether the truncated horizon is relative to the contract acquisition time
+    relative: bool,
+
+    // The contract acquisition time, once known (only used if relative)
+    base_time: Option<u64>,
 
     // The common combinator details
     combinator_details: CombinatorDetails
@@ -15,10 +22,23 @@ pub struct TruncateCombinator {
 
 // Method implementation for the truncate combinator
 impl TruncateCombinator {
-    pub fn new(sub_combinator: Box<ContractCombinator>, truncated_horizon: u32) -> TruncateCombinator {
+    pub fn new(sub_combinator: Box<ContractCombinator>, truncated_horizon: u64) -> TruncateCombinator {
         TruncateCombinator {
             sub_combinator,
             truncated_horizon,
+            relative: false,
+            base_time: None,
+            combinator_details: CombinatorDetails::new()
+        }
+    }
+
+    // Constructor for a truncate combinator whose horizon is the given offset from the contract acquisition time
+    pub fn new_relative(sub_combinator: Box<ContractCombinator>, horizon_offset: u64) -> TruncateCombinator {
+        TruncateCombinator {
+            sub_combinator,
+            truncated_horizon: horizon_offset,
+            relative: true,
+            base_time: None,
             combinator_details: CombinatorDetails::new()
         }
     }
@@ -34,30 +54,87 @@ impl TruncateCombinator {
             index0,
             Box::new(TruncateCombinator {
                 sub_combinator,
-                truncated_horizon: serialized_combinator[index + 2] as u32,
+                truncated_horizon: serialized_combinator[index + 2] as u64,
+                relative: false,
+                base_time: None,
                 combinator_details: CombinatorDetails::deserialize([serialized_combinator[index], serialized_combinator[index + 1]])
             })
         )
     }
+
+    // Deserialize a relative truncate combinator
+    pub fn deserialize_relative(index: usize, serialized_combinator: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        if index + 3 >= serialized_combinator.len() {
+            panic!("Attempted to deserialize ill-formed serialized TruncateCombinator.")
+        }
+        let (index0, sub_combinator) = deserialize_combinator(index + 4, serialized_combinator);
+        let base_time = serialized_combinator[index + 3];
+
+        (
+            index0,
+            Box::new(TruncateCombinator {
+                sub_combinator,
+                truncated_horizon: serialized_combinator[index + 2] as u64,
+                relative: true,
+                base_time: if base_time >= 0 { Some(base_time as u64) } else { None },
+                combinator_details: CombinatorDetails::deserialize([serialized_combinator[index], serialized_combinator[index + 1]])
+            })
+        )
+    }
+
+    // Returns the truncated horizon, or None if it is relative to a contract acquisition time which is not yet known
+    fn get_truncated_horizon(&self) -> Option<u64> {
+        if self.relative {
+            self.base_time.map(|base_time| base_time.saturating_add(self.truncated_horizon))
+        } else {
+            Some(self.truncated_horizon)
+        }
+    }
 }
 
 // Contract combinator implementation for the truncate combinator
 impl ContractCombinator for TruncateCombinator {
     fn get_combinator_number(&self) -> Combinator {
-        Combinator::TRUNCATE
+        if self.relative { Combinator::TRUNCATE_RELATIVE } else { Combinator::TRUNCATE }
     }
 
     // Returns the latest of the sub-horizon and the truncated horizon
-    fn get_horizon(&self) -> Option<u32> {
-        earliest_time(self.sub_combinator.get_horizon(), Some(self.truncated_horizon))
+    fn get_horizon(&self) -> Option<u64> {
+        earliest_time(self.sub_combinator.get_horizon(), self.get_truncated_horizon())
+    }
+
+    // Resolves the truncated horizon (if relative) and any relative sub-combinator times
+    fn resolve_relative_times(&mut self, base_time: u64) {
+        if self.relative {
+            self.base_time = Some(base_time);
+        }
+        self.sub_combinator.resolve_relative_times(base_time);
+    }
+
+    // Resets the anytime acquisition times in the sub-combinator
+    fn reset_anytime_acquisition_times(&self, storage: &mut Storage) {
+        self.sub_combinator.reset_anytime_acquisition_times(storage);
     }
 
     fn get_combinator_details(&self) -> &CombinatorDetails {
         &self.combinator_details
     }
 
+    // Returns the truncated horizon, -1 until relative times are resolved
+    fn get_parameters(&self) -> Vec<i64> {
+        vec![self.get_truncated_horizon().map(|time| time as i64).unwrap_or(-1)]
+    }
+
+    // Returns the sub-combinator
+    fn get_sub_combinators(&self) -> Vec<&Box<ContractCombinator>> {
+        vec![&self.sub_combinator]
+    }
+
     // Acquires the combinator and acquirable sub-combinators
-    fn acquire(&mut self, time: u32, storage: &mut Storage) {
+    fn acquire(&mut self, time: u64, storage: &mut Storage) {
+        if self.relative && self.base_time == None {
+            panic!("Relative combinator cannot be acquired before the contract acquisition time is known.");
+        }
         if self.past_horizon(time) {
             panic!("Cannot acquire an expired contract.");
         }
@@ -70,12 +147,12 @@ impl ContractCombinator for TruncateCombinator {
     }
 
     // Updates the combinator, returning the current balance to be paid from the holder to the counter-party
-    fn update(&mut self, time: u32, storage: &mut Storage) -> i64 {
+    fn update(&mut self, time: u64, storage: &mut Storage) -> AssetAmounts {
         // If not acquired yet or fully updated (no more pending balance), return 0
         if self.combinator_details.acquisition_time == None
             || self.combinator_details.acquisition_time.unwrap() > time
             || self.combinator_details.fully_updated {
-            return 0;
+            return AssetAmounts::zero();
         }
 
         let sub_value = self.sub_combinator.update(time, storage);
@@ -87,6 +164,12 @@ impl ContractCombinator for TruncateCombinator {
     fn serialize(&self) -> Vec<i64> {
         let mut serialized = self.serialize_details();
         serialized.push(self.truncated_horizon as i64);
+        if self.relative {
+            serialized.push(match self.base_time {
+                Some(time) => time as i64,
+                None => -1
+            });
+        }
         serialized.extend_from_slice(&self.sub_combinator.serialize());
         serialized
     }
@@ -96,7 +179,7 @@ impl ContractCombinator for TruncateCombinator {
 #[cfg(test)]
 mod tests {
     use super::super::{ ContractCombinator, Combinator, OneCombinator, TruncateCombinator };
-    use super::super::contract_combinator::{ Box };
+    use super::super::contract_combinator::{ Box, vec };
     use storage::Storage;
 
     // Combinator number is correct
@@ -105,6 +188,17 @@ mod tests {
         let combinator = TruncateCombinator::new(Box::new(OneCombinator::new()), 0);
         assert_eq!(combinator.get_combinator_number(), Combinator::TRUNCATE);
     }
+
+    // Parameters are the truncated horizon, only once relative times are resolved
+    #[test]
+    fn correct_parameters() {
+        assert_eq!(TruncateCombinator::new(Box::new(OneCombinator::new()), 5).get_parameters(), vec![5]);
+
+        let mut combinator = TruncateCombinator::new_relative(Box::new(OneCombinator::new()), 5);
+        assert_eq!(combinator.get_parameters(), vec![-1]);
+        combinator.resolve_relative_times(100);
+        assert_eq!(combinator.get_parameters(), vec![105]);
+    }
     
     // Horizon is correct
     #[test]
@@ -153,7 +247,7 @@ mod tests {
         );
 
         // Acquire and check details
-        let time: u32 = 1;
+        let time: u64 = 1;
         combinator.acquire(time, &mut Storage::new());
         let combinator_details = combinator.get_combinator_details();
 
@@ -292,7 +386,7 @@ mod tests {
         let combinator = TruncateCombinator::new(Box::new(sub_combinator), 3);
         let serialized = combinator.serialize();
         assert_eq!(serialized[0..3], combinator.serialize_details()[..]);
-        assert_eq!(serialized[3] as u32, combinator.truncated_horizon);
+        assert_eq!(serialized[3] as u64, combinator.truncated_horizon);
         assert_eq!(serialized[4..], sub_combinator_serialized[..]);
     }
 
@@ -336,4 +430,111 @@ mod tests {
         // Acquire at time = 3
         combinator.acquire(3, &mut Storage::new());
     }
+
+    // Relative combinator number is correct
+    #[test]
+    fn correct_relative_combinator_number() {
+        let combinator = TruncateCombinator::new_relative(Box::new(OneCombinator::new()), 0);
+        assert_eq!(combinator.get_combinator_number(), Combinator::TRUNCATE_RELATIVE);
+    }
+
+    // Relative horizon is None before the contract acquisition time is known
+    #[test]
+    fn relative_horizon_none_before_resolution() {
+        // Create truncate +5 one
+        let combinator = TruncateCombinator::new_relative(Box::from(OneCombinator::new()), 5);
+
+        // Check horizon = None
+        let horizon = combinator.get_horizon();
+        assert_eq!(
+            horizon,
+            None,
+            "Horizon of unresolved 'truncate +5 one' contract is not equal to None: {:?}",
+            horizon
+        );
+        assert!(!combinator.past_horizon(1000));
+    }
+
+    // Relative horizon is offset from the contract acquisition time once known
+    #[test]
+    fn correct_relative_horizon_after_resolution() {
+        // Create truncate +5 one, resolved at time = 10
+        let mut combinator = TruncateCombinator::new_relative(Box::from(OneCombinator::new()), 5);
+        combinator.resolve_relative_times(10);
+
+        // Check horizon = 15
+        let horizon = combinator.get_horizon();
+        assert_eq!(
+            horizon,
+            Some(15),
+            "Horizon of 'truncate +5 one' contract resolved at 10 is not equal to Some(15): {:?}",
+            horizon
+        );
+    }
+
+    // Resolving relative times also resolves relative sub-combinators
+    #[test]
+    fn resolving_relative_times_resolves_sub_combinators() {
+        // Create truncate 20 truncate +5 one, resolved at time = 10
+        let mut combinator = TruncateCombinator::new(
+            Box::from(TruncateCombinator::new_relative(
+                Box::from(OneCombinator::new()),
+                5
+            )),
+        20);
+        combinator.resolve_relative_times(10);
+
+        // Check horizon = 15
+        let horizon = combinator.get_horizon();
+        assert_eq!(
+            horizon,
+            Some(15),
+            "Horizon of 'truncate 20 truncate +5 one' contract resolved at 10 is not equal to Some(15): {:?}",
+            horizon
+        );
+    }
+
+    // Deserializing relative truncate-combinator is correct
+    #[test]
+    fn relative_deserialization_correct() {
+        let mut combinator = TruncateCombinator::new_relative(Box::new(OneCombinator::new()), 2);
+        let mut serialized = combinator.serialize();
+        let mut deserialized = TruncateCombinator::deserialize_relative(1, &serialized).1;
+        assert_eq!(deserialized.serialize(), serialized);
+
+        combinator.resolve_relative_times(1);
+        combinator.acquire(1, &mut Storage::new());
+        combinator.update(2, &mut Storage::new());
+        serialized = combinator.serialize();
+        deserialized = TruncateCombinator::deserialize_relative(1, &serialized).1;
+        assert_eq!(deserialized.serialize(), serialized);
+    }
+
+    // Acquiring a relative combinator post-expiry is not allowed
+    #[test]
+    #[should_panic(expected = "Cannot acquire an expired contract.")]
+    fn should_panic_when_acquiring_relative_post_expiry() {
+        // Create truncate +2 one, resolved at time = 0
+        let mut combinator = TruncateCombinator::new_relative(
+            Box::from(OneCombinator::new()),
+            2
+        );
+        combinator.resolve_relative_times(0);
+
+        // Acquire at time = 3
+        combinator.acquire(3, &mut Storage::new());
+    }
+
+    // Acquiring an unresolved relative combinator is not allowed
+    #[test]
+    #[should_panic(expected = "Relative combinator cannot be acquired before the contract acquisition time is known.")]
+    fn should_panic_when_acquiring_unresolved_relative() {
+        // Create truncate +2 one
+        let mut combinator = TruncateCombinator::new_relative(
+            Box::from(OneCombinator::new()),
+            2
+        );
+
+        combinator.acquire(0, &mut Storage::new());
+    }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use super::contract_combinator::{ Combinator, ContractCombinator, CombinatorDetails, Vec, Box };
+use super::asset_amounts::AssetAmounts;
 use storage::Storage;
 
 // The zero combinator
@@ -41,7 +42,7 @@ impl ContractCombinator for ZeroCombinator {
     }
 
     // Acquires the combinator and acquirable sub-combinators
-    fn acquire(&mut self, time: u32, _: &mut Storage) {
+    fn acquire(&mut self, time: u64, _: &mut Storage) {
         if self.combinator_details.acquisition_time != None {
             panic!("Acquiring a previously-acquired zero combinator is not allowed.");
         }
@@ -50,16 +51,16 @@ impl ContractCombinator for ZeroCombinator {
     }
 
     // Updates the combinator, returning the current balance to be paid from the holder to the counter-party
-    fn update(&mut self, time: u32, _: &mut Storage) -> i64 {
+    fn update(&mut self, time: u64, _: &mut Storage) -> AssetAmounts {
         // If not acquired yet or fully updated (no more pending balance), return 0
         if self.combinator_details.acquisition_time == None
             || self.combinator_details.acquisition_time.unwrap() > time
             || self.combinator_details.fully_updated {
-            return 0;
+            return AssetAmounts::zero();
         }
 
         self.combinator_details.fully_updated = true;
-        0
+        AssetAmounts::zero()
     }
 }
 
@@ -99,7 +100,7 @@ mod tests {
         let mut combinator = ZeroCombinator::new();
 
         // Acquire and check details
-        let time: u32 = 5;
+        let time: u64 = 5;
         combinator.acquire(time, &mut Storage::new());
         let combinator_details = combinator.get_combinator_details();
 
@@ -118,7 +119,7 @@ mod tests {
         let mut combinator = ZeroCombinator::new();
 
         // Acquire and check details
-        let time: u32 = 5;
+        let time: u64 = 5;
         combinator.acquire(time, &mut Storage::new());
         combinator.update(time, &mut Storage::new());
         let combinator_details = combinator.get_combinator_details();
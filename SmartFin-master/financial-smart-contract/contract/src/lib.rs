@@ -9,19 +9,58 @@ extern crate pwasm_abi;
 extern crate pwasm_abi_derive;
 extern crate pwasm_ethereum;
 extern crate pwasm_std;
+#[macro_use]
+extern crate lazy_static;
 
 mod combinators;
 pub mod storage;
+pub mod state_tree;
+pub mod builder;
+pub mod templates;
+pub mod simplify;
+pub mod registry;
+pub mod visitor;
+#[cfg(any(test, feature = "std"))]
+pub mod random;
 
 use pwasm_abi::eth::EndpointInterface;
 use pwasm_std::{ Box, Vec, types::{ Address, U256, H256 } };
 use pwasm_abi_derive::eth_abi;
 use combinators::*;
 use storage::*;
+use registry::get_combinator_entry;
 
-static CALL_GAS: i64 = 2300;
+pub use combinators::{ Combinator, CombinatorDetails, ContractCombinator, AssetAmounts, ETHER_ASSET };
+
+static TOKEN_CALL_GAS: u64 = 60000;
+
+// The ERC-20 function selectors for transferFrom(address,address,uint256) and transfer(address,uint256)
+static TRANSFER_FROM_SELECTOR: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+static TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+// The initial contents of a token call's return data buffer, left as they are if the token returns nothing (as some
+// non-standard tokens do), as this is not an ABI-encoded bool
+static TOKEN_NO_RETURN_DATA: [u8; 32] = [0xff; 32];
+
+// The operator permissions, combined as bit flags: setting or-choices, acquiring the contract and anytime sub-contracts,
+// withdrawing balances (paid to the party the operator acts for), and flagging disputable observable values
+pub static OPERATOR_CHOICES: u64 = 1;
+pub static OPERATOR_ACQUIRE: u64 = 2;
+pub static OPERATOR_WITHDRAW: u64 = 4;
+pub static OPERATOR_DISPUTE: u64 = 8;
+
+// How long after their timestamp signed observable reports may be submitted, in seconds
+pub static OBS_REPORT_VALIDITY: u64 = 60 * 60;
+
+// The address of the ecrecover precompiled contract, and the gas it is called with
+static ECRECOVER_ADDRESS: [u8; 20] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+static ECRECOVER_GAS: u64 = 5000;
+
+// The prefix of messages signed with eth_sign, followed by the length of the message (a 32-byte hash)
+static SIGNED_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n32";
 
 // Executed when the contract is called
+#[cfg(feature = "entry-points")]
 #[no_mangle]
 pub fn call() {
     // Dispatch contract call to contract endpoint with given input, return result
@@ -31,6 +70,7 @@ pub fn call() {
 }
 
 // Executed when the contract is deployed
+#[cfg(feature = "entry-points")]
 #[no_mangle]
 pub fn deploy() {
     // Dispatch contract constructor call with given input
@@ -69,6 +109,16 @@ fn last_updated_key() -> H256 {
     H256::from([5,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0])
 }
 
+// The storage key for the gas stipend forwarded with Ether payments
+fn gas_stipend_key() -> H256 {
+    H256::from([6,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0])
+}
+
+// The storage key for whether or not a withdrawal is being paid out
+fn withdrawal_lock_key() -> H256 {
+    H256::from([7,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0])
+}
+
 // The serialized combinator contract (obtained remotely) storage key
 fn serialized_remote_combinator_contract_key() -> H256 {
     // Store in own memory namespace as Vec storage size is not constant
@@ -105,11 +155,55 @@ fn serialized_local_combinator_contract_key() -> H256 {
     H256::from([0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,6])
 }
 
+// The assets (token address, holder balance, counter-party balance) storage key, asset index i is stored at position i - 1
+fn assets_key() -> H256 {
+    // Store in own memory namespace as Vec storage size is not constant
+    H256::from([0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,7])
+}
+
+// The operators (party, operator, permissions) storage key, revoked operators are kept with no permissions
+fn operators_key() -> H256 {
+    // Store in own memory namespace as Vec storage size is not constant
+    H256::from([0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,8])
+}
+
+// The storage key for the hashes of the signed observable reports submitted so far, so reports cannot be replayed
+fn obs_reports_key() -> H256 {
+    // Store in own memory namespace as Vec storage size is not constant
+    H256::from([0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,9])
+}
+
+// The storage key for the thresholds of the quorum observables, as observable index and threshold
+fn obs_quorums_key() -> H256 {
+    // Store in own memory namespace as Vec storage size is not constant
+    H256::from([0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,10])
+}
+
+// The storage key for the values submitted by each arbiter of the quorum observables, as observable index, arbiter and value
+fn obs_submissions_key() -> H256 {
+    // Store in own memory namespace as Vec storage size is not constant
+    H256::from([0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,11])
+}
+
+// The storage key for the dispute windows of the disputable observables, as observable index, window and resolver
+fn obs_disputes_key() -> H256 {
+    // Store in own memory namespace as Vec storage size is not constant
+    H256::from([0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,12])
+}
+
+// The storage key for the values awaiting the end of their dispute window, as value, time set and whether or not it was
+// flagged (stored at the same position as the observable's dispute window)
+fn obs_pending_values_key() -> H256 {
+    // Store in own memory namespace as Vec storage size is not constant
+    H256::from([0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,13])
+}
+
 // The financial smart contract interface
 #[eth_abi(FinancialScEndpoint)]
 pub trait FinancialScInterface {
-    // The contract constructor, takes the combinator contract definition (serialized) and the holder address
-    fn constructor(&mut self, contract_definition: Vec<i64>, holder: Address, use_gas: bool);
+    // The contract constructor, takes the combinator contract definition (serialized), the holder address, whether or not
+    // Ether payments forward the gas stipend and the gas stipend
+    fn constructor(&mut self, contract_definition: Vec<i64>, holder: Address, use_gas: bool, gas_stipend: u64);
 
     // Gets the address of the contract holder
     #[constant]
@@ -127,18 +221,36 @@ pub trait FinancialScInterface {
     #[constant]
     fn get_balance(&mut self, holderBalance: bool) -> i64;
 
+    // Gets the current balance of the given asset of the given party (the zero address is Ether)
+    #[constant]
+    fn get_asset_balance(&mut self, holderBalance: bool, asset: Address) -> i64;
+
+    // Gets the assets of the contract indexed by asset index (the zero address is Ether)
+    #[constant]
+    fn get_assets(&mut self) -> Vec<Address>;
+
     // Gets whether or not the contract has concluded all operation (i.e. updating will never change the balance).
     #[constant]
     fn get_concluded(&mut self) -> bool;
 
-    // Gets whether or not the contract allocates gas fees upon withdrawal.
+    // Gets whether or not Ether payments forward the gas stipend.
     #[constant]
     fn get_use_gas(&mut self) -> bool;
 
+    // Gets the gas stipend forwarded with Ether payments if using gas (paid for by the withdrawer's transaction, not deducted
+    // from the payment).
+    #[constant]
+    fn get_gas_stipend(&mut self) -> u64;
+
     // Gets the last-updated time.
     #[constant]
     fn get_last_updated(&mut self) -> i64;
 
+    // Gets the balances if the contract were updated at the given time with the current or choices and observable values,
+    // as [concluded (1 or 0), holder balance, counter-party balance] followed by the holder and counter-party balances of each other asset
+    #[constant]
+    fn get_projected_balances(&mut self, timestamp: u64) -> Vec<i64>;
+
     // Gets the contract acquisition times (top level acquisition time and anytime acquisition times)
     #[constant]
     fn get_acquisition_times(&mut self) -> Vec<i64>;
@@ -147,17 +259,54 @@ pub trait FinancialScInterface {
     #[constant]
     fn get_or_choices(&mut self) -> Vec<u8>;
 
-    // Gets the concrete observable values
+    // Gets the concrete observable values (quorum observables have the zero address as their arbiter)
     #[constant]
     fn get_obs_entries(&mut self) -> Vec<i64>;
 
+    // Gets the values submitted for the given observable, as its threshold and number of arbiters, followed by each arbiter's
+    // address and -1, or 0 followed by the value it submitted (an observable with a single arbiter has a threshold of 1)
+    #[constant]
+    fn get_obs_submissions(&mut self, obs_index: u64) -> Vec<i64>;
+
+    // Gets the dispute window of the given observable, as its window and resolver address followed by -1 if no value is set yet,
+    // or the value's status (0 awaiting the end of the window, 1 flagged, 2 settled), the time it was set and the value (empty
+    // if the observable is not disputable)
+    #[constant]
+    fn get_obs_dispute(&mut self, obs_index: u64) -> Vec<i64>;
+
+    // Gets the live state of every combinator in the contract (see state_tree for decoding)
+    #[constant]
+    fn get_state_tree(&mut self) -> Vec<i64>;
+
+    // Gets the permissions the given party (holder or counter-party) has approved for the given operator (0 if none)
+    #[constant]
+    fn get_operator_permissions(&mut self, party: Address, operator: Address) -> u64;
+
     // Sets the preference of the given or combinator's sub-combinators
     fn set_or_choice(&mut self, or_index: u64, choice: bool);
 
     // Sets a value for the given observable
     fn set_obs_value(&mut self, obs_index: u64, value: i64);
 
-    // Acquires the combinator contract at the current block-time (when called by the holder)
+    // Sets the preferences of the given or combinators' sub-combinators, then updates the contract if update_contract is set
+    fn set_or_choices(&mut self, or_indices: Vec<u64>, choices: Vec<bool>, update_contract: bool);
+
+    // Sets values for the given observables, then updates the contract if update_contract is set
+    fn set_obs_values(&mut self, obs_indices: Vec<u64>, values: Vec<i64>, update_contract: bool);
+
+    // Sets a value for the given observable from a report signed by its arbiter (can be called by anyone). The signature (r, s
+    // and v, 65 bytes) is of the eth_sign message of keccak256(name, obs_index, value, timestamp, contract address) packed as
+    // Solidity's abi.encodePacked(string, uint64, int64, uint64, address) does
+    fn submit_obs_report(&mut self, obs_index: u64, value: i64, timestamp: u64, signature: Vec<u8>);
+
+    // Flags the value of the given disputable observable during its dispute window (when called by the holder, counter-party or
+    // an operator approved to dispute), so it is not paid out on until confirmed or overridden by the resolver
+    fn flag_obs_value(&mut self, obs_index: u64);
+
+    // Confirms or overrides the flagged value of the given disputable observable (when called by its resolver)
+    fn resolve_obs_value(&mut self, obs_index: u64, value: i64);
+
+    // Acquires the combinator contract at the current block-time (when called by the holder or an operator approved to acquire)
     fn acquire(&mut self);
 
     // Updates the balances of the holder and counter-party
@@ -170,8 +319,32 @@ pub trait FinancialScInterface {
     #[payable]
     fn stake(&mut self) -> i64;
 
-    // Withdraws positive Eth balance up to the given amount from the contract (can be called by the holder or counter-party)
+    // Withdraws positive Eth balance up to the given amount from the contract (can be called by the holder, counter-party or their operators approved to withdraw)
     fn withdraw(&mut self, amount: u64) ;
+
+    // Withdraws positive balance of the given asset up to the given amount, paying it to the given recipient (the zero address is Ether)
+    fn withdraw_to(&mut self, asset: Address, recipient: Address, amount: u64);
+
+    // Stakes the given amount of an ERC-20 token asset (approved for the contract beforehand), returns the caller's total balance of the asset
+    fn stake_asset(&mut self, asset: Address, amount: u64) -> i64;
+
+    // Withdraws positive balance of the given asset up to the given amount from the contract (the zero address is Ether)
+    fn withdraw_asset(&mut self, asset: Address, amount: u64);
+
+    // Approves the given operator to act for the caller (holder or counter-party) with the given permissions, replacing any
+    // previous approval
+    fn approve_operator(&mut self, operator: Address, permissions: u64);
+
+    // Revokes the given operator's approval to act for the caller (holder or counter-party)
+    fn revoke_operator(&mut self, operator: Address);
+
+    // Logged when a party approves an operator
+    #[event]
+    fn OperatorApproved(&mut self, indexed_party: Address, indexed_operator: Address, permissions: u64);
+
+    // Logged when a party revokes an operator
+    #[event]
+    fn OperatorRevoked(&mut self, indexed_party: Address, indexed_operator: Address);
 }
 
 // The financial smart contract
@@ -183,13 +356,14 @@ pub struct FinancialScContract {
 // The financial smart contract interface implementation
 impl FinancialScInterface for FinancialScContract {
     // The financial smart contract constructor
-    fn constructor(&mut self, contract_definition: Vec<i64>, holder: Address, use_gas: bool) {
+    fn constructor(&mut self, contract_definition: Vec<i64>, holder: Address, use_gas: bool, gas_stipend: u64) {
         if holder == pwasm_ethereum::sender() {
             panic!("Holder and counter-party must be different addresses.");
         }
         if contract_definition.len() == 0 {
             panic!("Provided combinator contract not valid.");
         }
+        FinancialScContract::assert_U256_can_be_i64(U256::from(gas_stipend));
 
         // Initialise storage
         self.storage.write(&holder_address_key(), holder);
@@ -197,8 +371,12 @@ impl FinancialScInterface for FinancialScContract {
         self.storage.write(&holder_balance_key(), 0_i64);
         self.storage.write(&counter_party_balance_key(), 0_i64);
         self.storage.write(&use_gas_key(), use_gas);
+        self.storage.write(&gas_stipend_key(), gas_stipend as i64);
+        self.storage.write(&withdrawal_lock_key(), false);
         self.storage.write(&last_updated_key(), pwasm_ethereum::timestamp() as i64);
         self.storage.write_vec(&serialized_remote_combinator_contract_key() , &contract_definition);
+        self.storage.write_vec(&operators_key(), &Vec::<(Address, Address, i64)>::new());
+        self.storage.write_vec(&obs_reports_key(), &Vec::<[u8; 32]>::new());
 
         self.set_remote_combinator();
     }
@@ -227,26 +405,79 @@ impl FinancialScInterface for FinancialScContract {
         }
     }
 
+    // Gets the total balance of the given asset of the caller
+    fn get_asset_balance(&mut self, holderBalance: bool, asset: Address) -> i64 {
+        let asset_index = self.get_asset_index(asset);
+        let (holder_balance, counter_party_balance) = self.get_asset_balances(asset_index);
+        if holderBalance {
+            holder_balance
+        } else {
+            counter_party_balance
+        }
+    }
+
+    // Gets the assets of the contract indexed by asset index
+    fn get_assets(&mut self) -> Vec<Address> {
+        let assets: Vec<(Address, i64, i64)> = self.storage.read_vec(&assets_key()).0;
+
+        let mut asset_addresses: Vec<Address> = Vec::new();
+        asset_addresses.push(Address::zero());
+        asset_addresses.extend(assets.into_iter().map(|e| e.0));
+        asset_addresses
+    }
+
     // Gets whether or not the contract has concluded.
     fn get_concluded(&mut self) -> bool {
         let combinator = self.get_combinator();
         FinancialScContract::is_combinator_concluded(&combinator)
     }
 
-    // Gets whether or not the contract allocates gas fees upon withdrawal.
+    // Gets whether or not Ether payments forward the gas stipend.
     fn get_use_gas(&mut self) -> bool {
         self.storage.read(&use_gas_key()).0
     }
 
+    // Gets the gas stipend forwarded with Ether payments if using gas (paid for by the withdrawer's transaction, not deducted
+    // from the payment).
+    fn get_gas_stipend(&mut self) -> u64 {
+        let gas_stipend: i64 = self.storage.read(&gas_stipend_key()).0;
+        gas_stipend as u64
+    }
+
     // Gets the last-updated time.
     fn get_last_updated(&mut self) -> i64 {
         self.storage.read(&last_updated_key()).0
     }
 
+    // Gets the balances if the contract were updated at the given time, without persisting anything
+    fn get_projected_balances(&mut self, timestamp: u64) -> Vec<i64> {
+        let time = timestamp;
+        let mut projection = FinancialScContract {
+            storage: Storage::new_transient()
+        };
+
+        // Update a copy of the combinator contract on storage which is never written through
+        let mut combinator = projection.get_combinator();
+        if !FinancialScContract::is_combinator_concluded_at(&combinator, time) {
+            projection.settle_obs_values(time);
+            let differences = combinator.update(time, &mut projection.storage);
+            projection.adjust_balances(&differences);
+        }
+
+        let mut projected_balances: Vec<i64> = Vec::new();
+        projected_balances.push(if FinancialScContract::is_combinator_concluded_at(&combinator, time) { 1 } else { 0 });
+        for asset_index in 0..projection.get_assets().len() {
+            let (holder_balance, counter_party_balance) = projection.get_asset_balances(asset_index);
+            projected_balances.push(holder_balance);
+            projected_balances.push(counter_party_balance);
+        }
+        projected_balances
+    }
+
     // Gets the contract acquisition times (top level acquisition time and anytime acquisition times)
     fn get_acquisition_times(&mut self) -> Vec<i64> {
-        let acquisition_time: Option<u32> = self.get_combinator().get_combinator_details().acquisition_time;
-        let anytime_acquisition_times_full: Vec<(bool, Option<u32>)> = self.storage.read_vec(&anytime_acquisition_times_key()).0;
+        let acquisition_time: Option<u64> = self.get_combinator().get_combinator_details().acquisition_time;
+        let anytime_acquisition_times_full: Vec<(bool, Option<u64>)> = self.storage.read_vec(&anytime_acquisition_times_key()).0;
 
         let mut serialized_acquisition_times: Vec<i64> = Vec::new();
         serialized_acquisition_times.push(if acquisition_time == None { -1 } else { acquisition_time.unwrap() as i64 });
@@ -311,55 +542,226 @@ impl FinancialScInterface for FinancialScContract {
         serialized_obs_entries
     }
 
-    // Sets the given or combinator's preference between its sub-combinators
-    fn set_or_choice(&mut self, or_index: u64, prefer_first: bool) {
-        let holder: Address = self.storage.read(&holder_address_key()).0;
-        if pwasm_ethereum::sender() != holder {
-            panic!("Only the contract holder may set or-choices.");
+    // Gets the threshold, arbiters and submitted values of the given observable
+    fn get_obs_submissions(&mut self, obs_index: u64) -> Vec<i64> {
+        let (arbiter, value): (Address, Option<i64>) = self.storage.get(&obs_values_key(), obs_index as usize);
+        let mut submissions: Vec<(Address, Option<i64>)> = Vec::new();
+        let threshold: u64;
+
+        match self.get_quorum_threshold(obs_index) {
+            Some(quorum_threshold) => {
+                threshold = quorum_threshold;
+                let all_submissions: Vec<(u64, Address, Option<i64>)> = self.storage.read_vec(&obs_submissions_key()).0;
+                for submission in all_submissions.into_iter().filter(|e| e.0 == obs_index) {
+                    submissions.push((submission.1, submission.2));
+                }
+            },
+            None => {
+                threshold = 1;
+                submissions.push((arbiter, value));
+            }
         }
 
-        let index = or_index as usize;
-        let choice: Option<bool> = self.storage.get(&or_choices_key(), index);
-        if choice != None {
-            panic!("Or-choice has already been set.");
+        let mut serialized_submissions: Vec<i64> = Vec::new();
+        serialized_submissions.push(threshold as i64);
+        serialized_submissions.push(submissions.len() as i64);
+        for (arbiter, value) in submissions.into_iter() {
+            serialized_submissions.extend_from_slice(&address_to_i64(arbiter));
+            match value {
+                None => serialized_submissions.push(-1),
+                Some(v) => {
+                    serialized_submissions.push(0);
+                    serialized_submissions.push(v);
+                }
+            }
         }
 
-        self.storage.set(&or_choices_key(), index, Some(prefer_first));
+        serialized_submissions
+    }
+
+    // Gets the dispute window and the status of the value of the given observable
+    fn get_obs_dispute(&mut self, obs_index: u64) -> Vec<i64> {
+        let (_, value): (Address, Option<i64>) = self.storage.get(&obs_values_key(), obs_index as usize);
+        let mut serialized_dispute: Vec<i64> = Vec::new();
+        let position = match self.find_obs_dispute(obs_index) {
+            Some(position) => position,
+            None => return serialized_dispute
+        };
+
+        let (_, window, resolver): (u64, u64, Address) = self.storage.get(&obs_disputes_key(), position);
+        let (pending_value, set_time, flagged): (Option<i64>, Option<u64>, bool) = self.storage.get(&obs_pending_values_key(), position);
+        serialized_dispute.push(window as i64);
+        serialized_dispute.extend_from_slice(&address_to_i64(resolver));
+
+        match (pending_value, set_time) {
+            (Some(pending_value), Some(set_time)) => {
+                serialized_dispute.push(if value != None { 2 } else if flagged { 1 } else { 0 });
+                serialized_dispute.push(set_time as i64);
+                serialized_dispute.push(value.unwrap_or(pending_value));
+            },
+            _ => serialized_dispute.push(-1)
+        }
+        serialized_dispute
+    }
+
+    // Gets the live state of every combinator in the contract
+    fn get_state_tree(&mut self) -> Vec<i64> {
+        let combinator = self.get_combinator();
+        combinator.serialize_state(&mut self.storage)
+    }
+
+    // Gets the permissions the given party has approved for the given operator
+    fn get_operator_permissions(&mut self, party: Address, operator: Address) -> u64 {
+        match self.find_operator(party, operator) {
+            Some(index) => {
+                let entry: (Address, Address, i64) = self.storage.get(&operators_key(), index);
+                entry.2 as u64
+            },
+            None => 0
+        }
+    }
+
+    // Sets the given or combinator's preference between its sub-combinators
+    fn set_or_choice(&mut self, or_index: u64, prefer_first: bool) {
+        self.assert_sender_is_holder_for_or_choices();
+        self.write_or_choice(or_index, prefer_first);
     }
 
     // Sets the given observable's value
     fn set_obs_value(&mut self, obs_index: u64, value: i64) {
-        // Check index in bounds
-        let index: usize = obs_index as usize;
+        let sender = pwasm_ethereum::sender();
+        self.write_obs_value(obs_index, value, sender);
+    }
 
-        // Check not set yet
-        let value_and_arbiter: (Address, Option<i64>) = self.storage.get(&obs_values_key(), index);
-        if value_and_arbiter.1 != None {
-            panic!("Observable has already been set.");
+    // Sets the given or combinators' preferences between their sub-combinators, all or none are set
+    fn set_or_choices(&mut self, or_indices: Vec<u64>, choices: Vec<bool>, update_contract: bool) {
+        if or_indices.len() != choices.len() {
+            panic!("Index and value arrays must have the same length.");
         }
+        self.assert_sender_is_holder_for_or_choices();
 
-        // Check sender
-        let sender: Address = pwasm_ethereum::sender();
-        if sender != value_and_arbiter.0 {
-            panic!("Sender cannot set value for given observable-index.");
+        for (or_index, prefer_first) in or_indices.into_iter().zip(choices.into_iter()) {
+            self.write_or_choice(or_index, prefer_first);
+        }
+
+        if update_contract {
+            self.update();
+        }
+    }
+
+    // Sets the given observables' values, all or none are set
+    fn set_obs_values(&mut self, obs_indices: Vec<u64>, values: Vec<i64>, update_contract: bool) {
+        if obs_indices.len() != values.len() {
+            panic!("Index and value arrays must have the same length.");
+        }
+
+        let sender = pwasm_ethereum::sender();
+        for (obs_index, value) in obs_indices.into_iter().zip(values.into_iter()) {
+            self.write_obs_value(obs_index, value, sender);
+        }
+
+        if update_contract {
+            self.update();
+        }
+    }
+
+    // Sets the given observable's value from a report signed by its arbiter, which must be submitted within OBS_REPORT_VALIDITY
+    // seconds of its timestamp and only once
+    fn submit_obs_report(&mut self, obs_index: u64, value: i64, timestamp: u64, signature: Vec<u8>) {
+        let index = obs_index as usize;
+        let obs_names: Vec<ObsName> = self.storage.read_var(&obs_names_key()).0;
+        if index >= obs_names.len() {
+            panic!("Stored vector index out of bounds.");
+        }
+
+        let now = pwasm_ethereum::timestamp();
+        if timestamp > now {
+            panic!("Observable report timestamp is in the future.");
+        } else if now - timestamp > OBS_REPORT_VALIDITY {
+            panic!("Observable report has expired.");
+        }
+
+        // Reports are recorded by signer, as the arbiters of a quorum observable may sign the same report
+        let hash = FinancialScContract::get_obs_report_hash(&obs_names[index], obs_index, value, timestamp, pwasm_ethereum::address());
+        let signer = FinancialScContract::recover_signer(&hash, &signature);
+        let mut signed_report: Vec<u8> = Vec::new();
+        signed_report.extend_from_slice(&hash.0);
+        signed_report.extend_from_slice(&signer.0);
+        let report_key = pwasm_ethereum::keccak(&signed_report);
+
+        let reports: Vec<[u8; 32]> = self.storage.read_vec(&obs_reports_key()).0;
+        if reports.contains(&report_key.0) {
+            panic!("Observable report has already been submitted.");
+        }
+
+        StoresFixedVec::<[u8; 32]>::push(&mut self.storage, &obs_reports_key(), report_key.0);
+        self.write_obs_value(obs_index, value, signer);
+    }
+
+    // Flags the given observable's value, which must not yet be settled and still be within its dispute window
+    fn flag_obs_value(&mut self, obs_index: u64) {
+        let (_, value): (Address, Option<i64>) = self.storage.get(&obs_values_key(), obs_index as usize);
+        let position = match self.find_obs_dispute(obs_index) {
+            Some(position) => position,
+            None => panic!("Observable is not disputable.")
+        };
+
+        if self.get_principal(OPERATOR_DISPUTE) == Address::zero() {
+            panic!("Only the holder or counter-party may flag an observable value.");
+        }
+
+        let (_, window, _): (u64, u64, Address) = self.storage.get(&obs_disputes_key(), position);
+        let (pending_value, set_time, flagged): (Option<i64>, Option<u64>, bool) = self.storage.get(&obs_pending_values_key(), position);
+        if value != None {
+            panic!("Observable value has already been settled.");
+        } else if set_time == None {
+            panic!("Observable value has not been set.");
+        } else if flagged {
+            panic!("Observable value has already been flagged.");
+        } else if pwasm_ethereum::timestamp() >= set_time.unwrap() + window {
+            panic!("Observable value dispute window has ended.");
+        }
+
+        self.storage.set(&obs_pending_values_key(), position, (pending_value, set_time, true));
+    }
+
+    // Settles the given observable's flagged value as the given value
+    fn resolve_obs_value(&mut self, obs_index: u64, value: i64) {
+        let index = obs_index as usize;
+        let (arbiter, _): (Address, Option<i64>) = self.storage.get(&obs_values_key(), index);
+        let position = match self.find_obs_dispute(obs_index) {
+            Some(position) => position,
+            None => panic!("Observable is not disputable.")
+        };
+
+        let (_, _, resolver): (u64, u64, Address) = self.storage.get(&obs_disputes_key(), position);
+        let (pending_value, set_time, flagged): (Option<i64>, Option<u64>, bool) = self.storage.get(&obs_pending_values_key(), position);
+        if pwasm_ethereum::sender() != resolver {
+            panic!("Only the resolver may resolve an observable value.");
+        } else if !flagged {
+            panic!("Observable value has not been flagged.");
         }
 
-        // Set the value
-        self.storage.set(&obs_values_key(), index, (value_and_arbiter.0, Some(value)));
+        // Unflag the value so it is no longer awaiting the resolver
+        self.storage.set(&obs_pending_values_key(), position, (pending_value, set_time, false));
+        self.storage.set(&obs_values_key(), index, (arbiter, Some(value)));
     }
 
-    // Acquires the combinator contract at the current block-time (when called by the holder)
+    // Acquires the combinator contract at the current block-time (when called by the holder or an operator approved to acquire)
     fn acquire(&mut self) {
         let mut combinator = self.get_combinator();
         let holder: Address = self.storage.read(&holder_address_key()).0;
 
-        if pwasm_ethereum::sender() != holder {
+        if self.get_principal(OPERATOR_ACQUIRE) != holder {
             panic!("Only the contract holder may acquire the combinator contract.");
         } else if combinator.get_combinator_details().acquisition_time != None {
             panic!("The combinator contract cannot be acquired more than once.");
         }
 
-        combinator.acquire(pwasm_ethereum::timestamp() as u32, &mut self.storage);
+        // Times relative to the acquisition time become known now
+        let time = pwasm_ethereum::timestamp();
+        combinator.resolve_relative_times(time);
+        combinator.acquire(time, &mut self.storage);
 
         self.set_combinator(combinator);
 
@@ -378,35 +780,33 @@ impl FinancialScInterface for FinancialScContract {
         // Set the last-updated time
         self.storage.write(&last_updated_key(), pwasm_ethereum::timestamp() as i64);
 
+        // Pay out on disputable observable values once their dispute window has ended
+        self.settle_obs_values(pwasm_ethereum::timestamp());
+
         // Update combinators
-        let difference = combinator.update(pwasm_ethereum::timestamp() as u32, &mut self.storage);
+        let differences = combinator.update(pwasm_ethereum::timestamp(), &mut self.storage);
 
         self.set_combinator(combinator);
 
-        // Adjust balances
-        let counter_party_balance = self.storage.read(&counter_party_balance_key()).0;
-        self.storage.write(&counter_party_balance_key(), FinancialScContract::safe_add(counter_party_balance, -difference));
-
-        let holder_balance = self.storage.read(&holder_balance_key()).0;
-        self.storage.write(&holder_balance_key(), FinancialScContract::safe_add(holder_balance, difference));
+        self.adjust_balances(&differences);
     }
 
     // Acquires an anytime combinator's sub-contract
     fn acquire_anytime_sub_contract(&mut self, anytime_index: u64) {
         let index = anytime_index as usize;
-        let anytime_state: (bool, Option<u32>) = self.storage.get(&anytime_acquisition_times_key(), index);
+        let anytime_state: (bool, Option<u64>) = self.storage.get(&anytime_acquisition_times_key(), index);
 
         if !anytime_state.0 {
             panic!("Given anytime combinator has not been acquired.");
         }
 
         let holder: Address = self.storage.read(&holder_address_key()).0;
-        if pwasm_ethereum::sender() != holder {
+        if self.get_principal(OPERATOR_ACQUIRE) != holder {
             panic!("Only the contract holder may acquire the combinator contract.");
         }
 
         let prev_acquisition_time = anytime_state.1;
-        let new_acquisition_time = pwasm_ethereum::timestamp() as u32;
+        let new_acquisition_time = pwasm_ethereum::timestamp();
 
         if prev_acquisition_time != None && prev_acquisition_time.unwrap() <= new_acquisition_time {
             panic!("Cannot acquire a sub-combinator contract which has already been acquired.");
@@ -442,52 +842,112 @@ impl FinancialScInterface for FinancialScContract {
         balance
     }
 
-    // Withdraws positive Eth balance up to the given amount from the contract (can be called by the holder or counter-party)
+    // Withdraws positive Eth balance up to the given amount from the contract (can be called by the holder, counter-party or their operators approved to withdraw)
     fn withdraw(&mut self, amount: u64) {
+        let party = self.get_principal(OPERATOR_WITHDRAW);
+        self.withdraw_ether(party, party, amount);
+    }
+
+    // Withdraws positive balance of the given asset up to the given amount, paying it to the given recipient (the zero address is Ether)
+    fn withdraw_to(&mut self, asset: Address, recipient: Address, amount: u64) {
+        let party = self.get_principal(OPERATOR_WITHDRAW);
+        if party != pwasm_ethereum::sender() && party != Address::zero() && recipient != party {
+            panic!("Operators may only withdraw to the party they act for.");
+        }
+
+        let asset_index = self.get_asset_index(asset);
+        if asset_index == ETHER_ASSET {
+            self.withdraw_ether(party, recipient, amount);
+        } else {
+            self.withdraw_token(party, asset_index, asset, recipient, amount);
+        }
+    }
+
+    // Stakes the given amount of an ERC-20 token asset with the contract, returns the caller's total balance of the asset
+    fn stake_asset(&mut self, asset: Address, amount: u64) -> i64 {
         let sender = pwasm_ethereum::sender();
-        let final_amount;
-        let original_balance;
-        let key;
-        let holder_balance = self.storage.read(&holder_balance_key()).0;
-        let counter_party_balance = self.storage.read(&counter_party_balance_key()).0;
+        FinancialScContract::assert_U256_can_be_i64(U256::from(amount));
         let holder: Address = self.storage.read(&holder_address_key()).0;
         let counter_party: Address = self.storage.read(&counter_party_address_key()).0;
-        let use_gas = self.storage.read(&use_gas_key()).0;
-        
-        // Get the amount to send (clamp at balance amount)
+
+        let asset_index = self.get_asset_index(asset);
+        if asset_index == ETHER_ASSET {
+            panic!("Ether must be staked by sending value to stake.");
+        } else if sender != holder && sender != counter_party {
+            panic!("Only the contract holder or the counter-party may stake assets in the contract.");
+        }
+
+        // Transfer the approved tokens from the sender to the contract
+        let input = FinancialScContract::encode_token_call(TRANSFER_FROM_SELECTOR, &[sender, pwasm_ethereum::address()], amount);
+        let mut result = TOKEN_NO_RETURN_DATA;
+        if pwasm_ethereum::call(TOKEN_CALL_GAS, &asset, U256::zero(), &input, &mut result).is_err()
+            || !FinancialScContract::token_call_succeeded(&result) {
+            panic!("Token transfer failed.");
+        }
+
+        // Get the balance
+        let (mut holder_balance, mut counter_party_balance) = self.get_asset_balances(asset_index);
+        let balance;
         if sender == holder {
-            key = holder_balance_key();
-            original_balance = holder_balance;
-        } else if sender == counter_party {
-            key = counter_party_balance_key();
-            original_balance = counter_party_balance;
+            holder_balance = FinancialScContract::safe_add(holder_balance, amount as i64);
+            balance = holder_balance;
         } else {
-            panic!("Only the contract holder or the counter-party may withdraw Ether from the contract.");
+            counter_party_balance = FinancialScContract::safe_add(counter_party_balance, amount as i64);
+            balance = counter_party_balance;
         }
+        self.set_asset_balances(asset_index, holder_balance, counter_party_balance);
+        balance
+    }
 
-        let funds = holder_balance + counter_party_balance;
-        final_amount = FinancialScContract::get_withdrawal_amount(amount, original_balance, funds, use_gas);
+    // Withdraws positive balance of the given asset up to the given amount from the contract (can be called by the holder or counter-party)
+    fn withdraw_asset(&mut self, asset: Address, amount: u64) {
+        let party = self.get_principal(OPERATOR_WITHDRAW);
+        self.withdraw_to(asset, party, amount);
+    }
+
+    // Approves the given operator to act for the caller with the given permissions
+    fn approve_operator(&mut self, operator: Address, permissions: u64) {
+        let party = pwasm_ethereum::sender();
+        let holder: Address = self.storage.read(&holder_address_key()).0;
+        let counter_party: Address = self.storage.read(&counter_party_address_key()).0;
 
-        if use_gas && final_amount < CALL_GAS {
-            panic!("Not enough funds to pay the gas price while withdrawing.");
+        if party != holder && party != counter_party {
+            panic!("Only the contract holder or the counter-party may approve operators.");
+        } else if operator == holder || operator == counter_party {
+            panic!("The contract holder and counter-party cannot be operators.");
+        } else if permissions == 0 || permissions & !(OPERATOR_CHOICES | OPERATOR_ACQUIRE | OPERATOR_WITHDRAW | OPERATOR_DISPUTE) != 0 {
+            panic!("Operator permissions must be a non-empty combination of choices, acquire, withdraw and dispute.");
         }
 
-        let gas_cost = if use_gas { CALL_GAS } else { 0 };
-        let withdraw_amount = final_amount - gas_cost;
+        // An operator acts for one party only, so it is clear whose balance its withdrawals are taken from
+        let other_party = if party == holder { counter_party } else { holder };
+        if self.get_operator_permissions(other_party, operator) != 0 {
+            panic!("Operator already acts for the other party.");
+        }
 
-        if withdraw_amount <= 0 {
-            panic!("Not enough funds to withdraw.");
+        match self.find_operator(party, operator) {
+            Some(index) => self.storage.set(&operators_key(), index, (party, operator, permissions as i64)),
+            None => StoresFixedVec::<(Address, Address, i64)>::push(&mut self.storage, &operators_key(), (party, operator, permissions as i64))
         }
-        self.storage.write(&key, original_balance - final_amount);
 
-        match pwasm_ethereum::call(gas_cost as u64, &sender, U256::from(withdraw_amount), &[], &mut Vec::<u8>::new()) {
-            Ok(_v) => return,
-            Err(_e) => {
-                // Payment failed, roll-back balance
-                self.storage.write(&key, original_balance);
-                panic!("Payment failed");
-            }
+        self.OperatorApproved(party, operator, permissions);
+    }
+
+    // Revokes the given operator's approval to act for the caller
+    fn revoke_operator(&mut self, operator: Address) {
+        let party = pwasm_ethereum::sender();
+        let index = match self.find_operator(party, operator) {
+            Some(index) => index,
+            None => panic!("Operator has not been approved by the sender.")
+        };
+
+        let entry: (Address, Address, i64) = self.storage.get(&operators_key(), index);
+        if entry.2 == 0 {
+            panic!("Operator has not been approved by the sender.");
         }
+        self.storage.set(&operators_key(), index, (party, operator, 0_i64));
+
+        self.OperatorRevoked(party, operator);
     }
 }
 
@@ -500,146 +960,632 @@ impl FinancialScContract {
         }
     }
 
-    // Constructs the combinators from a serialized combinator contract
-    fn set_remote_combinator(&mut self) {
-        self.storage.write_vec(&or_choices_key(), &Vec::<Option<bool>>::new());
-        self.storage.write_vec(&obs_values_key(), &Vec::<(Address, Option<i64>)>::new());
-        self.storage.write_var(&obs_names_key(), &Vec::<ObsName>::new());
-        self.storage.write_vec(&anytime_acquisition_times_key(), &Vec::<Option<u32>>::new());
-
-        let (_, combinator) = self.deserialize_remote_combinator(0);
+    // Deserializes a combinator contract definition as the constructor does, without accessing contract storage (for use off-chain),
+    // returns the combinator contract and the storage holding its or-choices, observables and anytime acquisition times
+    pub fn deserialize_definition(contract_definition: &Vec<i64>) -> (Box<ContractCombinator>, Storage) {
+        let mut contract = FinancialScContract {
+            storage: Storage::new_detached()
+        };
+        contract.storage.write_vec(&serialized_remote_combinator_contract_key(), contract_definition);
 
-        self.set_combinator(combinator);
+        let (_, combinator) = contract.deserialize_remote_combinator(0);
+        (combinator, contract.storage)
     }
 
-    // Deserializes a combinator from the given combinator byte vector (obtained remotely) and index, returns the following index and the boxed combinator
-    fn deserialize_remote_combinator(&mut self, i: usize)-> (usize, Box<ContractCombinator>) {
-        let serialized_combinators: Vec<i64> = self.storage.read_vec(&serialized_remote_combinator_contract_key()).0;
-        if i >= serialized_combinators.len() {
-            panic!("Provided combinator contract not valid.");
-        }
-
-        match Combinator::from(serialized_combinators[i]) {
-            // zero combinator
-            Combinator::ZERO => (i + 1, Box::new(ZeroCombinator::new())),
+    // Withdraws positive Eth balance of the given party up to the given amount from the contract, paying it to the recipient
+    fn withdraw_ether(&mut self, party: Address, recipient: Address, amount: u64) {
+        self.assert_not_withdrawing();
 
-            // one combinator
-            Combinator::ONE => (i + 1, Box::new(OneCombinator::new())),
-
-            // and combinator
-            Combinator::AND => {
-                // Deserialize sub-combinators
-                let (i0, sub_combinator0) = self.deserialize_remote_combinator(i + 1);
-                let (i1, sub_combinator1) = self.deserialize_remote_combinator(i0);
+        let original_balance;
+        let key;
+        let holder_balance = self.storage.read(&holder_balance_key()).0;
+        let counter_party_balance = self.storage.read(&counter_party_balance_key()).0;
+        let holder: Address = self.storage.read(&holder_address_key()).0;
+        let counter_party: Address = self.storage.read(&counter_party_address_key()).0;
+        let use_gas = self.storage.read(&use_gas_key()).0;
+        let gas_stipend: i64 = self.storage.read(&gas_stipend_key()).0;
 
-                (i1, Box::new(AndCombinator::new(sub_combinator0, sub_combinator1)))
-            },
+        // Get the amount to send (clamp at balance amount)
+        if party == holder {
+            key = holder_balance_key();
+            original_balance = holder_balance;
+        } else if party == counter_party {
+            key = counter_party_balance_key();
+            original_balance = counter_party_balance;
+        } else {
+            panic!("Only the contract holder or the counter-party may withdraw Ether from the contract.");
+        }
 
-            // or combinator
-            Combinator::OR => {
-                // Keep track of or_index and or_choices
-                let length = StoresFixedVec::<Option<bool>>::length(&mut self.storage, &or_choices_key());
-                StoresFixedVec::<Option<bool>>::push(&mut self.storage, &or_choices_key(), None);
+        let funds = holder_balance + counter_party_balance;
+        let withdraw_amount = FinancialScContract::get_withdrawal_amount(amount, original_balance, funds);
 
-                // Deserialize sub-combinators
-                let (i0, sub_combinator0) = self.deserialize_remote_combinator(i + 1);
-                let (i1, sub_combinator1) = self.deserialize_remote_combinator(i0);
+        if withdraw_amount <= 0 {
+            panic!("Not enough funds to withdraw.");
+        }
+        self.storage.write(&key, original_balance - withdraw_amount);
+
+        // The gas stipend is forwarded on top of the payment, the withdrawer's transaction pays for it
+        let gas = if use_gas { gas_stipend as u64 } else { 0 };
+        if !self.locked_call(gas, &recipient, U256::from(withdraw_amount), &[], &mut []) {
+            // Payment failed, roll-back balance
+            self.storage.write(&key, original_balance);
+            panic!("Payment failed");
+        }
+    }
 
-                (i1, Box::new(OrCombinator::new(sub_combinator0, sub_combinator1, length)))
-            },
+    // Withdraws positive balance of the given token asset of the given party up to the given amount from the contract, paying it to the recipient
+    fn withdraw_token(&mut self, party: Address, asset_index: usize, asset: Address, recipient: Address, amount: u64) {
+        self.assert_not_withdrawing();
 
-            // truncate combinator
-            Combinator::TRUNCATE => {
-                // Deserialize timestamp from 4 bytes to 32-bit int
-                let mut timestamp: u32 = serialized_combinators[i + 1] as u32;
+        let holder: Address = self.storage.read(&holder_address_key()).0;
+        let counter_party: Address = self.storage.read(&counter_party_address_key()).0;
+        let (holder_balance, counter_party_balance) = self.get_asset_balances(asset_index);
+        let original_balance;
 
-                // Deserialize sub-combinator
-                let (i0, sub_combinator) = self.deserialize_remote_combinator(i + 2);
+        if party == holder {
+            original_balance = holder_balance;
+        } else if party == counter_party {
+            original_balance = counter_party_balance;
+        } else {
+            panic!("Only the contract holder or the counter-party may withdraw assets from the contract.");
+        }
 
-                (i0, Box::new(TruncateCombinator::new(sub_combinator, timestamp)))
-            },
+        // Get the amount to send (clamp at balance and funds amount, token transfers are paid for by the caller)
+        let funds = holder_balance + counter_party_balance;
+        let withdraw_amount = FinancialScContract::get_withdrawal_amount(amount, original_balance, funds);
 
-            // scale combinator
-            Combinator::SCALE => {
-                // Check if observable is provided, if so then deserialize it, otherwise record in obs_entries
-                let provided: i64 = serialized_combinators[i + 1];
-                let mut obs_index: Option<usize>;
-                let mut scale_value: Option<i64>;
-                let mut i0 = i + 2;
-
-                if provided == 1 {
-                    obs_index = None;
-                    scale_value = Some(serialized_combinators[i0]);
-                    i0 += 1;
-                } else {
-                    let mut obs_names: Vec<ObsName> = self.storage.read_var(&obs_names_key()).0;
-                    obs_index = Some(obs_names.len());
-                    scale_value = None;
-
-                    // Deserialize arbiter address
-                    let mut serialized_address: [i64; 4] = [0; 4];
-                    serialized_address.copy_from_slice(&serialized_combinators[(i0)..(i0 + 4)]);
-                    let address = i64_to_address(serialized_address);
-                    i0 += 4;
+        if withdraw_amount <= 0 {
+            panic!("Not enough funds to withdraw.");
+        }
+        if party == holder {
+            self.set_asset_balances(asset_index, holder_balance - withdraw_amount, counter_party_balance);
+        } else {
+            self.set_asset_balances(asset_index, holder_balance, counter_party_balance - withdraw_amount);
+        }
 
-                    // Store observable value/arbiter
-                    StoresFixedVec::<(Address, Option<i64>)>::push(&mut self.storage, &obs_values_key(), (address, None));
+        let input = FinancialScContract::encode_token_call(TRANSFER_SELECTOR, &[recipient], withdraw_amount as u64);
+        let mut result = TOKEN_NO_RETURN_DATA;
+        if !self.locked_call(TOKEN_CALL_GAS, &asset, U256::zero(), &input, &mut result)
+            || !FinancialScContract::token_call_succeeded(&result) {
+            // Payment failed, roll-back balance
+            self.set_asset_balances(asset_index, holder_balance, counter_party_balance);
+            panic!("Payment failed");
+        }
+    }
 
-                    // Deserialize name
-                    let name_len = serialized_combinators[(i0)] as usize;
-                    let mut name: Vec<i64> = Vec::new();
-                    name.extend_from_slice(&serialized_combinators[(i0 + 1)..(i0 + 1 + name_len)]);
-                    let mut obsName: ObsName = ObsName::new(&name);
-                    i0 += 1 + name_len;
+    // Checks that no withdrawal is being paid out, so a payment recipient cannot re-enter the contract to withdraw again
+    fn assert_not_withdrawing(&mut self) {
+        if self.storage.read(&withdrawal_lock_key()).0 {
+            panic!("Cannot withdraw while another withdrawal is being paid out.");
+        }
+    }
 
-                    // Store observable names
-                    obs_names.push(obsName);
-                    self.storage.write_var(&obs_names_key(), &obs_names);
-                }
+    // Calls the given address while holding the withdrawal lock, writing its return data to the given buffer, returns whether or
+    // not the call succeeded
+    fn locked_call(&mut self, gas: u64, address: &Address, value: U256, input: &[u8], output: &mut [u8]) -> bool {
+        self.storage.write(&withdrawal_lock_key(), true);
+        let result = pwasm_ethereum::call(gas, address, value, input, output);
+        self.storage.write(&withdrawal_lock_key(), false);
+        result.is_ok()
+    }
 
-                // Deserialize sub-contract
-                let (i1, sub_combinator) = self.deserialize_remote_combinator(i0);
+    // Returns the party (holder or counter-party) the sender acts for with the given operator permission: the sender itself if it
+    // is a party, otherwise the party which approved the sender as an operator with the permission (the zero address if none)
+    fn get_principal(&mut self, permission: u64) -> Address {
+        let sender = pwasm_ethereum::sender();
+        let holder: Address = self.storage.read(&holder_address_key()).0;
+        let counter_party: Address = self.storage.read(&counter_party_address_key()).0;
+        if sender == holder || sender == counter_party {
+            return sender;
+        }
 
-                (i1, Box::new(ScaleCombinator::new(sub_combinator, obs_index, scale_value)))
-            },
+        let operators: Vec<(Address, Address, i64)> = self.storage.read_vec(&operators_key()).0;
+        for (party, operator, permissions) in operators {
+            if operator == sender && permissions as u64 & permission != 0 {
+                return party;
+            }
+        }
+        Address::zero()
+    }
 
-            // give combinator
-            Combinator::GIVE => {
-                // Deserialize sub-combinator
-                let (i0, sub_combinator) = self.deserialize_remote_combinator(i + 1);
+    // Returns the index of the given party's approval of the given operator in the operators storage (revoked or not), if any
+    fn find_operator(&mut self, party: Address, operator: Address) -> Option<usize> {
+        let operators: Vec<(Address, Address, i64)> = self.storage.read_vec(&operators_key()).0;
+        operators.iter().position(|entry| entry.0 == party && entry.1 == operator)
+    }
 
-                (i0, Box::new(GiveCombinator::new(sub_combinator)))
-            },
+    // Checks that the sender is the holder (or an operator approved to set choices), who alone may set or-choices
+    fn assert_sender_is_holder_for_or_choices(&mut self) {
+        let holder: Address = self.storage.read(&holder_address_key()).0;
+        if self.get_principal(OPERATOR_CHOICES) != holder {
+            panic!("Only the contract holder may set or-choices.");
+        }
+    }
+
+    // Sets the given or combinator's preference if not yet set (the sender must already be checked)
+    fn write_or_choice(&mut self, or_index: u64, prefer_first: bool) {
+        let index = or_index as usize;
+        let choice: Option<bool> = self.storage.get(&or_choices_key(), index);
+        if choice != None {
+            panic!("Or-choice has already been set.");
+        }
 
-            // then combinator
-            Combinator::THEN => {
-                // Deserialize sub-combinators
-                let (i0, sub_combinator0) = self.deserialize_remote_combinator(i + 1);
-                let (i1, sub_combinator1) = self.deserialize_remote_combinator(i0);
+        self.storage.set(&or_choices_key(), index, Some(prefer_first));
+    }
 
-                (i1, Box::new(ThenCombinator::new(sub_combinator0, sub_combinator1)))
-            },
+    // Sets the given observable's value if not yet set and the setter (the sender, or the signer of a report) is its arbiter. A
+    // quorum observable records the value submitted by the setter instead, and is set once enough of its arbiters have submitted.
+    fn write_obs_value(&mut self, obs_index: u64, value: i64, setter: Address) {
+        // Check index in bounds
+        let index: usize = obs_index as usize;
+
+        // Check not set yet (disputable observables are set once their value is pending)
+        let value_and_arbiter: (Address, Option<i64>) = self.storage.get(&obs_values_key(), index);
+        let dispute_position = self.find_obs_dispute(obs_index);
+        let pending: bool = match dispute_position {
+            Some(position) => StoresFixedVec::<(Option<i64>, Option<u64>, bool)>::get(&mut self.storage, &obs_pending_values_key(), position).0 != None,
+            None => false
+        };
+        if value_and_arbiter.1 != None || pending {
+            panic!("Observable has already been set.");
+        }
 
-            // get combinator
-            Combinator::GET => {
-                // Deserialize sub-combinator
-                let (i0, sub_combinator) = self.deserialize_remote_combinator(i + 1);
+        // Quorum observables have no single arbiter
+        let resolved: i64;
+        if self.get_quorum_threshold(obs_index) != None {
+            match self.submit_quorum_value(obs_index, value, setter) {
+                Some(median) => resolved = median,
+                None => return
+            }
+        } else if setter != value_and_arbiter.0 {
+            panic!("Sender cannot set value for given observable-index.");
+        } else {
+            resolved = value;
+        }
 
-                (i0, Box::new(GetCombinator::new(sub_combinator)))
+        // Set the value, or hold it until the end of the dispute window
+        match dispute_position {
+            Some(position) => {
+                let time = pwasm_ethereum::timestamp();
+                self.storage.set(&obs_pending_values_key(), position, (Some(resolved), Some(time), false));
             },
+            None => self.storage.set(&obs_values_key(), index, (value_and_arbiter.0, Some(resolved)))
+        }
+    }
+
+    // Gets the position of the given observable's dispute window, if it is a disputable observable
+    fn find_obs_dispute(&mut self, obs_index: u64) -> Option<usize> {
+        let disputes: Vec<(u64, u64, Address)> = self.storage.read_vec(&obs_disputes_key()).0;
+        disputes.into_iter().position(|e| e.0 == obs_index)
+    }
+
+    // Sets the pending values of the disputable observables which were not flagged and whose dispute window has ended by the
+    // given time
+    fn settle_obs_values(&mut self, time: u64) {
+        let disputes: Vec<(u64, u64, Address)> = self.storage.read_vec(&obs_disputes_key()).0;
+        if disputes.is_empty() {
+            return;
+        }
+
+        let pending_values: Vec<(Option<i64>, Option<u64>, bool)> = self.storage.read_vec(&obs_pending_values_key()).0;
+        for ((obs_index, window, _), (pending_value, set_time, flagged)) in disputes.into_iter().zip(pending_values) {
+            let index = obs_index as usize;
+            if flagged || set_time == None || time < set_time.unwrap() + window {
+                continue;
+            }
+
+            let (arbiter, value): (Address, Option<i64>) = self.storage.get(&obs_values_key(), index);
+            if value == None {
+                self.storage.set(&obs_values_key(), index, (arbiter, pending_value));
+            }
+        }
+    }
+
+    // Records the value submitted by the given arbiter of a quorum observable, returns the observable's value (the median of the
+    // submitted values) once the quorum's threshold is reached
+    fn submit_quorum_value(&mut self, obs_index: u64, value: i64, arbiter: Address) -> Option<i64> {
+        let submissions: Vec<(u64, Address, Option<i64>)> = self.storage.read_vec(&obs_submissions_key()).0;
+        match submissions.iter().position(|e| e.0 == obs_index && e.1 == arbiter) {
+            None => panic!("Sender cannot set value for given observable-index."),
+            Some(position) if submissions[position].2 != None => panic!("Arbiter has already submitted a value for given observable-index."),
+            Some(position) => self.storage.set(&obs_submissions_key(), position, (obs_index, arbiter, Some(value)))
+        }
+
+        let mut values: Vec<i64> = submissions.into_iter().filter(|e| e.0 == obs_index).filter_map(|e| e.2).collect();
+        values.push(value);
+        match self.get_quorum_threshold(obs_index) {
+            Some(threshold) if values.len() as u64 >= threshold => Some(FinancialScContract::median(values)),
+            _ => None
+        }
+    }
+
+    // Gets the threshold of the given observable, if it is a quorum observable
+    fn get_quorum_threshold(&mut self, obs_index: u64) -> Option<u64> {
+        let quorums: Vec<(u64, u64)> = self.storage.read_vec(&obs_quorums_key()).0;
+        quorums.into_iter().find(|e| e.0 == obs_index).map(|e| e.1)
+    }
+
+    // Constructs the combinators from a serialized combinator contract
+    fn set_remote_combinator(&mut self) {
+        self.storage.write_vec(&or_choices_key(), &Vec::<Option<bool>>::new());
+        self.storage.write_vec(&obs_values_key(), &Vec::<(Address, Option<i64>)>::new());
+        self.storage.write_var(&obs_names_key(), &Vec::<ObsName>::new());
+        self.storage.write_vec(&obs_quorums_key(), &Vec::<(u64, u64)>::new());
+        self.storage.write_vec(&obs_submissions_key(), &Vec::<(u64, Address, Option<i64>)>::new());
+        self.storage.write_vec(&obs_disputes_key(), &Vec::<(u64, u64, Address)>::new());
+        self.storage.write_vec(&obs_pending_values_key(), &Vec::<(Option<i64>, Option<u64>, bool)>::new());
+        self.storage.write_vec(&anytime_acquisition_times_key(), &Vec::<Option<u64>>::new());
+        self.storage.write_vec(&assets_key(), &Vec::<(Address, i64, i64)>::new());
+
+        let (_, combinator) = self.deserialize_remote_combinator(0);
+
+        self.set_combinator(combinator);
+    }
+
+    // Deserializes a combinator from the given combinator byte vector (obtained remotely) and index, returns the following index and the boxed combinator
+    fn deserialize_remote_combinator(&mut self, i: usize)-> (usize, Box<ContractCombinator>) {
+        let serialized_combinators: Vec<i64> = self.storage.read_vec(&serialized_remote_combinator_contract_key()).0;
+        self.deserialize_sub_combinator(i, &serialized_combinators)
+    }
+
+    // Deserializes the combinator at the given index of a contract definition with the deserializer registered for its tag,
+    // returns the following index and the boxed combinator (used by registered deserializers to deserialize sub-combinators)
+    pub fn deserialize_sub_combinator(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        if i >= serialized_combinators.len() {
+            panic!("Provided combinator contract not valid.");
+        }
+        let entry = get_combinator_entry(serialized_combinators[i]);
+        (entry.deserialize_definition)(self, i + 1, serialized_combinators)
+    }
+
+    // Gets the contract storage, in which registered combinators can keep their own entries
+    pub fn get_storage(&mut self) -> &mut Storage {
+        &mut self.storage
+    }
+
+    // Deserializes a zero combinator from a contract definition, given the index following its tag
+    fn deserialize_zero(&mut self, i: usize, _: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        (i, Box::new(ZeroCombinator::new()))
+    }
+
+    // Deserializes a one combinator from a contract definition, given the index following its tag
+    fn deserialize_one(&mut self, i: usize, _: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        (i, Box::new(OneCombinator::new()))
+    }
+
+    // Deserializes an and combinator from a contract definition, given the index following its tag
+    fn deserialize_and(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Deserialize sub-combinators
+        let (i0, sub_combinator0) = self.deserialize_sub_combinator(i, serialized_combinators);
+        let (i1, sub_combinator1) = self.deserialize_sub_combinator(i0, serialized_combinators);
+
+        (i1, Box::new(AndCombinator::new(sub_combinator0, sub_combinator1)))
+    }
+
+    // Deserializes an or combinator from a contract definition, given the index following its tag
+    fn deserialize_or(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Keep track of or_index and or_choices
+        let length = StoresFixedVec::<Option<bool>>::length(&mut self.storage, &or_choices_key());
+        StoresFixedVec::<Option<bool>>::push(&mut self.storage, &or_choices_key(), None);
+
+        // Deserialize sub-combinators
+        let (i0, sub_combinator0) = self.deserialize_sub_combinator(i, serialized_combinators);
+        let (i1, sub_combinator1) = self.deserialize_sub_combinator(i0, serialized_combinators);
+
+        (i1, Box::new(OrCombinator::new(sub_combinator0, sub_combinator1, length)))
+    }
+
+    // Deserializes a truncate combinator from a contract definition, given the index following its tag
+    fn deserialize_truncate(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Deserialize timestamp
+        let timestamp: u64 = FinancialScContract::deserialize_time(serialized_combinators[i]);
+
+        // Deserialize sub-combinator
+        let (i0, sub_combinator) = self.deserialize_sub_combinator(i + 1, serialized_combinators);
+
+        (i0, Box::new(TruncateCombinator::new(sub_combinator, timestamp)))
+    }
+
+    // Deserializes a scale combinator from a contract definition, given the index following its tag
+    fn deserialize_scale(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Check if observable is provided, if so then deserialize it, otherwise record in obs_entries
+        let mut provided: i64 = serialized_combinators[i];
+        let obs_index: Option<usize>;
+        let scale_value: Option<i64>;
+        let mut i0 = i + 1;
+
+        // Deserialize the dispute window and resolver address of a disputable observable, which precede the observable
+        let mut dispute: Option<(u64, Address)> = None;
+        if provided == 3 {
+            if serialized_combinators[i0] < 0 {
+                panic!("Dispute windows must not be negative.");
+            }
+            let mut serialized_address: [i64; 4] = [0; 4];
+            serialized_address.copy_from_slice(&serialized_combinators[(i0 + 1)..(i0 + 5)]);
+            dispute = Some((serialized_combinators[i0] as u64, i64_to_address(serialized_address)));
+
+            provided = serialized_combinators[i0 + 5];
+            if provided == 1 || provided == 3 {
+                panic!("Provided combinator contract not valid.");
+            }
+            i0 += 6;
+        }
+
+        if provided == 1 {
+            obs_index = None;
+            scale_value = Some(serialized_combinators[i0]);
+            i0 += 1;
+        } else {
+            let mut obs_names: Vec<ObsName> = self.storage.read_var(&obs_names_key()).0;
+            obs_index = Some(obs_names.len());
+            scale_value = None;
+
+            if provided == 2 {
+                // Deserialize the quorum's threshold and arbiter addresses
+                let threshold: i64 = serialized_combinators[i0];
+                let arbiter_count: i64 = serialized_combinators[i0 + 1];
+                if threshold < 1 || threshold > arbiter_count {
+                    panic!("Quorum threshold must be between one and the number of arbiters.");
+                }
+                i0 += 2;
+
+                let quorum_index = obs_names.len() as u64;
+                let mut arbiters: Vec<Address> = Vec::new();
+                for _ in 0..arbiter_count {
+                    let mut serialized_address: [i64; 4] = [0; 4];
+                    serialized_address.copy_from_slice(&serialized_combinators[(i0)..(i0 + 4)]);
+                    let address = i64_to_address(serialized_address);
+                    if arbiters.contains(&address) {
+                        panic!("Quorum arbiters must be distinct.");
+                    }
+                    arbiters.push(address);
+                    i0 += 4;
+
+                    StoresFixedVec::<(u64, Address, Option<i64>)>::push(&mut self.storage, &obs_submissions_key(), (quorum_index, address, None));
+                }
+                StoresFixedVec::<(u64, u64)>::push(&mut self.storage, &obs_quorums_key(), (quorum_index, threshold as u64));
+
+                // Store observable value, with no single arbiter
+                StoresFixedVec::<(Address, Option<i64>)>::push(&mut self.storage, &obs_values_key(), (Address::zero(), None));
+            } else {
+                // Deserialize arbiter address
+                let mut serialized_address: [i64; 4] = [0; 4];
+                serialized_address.copy_from_slice(&serialized_combinators[(i0)..(i0 + 4)]);
+                let address = i64_to_address(serialized_address);
+                i0 += 4;
+
+                // Store observable value/arbiter
+                StoresFixedVec::<(Address, Option<i64>)>::push(&mut self.storage, &obs_values_key(), (address, None));
+            }
+
+            // Deserialize name
+            let name_len = serialized_combinators[(i0)] as usize;
+            let mut name: Vec<i64> = Vec::new();
+            name.extend_from_slice(&serialized_combinators[(i0 + 1)..(i0 + 1 + name_len)]);
+            let obsName: ObsName = ObsName::new(&name);
+            i0 += 1 + name_len;
+
+            // Store observable names
+            obs_names.push(obsName);
+            self.storage.write_var(&obs_names_key(), &obs_names);
+
+            // Store the dispute window, with no value pending
+            if let Some((window, resolver)) = dispute {
+                let dispute_index = obs_index.unwrap() as u64;
+                StoresFixedVec::<(u64, u64, Address)>::push(&mut self.storage, &obs_disputes_key(), (dispute_index, window, resolver));
+                StoresFixedVec::<(Option<i64>, Option<u64>, bool)>::push(&mut self.storage, &obs_pending_values_key(), (None, None, false));
+            }
+        }
+
+        // Deserialize sub-contract
+        let (i1, sub_combinator) = self.deserialize_sub_combinator(i0, serialized_combinators);
+
+        (i1, Box::new(ScaleCombinator::new(sub_combinator, obs_index, scale_value)))
+    }
+
+    // Deserializes a give combinator from a contract definition, given the index following its tag
+    fn deserialize_give(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Deserialize sub-combinator
+        let (i0, sub_combinator) = self.deserialize_sub_combinator(i, serialized_combinators);
+
+        (i0, Box::new(GiveCombinator::new(sub_combinator)))
+    }
+
+    // Deserializes a then combinator from a contract definition, given the index following its tag
+    fn deserialize_then(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Deserialize sub-combinators
+        let (i0, sub_combinator0) = self.deserialize_sub_combinator(i, serialized_combinators);
+        let (i1, sub_combinator1) = self.deserialize_sub_combinator(i0, serialized_combinators);
+
+        (i1, Box::new(ThenCombinator::new(sub_combinator0, sub_combinator1)))
+    }
+
+    // Deserializes a get combinator from a contract definition, given the index following its tag
+    fn deserialize_get(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Deserialize sub-combinator
+        let (i0, sub_combinator) = self.deserialize_sub_combinator(i, serialized_combinators);
+
+        (i0, Box::new(GetCombinator::new(sub_combinator)))
+    }
+
+    // Deserializes an anytime combinator from a contract definition, given the index following its tag
+    fn deserialize_anytime(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Keep track of anytime_index and anytime_acquisition_times (shared by all periods of any enclosing schedules)
+        let anytime_index = StoresFixedVec::<(bool, Option<u64>)>::length(&mut self.storage, &anytime_acquisition_times_key());
+        StoresFixedVec::<(bool, Option<u64>)>::push(&mut self.storage, &anytime_acquisition_times_key(), (false, None));
+
+        // Deserialize sub-combinator
+        let (i0, sub_combinator) = self.deserialize_sub_combinator(i, serialized_combinators);
+
+        (i0, Box::new(AnytimeCombinator::new(sub_combinator, anytime_index)))
+    }
 
-            // anytime combinator
-            Combinator::ANYTIME => {
-                // Keep track of anytime_index and anytime_acquisition_times
-                let anytime_index = StoresFixedVec::<(bool, Option<u32>)>::length(&mut self.storage, &anytime_acquisition_times_key());
-                StoresFixedVec::<(bool, Option<u32>)>::push(&mut self.storage, &anytime_acquisition_times_key(), (false, None));
+    // Deserializes a schedule combinator from a contract definition, given the index following its tag
+    fn deserialize_schedule(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Deserialize first period date, period length and period count
+        let start: u64 = FinancialScContract::deserialize_time(serialized_combinators[i]);
+        let period: u64 = FinancialScContract::deserialize_schedule_period(serialized_combinators[i + 1]);
+        let count: u64 = FinancialScContract::deserialize_schedule_count(serialized_combinators[i + 2]);
 
-                // Deserialize sub-combinator
-                let (i0, sub_combinator) = self.deserialize_remote_combinator(i + 1);
+        // Deserialize sub-combinator (or-choices, observables and anytime indices are shared by all periods)
+        let (i0, sub_combinator) = self.deserialize_sub_combinator(i + 3, serialized_combinators);
 
-                (i0, Box::new(AnytimeCombinator::new(sub_combinator, anytime_index)))
+        (i0, Box::new(ScheduleCombinator::new(sub_combinator, start, period, count)))
+    }
+
+    // Deserializes a relative truncate combinator from a contract definition, given the index following its tag
+    fn deserialize_truncate_relative(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Deserialize offset from the acquisition time
+        let horizon_offset: u64 = FinancialScContract::deserialize_time_offset(serialized_combinators[i]);
+
+        // Deserialize sub-combinator
+        let (i0, sub_combinator) = self.deserialize_sub_combinator(i + 1, serialized_combinators);
+
+        (i0, Box::new(TruncateCombinator::new_relative(sub_combinator, horizon_offset)))
+    }
+
+    // Deserializes a relative schedule combinator from a contract definition, given the index following its tag
+    fn deserialize_schedule_relative(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Deserialize first period offset from the acquisition time, period length and period count
+        let start_offset: u64 = FinancialScContract::deserialize_time_offset(serialized_combinators[i]);
+        let period: u64 = FinancialScContract::deserialize_schedule_period(serialized_combinators[i + 1]);
+        let count: u64 = FinancialScContract::deserialize_schedule_count(serialized_combinators[i + 2]);
+
+        // Deserialize sub-combinator
+        let (i0, sub_combinator) = self.deserialize_sub_combinator(i + 3, serialized_combinators);
+
+        (i0, Box::new(ScheduleCombinator::new_relative(sub_combinator, start_offset, period, count)))
+    }
+
+    // Deserializes a one combinator of a given asset from a contract definition, given the index following its tag
+    fn deserialize_one_asset(&mut self, i: usize, serialized_combinators: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        // Deserialize token address (the zero address is Ether)
+        let mut serialized_address: [i64; 4] = [0; 4];
+        serialized_address.copy_from_slice(&serialized_combinators[i..(i + 4)]);
+        let asset_index = self.register_asset(i64_to_address(serialized_address));
+
+        (i + 4, Box::new(OneCombinator::new_asset(asset_index)))
+    }
+
+    // Registers the given token as an asset of the contract if not yet registered, returns its asset index
+    fn register_asset(&mut self, asset: Address) -> usize {
+        if asset == Address::zero() {
+            return ETHER_ASSET;
+        }
+
+        let assets: Vec<(Address, i64, i64)> = self.storage.read_vec(&assets_key()).0;
+        match assets.iter().position(|e| e.0 == asset) {
+            Some(position) => position + 1,
+            None => {
+                StoresFixedVec::<(Address, i64, i64)>::push(&mut self.storage, &assets_key(), (asset, 0, 0));
+                assets.len() + 1
+            }
+        }
+    }
+
+    // Gets the asset index of the given asset (the zero address is Ether)
+    fn get_asset_index(&mut self, asset: Address) -> usize {
+        if asset == Address::zero() {
+            return ETHER_ASSET;
+        }
+
+        let assets: Vec<(Address, i64, i64)> = self.storage.read_vec(&assets_key()).0;
+        match assets.iter().position(|e| e.0 == asset) {
+            Some(position) => position + 1,
+            None => panic!("Asset is not used by the contract.")
+        }
+    }
+
+    // Gets the holder and counter-party balances of the asset with the given asset index
+    fn get_asset_balances(&mut self, asset_index: usize) -> (i64, i64) {
+        if asset_index == ETHER_ASSET {
+            (self.storage.read(&holder_balance_key()).0, self.storage.read(&counter_party_balance_key()).0)
+        } else {
+            let asset: (Address, i64, i64) = self.storage.get(&assets_key(), asset_index - 1);
+            (asset.1, asset.2)
+        }
+    }
+
+    // Sets the holder and counter-party balances of the asset with the given asset index
+    fn set_asset_balances(&mut self, asset_index: usize, holder_balance: i64, counter_party_balance: i64) {
+        if asset_index == ETHER_ASSET {
+            self.storage.write(&holder_balance_key(), holder_balance);
+            self.storage.write(&counter_party_balance_key(), counter_party_balance);
+        } else {
+            let asset: (Address, i64, i64) = self.storage.get(&assets_key(), asset_index - 1);
+            self.storage.set(&assets_key(), asset_index - 1, (asset.0, holder_balance, counter_party_balance));
+        }
+    }
+
+    // Adjusts the holder and counter-party balances of each asset by the given amounts paid to the holder
+    fn adjust_balances(&mut self, differences: &AssetAmounts) {
+        for (asset_index, difference) in differences.get_amounts().iter().enumerate() {
+            if *difference == 0 {
+                continue;
             }
+
+            let (holder_balance, counter_party_balance) = self.get_asset_balances(asset_index);
+            self.set_asset_balances(
+                asset_index,
+                FinancialScContract::safe_add(holder_balance, *difference),
+                FinancialScContract::safe_add(counter_party_balance, -*difference)
+            );
+        }
+    }
+
+    // Encodes an ERC-20 token call with the given function selector, address arguments and amount argument
+    fn encode_token_call(selector: [u8; 4], addresses: &[Address], amount: u64) -> Vec<u8> {
+        let mut input: Vec<u8> = Vec::new();
+        input.extend_from_slice(&selector);
+
+        // Addresses are left-padded to 32 bytes
+        for address in addresses {
+            let word: [u8; 32] = H256::from(address.clone()).into();
+            input.extend_from_slice(&word);
+        }
+
+        // Amounts are 32-byte big-endian integers
+        let mut word: [u8; 32] = [0; 32];
+        U256::from(amount).to_big_endian(&mut word);
+        input.extend_from_slice(&word);
+        input
+    }
+
+    // Checks the return data of a successful ERC-20 token call as SafeERC20 does: the token must return true, or nothing at all
+    fn token_call_succeeded(result: &[u8; 32]) -> bool {
+        *result == TOKEN_NO_RETURN_DATA || (result[31] == 1 && result[0..31].iter().all(|&byte| byte == 0))
+    }
+
+    // Deserializes a time, which must not be negative
+    fn deserialize_time(time: i64) -> u64 {
+        if time < 0 {
+            panic!("Times must not be negative.");
+        }
+        time as u64
+    }
+
+    // Deserializes an offset from the acquisition time, which must not be negative
+    fn deserialize_time_offset(offset: i64) -> u64 {
+        if offset < 0 {
+            panic!("Relative time offsets must not be negative.");
+        }
+        offset as u64
+    }
+
+    // Deserializes the period length of a schedule combinator, which must not be negative
+    fn deserialize_schedule_period(period: i64) -> u64 {
+        if period < 0 {
+            panic!("Schedule periods must not be negative.");
+        }
+        period as u64
+    }
+
+    // Deserializes the period count of a schedule combinator, which must be at least one
+    fn deserialize_schedule_count(count: i64) -> u64 {
+        if count < 1 {
+            panic!("Schedule combinator must have at least one period.");
         }
+        count as u64
     }
 
     // Gets and deserializes the ContractCombinator from storage
@@ -660,6 +1606,68 @@ impl FinancialScContract {
         obs_values.into_iter().map(|e| e.1).collect()
     }
 
+    // Returns the eth_sign message hash of an observable report: the hash of the observable name (as UTF-8), observable index,
+    // value, timestamp and contract address, packed as abi.encodePacked(string, uint64, int64, uint64, address). The index
+    // keeps a report for one observable from being submitted for another of the same name and arbiter.
+    fn get_obs_report_hash(name: &ObsName, obs_index: u64, value: i64, timestamp: u64, contract: Address) -> H256 {
+        let mut report: Vec<u8> = Vec::new();
+        for code in name.name.iter() {
+            let mut buffer = [0; 4];
+            match core::char::from_u32(*code as u32) {
+                Some(character) if *code >= 0 => report.extend_from_slice(character.encode_utf8(&mut buffer).as_bytes()),
+                _ => panic!("Observable name is not valid text.")
+            }
+        }
+
+        // The index, value and timestamp are 8-byte big-endian integers
+        for number in [obs_index, value as u64, timestamp].iter() {
+            let mut word: [u8; 32] = [0; 32];
+            U256::from(*number).to_big_endian(&mut word);
+            report.extend_from_slice(&word[24..32]);
+        }
+        report.extend_from_slice(&contract.0);
+
+        let mut message: Vec<u8> = Vec::new();
+        message.extend_from_slice(SIGNED_MESSAGE_PREFIX);
+        message.extend_from_slice(&pwasm_ethereum::keccak(&report).0);
+        pwasm_ethereum::keccak(&message)
+    }
+
+    // Recovers the signer of the given message hash from a 65-byte (r, s, v) signature with the ecrecover precompiled contract,
+    // panics if the signature is invalid
+    fn recover_signer(hash: &H256, signature: &Vec<u8>) -> Address {
+        if signature.len() != 65 {
+            panic!("Observable report signature must be 65 bytes.");
+        }
+
+        // The input is the hash, v (27 or 28, padded to 32 bytes), r and s
+        let mut input = [0; 128];
+        input[0..32].copy_from_slice(&hash.0);
+        input[63] = if signature[64] < 27 { signature[64] + 27 } else { signature[64] };
+        input[64..128].copy_from_slice(&signature[0..64]);
+
+        let mut result = [0; 32];
+        let recovered = pwasm_ethereum::call(ECRECOVER_GAS, &Address::from(ECRECOVER_ADDRESS), U256::zero(), &input, &mut result);
+        let signer = Address::from_slice(&result[12..32]);
+        if recovered.is_err() || signer == Address::zero() {
+            panic!("Observable report signature is not valid.");
+        }
+        signer
+    }
+
+    // Returns the median of the given values (the mean of the middle two, rounded down, for an even number of values)
+    fn median(mut values: Vec<i64>) -> i64 {
+        values.sort_unstable();
+        let middle = values.len() / 2;
+        if values.len() % 2 == 1 {
+            return values[middle];
+        }
+
+        // Halve before adding to avoid overflow
+        let (low, high) = (values[middle - 1], values[middle]);
+        (low >> 1) + (high >> 1) + (low & high & 1)
+    }
+
     // Add numbers safely to avoid integer overflow/underflow
     fn safe_add(x: i64, y: i64) -> i64 {
         if y > 0 && x > 2_i64.pow(62) + (2_i64.pow(62) - 1) - y {
@@ -678,17 +1686,9 @@ impl FinancialScContract {
         }
     }
 
-    // Withdraws Ether from the given contract participant, returns the amount to send including gas price
-    fn get_withdrawal_amount(amount: u64, balance: i64, funds: i64, use_gas: bool) -> i64 {
+    // Withdraws from the given contract participant, returns the amount to send
+    fn get_withdrawal_amount(amount: u64, balance: i64, funds: i64) -> i64 {
         let mut final_amount = amount as i64;
-        if use_gas {
-            final_amount = final_amount + CALL_GAS;
-        }
-
-        // If the withdrawer or contract can't afford the gas for the transaction, do nothing more
-        if use_gas && (balance < CALL_GAS || funds < CALL_GAS) {
-            return 0;
-        }
 
         // Clamp withdrawal at balance and fund amount
         if balance < final_amount {
@@ -702,9 +1702,14 @@ impl FinancialScContract {
     }
 
     fn is_combinator_concluded(combinator: &Box<ContractCombinator>) -> bool {
+        FinancialScContract::is_combinator_concluded_at(combinator, pwasm_ethereum::timestamp())
+    }
+
+    // Whether or not the combinator contract has concluded at the given time
+    fn is_combinator_concluded_at(combinator: &Box<ContractCombinator>, time: u64) -> bool {
         let combinator_details = combinator.get_combinator_details();
         combinator_details.fully_updated
-            || combinator_details.acquisition_time == None && combinator.past_horizon(pwasm_ethereum::timestamp() as u32)
+            || combinator_details.acquisition_time == None && combinator.past_horizon(time)
     }
 }
 
@@ -714,10 +1719,18 @@ impl FinancialScContract {
 mod tests {
     extern crate pwasm_test;
 
-    use super::{ FinancialScContract, FinancialScInterface, Storage, StoresFixed, StoresFixedVec, StoresVariable };
+    use super::{ FinancialScContract, FinancialScInterface, ContractCombinator, Storage, StoresFixed, StoresFixedVec, StoresVariable };
+    use super::{ OPERATOR_CHOICES, OPERATOR_ACQUIRE, OPERATOR_WITHDRAW, OPERATOR_DISPUTE, OBS_REPORT_VALIDITY };
     use storage::{ address_to_i64, i64_to_address };
-    use super::pwasm_std::{ Vec, vec, types::{ Address, U256, H256 } };
-    use self::pwasm_test::{ ext_reset, ext_update };
+    use builder::{ ScaleValue, one, observable, quorum, dispute, scale_by_quorum, scale_by_disputable };
+    use state_tree::deserialize_state_tree;
+    use combinators::Combinator;
+    use super::pwasm_std::{ Box, Vec, vec, types::{ Address, U256, H256 } };
+    use super::pwasm_ethereum::Error;
+    use self::pwasm_test::{ ext_reset, ext_update, ext_get };
+
+    // The gas stipend forwarded with Ether payments in tests
+    static GAS_STIPEND: i64 = 2300;
 
     // Initialise a FinancialScContract with the given values (and mock blockchain parameters)
     fn setup_contract(sender: Address, holder: Address, timestamp: u64, serialized_combinator_contract: Vec<i64>) -> FinancialScContract {
@@ -727,7 +1740,7 @@ mod tests {
             .sender(sender)
             .timestamp(timestamp)
         );
-        contract.constructor(serialized_combinator_contract, holder, true);
+        contract.constructor(serialized_combinator_contract, holder, true, GAS_STIPEND as u64);
         contract
     }
 
@@ -739,10 +1752,94 @@ mod tests {
             .sender(sender)
             .timestamp(timestamp)
         );
-        contract.constructor(serialized_combinator_contract, holder, false);
+        contract.constructor(serialized_combinator_contract, holder, false, GAS_STIPEND as u64);
+        contract
+    }
+
+    // Serializes a definition of nested scale combinators over one, by the given number of observables named "obs" of the arbiter
+    fn obs_definition(arbiter: Address, count: usize) -> Vec<i64> {
+        let arbiter_serialized: [i64; 4] = address_to_i64(arbiter);
+        let mut definition = Vec::new();
+        for _ in 0..count {
+            definition.extend_from_slice(&[5, -1, arbiter_serialized[0], arbiter_serialized[1], arbiter_serialized[2], arbiter_serialized[3]]);
+            definition.extend_from_slice(&[3, 'o' as i64, 'b' as i64, 's' as i64]);
+        }
+        definition.push(1);
+        definition
+    }
+
+    // Hashes a report for an observable named "obs" as it is signed off-chain, with eth_sign of
+    // keccak256(abi.encodePacked("obs", uint64 obs_index, int64 value, uint64 timestamp, address contract))
+    fn obs_report_hash(obs_index: u64, value: i64, timestamp: u64, contract: Address) -> H256 {
+        let mut report: Vec<u8> = b"obs".to_vec();
+        report.extend((0..8).map(|i| (obs_index >> (56 - 8 * i)) as u8));
+        report.extend((0..8).map(|i| (value >> (56 - 8 * i)) as u8));
+        report.extend((0..8).map(|i| (timestamp >> (56 - 8 * i)) as u8));
+        report.extend_from_slice(&contract.0);
+
+        let mut message: Vec<u8> = b"\x19Ethereum Signed Message:\n32".to_vec();
+        message.extend_from_slice(&super::pwasm_ethereum::keccak(&report).0);
+        super::pwasm_ethereum::keccak(&message)
+    }
+
+    // The arbiters of the quorum observables in tests
+    fn quorum_arbiters() -> Vec<Address> {
+        vec![
+            "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap(),
+            "0e6D2ABbe3C0d1e0C2f4Bb1aa4B9bC8b5D1f4a2E".parse().unwrap(),
+            "7E5F4552091A69125d5DfCb7b8C2659029395Bdf".parse().unwrap()
+        ]
+    }
+
+    // Initialise a FinancialScContract scaling one by a quorum observable of the test arbiters, with the given threshold, and
+    // acquire it
+    fn setup_quorum_contract(threshold: u64) -> FinancialScContract {
+        let holder: Address = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            scale_by_quorum(quorum(quorum_arbiters(), threshold, "obs"), one()).serialize()
+        );
+
+        ext_update(|e| e.sender(holder));
+        contract.acquire();
+        contract
+    }
+
+    // The resolver of the disputable observables in tests
+    fn dispute_resolver() -> Address {
+        "00a329c0648769A73afAc7F9381E08FB43dBEA72".parse().unwrap()
+    }
+
+    // Initialise a FinancialScContract scaling one by the given observable, disputable for 100 seconds, and acquire it at time 0
+    fn setup_disputable_contract(obs: ScaleValue) -> FinancialScContract {
+        let holder: Address = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            scale_by_disputable(dispute(100, dispute_resolver()), obs, one()).serialize()
+        );
+
+        ext_update(|e| e.sender(holder));
+        contract.acquire();
         contract
     }
 
+    // Mocks the ecrecover precompiled contract, recovering the given signer from the given (r, s, v) signature of the given hash
+    // only, and the zero address otherwise
+    fn mock_ecrecover(hash: H256, signature: Vec<u8>, signer: Address) {
+        ext_update(|e| e
+            .endpoint("0000000000000000000000000000000000000001".parse().unwrap(), Box::new(move |_value: U256, input: &[u8], result: &mut [u8]| {
+                if &input[0..32] == &hash.0[..] && input[63] == signature[64] && &input[64..128] == &signature[0..64] {
+                    result[12..32].copy_from_slice(&signer.0);
+                }
+                Ok(())
+            }))
+        );
+    }
+
     // The counter-party of the contract is set to the deployer
     #[test]
     fn correct_counter_party() {
@@ -953,24 +2050,73 @@ mod tests {
         assert_eq!(contract.get_acquisition_times(), vec![0, 1, 2, 3, 4, -1]);
     }
 
-    // Or choices returned correctly
+    // Each period of a schedule acquires its anytime sub-contract separately, resetting the acquisition time of the previous period
+    #[cfg(feature = "schedule")]
     #[test]
-    fn get_or_choices_returns_correct_values() {
-        let combinator_contract = vec![3, 3, 3, 1, 0, 1, 0];
+    fn anytime_sub_contracts_acquired_for_each_schedule_period() {
         let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             holder,
             0,
-            combinator_contract.clone()
+            vec![10, 10, 10, 2, 9, 1]
         );
+        assert_eq!(contract.get_acquisition_times(), vec![-1, -1]);
 
-        ext_update(|e| e.sender(holder));
+        ext_update(|e| e
+            .sender(holder)
+            .timestamp(0)
+        );
+        contract.acquire();
 
-        contract.set_or_choice(0, true);
-        contract.set_or_choice(1, false);
+        ext_update(|e| e.timestamp(12));
+        contract.update();
+        contract.acquire_anytime_sub_contract(0);
+        assert_eq!(contract.get_balance(true), 1);
+        assert_eq!(contract.get_acquisition_times(), vec![0, -1]);
 
-        assert_eq!(contract.get_or_choices(), vec![1, 0, 2]);
+        ext_update(|e| e.timestamp(22));
+        contract.update();
+        contract.acquire_anytime_sub_contract(0);
+        assert_eq!(contract.get_balance(true), 2);
+        assert_eq!(contract.get_acquisition_times(), vec![0, 22]);
+    }
+
+    // Anytime combinators have a single acquisition time however many periods their enclosing schedules have
+    #[cfg(feature = "schedule")]
+    #[test]
+    fn anytime_storage_independent_of_schedule_count() {
+        for count in vec![1, 2, 1000] {
+            for definition in vec![vec![10, 10, 10, count, 9, 1], vec![10, 10, 10, count, 12, 0, 1, count, 9, 1]] {
+                let mut contract = setup_contract(
+                    "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+                    "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+                    0,
+                    definition
+                );
+                assert_eq!(contract.get_acquisition_times(), vec![-1, -1]);
+            }
+        }
+    }
+
+    // Or choices returned correctly
+    #[test]
+    fn get_or_choices_returns_correct_values() {
+        let combinator_contract = vec![3, 3, 3, 1, 0, 1, 0];
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            combinator_contract.clone()
+        );
+
+        ext_update(|e| e.sender(holder));
+
+        contract.set_or_choice(0, true);
+        contract.set_or_choice(1, false);
+
+        assert_eq!(contract.get_or_choices(), vec![1, 0, 2]);
     }
 
     // Obs entries returned correctly
@@ -1041,22 +2187,34 @@ mod tests {
         assert_eq!(contract.get_obs_values(), vec![Some(1), None, Some(-1)]);
     }
 
-    // Withdrawal amount is calculated correctly for a normal withdrawal
+    // Deserializing a definition off-chain gives the same combinator contract and indices as the constructor
     #[test]
-    fn get_withdrawal_amount_calculates_correct_normal_amount() {
-        let balance = 10000;
-        let withdrawal = 5000;
-        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, balance as i64, true);
+    fn deserialize_definition_correct() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let arbiter_serialized: [i64; 4] = address_to_i64(arbiter);
+
+        // Create contract or (scale obs one) (anytime one)
+        let (combinator, mut storage) = FinancialScContract::deserialize_definition(&vec![
+            3, 5, -1, arbiter_serialized[0], arbiter_serialized[1], arbiter_serialized[2], arbiter_serialized[3], 1, 1, 1, 9, 1
+        ]);
+
+        assert_eq!(combinator.get_combinator_number(), Combinator::OR);
+        assert_eq!(combinator.get_index(), Some(0));
+        let sub_combinators = combinator.get_sub_combinators();
+        assert_eq!(sub_combinators[0].get_index(), Some(0));
+        assert_eq!(sub_combinators[1].get_combinator_number(), Combinator::ANYTIME);
 
-        assert_eq!(amount, withdrawal as i64 + super::CALL_GAS);
+        let obs_values: Vec<(Address, Option<i64>)> = storage.read_vec(&super::obs_values_key()).0;
+        assert_eq!(obs_values, vec![(arbiter, None)]);
+        assert_eq!(StoresFixedVec::<Option<bool>>::length(&mut storage, &super::or_choices_key()), 1);
     }
 
-    // Withdrawal amount is calculated correctly for a normal withdrawal when not using gas
+    // Withdrawal amount is calculated correctly for a normal withdrawal
     #[test]
-    fn get_withdrawal_amount_calculates_correct_normal_amount_no_gas() {
+    fn get_withdrawal_amount_calculates_correct_normal_amount() {
         let balance = 10000;
         let withdrawal = 5000;
-        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, balance as i64, false);
+        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, balance as i64);
 
         assert_eq!(amount, withdrawal as i64);
     }
@@ -1066,7 +2224,7 @@ mod tests {
     fn get_withdrawal_amount_clamps_withdrawal_to_balance() {
         let balance = 5000;
         let withdrawal = 10000;
-        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, balance as i64, true);
+        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, balance as i64);
 
         assert_eq!(balance, amount);
     }
@@ -1077,382 +2235,813 @@ mod tests {
         let balance = 5000;
         let withdrawal = 10000;
         let funds = 2500;
-        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, funds, true);
+        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, funds);
 
         assert_eq!(funds, amount);
     }
 
-    // Withdrawal does not withdraw anything if the balance is below the required call gas price
+    // Withdrawal withdraws even if the balance is below the gas stipend
     #[test]
-    fn withdraw_does_not_withdraw_if_balance_below_gas_price() {
-        let balance = (super::CALL_GAS - 1) as i64;
+    fn withdraw_withdraws_if_balance_below_gas_stipend() {
+        let balance = (GAS_STIPEND - 1) as i64;
         let withdrawal = 1 as u64;
-        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, 10000, true);
+        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, 10000);
 
-        assert_eq!(amount, 0);
+        assert_eq!(amount, 1);
     }
 
-    // Withdrawal does not withdraw anything if the funds are below the required call gas price
+    // The gas stipend is set by the constructor
     #[test]
-    fn withdraw_does_not_withdraw_if_funds_below_gas_price() {
-        let balance = 10000 as i64;
-        let withdrawal = 1 as u64;
-        let funds = super::CALL_GAS - 1;
-        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, funds as i64, true);
+    fn correct_gas_stipend() {
+        let mut contract = FinancialScContract::new();
+        ext_reset(|e| e.sender("1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap()));
+        contract.constructor(vec![0], "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(), true, 5000);
 
-        assert_eq!(amount, 0);
+        assert_eq!(contract.get_gas_stipend(), 5000);
     }
 
-    // Withdrawal withdraws even if the balance is below the required call gas price when not using gas
+    // Withdrawing forwards the configured gas stipend without deducting it from the withdrawer's balance
     #[test]
-    fn withdraw_withdraws_if_balance_below_gas_price_when_not_using_gas() {
-        let balance = (super::CALL_GAS - 1) as i64;
-        let withdrawal = 1 as u64;
-        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, 10000, false);
-
-        assert_eq!(amount, 1);
-    }
+    fn withdraw_does_not_deduct_gas_stipend() {
+        let mut contract = FinancialScContract::new();
+        ext_reset(|e| e.sender("1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap()));
+        contract.constructor(vec![0], "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(), true, 5000);
 
-    // Withdrawal withdraws if the funds are below the required call gas price when not using gas
-    #[test]
-    fn withdraw_withdraws_if_funds_below_gas_price_when_not_using_gas() {
-        let balance = 10000 as i64;
-        let withdrawal = 1 as u64;
-        let funds = super::CALL_GAS - 1;
-        let amount = FinancialScContract::get_withdrawal_amount(withdrawal, balance, funds as i64, false);
+        ext_update(|e| e.value(U256::from(10000)));
+        contract.stake();
+        contract.withdraw(1000);
 
-        assert_eq!(amount, 1);
+        assert_eq!(contract.get_balance(false), 9000);
     }
 
-    // Attempting to create a contract with the same holder and counter-party should panic
+    // Withdrawing to another recipient takes the amount from the sender's balance
     #[test]
-    #[should_panic(expected = "Holder and counter-party must be different addresses.")]
-    fn should_panic_if_holder_equals_counter_party() {
-        let sender = "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap();
-        setup_contract(
-            sender,
-            sender,
+    fn withdraw_to_takes_amount_from_sender_balance() {
+        let recipient: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let mut contract = setup_contract_no_gas(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
             0,
             vec![0]
         );
+
+        ext_update(|e| e.value(U256::from(1000)));
+        contract.stake();
+        contract.withdraw_to(Address::zero(), recipient, 400);
+
+        assert_eq!(contract.get_balance(false), 600);
+        assert_eq!(contract.get_balance(true), 0);
     }
 
-    // An empty deserialized combinator vector is not allowed
+    // Relative horizons are resolved from the acquisition time when acquiring the contract
     #[test]
-    #[should_panic(expected = "Provided combinator contract not valid.")]
-    fn should_panic_if_no_combinators_given() {
-        // Mock values and instantiate contract
-        setup_contract(
+    fn acquiring_resolves_relative_times() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
-            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            holder,
             0,
-            vec![]
+            vec![8, 11, 30, 1]
+        );
+
+        ext_update(|e| e
+            .sender(holder)
+            .timestamp(100)
         );
+        contract.acquire();
+        assert_eq!(contract.get_combinator().get_horizon(), Some(130));
     }
 
-    // An undefined combinator vector value is not allowed
+    // Updating a contract with a non-Ether asset adjusts the balances of that asset only
+    #[cfg(feature = "assets")]
     #[test]
-    #[should_panic(expected = "Unrecognised combinator.")]
-    fn should_panic_if_combinator_value_unrecognised() {
-        // Mock values and instantiate contract
-        setup_contract(
+    fn updating_adjusts_asset_balances() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let token: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let token_serialized: [i64; 4] = address_to_i64(token);
+        let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
-            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            holder,
             0,
-            vec![255]
+            vec![2, 1, 5, 1, 3, 13, token_serialized[0], token_serialized[1], token_serialized[2], token_serialized[3]]
         );
+
+        ext_update(|e| e.sender(holder));
+        contract.acquire();
+
+        assert_eq!(contract.get_balance(true), 1);
+        assert_eq!(contract.get_balance(false), -1);
+        assert_eq!(contract.get_asset_balance(true, Address::zero()), 1);
+        assert_eq!(contract.get_asset_balance(true, token), 3);
+        assert_eq!(contract.get_asset_balance(false, token), -3);
     }
 
-    // A non-holder account providing an or-choice is not allowed.
+    // Assets are registered once each, in the order they appear in the contract definition
+    #[cfg(feature = "assets")]
     #[test]
-    #[should_panic(expected = "Only the contract holder may set or-choices.")]
-    fn should_panic_if_non_holder_provides_or_choice() {
+    fn get_assets_returns_registered_assets() {
+        let token0: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let token1: Address = "000000000000000000000000000000000000dEaD".parse().unwrap();
+        let token0_serialized: [i64; 4] = address_to_i64(token0);
+        let token1_serialized: [i64; 4] = address_to_i64(token1);
+        let zero_serialized: [i64; 4] = address_to_i64(Address::zero());
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
             0,
-            vec![0]
+            vec![
+                2, 13, token0_serialized[0], token0_serialized[1], token0_serialized[2], token0_serialized[3],
+                2, 13, token1_serialized[0], token1_serialized[1], token1_serialized[2], token1_serialized[3],
+                2, 13, token0_serialized[0], token0_serialized[1], token0_serialized[2], token0_serialized[3],
+                13, zero_serialized[0], zero_serialized[1], zero_serialized[2], zero_serialized[3]
+            ]
         );
 
-        ext_update(|e| e.sender(Address::zero()));
-        contract.set_or_choice(0, true);
+        assert_eq!(contract.get_assets(), vec![Address::zero(), token0, token1]);
     }
 
-    // Setting a non-existent or-choice is not allowed.
+    // ERC-20 token calls are ABI-encoded correctly
     #[test]
-    #[should_panic(expected = "Stored vector index out of bounds.")]
-    fn should_panic_if_setting_non_existent_or_choice() {
-        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
-        let mut contract = setup_contract(
-            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
-            holder,
-            0,
-            vec![0]
-        );
+    fn encode_token_call_correct() {
+        let address: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let input = FinancialScContract::encode_token_call(super::TRANSFER_SELECTOR, &[address], 258);
 
-        ext_update(|e| e.sender(holder));
-        contract.set_or_choice(0, true);
+        assert_eq!(input.len(), 4 + 32 * 2);
+        assert_eq!(&input[0..4], &super::TRANSFER_SELECTOR);
+        assert_eq!(&input[4..16], &[0; 12]);
+        assert_eq!(&input[16..36], &address[..]);
+        assert_eq!(&input[36..66], &[0; 30][..]);
+        assert_eq!(&input[66..68], &[1, 2]);
     }
 
-    // Providing an or choice for a non-existent or combinator is not allowed
+    // Setting or choices in a batch sets each or choice
     #[test]
-    #[should_panic(expected = "Stored vector index out of bounds.")]
-    fn should_panic_if_non_existent_or_choice_provided() {
+    fn set_or_choices_sets_all_choices() {
         let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             holder,
             0,
-            vec![0]
+            vec![3, 3, 3, 1, 0, 1, 0]
         );
 
-        ext_update(|e| e.sender("25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap()));
-        contract.set_or_choice(0, true);
+        ext_update(|e| e.sender(holder));
+        contract.set_or_choices(vec![0, 2], vec![true, false], false);
+
+        assert_eq!(contract.get_or_choices(), vec![1, 2, 0]);
     }
 
-    // Non-holders acquiring the contract is not allowed
+    // Setting obs values in a batch sets each value, and updates the contract when requested
     #[test]
-    #[should_panic(expected = "Only the contract holder may acquire the combinator contract.")]
-    fn should_panic_if_non_holder_acquires() {
+    fn set_obs_values_sets_all_values_and_updates() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let arbiter_serialized: [i64; 4] = address_to_i64(arbiter);
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
-            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            holder,
             0,
-            vec![0]
+            vec![
+                5, -1, arbiter_serialized[0], arbiter_serialized[1], arbiter_serialized[2], arbiter_serialized[3], 1, 100,
+                5, -1, arbiter_serialized[0], arbiter_serialized[1], arbiter_serialized[2], arbiter_serialized[3], 1, 101,
+                1
+            ]
         );
 
-        ext_update(|e| e.sender(Address::zero()));
+        ext_update(|e| e.sender(holder));
         contract.acquire();
+        assert_eq!(contract.get_balance(true), 0);
+
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_values(vec![0, 1], vec![3, 4], true);
+
+        assert_eq!(contract.get_obs_values(), vec![Some(3), Some(4)]);
+        assert_eq!(contract.get_balance(true), 12);
     }
 
-    // Acquiring the contract twice is not allowed
+    // Projected balances reflect an update at the given time
     #[test]
-    #[should_panic(expected = "The combinator contract cannot be acquired more than once.")]
-    fn should_panic_if_contract_acquired_twice() {
+    fn get_projected_balances_returns_balances_at_time() {
         let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             holder,
             0,
-            vec![0]
+            vec![8, 4, 10, 1]
         );
 
         ext_update(|e| e.sender(holder));
         contract.acquire();
-        contract.acquire();
+
+        assert_eq!(contract.get_projected_balances(5), vec![0, 0, 0]);
+        assert_eq!(contract.get_projected_balances(10), vec![1, 1, -1]);
     }
 
-    // Non-holders acquiring anytime sub-contracts is not allowed
+    // Projected balances include each asset's balances
+    #[cfg(feature = "assets")]
     #[test]
-    #[should_panic(expected = "Only the contract holder may acquire the combinator contract.")]
-    fn should_panic_if_non_holder_acquires_anytime_sub_contract() {
+    fn get_projected_balances_includes_assets() {
         let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let token: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let token_serialized: [i64; 4] = address_to_i64(token);
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             holder,
             0,
-            vec![9, 1]
+            vec![8, 4, 10, 6, 13, token_serialized[0], token_serialized[1], token_serialized[2], token_serialized[3]]
         );
+
         ext_update(|e| e.sender(holder));
         contract.acquire();
 
-        ext_update(|e| e.sender(Address::zero()));
-        contract.acquire_anytime_sub_contract(0);
+        assert_eq!(contract.get_projected_balances(10), vec![1, 0, 0, -1, 1]);
     }
 
-    // Acquiring non-existent anytime sub-contracts is not allowed
+    // Staking and withdrawing tokens succeeds if the token returns true or nothing
+    #[cfg(feature = "assets")]
     #[test]
-    #[should_panic(expected = "Stored vector index out of bounds.")]
-    fn should_panic_when_acquiring_non_existent_anytime_sub_contract() {
+    fn token_transfers_succeed_if_token_returns_true_or_nothing() {
         let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let token: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let token_serialized: [i64; 4] = address_to_i64(token);
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             holder,
             0,
-            vec![9, 1]
+            vec![13, token_serialized[0], token_serialized[1], token_serialized[2], token_serialized[3]]
         );
 
         ext_update(|e| e
             .sender(holder)
-            .timestamp(0)
+            .endpoint(token, Box::new(|_value: U256, _input: &[u8], result: &mut [u8]| {
+                result[31] = 1;
+                for byte in result[0..31].iter_mut() {
+                    *byte = 0;
+                }
+                Ok(())
+            }))
         );
-        contract.acquire();
-        contract.acquire_anytime_sub_contract(1);
+        assert_eq!(contract.stake_asset(token, 100), 100);
+
+        ext_update(|e| e.endpoint(token, Box::new(|_value: U256, _input: &[u8], _result: &mut [u8]| Ok(()))));
+        contract.withdraw_asset(token, 40);
+        assert_eq!(contract.get_asset_balance(true, token), 60);
     }
 
-    // Acquiring anytime sub-contracts twice is not allowed
+    // Projecting balances does not change the stored contract
     #[test]
-    #[should_panic(expected = "Cannot acquire a sub-combinator contract which has already been acquired.")]
-    fn should_panic_when_acquiring_anytime_sub_contract_twice() {
+    fn get_projected_balances_does_not_persist() {
         let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             holder,
             0,
-            vec![9, 1]
+            vec![8, 4, 10, 1]
         );
 
-        ext_update(|e| e
-            .sender(holder)
-            .timestamp(0)
-        );
+        ext_update(|e| e.sender(holder));
         contract.acquire();
-        contract.acquire_anytime_sub_contract(0);
-        contract.acquire_anytime_sub_contract(0);
+        contract.get_projected_balances(10);
+
+        // Read back from contract storage with a fresh storage table
+        let mut stored_contract = FinancialScContract::new();
+        assert_eq!(stored_contract.get_balance(true), 0);
+        assert_eq!(stored_contract.get_balance(false), 0);
+        assert!(!stored_contract.get_combinator().get_combinator_details().fully_updated);
     }
 
-    // Acquiring anytime sub-contracts before the parent contract is not allowed
+    // The state tree reflects the live contract
     #[test]
-    #[should_panic(expected = "Given anytime combinator has not been acquired.")]
-    fn should_panic_if_acquiring_anytime_sub_contract_before_parent_contract() {
+    fn get_state_tree_returns_live_state() {
         let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             holder,
             0,
-            vec![9, 1]
+            vec![3, 1, 0]
         );
-        ext_update(|e| e.sender(holder));
-        contract.acquire_anytime_sub_contract(0);
+
+        ext_update(|e| e
+            .sender(holder)
+            .timestamp(3)
+        );
+        contract.set_or_choice(0, true);
+        contract.acquire();
+
+        let state_tree = deserialize_state_tree(&contract.get_state_tree());
+        assert_eq!(state_tree.combinator, Combinator::OR);
+        assert_eq!(state_tree.index, Some(0));
+        assert_eq!(state_tree.acquisition_time, Some(3));
+        assert!(state_tree.fully_updated);
+        assert_eq!(state_tree.accrued, vec![1]);
+
+        assert_eq!(state_tree.sub_nodes[0].acquisition_time, Some(3));
+        assert_eq!(state_tree.sub_nodes[0].accrued, vec![1]);
+        assert_eq!(state_tree.sub_nodes[1].acquisition_time, None);
     }
 
-    // Overflowing the holder's stake is not allowed
+    // An operator approved by the holder can set or-choices and acquire on the holder's behalf
     #[test]
-    #[should_panic(expected = "Integer overflow.")]
-    fn should_panic_if_holder_balance_overflows() {
+    fn operator_can_set_choices_and_acquire_for_holder() {
         let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let operator: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             holder,
-            0,
-            vec![0]
+            5,
+            vec![3, 1, 0]
         );
 
-        // Set the stake to the maximum i64 value
-        ext_update(|e| e
-            .sender(holder)
-            .value(U256::from(2_i64.pow(62) + (2_i64.pow(62) - 1)))
-        );
-        contract.stake();
+        ext_update(|e| e.sender(holder));
+        contract.approve_operator(operator, OPERATOR_CHOICES | OPERATOR_ACQUIRE);
 
-        // Overflow the stake
-        ext_update(|e| e
-            .sender(holder)
-            .value(U256::from(1))
-        );
-        contract.stake();
+        ext_update(|e| e.sender(operator));
+        contract.set_or_choice(0, true);
+        contract.acquire();
+
+        assert_eq!(contract.get_or_choices(), vec![1]);
+        assert_eq!(contract.get_acquisition_times(), vec![5]);
+        assert_eq!(contract.get_balance(true), 1);
     }
 
-    // Overflowing the counter-party's stake is not allowed
+    // An operator approved to withdraw takes the withdrawal from the balance of the party it acts for
     #[test]
-    #[should_panic(expected = "Integer overflow.")]
-    fn should_panic_if_counter_party_balance_overflows() {
-        let sender = "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap();
-        let mut contract = setup_contract(
-            sender,
+    fn operator_withdraws_from_party_balance() {
+        let counter_party: Address = "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap();
+        let operator: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let mut contract = setup_contract_no_gas(
+            counter_party,
             "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
             0,
             vec![0]
         );
 
-        // Set the stake to the maximum i64 value
-        ext_update(|e| e
-            .sender(sender)
-            .value(U256::from(2_i64.pow(62) + (2_i64.pow(62) - 1)))
-        );
+        ext_update(|e| e.value(U256::from(1000)));
         contract.stake();
+        contract.approve_operator(operator, OPERATOR_WITHDRAW);
 
-        // Overflow the stake
-        ext_update(|e| e
-            .sender(sender)
-            .value(U256::from(1))
-        );
+        ext_update(|e| e.sender(operator).value(U256::zero()));
+        contract.withdraw(300);
+        contract.withdraw_to(Address::zero(), counter_party, 100);
 
-        // Check that integer overflow panic is caught
-        contract.stake();
+        assert_eq!(contract.get_balance(false), 600);
+        assert_eq!(contract.get_balance(true), 0);
     }
 
-    // The holder staking more than the maximum value of an i64 is not allowed
+    // Approving and revoking operators sets their permissions and logs events
     #[test]
-    #[should_panic(expected = "Given value is too large to be converted to i64.")]
-    fn should_panic_if_holder_stake_too_large() {
-        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+    fn approving_and_revoking_operators_logs_events() {
+        let holder: Address = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let operator: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             holder,
             0,
-            vec![0]
+            vec![1]
         );
 
-        // Set the stake to the maximum u64 value
-        ext_update(|e| e
-            .sender(holder)
-            .value(U256::from(2_u64.pow(63)))
-        );
-        contract.stake();
+        ext_update(|e| e.sender(holder));
+        contract.approve_operator(operator, OPERATOR_CHOICES);
+        contract.approve_operator(operator, OPERATOR_ACQUIRE | OPERATOR_WITHDRAW);
+        assert_eq!(contract.get_operator_permissions(holder, operator), 6);
+
+        contract.revoke_operator(operator);
+        assert_eq!(contract.get_operator_permissions(holder, operator), 0);
+
+        let logs = ext_get().logs();
+        assert_eq!(logs.len(), 3);
+        assert_eq!(logs[1].topics[0], super::pwasm_ethereum::keccak("OperatorApproved(address,address,uint64)"));
+        assert_eq!(&logs[1].topics[1].0[12..], &holder.0[..]);
+        assert_eq!(&logs[1].topics[2].0[12..], &operator.0[..]);
+        assert_eq!(logs[1].data[31], 6);
+        assert_eq!(logs[2].topics[0], super::pwasm_ethereum::keccak("OperatorRevoked(address,address)"));
+        assert_eq!(&logs[2].topics[2].0[12..], &operator.0[..]);
     }
 
-    // The counter-party staking more than the maximum value of an i64 is not allowed
+    // A report signed by the arbiter sets the observable's value, whoever submits it
     #[test]
-    #[should_panic(expected = "Given value is too large to be converted to i64.")]
-    fn should_panic_if_counter_party_stake_too_large() {
-        let sender = "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap();
+    fn submitting_signed_obs_report_sets_value() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let address: Address = "000000000000000000000000000000000000dEaD".parse().unwrap();
+        let signature: Vec<u8> = (0..65).map(|i| if i == 64 { 28 } else { i as u8 }).collect();
         let mut contract = setup_contract(
-            sender,
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
-            0,
-            vec![0]
+            5000,
+            obs_definition(arbiter, 1)
         );
 
-        // Set the stake to the maximum u64 value
         ext_update(|e| e
-            .sender(sender)
-            .value(U256::from(2_u64.pow(63)))
+            .address(address)
+            .sender("0e6D2ABbe3C0d1e0C2f4Bb1aa4B9bC8b5D1f4a2E".parse().unwrap())
         );
-        contract.stake();
+        mock_ecrecover(obs_report_hash(0, -7, 4000, address), signature.clone(), arbiter);
+        contract.submit_obs_report(0, -7, 4000, signature);
+
+        assert_eq!(contract.get_obs_values(), vec![Some(-7)]);
     }
 
-    // An uninvolved user attempting to stake Eth is not allowed
+    // A quorum observable resolves to the median of its arbiters' values once all have been submitted, even if they disagree
     #[test]
-    #[should_panic(expected = "Only the contract holder or the counter-party may stake Ether in the contract.")]
-    fn should_panic_if_uninvolved_user_stakes() {
+    fn quorum_resolves_to_median_of_disagreeing_arbiters() {
+        let arbiters = quorum_arbiters();
+        let mut contract = setup_quorum_contract(3);
+
+        for (arbiter, value) in arbiters.iter().zip([20, -4, 7].iter()) {
+            assert_eq!(contract.get_obs_values(), vec![None]);
+            ext_update(|e| e.sender(*arbiter));
+            contract.set_obs_value(0, *value);
+        }
+        assert_eq!(contract.get_obs_values(), vec![Some(7)]);
+
+        contract.update();
+        assert_eq!(contract.get_balance(true), 7);
+    }
+
+    // A quorum observable resolves once its threshold is reached, without the missing arbiters, to the mean of the middle two
+    // values for an even threshold
+    #[test]
+    fn quorum_resolves_without_missing_arbiter() {
+        let arbiters = quorum_arbiters();
+        let mut contract = setup_quorum_contract(2);
+
+        ext_update(|e| e.sender(arbiters[2]));
+        contract.set_obs_value(0, 4);
+        ext_update(|e| e.sender(arbiters[0]));
+        contract.set_obs_value(0, 9);
+
+        assert_eq!(contract.get_obs_values(), vec![Some(6)]);
+        assert_eq!(contract.get_balance(true), 0);
+        contract.update();
+        assert_eq!(contract.get_balance(true), 6);
+    }
+
+    // A quorum observable is not resolved, and pays nothing, until its threshold is reached
+    #[test]
+    fn quorum_unresolved_below_threshold() {
+        let arbiters = quorum_arbiters();
+        let mut contract = setup_quorum_contract(3);
+
+        ext_update(|e| e.sender(arbiters[0]));
+        contract.set_obs_value(0, 4);
+        ext_update(|e| e.sender(arbiters[1]));
+        contract.set_obs_value(0, 5);
+        contract.update();
+
+        assert_eq!(contract.get_obs_values(), vec![None]);
+        assert_eq!(contract.get_balance(true), 0);
+    }
+
+    // The submissions of each arbiter of a quorum observable are returned, and a single arbiter has a threshold of 1
+    #[test]
+    fn get_obs_submissions_returns_submissions() {
+        let arbiters = quorum_arbiters();
+        let serialized: Vec<[i64; 4]> = arbiters.iter().map(|arbiter| address_to_i64(*arbiter)).collect();
+        let mut contract = setup_quorum_contract(2);
+
+        ext_update(|e| e.sender(arbiters[1]));
+        contract.set_obs_value(0, -3);
+
+        assert_eq!(contract.get_obs_submissions(0), vec![
+            2, 3,
+            serialized[0][0], serialized[0][1], serialized[0][2], serialized[0][3], -1,
+            serialized[1][0], serialized[1][1], serialized[1][2], serialized[1][3], 0, -3,
+            serialized[2][0], serialized[2][1], serialized[2][2], serialized[2][3], -1
+        ]);
+        assert_eq!(contract.get_obs_entries(), vec![0, 0, 0, 0, -1, 3, 'o' as i64, 'b' as i64, 's' as i64]);
+
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
             0,
-            vec![0]
+            obs_definition(arbiters[0], 1)
         );
+        assert_eq!(contract.get_obs_submissions(0), vec![1, 1, serialized[0][0], serialized[0][1], serialized[0][2], serialized[0][3], -1]);
+    }
+
+    // An observable whose arbiter is the zero address is not mistaken for a quorum observable
+    #[test]
+    fn zero_address_arbiter_not_quorum() {
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            obs_definition(Address::zero(), 1)
+        );
+        assert_eq!(contract.get_obs_submissions(0), vec![1, 1, 0, 0, 0, 0, -1]);
+
+        ext_update(|e| e.sender(Address::zero()));
+        contract.set_obs_value(0, 5);
+        assert_eq!(contract.get_obs_values(), vec![Some(5)]);
+    }
+
+    // Each arbiter of a quorum observable may submit the same signed report
+    #[test]
+    fn quorum_arbiters_may_sign_same_report() {
+        let arbiters = quorum_arbiters();
+        let signature0: Vec<u8> = (0..65).map(|i| if i == 64 { 27 } else { i as u8 }).collect();
+        let signature1: Vec<u8> = (0..65).map(|i| if i == 64 { 27 } else { 100 + i as u8 }).collect();
+        let mut contract = setup_quorum_contract(2);
+
+        mock_ecrecover(obs_report_hash(0, 5, 0, Address::zero()), signature0.clone(), arbiters[0]);
+        contract.submit_obs_report(0, 5, 0, signature0);
+        mock_ecrecover(obs_report_hash(0, 5, 0, Address::zero()), signature1.clone(), arbiters[1]);
+        contract.submit_obs_report(0, 5, 0, signature1);
+
+        assert_eq!(contract.get_obs_values(), vec![Some(5)]);
+    }
+
+    // A disputable observable's value is only paid out once its dispute window has ended
+    #[test]
+    fn disputable_value_paid_after_window() {
+        let arbiter = quorum_arbiters()[0];
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
 
-        // Check state as an uninvolved user
         ext_update(|e| e
-            .sender(Address::zero())
-            .value(U256::from(10))
+            .sender(arbiter)
+            .timestamp(10)
         );
-        contract.stake();
+        contract.set_obs_value(0, 5);
+        ext_update(|e| e.timestamp(109));
+        contract.update();
+        assert_eq!(contract.get_obs_values(), vec![None]);
+        assert_eq!(contract.get_balance(true), 0);
+        assert_eq!(contract.get_projected_balances(110), vec![1, 5, -5]);
+
+        ext_update(|e| e.timestamp(110));
+        contract.update();
+        assert_eq!(contract.get_obs_values(), vec![Some(5)]);
+        assert_eq!(contract.get_balance(true), 5);
+    }
+
+    // A flagged value is not paid out after its dispute window, until the resolver overrides it
+    #[test]
+    fn flagged_value_frozen_until_resolved() {
+        let arbiter = quorum_arbiters()[0];
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_value(0, 5);
+        ext_update(|e| e
+            .sender("25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap())
+            .timestamp(50)
+        );
+        contract.flag_obs_value(0);
+        ext_update(|e| e.timestamp(500));
+        contract.update();
+        assert_eq!(contract.get_obs_values(), vec![None]);
+        assert_eq!(contract.get_balance(true), 0);
+
+        ext_update(|e| e.sender(dispute_resolver()));
+        contract.resolve_obs_value(0, 8);
+        contract.update();
+        assert_eq!(contract.get_obs_values(), vec![Some(8)]);
+        assert_eq!(contract.get_balance(true), 8);
+    }
+
+    // An operator approved to dispute may flag a value for the party it acts for
+    #[test]
+    fn operator_flags_value() {
+        let arbiter = quorum_arbiters()[0];
+        let operator: Address = "0D0d2D7CD8e4d7BfBa8DA6aC7b67c23AC1eCCb0F".parse().unwrap();
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+
+        ext_update(|e| e.sender("1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap()));
+        contract.approve_operator(operator, OPERATOR_DISPUTE);
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_value(0, 5);
+
+        ext_update(|e| e.sender(operator).timestamp(50));
+        contract.flag_obs_value(0);
+        ext_update(|e| e.timestamp(500));
+        contract.update();
+        assert_eq!(contract.get_obs_values(), vec![None]);
+        assert_eq!(contract.get_obs_dispute(0)[5], 1);
+    }
+
+    // The resolved value of a disputable quorum observable is held for its dispute window, and can be flagged by the counter-party
+    // and confirmed by the resolver
+    #[test]
+    fn disputable_quorum_value_confirmed() {
+        let arbiters = quorum_arbiters();
+        let mut contract = setup_disputable_contract(ScaleValue::Quorum(quorum(arbiters.clone(), 2, "obs")));
+
+        ext_update(|e| e.sender(arbiters[0]));
+        contract.set_obs_value(0, 4);
+        ext_update(|e| e.sender(arbiters[1]));
+        contract.set_obs_value(0, 6);
+        assert_eq!(contract.get_obs_values(), vec![None]);
+
+        ext_update(|e| e.sender("1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap()));
+        contract.flag_obs_value(0);
+        ext_update(|e| e.sender(dispute_resolver()));
+        contract.resolve_obs_value(0, 5);
+        contract.update();
+        assert_eq!(contract.get_balance(true), 5);
+    }
+
+    // The dispute window, resolver and status of a disputable observable's value are returned, and nothing for other observables
+    #[test]
+    fn get_obs_dispute_returns_status() {
+        let arbiter = quorum_arbiters()[0];
+        let resolver: [i64; 4] = address_to_i64(dispute_resolver());
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+        assert_eq!(contract.get_obs_dispute(0), vec![100, resolver[0], resolver[1], resolver[2], resolver[3], -1]);
+
+        ext_update(|e| e
+            .sender(arbiter)
+            .timestamp(10)
+        );
+        contract.set_obs_value(0, 5);
+        assert_eq!(contract.get_obs_dispute(0), vec![100, resolver[0], resolver[1], resolver[2], resolver[3], 0, 10, 5]);
+
+        ext_update(|e| e.sender("25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap()));
+        contract.flag_obs_value(0);
+        assert_eq!(contract.get_obs_dispute(0), vec![100, resolver[0], resolver[1], resolver[2], resolver[3], 1, 10, 5]);
+
+        ext_update(|e| e.sender(dispute_resolver()));
+        contract.resolve_obs_value(0, -2);
+        assert_eq!(contract.get_obs_dispute(0), vec![100, resolver[0], resolver[1], resolver[2], resolver[3], 2, 10, -2]);
+
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            obs_definition(arbiter, 1)
+        );
+        assert_eq!(contract.get_obs_dispute(0), vec![]);
     }
 
-    // Attempting to withdraw without enough funds to pay the gas fees is not allowed
+    // Times after 2106 (beyond 32 bits) are neither truncated in definitions nor at acquisition
     #[test]
-    #[should_panic(expected = "Not enough funds to pay the gas price while withdrawing.")]
-    fn should_panic_if_withdrawal_amount_below_gas_fees() {
+    fn times_beyond_32_bits_are_not_truncated() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
         let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            5_000_000_000,
+            vec![9, 4, 6_000_000_000, 1]
+        );
+
+        ext_update(|e| e
+            .sender(holder)
+            .timestamp(5_000_000_000)
+        );
+        contract.acquire();
+        assert!(!contract.get_concluded());
+
+        ext_update(|e| e.timestamp(5_500_000_000));
+        contract.acquire_anytime_sub_contract(0);
+
+        assert_eq!(contract.get_acquisition_times(), vec![5_000_000_000, 5_500_000_000]);
+        assert_eq!(contract.get_balance(true), 1);
+    }
+
+    // Attempting to create a contract with a negative time should panic
+    #[test]
+    #[should_panic(expected = "Times must not be negative.")]
+    fn should_panic_if_time_negative() {
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![4, -1, 1]
+        );
+    }
+
+    // Attempting to create a contract with a negative relative time offset should panic
+    #[test]
+    #[should_panic(expected = "Relative time offsets must not be negative.")]
+    fn should_panic_if_relative_offset_negative() {
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![11, -1, 1]
+        );
+    }
+
+    // Attempting to create a contract with a schedule with a negative period should panic
+    #[cfg(feature = "schedule")]
+    #[test]
+    #[should_panic(expected = "Schedule periods must not be negative.")]
+    fn should_panic_if_schedule_period_negative() {
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![10, 0, -1, 2, 1]
+        );
+    }
+
+    // Attempting to create a contract with a schedule with a negative period count should panic
+    #[cfg(feature = "schedule")]
+    #[test]
+    #[should_panic(expected = "Schedule combinator must have at least one period.")]
+    fn should_panic_if_schedule_count_negative() {
+        setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
             0,
+            vec![10, 0, 5, -1, 1]
+        );
+    }
+
+    // Attempting to create a contract with a schedule with no periods should panic
+    #[cfg(feature = "schedule")]
+    #[test]
+    #[should_panic(expected = "Schedule combinator must have at least one period.")]
+    fn should_panic_if_schedule_count_zero() {
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![10, 0, 5, 0, 1]
+        );
+    }
+
+    // Attempting to create a contract with a relative schedule with a negative period should panic
+    #[cfg(feature = "schedule")]
+    #[test]
+    #[should_panic(expected = "Schedule periods must not be negative.")]
+    fn should_panic_if_relative_schedule_period_negative() {
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![12, 0, -1, 2, 1]
+        );
+    }
+
+    // Attempting to create a contract with a relative schedule with a negative period count should panic
+    #[cfg(feature = "schedule")]
+    #[test]
+    #[should_panic(expected = "Schedule combinator must have at least one period.")]
+    fn should_panic_if_relative_schedule_count_negative() {
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![12, 0, 5, -1, 1]
+        );
+    }
+
+    // Attempting to create a contract with a relative schedule with no periods should panic
+    #[cfg(feature = "schedule")]
+    #[test]
+    #[should_panic(expected = "Schedule combinator must have at least one period.")]
+    fn should_panic_if_relative_schedule_count_zero() {
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![12, 0, 5, 0, 1]
+        );
+    }
+
+    // Attempting to create a contract with the same holder and counter-party should panic
+    #[test]
+    #[should_panic(expected = "Holder and counter-party must be different addresses.")]
+    fn should_panic_if_holder_equals_counter_party() {
+        let sender = "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap();
+        setup_contract(
+            sender,
+            sender,
+            0,
             vec![0]
         );
+    }
 
-        // Check state as an uninvolved user
-        ext_update(|e| e.value(U256::from(2299)));
-        contract.stake();
+    // An empty deserialized combinator vector is not allowed
+    #[test]
+    #[should_panic(expected = "Provided combinator contract not valid.")]
+    fn should_panic_if_no_combinators_given() {
+        // Mock values and instantiate contract
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![]
+        );
+    }
 
-        contract.withdraw(1);
+    // An undefined combinator vector value is not allowed
+    #[test]
+    #[should_panic(expected = "Unrecognised combinator.")]
+    fn should_panic_if_combinator_value_unrecognised() {
+        // Mock values and instantiate contract
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![255]
+        );
     }
 
-    // Attempting to withdraw without enough funds to withdraw any Eth is not allowed
+    // A non-holder account providing an or-choice is not allowed.
     #[test]
-    #[should_panic(expected = "Not enough funds to withdraw.")]
-    fn should_panic_if_withdrawal_amount_is_0() {
+    #[should_panic(expected = "Only the contract holder may set or-choices.")]
+    fn should_panic_if_non_holder_provides_or_choice() {
         let mut contract = setup_contract(
             "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
             "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
@@ -1460,10 +3049,920 @@ mod tests {
             vec![0]
         );
 
-        // Check state as an uninvolved user
-        ext_update(|e| e.value(U256::from(2300)));
-        contract.stake();
+        ext_update(|e| e.sender(Address::zero()));
+        contract.set_or_choice(0, true);
+    }
+
+    // Setting a non-existent or-choice is not allowed.
+    #[test]
+    #[should_panic(expected = "Stored vector index out of bounds.")]
+    fn should_panic_if_setting_non_existent_or_choice() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![0]
+        );
+
+        ext_update(|e| e.sender(holder));
+        contract.set_or_choice(0, true);
+    }
+
+    // Providing an or choice for a non-existent or combinator is not allowed
+    #[test]
+    #[should_panic(expected = "Stored vector index out of bounds.")]
+    fn should_panic_if_non_existent_or_choice_provided() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![0]
+        );
+
+        ext_update(|e| e.sender("25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap()));
+        contract.set_or_choice(0, true);
+    }
+
+    // Non-holders acquiring the contract is not allowed
+    #[test]
+    #[should_panic(expected = "Only the contract holder may acquire the combinator contract.")]
+    fn should_panic_if_non_holder_acquires() {
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![0]
+        );
 
-        contract.withdraw(1);
+        ext_update(|e| e.sender(Address::zero()));
+        contract.acquire();
     }
-}
\ No newline at end of file
+
+    // Acquiring the contract twice is not allowed
+    #[test]
+    #[should_panic(expected = "The combinator contract cannot be acquired more than once.")]
+    fn should_panic_if_contract_acquired_twice() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![0]
+        );
+
+        ext_update(|e| e.sender(holder));
+        contract.acquire();
+        contract.acquire();
+    }
+
+    // Non-holders acquiring anytime sub-contracts is not allowed
+    #[test]
+    #[should_panic(expected = "Only the contract holder may acquire the combinator contract.")]
+    fn should_panic_if_non_holder_acquires_anytime_sub_contract() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![9, 1]
+        );
+        ext_update(|e| e.sender(holder));
+        contract.acquire();
+
+        ext_update(|e| e.sender(Address::zero()));
+        contract.acquire_anytime_sub_contract(0);
+    }
+
+    // Acquiring non-existent anytime sub-contracts is not allowed
+    #[test]
+    #[should_panic(expected = "Stored vector index out of bounds.")]
+    fn should_panic_when_acquiring_non_existent_anytime_sub_contract() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![9, 1]
+        );
+
+        ext_update(|e| e
+            .sender(holder)
+            .timestamp(0)
+        );
+        contract.acquire();
+        contract.acquire_anytime_sub_contract(1);
+    }
+
+    // Acquiring anytime sub-contracts twice is not allowed
+    #[test]
+    #[should_panic(expected = "Cannot acquire a sub-combinator contract which has already been acquired.")]
+    fn should_panic_when_acquiring_anytime_sub_contract_twice() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![9, 1]
+        );
+
+        ext_update(|e| e
+            .sender(holder)
+            .timestamp(0)
+        );
+        contract.acquire();
+        contract.acquire_anytime_sub_contract(0);
+        contract.acquire_anytime_sub_contract(0);
+    }
+
+    // Acquiring anytime sub-contracts before the parent contract is not allowed
+    #[test]
+    #[should_panic(expected = "Given anytime combinator has not been acquired.")]
+    fn should_panic_if_acquiring_anytime_sub_contract_before_parent_contract() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![9, 1]
+        );
+        ext_update(|e| e.sender(holder));
+        contract.acquire_anytime_sub_contract(0);
+    }
+
+    // Overflowing the holder's stake is not allowed
+    #[test]
+    #[should_panic(expected = "Integer overflow.")]
+    fn should_panic_if_holder_balance_overflows() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![0]
+        );
+
+        // Set the stake to the maximum i64 value
+        ext_update(|e| e
+            .sender(holder)
+            .value(U256::from(2_i64.pow(62) + (2_i64.pow(62) - 1)))
+        );
+        contract.stake();
+
+        // Overflow the stake
+        ext_update(|e| e
+            .sender(holder)
+            .value(U256::from(1))
+        );
+        contract.stake();
+    }
+
+    // Overflowing the counter-party's stake is not allowed
+    #[test]
+    #[should_panic(expected = "Integer overflow.")]
+    fn should_panic_if_counter_party_balance_overflows() {
+        let sender = "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap();
+        let mut contract = setup_contract(
+            sender,
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![0]
+        );
+
+        // Set the stake to the maximum i64 value
+        ext_update(|e| e
+            .sender(sender)
+            .value(U256::from(2_i64.pow(62) + (2_i64.pow(62) - 1)))
+        );
+        contract.stake();
+
+        // Overflow the stake
+        ext_update(|e| e
+            .sender(sender)
+            .value(U256::from(1))
+        );
+
+        // Check that integer overflow panic is caught
+        contract.stake();
+    }
+
+    // The holder staking more than the maximum value of an i64 is not allowed
+    #[test]
+    #[should_panic(expected = "Given value is too large to be converted to i64.")]
+    fn should_panic_if_holder_stake_too_large() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![0]
+        );
+
+        // Set the stake to the maximum u64 value
+        ext_update(|e| e
+            .sender(holder)
+            .value(U256::from(2_u64.pow(63)))
+        );
+        contract.stake();
+    }
+
+    // The counter-party staking more than the maximum value of an i64 is not allowed
+    #[test]
+    #[should_panic(expected = "Given value is too large to be converted to i64.")]
+    fn should_panic_if_counter_party_stake_too_large() {
+        let sender = "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap();
+        let mut contract = setup_contract(
+            sender,
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![0]
+        );
+
+        // Set the stake to the maximum u64 value
+        ext_update(|e| e
+            .sender(sender)
+            .value(U256::from(2_u64.pow(63)))
+        );
+        contract.stake();
+    }
+
+    // An uninvolved user attempting to stake Eth is not allowed
+    #[test]
+    #[should_panic(expected = "Only the contract holder or the counter-party may stake Ether in the contract.")]
+    fn should_panic_if_uninvolved_user_stakes() {
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![0]
+        );
+
+        // Check state as an uninvolved user
+        ext_update(|e| e
+            .sender(Address::zero())
+            .value(U256::from(10))
+        );
+        contract.stake();
+    }
+
+    // Attempting to withdraw without enough funds to withdraw any Eth is not allowed
+    #[test]
+    #[should_panic(expected = "Not enough funds to withdraw.")]
+    fn should_panic_if_withdrawal_amount_is_0() {
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![0]
+        );
+
+        // Withdraw nothing from a staked balance
+        ext_update(|e| e.value(U256::from(2300)));
+        contract.stake();
+
+        contract.withdraw(0);
+    }
+
+    // A failed Ether payment is not allowed (and rolls back the withdrawal)
+    #[test]
+    #[should_panic(expected = "Payment failed")]
+    fn should_panic_if_payment_fails() {
+        let recipient: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let mut contract = setup_contract_no_gas(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![0]
+        );
+
+        ext_update(|e| e
+            .value(U256::from(1000))
+            .endpoint(recipient, Box::new(|_value: U256, _input: &[u8], _result: &mut [u8]| Err(Error)))
+        );
+        contract.stake();
+        contract.withdraw_to(Address::zero(), recipient, 400);
+    }
+
+    // Withdrawing again from within a payment is not allowed
+    #[test]
+    #[should_panic(expected = "Cannot withdraw while another withdrawal is being paid out.")]
+    fn should_panic_if_withdrawal_reenters() {
+        let sender: Address = "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap();
+        let mut contract = setup_contract_no_gas(
+            sender,
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![0]
+        );
+
+        // The sender's fallback function attempts to withdraw again
+        ext_update(|e| e
+            .value(U256::from(1000))
+            .endpoint(sender, Box::new(|_value: U256, _input: &[u8], _result: &mut [u8]| {
+                FinancialScContract::new().withdraw(100);
+                Ok(())
+            }))
+        );
+        contract.stake();
+        contract.withdraw(400);
+    }
+
+    // Withdrawing to another recipient as an uninvolved user is not allowed
+    #[test]
+    #[should_panic(expected = "Only the contract holder or the counter-party may withdraw Ether from the contract.")]
+    fn should_panic_if_uninvolved_user_withdraws_to_recipient() {
+        let recipient: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let mut contract = setup_contract_no_gas(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![0]
+        );
+
+        ext_update(|e| e.value(U256::from(1000)));
+        contract.stake();
+
+        ext_update(|e| e.sender(recipient));
+        contract.withdraw_to(Address::zero(), recipient, 400);
+    }
+
+    // Getting the balance of an asset the contract does not use is not allowed
+    #[test]
+    #[should_panic(expected = "Asset is not used by the contract.")]
+    fn should_panic_if_getting_balance_of_unused_asset() {
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![1]
+        );
+
+        contract.get_asset_balance(true, "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap());
+    }
+
+    // Staking Ether as an asset is not allowed, Ether is staked by sending value to stake
+    #[test]
+    #[should_panic(expected = "Ether must be staked by sending value to stake.")]
+    fn should_panic_if_staking_ether_as_asset() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![1]
+        );
+
+        ext_update(|e| e.sender(holder));
+        contract.stake_asset(Address::zero(), 10);
+    }
+
+    // Staking tokens with a token which returns false is not allowed
+    #[cfg(feature = "assets")]
+    #[test]
+    #[should_panic(expected = "Token transfer failed.")]
+    fn should_panic_if_token_returns_false_when_staking() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let token: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let token_serialized: [i64; 4] = address_to_i64(token);
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![13, token_serialized[0], token_serialized[1], token_serialized[2], token_serialized[3]]
+        );
+
+        // The token returns false without reverting
+        ext_update(|e| e
+            .sender(holder)
+            .endpoint(token, Box::new(|_value: U256, _input: &[u8], result: &mut [u8]| {
+                for byte in result.iter_mut() {
+                    *byte = 0;
+                }
+                Ok(())
+            }))
+        );
+        contract.stake_asset(token, 100);
+    }
+
+    // Withdrawing tokens with a token which returns false is not allowed
+    #[cfg(feature = "assets")]
+    #[test]
+    #[should_panic(expected = "Payment failed")]
+    fn should_panic_if_token_returns_false_when_withdrawing() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let token: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let token_serialized: [i64; 4] = address_to_i64(token);
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![13, token_serialized[0], token_serialized[1], token_serialized[2], token_serialized[3]]
+        );
+
+        ext_update(|e| e
+            .sender(holder)
+            .endpoint(token, Box::new(|_value: U256, _input: &[u8], _result: &mut [u8]| Ok(())))
+        );
+        contract.stake_asset(token, 100);
+
+        // The token returns false without reverting
+        ext_update(|e| e.endpoint(token, Box::new(|_value: U256, _input: &[u8], result: &mut [u8]| {
+            for byte in result.iter_mut() {
+                *byte = 0;
+            }
+            Ok(())
+        })));
+        contract.withdraw_asset(token, 40);
+    }
+
+    // Setting or choices in a batch with mismatched arrays is not allowed
+    #[test]
+    #[should_panic(expected = "Index and value arrays must have the same length.")]
+    fn should_panic_if_or_choices_arrays_mismatched() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![3, 1, 0]
+        );
+
+        ext_update(|e| e.sender(holder));
+        contract.set_or_choices(vec![0], vec![true, false], false);
+    }
+
+    // Setting or choices in a batch as a non-holder is not allowed
+    #[test]
+    #[should_panic(expected = "Only the contract holder may set or-choices.")]
+    fn should_panic_if_non_holder_provides_or_choices() {
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![3, 1, 0]
+        );
+
+        contract.set_or_choices(vec![0], vec![true], false);
+    }
+
+    // Setting an obs value twice within a batch is not allowed
+    #[test]
+    #[should_panic(expected = "Observable has already been set.")]
+    fn should_panic_if_obs_value_set_twice_in_batch() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let arbiter_serialized: [i64; 4] = address_to_i64(arbiter);
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![5, -1, arbiter_serialized[0], arbiter_serialized[1], arbiter_serialized[2], arbiter_serialized[3], 1, 100, 1]
+        );
+
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_values(vec![0, 0], vec![1, 2], false);
+    }
+
+    // Operators may only act within the permissions they were approved with
+    #[test]
+    #[should_panic(expected = "Only the contract holder may acquire the combinator contract.")]
+    fn should_panic_if_operator_acquires_without_permission() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let operator: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![1]
+        );
+
+        ext_update(|e| e.sender(holder));
+        contract.approve_operator(operator, OPERATOR_CHOICES | OPERATOR_WITHDRAW);
+
+        ext_update(|e| e.sender(operator));
+        contract.acquire();
+    }
+
+    // Revoked operators may no longer act for the party
+    #[test]
+    #[should_panic(expected = "Only the contract holder may set or-choices.")]
+    fn should_panic_if_revoked_operator_sets_or_choice() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let operator: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![3, 1, 0]
+        );
+
+        ext_update(|e| e.sender(holder));
+        contract.approve_operator(operator, OPERATOR_CHOICES);
+        contract.revoke_operator(operator);
+
+        ext_update(|e| e.sender(operator));
+        contract.set_or_choice(0, true);
+    }
+
+    // Operators cannot pay a party's balance to anyone else
+    #[test]
+    #[should_panic(expected = "Operators may only withdraw to the party they act for.")]
+    fn should_panic_if_operator_withdraws_to_other_recipient() {
+        let operator: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let mut contract = setup_contract_no_gas(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![0]
+        );
+
+        ext_update(|e| e.value(U256::from(1000)));
+        contract.stake();
+        contract.approve_operator(operator, OPERATOR_WITHDRAW);
+
+        ext_update(|e| e.sender(operator).value(U256::zero()));
+        contract.withdraw_to(Address::zero(), operator, 100);
+    }
+
+    // Only the holder or counter-party may approve operators
+    #[test]
+    #[should_panic(expected = "Only the contract holder or the counter-party may approve operators.")]
+    fn should_panic_if_uninvolved_user_approves_operator() {
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![1]
+        );
+
+        ext_update(|e| e.sender("3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap()));
+        contract.approve_operator("0D0d2D7CD8e4d7BfBa8DA6aC7b67c23AC1eCCb0F".parse().unwrap(), OPERATOR_CHOICES);
+    }
+
+    // An operator may only act for one of the parties
+    #[test]
+    #[should_panic(expected = "Operator already acts for the other party.")]
+    fn should_panic_if_operator_approved_by_both_parties() {
+        let holder = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let operator: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            vec![1]
+        );
+
+        contract.approve_operator(operator, OPERATOR_WITHDRAW);
+
+        ext_update(|e| e.sender(holder));
+        contract.approve_operator(operator, OPERATOR_CHOICES);
+    }
+
+    // Operators must be given known permissions
+    #[test]
+    #[should_panic(expected = "Operator permissions must be a non-empty combination of choices, acquire, withdraw and dispute.")]
+    fn should_panic_if_operator_permissions_unknown() {
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            vec![1]
+        );
+
+        contract.approve_operator("3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap(), 16);
+    }
+
+    // A signed report cannot be submitted again
+    #[test]
+    #[should_panic(expected = "Observable report has already been submitted.")]
+    fn should_panic_if_obs_report_replayed() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let signature: Vec<u8> = (0..65).map(|i| if i == 64 { 27 } else { i as u8 }).collect();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            5000,
+            obs_definition(arbiter, 2)
+        );
+
+        mock_ecrecover(obs_report_hash(0, 3, 5000, Address::zero()), signature.clone(), arbiter);
+        contract.submit_obs_report(0, 3, 5000, signature.clone());
+        contract.submit_obs_report(0, 3, 5000, signature);
+    }
+
+    // A signed report cannot be submitted for another observable of the same name and arbiter, as it signs the observable index
+    #[test]
+    #[should_panic(expected = "Observable report signature is not valid.")]
+    fn should_panic_if_obs_report_submitted_for_other_observable() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let signature: Vec<u8> = (0..65).map(|i| if i == 64 { 27 } else { i as u8 }).collect();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            5000,
+            obs_definition(arbiter, 2)
+        );
+
+        mock_ecrecover(obs_report_hash(0, 3, 5000, Address::zero()), signature.clone(), arbiter);
+        contract.submit_obs_report(0, 3, 5000, signature.clone());
+        contract.submit_obs_report(1, 3, 5000, signature);
+    }
+
+    // Reports cannot be submitted once they have expired
+    #[test]
+    #[should_panic(expected = "Observable report has expired.")]
+    fn should_panic_if_obs_report_expired() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let signature: Vec<u8> = (0..65).map(|i| if i == 64 { 27 } else { i as u8 }).collect();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            5000 + OBS_REPORT_VALIDITY + 1,
+            obs_definition(arbiter, 1)
+        );
+
+        mock_ecrecover(obs_report_hash(0, 3, 5000, Address::zero()), signature.clone(), arbiter);
+        contract.submit_obs_report(0, 3, 5000, signature);
+    }
+
+    // Reports cannot be dated after the current block
+    #[test]
+    #[should_panic(expected = "Observable report timestamp is in the future.")]
+    fn should_panic_if_obs_report_from_future() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let signature: Vec<u8> = (0..65).map(|i| if i == 64 { 27 } else { i as u8 }).collect();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            5000,
+            obs_definition(arbiter, 1)
+        );
+
+        mock_ecrecover(obs_report_hash(0, 3, 5001, Address::zero()), signature.clone(), arbiter);
+        contract.submit_obs_report(0, 3, 5001, signature);
+    }
+
+    // A report whose signature is not of the submitted report is rejected
+    #[test]
+    #[should_panic(expected = "Observable report signature is not valid.")]
+    fn should_panic_if_obs_report_altered() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let signature: Vec<u8> = (0..65).map(|i| if i == 64 { 27 } else { i as u8 }).collect();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            5000,
+            obs_definition(arbiter, 1)
+        );
+
+        mock_ecrecover(obs_report_hash(0, 3, 5000, Address::zero()), signature.clone(), arbiter);
+        contract.submit_obs_report(0, 4, 5000, signature);
+    }
+
+    // A report signed by anyone but the observable's arbiter is rejected
+    #[test]
+    #[should_panic(expected = "Sender cannot set value for given observable-index.")]
+    fn should_panic_if_obs_report_not_signed_by_arbiter() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let signature: Vec<u8> = (0..65).map(|i| if i == 64 { 27 } else { i as u8 }).collect();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            5000,
+            obs_definition(arbiter, 1)
+        );
+
+        mock_ecrecover(obs_report_hash(0, 3, 5000, Address::zero()), signature.clone(), "0e6D2ABbe3C0d1e0C2f4Bb1aa4B9bC8b5D1f4a2E".parse().unwrap());
+        contract.submit_obs_report(0, 3, 5000, signature);
+    }
+
+    // Report signatures must be 65 bytes
+    #[test]
+    #[should_panic(expected = "Observable report signature must be 65 bytes.")]
+    fn should_panic_if_obs_report_signature_wrong_length() {
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            5000,
+            obs_definition("3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap(), 1)
+        );
+
+        contract.submit_obs_report(0, 3, 5000, vec![0; 64]);
+    }
+
+    // Arbiters of a quorum observable cannot change the value they submitted
+    #[test]
+    #[should_panic(expected = "Arbiter has already submitted a value for given observable-index.")]
+    fn should_panic_if_quorum_arbiter_submits_twice() {
+        let arbiters = quorum_arbiters();
+        let mut contract = setup_quorum_contract(2);
+
+        ext_update(|e| e.sender(arbiters[0]));
+        contract.set_obs_value(0, 4);
+        contract.set_obs_value(0, 5);
+    }
+
+    // Only the arbiters of a quorum observable can submit values for it
+    #[test]
+    #[should_panic(expected = "Sender cannot set value for given observable-index.")]
+    fn should_panic_if_non_arbiter_submits_to_quorum() {
+        let mut contract = setup_quorum_contract(2);
+
+        ext_update(|e| e.sender("25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap()));
+        contract.set_obs_value(0, 4);
+    }
+
+    // Arbiters cannot submit values once a quorum observable has resolved
+    #[test]
+    #[should_panic(expected = "Observable has already been set.")]
+    fn should_panic_if_submitting_to_resolved_quorum() {
+        let arbiters = quorum_arbiters();
+        let mut contract = setup_quorum_contract(1);
+
+        ext_update(|e| e.sender(arbiters[0]));
+        contract.set_obs_value(0, 4);
+        ext_update(|e| e.sender(arbiters[1]));
+        contract.set_obs_value(0, 5);
+    }
+
+    // A quorum's threshold cannot be more than its number of arbiters
+    #[test]
+    #[should_panic(expected = "Quorum threshold must be between one and the number of arbiters.")]
+    fn should_panic_if_quorum_threshold_too_high() {
+        setup_quorum_contract(4);
+    }
+
+    // A quorum's threshold cannot be zero
+    #[test]
+    #[should_panic(expected = "Quorum threshold must be between one and the number of arbiters.")]
+    fn should_panic_if_quorum_threshold_zero() {
+        setup_quorum_contract(0);
+    }
+
+    // A quorum's arbiters cannot be repeated, so one arbiter cannot outweigh the others
+    #[test]
+    #[should_panic(expected = "Quorum arbiters must be distinct.")]
+    fn should_panic_if_quorum_arbiters_repeated() {
+        let arbiters = quorum_arbiters();
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            scale_by_quorum(quorum(vec![arbiters[0], arbiters[1], arbiters[0]], 2, "obs"), one()).serialize()
+        );
+    }
+
+    // Arbiters cannot set a disputable observable's value again while it awaits the end of its dispute window
+    #[test]
+    #[should_panic(expected = "Observable has already been set.")]
+    fn should_panic_if_setting_pending_disputable_value() {
+        let arbiter = quorum_arbiters()[0];
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_value(0, 5);
+        contract.set_obs_value(0, 6);
+    }
+
+    // Only disputable observables can be flagged
+    #[test]
+    #[should_panic(expected = "Observable is not disputable.")]
+    fn should_panic_if_flagging_non_disputable_value() {
+        let holder: Address = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            holder,
+            0,
+            obs_definition(quorum_arbiters()[0], 1)
+        );
+
+        ext_update(|e| e.sender(holder));
+        contract.flag_obs_value(0);
+    }
+
+    // A disputable observable's value cannot be flagged before it is set
+    #[test]
+    #[should_panic(expected = "Observable value has not been set.")]
+    fn should_panic_if_flagging_unset_value() {
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(quorum_arbiters()[0], "obs")));
+        contract.flag_obs_value(0);
+    }
+
+    // Only the holder or counter-party can flag a value
+    #[test]
+    #[should_panic(expected = "Only the holder or counter-party may flag an observable value.")]
+    fn should_panic_if_arbiter_flags_value() {
+        let arbiter = quorum_arbiters()[0];
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_value(0, 5);
+        contract.flag_obs_value(0);
+    }
+
+    // Operators may only flag a value if approved to dispute
+    #[test]
+    #[should_panic(expected = "Only the holder or counter-party may flag an observable value.")]
+    fn should_panic_if_operator_flags_without_permission() {
+        let arbiter = quorum_arbiters()[0];
+        let operator: Address = "0D0d2D7CD8e4d7BfBa8DA6aC7b67c23AC1eCCb0F".parse().unwrap();
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+
+        contract.approve_operator(operator, OPERATOR_CHOICES | OPERATOR_ACQUIRE | OPERATOR_WITHDRAW);
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_value(0, 5);
+
+        ext_update(|e| e.sender(operator));
+        contract.flag_obs_value(0);
+    }
+
+    // A value cannot be flagged once its dispute window has ended
+    #[test]
+    #[should_panic(expected = "Observable value dispute window has ended.")]
+    fn should_panic_if_flagging_after_window() {
+        let arbiter = quorum_arbiters()[0];
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_value(0, 5);
+        ext_update(|e| e
+            .sender("25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap())
+            .timestamp(100)
+        );
+        contract.flag_obs_value(0);
+    }
+
+    // A value cannot be flagged twice
+    #[test]
+    #[should_panic(expected = "Observable value has already been flagged.")]
+    fn should_panic_if_flagging_twice() {
+        let arbiter = quorum_arbiters()[0];
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_value(0, 5);
+        ext_update(|e| e.sender("25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap()));
+        contract.flag_obs_value(0);
+        ext_update(|e| e.sender("1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap()));
+        contract.flag_obs_value(0);
+    }
+
+    // A resolved value cannot be flagged again
+    #[test]
+    #[should_panic(expected = "Observable value has already been settled.")]
+    fn should_panic_if_flagging_resolved_value() {
+        let arbiter = quorum_arbiters()[0];
+        let holder: Address = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_value(0, 5);
+        ext_update(|e| e.sender(holder));
+        contract.flag_obs_value(0);
+        ext_update(|e| e.sender(dispute_resolver()));
+        contract.resolve_obs_value(0, 5);
+        ext_update(|e| e.sender(holder));
+        contract.flag_obs_value(0);
+    }
+
+    // Only the resolver can resolve a flagged value
+    #[test]
+    #[should_panic(expected = "Only the resolver may resolve an observable value.")]
+    fn should_panic_if_non_resolver_resolves_value() {
+        let arbiter = quorum_arbiters()[0];
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_value(0, 5);
+        ext_update(|e| e.sender("25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap()));
+        contract.flag_obs_value(0);
+        contract.resolve_obs_value(0, 8);
+    }
+
+    // The resolver cannot override a value which was not flagged
+    #[test]
+    #[should_panic(expected = "Observable value has not been flagged.")]
+    fn should_panic_if_resolving_unflagged_value() {
+        let arbiter = quorum_arbiters()[0];
+        let mut contract = setup_disputable_contract(ScaleValue::Observable(observable(arbiter, "obs")));
+
+        ext_update(|e| e.sender(arbiter));
+        contract.set_obs_value(0, 5);
+        ext_update(|e| e.sender(dispute_resolver()));
+        contract.resolve_obs_value(0, 8);
+    }
+
+    // Dispute windows cannot be negative
+    #[test]
+    #[should_panic(expected = "Dispute windows must not be negative.")]
+    fn should_panic_if_dispute_window_negative() {
+        let mut definition = vec![5, 3, -1, 0, 0, 0, 0];
+        definition.extend_from_slice(&obs_definition(quorum_arbiters()[0], 1)[1..]);
+        setup_contract(
+            "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+            "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+            0,
+            definition
+        );
+    }
+}
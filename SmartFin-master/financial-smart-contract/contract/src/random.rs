@@ -0,0 +1,62 @@
+// A seeded pseudo-random number generator (xorshift64*), shared by the property tests and the off-chain valuation so both
+// are reproducible
+pub struct Random {
+    state: u64
+}
+
+// Method implementation of the random number generator
+impl Random {
+    // Instantiates a new generator from the given seed
+    pub fn new(seed: u64) -> Random {
+        Random {
+            // The state must never be zero
+            state: if seed == 0 { 0x9e37_79b9_7f4a_7c15 } else { seed }
+        }
+    }
+
+    // Gets the next uniformly distributed 64-bit sample
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // Gets a sample in [0, bound)
+    pub fn next_below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    // Gets a uniformly distributed sample in (0, 1)
+    pub fn next_uniform(&mut self) -> f64 {
+        // Use the top 53 bits, offset by half a step to exclude 0 and 1
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1_u64 << 53) as f64
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::Random;
+
+    // The same seed gives the same samples
+    #[test]
+    fn samples_reproducible() {
+        let mut random0 = Random::new(3);
+        let mut random1 = Random::new(3);
+        for _ in 0..10 {
+            assert_eq!(random0.next_u64(), random1.next_u64());
+        }
+    }
+
+    // Bounded samples stay below their bound, and uniform samples strictly between 0 and 1
+    #[test]
+    fn samples_in_range() {
+        let mut random = Random::new(0);
+        for _ in 0..1000 {
+            assert!(random.next_below(7) < 7);
+            let uniform = random.next_uniform();
+            assert!(uniform > 0.0 && uniform < 1.0);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+extern crate pwasm_std;
+extern crate spin;
+
+use pwasm_std::{ Box, Vec };
+use self::spin::RwLock;
+use combinators::*;
+use FinancialScContract;
+
+pub use combinators::{ CombinatorDetails, deserialize_combinator };
+
+// Deserializes a combinator from a contract definition, given the contract being constructed, the definition and the index
+// following the combinator's tag, returns the following index and the boxed combinator. Sub-combinators are deserialized
+// with FinancialScContract::deserialize_sub_combinator, and any storage the combinator needs is set up through
+// FinancialScContract::get_storage.
+pub type DefinitionDeserializer = fn(&mut FinancialScContract, usize, &Vec<i64>) -> (usize, Box<ContractCombinator>);
+
+// Deserializes a combinator from its serialized state (as returned by ContractCombinator::serialize), given the serialized
+// state and the index following the combinator's tag, returns the following index and the boxed combinator.
+// Sub-combinators are deserialized with deserialize_combinator.
+pub type StateDeserializer = fn(usize, &Vec<i64>) -> (usize, Box<ContractCombinator>);
+
+// A combinator known to the contract, identified by the tag it is serialized with
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct CombinatorEntry {
+    // The tag of the combinator in contract definitions and serialized state
+    pub tag: i64,
+
+    // The deserializer of the combinator from contract definitions
+    pub deserialize_definition: DefinitionDeserializer,
+
+    // The deserializer of the combinator from serialized state
+    pub deserialize_state: StateDeserializer
+}
+
+// Method implementation for combinator entries
+impl CombinatorEntry {
+    // Constructor
+    pub fn new(tag: i64, deserialize_definition: DefinitionDeserializer, deserialize_state: StateDeserializer) -> CombinatorEntry {
+        CombinatorEntry {
+            tag,
+            deserialize_definition,
+            deserialize_state
+        }
+    }
+}
+
+// The combinators which can be deserialized, initially the built-in combinators enabled by the crate's features
+lazy_static! {
+    static ref COMBINATOR_REGISTRY: RwLock<Vec<CombinatorEntry>> = RwLock::new(builtin_combinators());
+}
+
+// Returns the entries of the built-in combinators, the schedule and asset combinators being optional features
+fn builtin_combinators() -> Vec<CombinatorEntry> {
+    let mut entries = Vec::new();
+    entries.push(CombinatorEntry::new(i64::from(Combinator::ZERO), FinancialScContract::deserialize_zero, ZeroCombinator::deserialize));
+    entries.push(CombinatorEntry::new(i64::from(Combinator::ONE), FinancialScContract::deserialize_one, OneCombinator::deserialize));
+    entries.push(CombinatorEntry::new(i64::from(Combinator::AND), FinancialScContract::deserialize_and, AndCombinator::deserialize));
+    entries.push(CombinatorEntry::new(i64::from(Combinator::OR), FinancialScContract::deserialize_or, OrCombinator::deserialize));
+    entries.push(CombinatorEntry::new(i64::from(Combinator::TRUNCATE), FinancialScContract::deserialize_truncate, TruncateCombinator::deserialize));
+    entries.push(CombinatorEntry::new(i64::from(Combinator::SCALE), FinancialScContract::deserialize_scale, ScaleCombinator::deserialize));
+    entries.push(CombinatorEntry::new(i64::from(Combinator::GIVE), FinancialScContract::deserialize_give, GiveCombinator::deserialize));
+    entries.push(CombinatorEntry::new(i64::from(Combinator::THEN), FinancialScContract::deserialize_then, ThenCombinator::deserialize));
+    entries.push(CombinatorEntry::new(i64::from(Combinator::GET), FinancialScContract::deserialize_get, GetCombinator::deserialize));
+    entries.push(CombinatorEntry::new(
+        i64::from(Combinator::TRUNCATE_RELATIVE), FinancialScContract::deserialize_truncate_relative, TruncateCombinator::deserialize_relative
+    ));
+
+    entries.push(CombinatorEntry::new(i64::from(Combinator::ANYTIME), FinancialScContract::deserialize_anytime, AnytimeCombinator::deserialize));
+
+    #[cfg(feature = "schedule")]
+    {
+        entries.push(CombinatorEntry::new(i64::from(Combinator::SCHEDULE), FinancialScContract::deserialize_schedule, ScheduleCombinator::deserialize));
+        entries.push(CombinatorEntry::new(
+            i64::from(Combinator::SCHEDULE_RELATIVE), FinancialScContract::deserialize_schedule_relative, ScheduleCombinator::deserialize_relative
+        ));
+    }
+
+    #[cfg(feature = "assets")]
+    entries.push(CombinatorEntry::new(i64::from(Combinator::ONE_ASSET), FinancialScContract::deserialize_one_asset, OneCombinator::deserialize_asset));
+
+    entries
+}
+
+// Registers a combinator so contracts can be deserialized with it, which must be done before any contract using it is
+// constructed or called. Custom combinators report Combinator::CUSTOM with their tag as their combinator number.
+pub fn register_combinator(entry: CombinatorEntry) {
+    let mut registry = COMBINATOR_REGISTRY.write();
+    if registry.iter().any(|e| e.tag == entry.tag) {
+        panic!("Combinator tag already registered.");
+    }
+    registry.push(entry);
+}
+
+// Returns whether a combinator is registered with the given tag
+pub fn is_registered(tag: i64) -> bool {
+    COMBINATOR_REGISTRY.read().iter().any(|e| e.tag == tag)
+}
+
+// Gets the entry of the combinator registered with the given tag
+pub fn get_combinator_entry(tag: i64) -> CombinatorEntry {
+    match COMBINATOR_REGISTRY.read().iter().find(|e| e.tag == tag) {
+        Some(entry) => *entry,
+        None => panic!("Unrecognised combinator.")
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pwasm_std::vec;
+
+    // Every built-in combinator is registered with its combinator number, the optional combinators only with their features
+    #[test]
+    fn builtin_combinators_registered() {
+        for tag in 0..14 {
+            let enabled = match tag {
+                10 | 12 => cfg!(feature = "schedule"),
+                13 => cfg!(feature = "assets"),
+                _ => true
+            };
+            assert_eq!(is_registered(tag), enabled);
+            if enabled {
+                assert_eq!(get_combinator_entry(tag).tag, tag);
+            }
+        }
+        assert!(!is_registered(14));
+    }
+
+    // A registered combinator is used to deserialize definitions and state with its tag
+    #[test]
+    fn registered_combinator_deserialized() {
+        register_combinator(CombinatorEntry::new(1000, FinancialScContract::deserialize_zero, ZeroCombinator::deserialize));
+        assert!(is_registered(1000));
+
+        let (combinator, _) = FinancialScContract::deserialize_definition(&vec![2, 1000, 1]);
+        assert_eq!(combinator.serialize(), vec![2, -1, 0, 0, -1, 0, 1, -1, 0]);
+
+        let (index, combinator) = deserialize_combinator(0, &vec![1000, -1, 0]);
+        assert_eq!(index, 3);
+        assert_eq!(combinator.get_combinator_number(), Combinator::ZERO);
+    }
+
+    // Registering a tag twice is not allowed
+    #[test]
+    #[should_panic(expected = "Combinator tag already registered.")]
+    fn should_panic_if_registering_tag_twice() {
+        register_combinator(CombinatorEntry::new(1001, FinancialScContract::deserialize_zero, ZeroCombinator::deserialize));
+        register_combinator(CombinatorEntry::new(1001, FinancialScContract::deserialize_one, OneCombinator::deserialize));
+    }
+
+    // Registering a built-in combinator's tag is not allowed
+    #[test]
+    #[should_panic(expected = "Combinator tag already registered.")]
+    fn should_panic_if_registering_builtin_tag() {
+        register_combinator(CombinatorEntry::new(i64::from(Combinator::AND), FinancialScContract::deserialize_zero, ZeroCombinator::deserialize));
+    }
+
+    // Getting an unregistered combinator is not allowed
+    #[test]
+    #[should_panic(expected = "Unrecognised combinator.")]
+    fn should_panic_if_combinator_unregistered() {
+        get_combinator_entry(1002);
+    }
+}
@@ -0,0 +1,411 @@
+extern crate pwasm_std;
+
+use pwasm_std::{ Box, Vec };
+use builder::*;
+
+// The horizon of a definition, as far as it is known before the contract is acquired
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+enum Horizon {
+    // The definition never expires
+    Unbounded,
+
+    // The definition expires after the given time
+    Absolute(u64),
+
+    // The horizon depends on the acquisition time (or is not worked out here)
+    Unknown
+}
+
+// Simplifies a contract definition by rewriting it with laws which preserve its behaviour: its horizon, what it pays in every
+// scenario, and the order of its or, anytime, observable and asset indices (so or choices and observable values are provided
+// in the same way). For example give(give(c)) = c, scale(1, c) = c and truncate(t0, truncate(t1, c)) = truncate(min(t0, t1), c).
+pub fn simplify(definition: Definition) -> Definition {
+    match definition {
+        Definition::And(sub_combinator0, sub_combinator1) => simplify_and(simplify(*sub_combinator0), simplify(*sub_combinator1)),
+        Definition::Or(sub_combinator0, sub_combinator1) => or(simplify(*sub_combinator0), simplify(*sub_combinator1)),
+        Definition::Truncate(time, sub_combinator) => simplify_truncate(time, simplify(*sub_combinator)),
+        Definition::Scale(scale_value, sub_combinator) => simplify_scale(scale_value, simplify(*sub_combinator)),
+        Definition::Give(sub_combinator) => simplify_give(simplify(*sub_combinator)),
+        Definition::Then(sub_combinator0, sub_combinator1) => simplify_then(simplify(*sub_combinator0), simplify(*sub_combinator1)),
+        Definition::Get(sub_combinator) => simplify_get(simplify(*sub_combinator)),
+        Definition::Anytime(sub_combinator) => anytime(simplify(*sub_combinator)),
+        Definition::Schedule(start, period, count, sub_combinator) => schedule(start, period, count, simplify(*sub_combinator)),
+        Definition::TruncateRelative(offset, sub_combinator) => simplify_truncate_relative(offset, simplify(*sub_combinator)),
+        Definition::ScheduleRelative(start_offset, period, count, sub_combinator) => {
+            schedule_relative(start_offset, period, count, simplify(*sub_combinator))
+        },
+        definition => definition
+    }
+}
+
+// Simplifies a serialized contract definition (as taken by the contract constructor)
+pub fn simplify_definition(contract_definition: &Vec<i64>) -> Vec<i64> {
+    simplify(Definition::deserialize(contract_definition)).serialize()
+}
+
+// Gets the normal form of a contract definition: its simplification, with the sub-combinators of nested and combinators in a
+// fixed order. Contracts with the same normal form pay the same in every scenario, although their or, anytime and observable
+// indices may be in a different order, so the normal form is for comparing contracts rather than deploying them.
+pub fn normalize(definition: Definition) -> Definition {
+    normalize_simplified(simplify(definition))
+}
+
+// Returns whether or not two contract definitions are structurally equivalent (have the same normal form)
+pub fn equivalent(definition0: &Definition, definition1: &Definition) -> bool {
+    normalize(definition0.clone()) == normalize(definition1.clone())
+}
+
+// Gets the normal form of a simplified definition
+fn normalize_simplified(definition: Definition) -> Definition {
+    match definition {
+        Definition::And(sub_combinator0, sub_combinator1) => {
+            // And is associative and commutative, so flatten nested and combinators and order them by their serialization
+            let mut operands: Vec<Definition> = Vec::new();
+            flatten_and(*sub_combinator0, &mut operands);
+            flatten_and(*sub_combinator1, &mut operands);
+            let mut operands: Vec<(Vec<i64>, Definition)> = operands.into_iter()
+                .map(normalize_simplified)
+                .map(|operand| (operand.serialize(), operand))
+                .collect();
+            operands.sort_by(|operand0, operand1| operand0.0.cmp(&operand1.0));
+
+            let mut operands = operands.into_iter().map(|operand| operand.1).rev();
+            let last = operands.next().unwrap();
+            operands.fold(last, |normalized, operand| and(operand, normalized))
+        },
+        Definition::Or(sub_combinator0, sub_combinator1) => {
+            or(normalize_simplified(*sub_combinator0), normalize_simplified(*sub_combinator1))
+        },
+        Definition::Truncate(time, sub_combinator) => truncate(time, normalize_simplified(*sub_combinator)),
+        Definition::Scale(scale_value, sub_combinator) => Definition::Scale(scale_value, Box::new(normalize_simplified(*sub_combinator))),
+        Definition::Give(sub_combinator) => give(normalize_simplified(*sub_combinator)),
+        Definition::Then(sub_combinator0, sub_combinator1) => {
+            then(normalize_simplified(*sub_combinator0), normalize_simplified(*sub_combinator1))
+        },
+        Definition::Get(sub_combinator) => get(normalize_simplified(*sub_combinator)),
+        Definition::Anytime(sub_combinator) => anytime(normalize_simplified(*sub_combinator)),
+        Definition::Schedule(start, period, count, sub_combinator) => schedule(start, period, count, normalize_simplified(*sub_combinator)),
+        Definition::TruncateRelative(offset, sub_combinator) => truncate_relative(offset, normalize_simplified(*sub_combinator)),
+        Definition::ScheduleRelative(start_offset, period, count, sub_combinator) => {
+            schedule_relative(start_offset, period, count, normalize_simplified(*sub_combinator))
+        },
+        definition => definition
+    }
+}
+
+// Collects the sub-combinators of nested and combinators
+fn flatten_and(definition: Definition, operands: &mut Vec<Definition>) {
+    match definition {
+        Definition::And(sub_combinator0, sub_combinator1) => {
+            flatten_and(*sub_combinator0, operands);
+            flatten_and(*sub_combinator1, operands);
+        },
+        definition => operands.push(definition)
+    }
+}
+
+// and(zero, c) = and(c, zero) = c, if c never expires (as zero never expires)
+fn simplify_and(sub_combinator0: Definition, sub_combinator1: Definition) -> Definition {
+    if sub_combinator0 == Definition::Zero && get_horizon(&sub_combinator1) == Horizon::Unbounded {
+        sub_combinator1
+    } else if sub_combinator1 == Definition::Zero && get_horizon(&sub_combinator0) == Horizon::Unbounded {
+        sub_combinator0
+    } else {
+        and(sub_combinator0, sub_combinator1)
+    }
+}
+
+// truncate(t0, truncate(t1, c)) = truncate(min(t0, t1), c), and truncate(t, c) = c if c expires by t
+fn simplify_truncate(time: u64, sub_combinator: Definition) -> Definition {
+    match sub_combinator {
+        Definition::Truncate(sub_time, sub_sub_combinator) => simplify_truncate(time.min(sub_time), *sub_sub_combinator),
+        sub_combinator => match get_horizon(&sub_combinator) {
+            Horizon::Absolute(horizon) if horizon <= time => sub_combinator,
+            _ => truncate(time, sub_combinator)
+        }
+    }
+}
+
+// truncate_relative(o0, truncate_relative(o1, c)) = truncate_relative(min(o0, o1), c)
+fn simplify_truncate_relative(offset: u64, sub_combinator: Definition) -> Definition {
+    match sub_combinator {
+        Definition::TruncateRelative(sub_offset, sub_sub_combinator) => simplify_truncate_relative(offset.min(sub_offset), *sub_sub_combinator),
+        sub_combinator => truncate_relative(offset, sub_combinator)
+    }
+}
+
+// scale(1, c) = c, scale(-1, c) = give(c), scale(k, zero) = zero, scale(k0, scale(k1, c)) = scale(k0 * k1, c)
+// and scale(k, give(c)) = scale(-k, c), for provided scale values
+fn simplify_scale(scale_value: ScaleValue, sub_combinator: Definition) -> Definition {
+    let value = match scale_value {
+        ScaleValue::Provided(value) => value,
+        scale_value => return Definition::Scale(scale_value, Box::new(sub_combinator))
+    };
+
+    match sub_combinator {
+        _ if value == 1 => sub_combinator,
+        _ if value == -1 => simplify_give(sub_combinator),
+        Definition::Zero => Definition::Zero,
+        Definition::Scale(ScaleValue::Provided(sub_value), sub_sub_combinator) => match value.checked_mul(sub_value) {
+            Some(product) => simplify_scale(ScaleValue::Provided(product), *sub_sub_combinator),
+            None => scale(value, scale(sub_value, *sub_sub_combinator))
+        },
+        Definition::Give(sub_sub_combinator) => match value.checked_neg() {
+            Some(negated) => simplify_scale(ScaleValue::Provided(negated), *sub_sub_combinator),
+            None => scale(value, give(*sub_sub_combinator))
+        },
+        sub_combinator => scale(value, sub_combinator)
+    }
+}
+
+// give(give(c)) = c, give(zero) = zero and give(scale(k, c)) = scale(-k, c), for provided scale values
+fn simplify_give(sub_combinator: Definition) -> Definition {
+    match sub_combinator {
+        Definition::Give(sub_sub_combinator) => *sub_sub_combinator,
+        Definition::Zero => Definition::Zero,
+        Definition::Scale(ScaleValue::Provided(value), sub_sub_combinator) => match value.checked_neg() {
+            Some(negated) => simplify_scale(ScaleValue::Provided(negated), *sub_sub_combinator),
+            None => give(scale(value, *sub_sub_combinator))
+        },
+        sub_combinator => give(sub_combinator)
+    }
+}
+
+// then(c0, c1) = c0 if c0 never expires (so c1 is never acquired)
+fn simplify_then(sub_combinator0: Definition, sub_combinator1: Definition) -> Definition {
+    if get_horizon(&sub_combinator0) == Horizon::Unbounded && !has_indices(&sub_combinator1) {
+        sub_combinator0
+    } else {
+        then(sub_combinator0, sub_combinator1)
+    }
+}
+
+// get(c) = zero if c never expires (so is never acquired)
+fn simplify_get(sub_combinator: Definition) -> Definition {
+    if get_horizon(&sub_combinator) == Horizon::Unbounded && !has_indices(&sub_combinator) {
+        Definition::Zero
+    } else {
+        get(sub_combinator)
+    }
+}
+
+// Gets the horizon of a definition, as the combinators work it out
+fn get_horizon(definition: &Definition) -> Horizon {
+    match definition {
+        Definition::Zero | Definition::One | Definition::OneAsset(_) => Horizon::Unbounded,
+        Definition::And(sub_combinator0, sub_combinator1)
+            | Definition::Or(sub_combinator0, sub_combinator1)
+            | Definition::Then(sub_combinator0, sub_combinator1) => {
+            match (get_horizon(sub_combinator0), get_horizon(sub_combinator1)) {
+                (Horizon::Unbounded, _) | (_, Horizon::Unbounded) => Horizon::Unbounded,
+                (Horizon::Absolute(horizon0), Horizon::Absolute(horizon1)) => Horizon::Absolute(horizon0.max(horizon1)),
+                _ => Horizon::Unknown
+            }
+        },
+        Definition::Truncate(time, sub_combinator) => match get_horizon(sub_combinator) {
+            Horizon::Unbounded => Horizon::Absolute(*time),
+            Horizon::Absolute(horizon) => Horizon::Absolute(horizon.min(*time)),
+            Horizon::Unknown => Horizon::Unknown
+        },
+        Definition::Scale(_, sub_combinator)
+            | Definition::Give(sub_combinator)
+            | Definition::Get(sub_combinator)
+            | Definition::Anytime(sub_combinator) => get_horizon(sub_combinator),
+        Definition::Schedule(..) | Definition::TruncateRelative(..) | Definition::ScheduleRelative(..) => Horizon::Unknown
+    }
+}
+
+// Returns whether or not a definition contains a combinator which is given an index when deserialized (an or, anytime,
+// observable scale or token one combinator), which must be kept so later indices do not change
+fn has_indices(definition: &Definition) -> bool {
+    match definition {
+        Definition::Zero | Definition::One => false,
+        Definition::OneAsset(_) | Definition::Or(..) | Definition::Anytime(_) => true,
+        Definition::Scale(ScaleValue::Observable(_), _)
+            | Definition::Scale(ScaleValue::Quorum(_), _)
+            | Definition::Scale(ScaleValue::Disputable(..), _) => true,
+        Definition::And(sub_combinator0, sub_combinator1) | Definition::Then(sub_combinator0, sub_combinator1) => {
+            has_indices(sub_combinator0) || has_indices(sub_combinator1)
+        },
+        Definition::Truncate(_, sub_combinator)
+            | Definition::Scale(_, sub_combinator)
+            | Definition::Give(sub_combinator)
+            | Definition::Get(sub_combinator)
+            | Definition::Schedule(_, _, _, sub_combinator)
+            | Definition::TruncateRelative(_, sub_combinator)
+            | Definition::ScheduleRelative(_, _, _, sub_combinator) => has_indices(sub_combinator)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pwasm_std::{ vec, types::Address };
+    use { FinancialScContract, ContractCombinator, AssetAmounts, or_choices_key, obs_values_key };
+    use storage::{ Storage, StoresFixedVec };
+    use random::Random;
+
+    // The number of random definitions checked by each property test
+    static PROPERTY_TEST_CASES: usize = 500;
+
+    // Generates a random definition, biased towards the redundant forms simplification removes
+    fn random_definition(random: &mut Random, depth: u32) -> Definition {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        if depth == 0 {
+            return if random.next_below(4) == 0 { zero() } else { one() };
+        }
+
+        let sub = |random: &mut Random| random_definition(random, depth - 1);
+        match random.next_below(15) {
+            0 => zero(),
+            1 => one(),
+            2 => { let sub0 = sub(random); and(sub0, sub(random)) },
+            3 => { let sub0 = sub(random); or(sub0, sub(random)) },
+            4 => truncate(10 * random.next_below(6), sub(random)),
+            5 => scale(random.next_below(5) as i64 - 2, sub(random)),
+            6 => scale_by(observable(arbiter, "obs"), sub(random)),
+            7 => give(sub(random)),
+            8 => { let sub0 = sub(random); then(sub0, sub(random)) },
+            9 => get(sub(random)),
+            10 => anytime(sub(random)),
+            11 => schedule(10 * random.next_below(3), 10, 1 + random.next_below(3), sub(random)),
+            12 => truncate_relative(10 * random.next_below(4), sub(random)),
+            13 => give(give(sub(random))),
+            _ => and(zero(), sub(random))
+        }
+    }
+
+    // Deserializes and acquires a definition at the given time, with the given or choices and observable values, or returns
+    // None if it has expired
+    fn acquire(definition: &Definition, time: u64, or_choices: &Vec<bool>, obs_values: &Vec<i64>) -> Option<(Box<ContractCombinator>, Storage)> {
+        let (mut combinator, mut storage) = FinancialScContract::deserialize_definition(&definition.serialize());
+        combinator.resolve_relative_times(time);
+        if combinator.past_horizon(time) {
+            return None;
+        }
+
+        let or_count = StoresFixedVec::<Option<bool>>::length(&mut storage, &or_choices_key());
+        for or_index in 0..or_count {
+            storage.set(&or_choices_key(), or_index, Some(or_choices[or_index]));
+        }
+        let obs_count = StoresFixedVec::<(Address, Option<i64>)>::length(&mut storage, &obs_values_key());
+        for obs_index in 0..obs_count {
+            let (arbiter, _): (Address, Option<i64>) = storage.get(&obs_values_key(), obs_index);
+            storage.set(&obs_values_key(), obs_index, (arbiter, Some(obs_values[obs_index])));
+        }
+
+        combinator.acquire(time, &mut storage);
+        Some((combinator, storage))
+    }
+
+    // Gets what a definition pays at each of a series of update times, when acquired at the given time
+    fn simulate(definition: &Definition, time: u64, or_choices: &Vec<bool>, obs_values: &Vec<i64>) -> Option<Vec<AssetAmounts>> {
+        let (mut combinator, mut storage) = acquire(definition, time, or_choices, obs_values)?;
+        Some((0..8).map(|step| time + 10 * step).map(|update_time| combinator.update(update_time, &mut storage)).collect())
+    }
+
+    // Simplification does not change the horizon or payouts of a contract
+    #[cfg(feature = "schedule")]
+    #[test]
+    fn simplification_preserves_payouts() {
+        let mut random = Random::new(0x2545F4914F6CDD1D);
+        let mut simplified_count = 0;
+        for _ in 0..PROPERTY_TEST_CASES {
+            let definition = random_definition(&mut random, 4);
+            let simplified = simplify(definition.clone());
+            if simplified != definition {
+                simplified_count += 1;
+            }
+
+            let or_choices: Vec<bool> = (0..32).map(|_| random.next_below(2) == 0).collect();
+            let obs_values: Vec<i64> = (0..32).map(|_| random.next_below(7) as i64 - 3).collect();
+            for time in vec![0, 15, 35] {
+                assert_eq!(
+                    simulate(&definition, time, &or_choices, &obs_values),
+                    simulate(&simplified, time, &or_choices, &obs_values),
+                    "{:?} simplified to {:?} at {}", definition, simplified, time
+                );
+            }
+        }
+
+        // Make sure the generated definitions exercise the rewrites
+        assert!(simplified_count > PROPERTY_TEST_CASES / 4, "Only {} definitions were simplified", simplified_count);
+    }
+
+    // Contracts with the same normal form have the same payouts (with or choices and observable values in index order)
+    #[cfg(feature = "schedule")]
+    #[test]
+    fn normal_form_preserves_payouts() {
+        let mut random = Random::new(0x9E3779B97F4A7C15);
+        for _ in 0..PROPERTY_TEST_CASES {
+            // Keep the contract free of indices, so they cannot be reordered
+            let definition = random_definition(&mut random, 4);
+            if has_indices(&definition) {
+                continue;
+            }
+            let normalized = normalize(definition.clone());
+
+            for time in vec![0, 15, 35] {
+                assert_eq!(simulate(&definition, time, &vec![], &vec![]), simulate(&normalized, time, &vec![], &vec![]),
+                    "{:?} normalized to {:?} at {}", definition, normalized, time);
+            }
+        }
+    }
+
+    // Simplification never makes a definition longer
+    #[test]
+    fn simplification_does_not_grow_definition() {
+        let mut random = Random::new(0x853C49E6748FEA9B);
+        for _ in 0..PROPERTY_TEST_CASES {
+            let definition = random_definition(&mut random, 4);
+            assert!(simplify(definition.clone()).serialize().len() <= definition.serialize().len(), "{:?}", definition);
+        }
+    }
+
+    // Redundant combinators are removed
+    #[test]
+    fn redundant_combinators_removed() {
+        assert_eq!(simplify(give(give(one()))), one());
+        assert_eq!(simplify(and(zero(), scale(1, one()))), one());
+        assert_eq!(simplify(truncate(20, truncate(10, one()))), truncate(10, one()));
+        assert_eq!(simplify(truncate(20, get(truncate(10, one())))), get(truncate(10, one())));
+        assert_eq!(simplify(scale(2, give(scale(3, one())))), scale(-6, one()));
+        assert_eq!(simplify(scale(-1, scale(-1, give(give(one()))))), one());
+        assert_eq!(simplify(then(one(), truncate(10, one()))), one());
+        assert_eq!(simplify(get(scale(5, one()))), zero());
+    }
+
+    // Laws which would change the horizon or indices are not applied
+    #[test]
+    fn behaviour_changing_rewrites_not_applied() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let definitions = vec![
+            and(zero(), truncate(10, one())),
+            then(one(), or(one(), zero())),
+            scale_by(observable(arbiter, "obs"), zero()),
+            get(anytime(one()))
+        ];
+        for definition in definitions {
+            assert_eq!(simplify(definition.clone()), definition);
+        }
+    }
+
+    // Serialized definitions are simplified
+    #[test]
+    fn simplify_definition_correct() {
+        assert_eq!(simplify_definition(&give(give(scale(1, one()))).serialize()), vec![1]);
+    }
+
+    // Contracts differing only in the order of and combinators are equivalent
+    #[test]
+    fn equivalence_correct() {
+        let bond0 = and(give(scale(90, one())), get(truncate(10, scale(100, one()))));
+        let bond1 = and(get(truncate(10, scale(100, give(give(one()))))), scale(-90, one()));
+        assert!(equivalent(&bond0, &bond1));
+        assert!(!equivalent(&bond0, &and(scale(-91, one()), get(truncate(10, scale(100, one()))))));
+    }
+}
@@ -0,0 +1,235 @@
+extern crate pwasm_std;
+
+use pwasm_std::Vec;
+use combinators::Combinator;
+
+// A combinator in the live contract state tree (as returned by get_state_tree)
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct StateNode {
+    // The type of combinator
+    pub combinator: Combinator,
+
+    // The or index (or combinators), anytime index (anytime combinators) or observable index (scale combinators with an observable)
+    pub index: Option<usize>,
+
+    // The acquisition time of the combinator
+    pub acquisition_time: Option<u64>,
+
+    // The horizon of the combinator
+    pub horizon: Option<u64>,
+
+    // Whether or not the combinator is fully updated
+    pub fully_updated: bool,
+
+    // The value paid out by the combinator so far, indexed by asset index (0 is Ether)
+    pub accrued: Vec<i64>,
+
+    // The sub-combinators which make up the live contract (the current period's sub-combinator for schedule combinators)
+    pub sub_nodes: Vec<StateNode>
+}
+
+// The reason a serialized state tree is rejected when it is truncated, has trailing values or has invalid fields
+const INVALID_STATE_TREE: &str = "Attempted to deserialize ill-formed serialized state tree.";
+
+// Deserializes a serialized state tree
+pub fn deserialize_state_tree(serialized: &Vec<i64>) -> StateNode {
+    match try_deserialize_state_tree(serialized) {
+        Ok(node) => node,
+        Err(message) => panic!("{}", message)
+    }
+}
+
+// Deserializes a serialized state tree, returns the reason the tree is ill-formed instead of panicking
+pub fn try_deserialize_state_tree(serialized: &Vec<i64>) -> Result<StateNode, &'static str> {
+    let (index, node) = deserialize_state_node(0, serialized)?;
+    if index != serialized.len() {
+        return Err(INVALID_STATE_TREE);
+    }
+    Ok(node)
+}
+
+// Serializes a state tree, as get_state_tree does
+pub fn serialize_state_tree(node: &StateNode) -> Vec<i64> {
+    let mut serialized: Vec<i64> = Vec::new();
+    serialize_state_node(node, &mut serialized);
+    serialized
+}
+
+// Appends the serialized state tree node to the given vector
+fn serialize_state_node(node: &StateNode, serialized: &mut Vec<i64>) {
+    serialized.push(i64::from(node.combinator));
+    serialized.push(match node.index { Some(index) => index as i64, None => -1 });
+    serialized.push(match node.acquisition_time { Some(time) => time as i64, None => -1 });
+    serialized.push(match node.horizon { Some(horizon) => horizon as i64, None => -1 });
+    serialized.push(if node.fully_updated { 1 } else { 0 });
+    serialized.push(node.sub_nodes.len() as i64);
+    serialized.push(node.accrued.len() as i64);
+    serialized.extend_from_slice(&node.accrued);
+    for sub_node in node.sub_nodes.iter() {
+        serialize_state_node(sub_node, serialized);
+    }
+}
+
+// Deserializes the state tree node at the given index, returns the following index and the node
+fn deserialize_state_node(index: usize, serialized: &Vec<i64>) -> Result<(usize, StateNode), &'static str> {
+    if index + 6 >= serialized.len() {
+        return Err(INVALID_STATE_TREE);
+    }
+
+    let accrued_length = serialized[index + 6];
+    if accrued_length < 0 || index + 7 + accrued_length as usize > serialized.len() {
+        return Err(INVALID_STATE_TREE);
+    }
+    let mut accrued: Vec<i64> = Vec::new();
+    accrued.extend_from_slice(&serialized[(index + 7)..(index + 7 + accrued_length as usize)]);
+
+    // Deserialize sub-nodes
+    let sub_node_count = serialized[index + 5];
+    if sub_node_count < 0 {
+        return Err(INVALID_STATE_TREE);
+    }
+    let mut next_index = index + 7 + accrued_length as usize;
+    let mut sub_nodes: Vec<StateNode> = Vec::new();
+    for _ in 0..sub_node_count {
+        let (sub_index, sub_node) = deserialize_state_node(next_index, serialized)?;
+        next_index = sub_index;
+        sub_nodes.push(sub_node);
+    }
+
+    Ok((
+        next_index,
+        StateNode {
+            combinator: Combinator::from(serialized[index]),
+            index: if serialized[index + 1] >= 0 { Some(serialized[index + 1] as usize) } else { None },
+            acquisition_time: if serialized[index + 2] >= 0 { Some(serialized[index + 2] as u64) } else { None },
+            horizon: if serialized[index + 3] >= 0 { Some(serialized[index + 3] as u64) } else { None },
+            fully_updated: serialized[index + 4] == 1,
+            accrued,
+            sub_nodes
+        }
+    ))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use combinators::{ ContractCombinator, AndCombinator, OrCombinator, OneCombinator, GiveCombinator, ScaleCombinator, TruncateCombinator, ZeroCombinator };
+    use storage::Storage;
+    use pwasm_std::{ Box, vec };
+
+    // Deserializing a serialized state tree gives the state of each combinator
+    #[test]
+    fn deserialization_correct() {
+        // Create combinator and (scale 3 truncate 10 one) (give one)
+        let mut combinator = AndCombinator::new(
+            Box::new(ScaleCombinator::new(
+                Box::new(TruncateCombinator::new(
+                    Box::new(OneCombinator::new()),
+                    10
+                )),
+                None,
+                Some(3)
+            )),
+            Box::new(GiveCombinator::new(Box::new(OneCombinator::new())))
+        );
+        let mut storage = Storage::new();
+        combinator.acquire(5, &mut storage);
+        combinator.update(5, &mut storage);
+
+        let state_tree = deserialize_state_tree(&combinator.serialize_state(&mut storage));
+        assert_eq!(state_tree.combinator, Combinator::AND);
+        assert_eq!(state_tree.index, None);
+        assert_eq!(state_tree.acquisition_time, Some(5));
+        assert_eq!(state_tree.horizon, None);
+        assert!(state_tree.fully_updated);
+        assert_eq!(state_tree.accrued, vec![2]);
+        assert_eq!(state_tree.sub_nodes.len(), 2);
+
+        let scale_node = &state_tree.sub_nodes[0];
+        assert_eq!(scale_node.combinator, Combinator::SCALE);
+        assert_eq!(scale_node.horizon, Some(10));
+        assert_eq!(scale_node.accrued, vec![3]);
+
+        let truncate_node = &scale_node.sub_nodes[0];
+        assert_eq!(truncate_node.combinator, Combinator::TRUNCATE);
+        assert_eq!(truncate_node.accrued, vec![1]);
+        assert_eq!(truncate_node.sub_nodes[0], StateNode {
+            combinator: Combinator::ONE,
+            index: None,
+            acquisition_time: Some(5),
+            horizon: None,
+            fully_updated: true,
+            accrued: vec![1],
+            sub_nodes: vec![]
+        });
+
+        let give_node = &state_tree.sub_nodes[1];
+        assert_eq!(give_node.combinator, Combinator::GIVE);
+        assert_eq!(give_node.accrued, vec![-1]);
+        assert_eq!(give_node.sub_nodes[0].accrued, vec![1]);
+    }
+
+    // Unacquired combinators have no acquisition time and nothing accrued, or combinators record their or index
+    #[test]
+    fn unacquired_deserialization_correct() {
+        let combinator = OrCombinator::new(Box::new(ZeroCombinator::new()), Box::new(OneCombinator::new()), 4);
+
+        let state_tree = deserialize_state_tree(&combinator.serialize_state(&mut Storage::new()));
+        assert_eq!(state_tree.combinator, Combinator::OR);
+        assert_eq!(state_tree.index, Some(4));
+        assert_eq!(state_tree.acquisition_time, None);
+        assert!(!state_tree.fully_updated);
+        assert_eq!(state_tree.accrued, vec![]);
+        assert_eq!(state_tree.sub_nodes[0].combinator, Combinator::ZERO);
+        assert_eq!(state_tree.sub_nodes[1].combinator, Combinator::ONE);
+    }
+
+    // Serializing a deserialized state tree gives the original state tree
+    #[test]
+    fn serialization_correct() {
+        let mut combinator = AndCombinator::new(
+            Box::new(ScaleCombinator::new(Box::new(TruncateCombinator::new(Box::new(OneCombinator::new()), 10)), None, Some(3))),
+            Box::new(GiveCombinator::new(Box::new(OneCombinator::new())))
+        );
+        let mut storage = Storage::new();
+        combinator.acquire(5, &mut storage);
+        combinator.update(5, &mut storage);
+
+        let serialized = combinator.serialize_state(&mut storage);
+        assert_eq!(serialize_state_tree(&deserialize_state_tree(&serialized)), serialized);
+
+        let combinator = OrCombinator::new(Box::new(ZeroCombinator::new()), Box::new(OneCombinator::new()), 4);
+        let serialized = combinator.serialize_state(&mut Storage::new());
+        assert_eq!(serialize_state_tree(&deserialize_state_tree(&serialized)), serialized);
+    }
+
+    // Ill-formed state trees are rejected with the reason instead of panicking
+    #[test]
+    fn try_deserialize_correct() {
+        assert_eq!(try_deserialize_state_tree(&vec![1, -1, 5, -1, 1, 0, 1, 1]).unwrap().accrued, vec![1]);
+        assert_eq!(try_deserialize_state_tree(&vec![1, -1, -1, -1, 0, 0, 0, 5]), Err("Attempted to deserialize ill-formed serialized state tree."));
+    }
+
+    // Attempting to deserialize a state tree with trailing values is not allowed
+    #[test]
+    #[should_panic(expected = "Attempted to deserialize ill-formed serialized state tree.")]
+    fn should_panic_if_deserializing_trailing_values() {
+        deserialize_state_tree(&vec![1, -1, -1, -1, 0, 0, 0, 5]);
+    }
+
+    // Attempting to deserialize a truncated state tree is not allowed
+    #[test]
+    #[should_panic(expected = "Attempted to deserialize ill-formed serialized state tree.")]
+    fn should_panic_if_deserializing_truncated_state_tree() {
+        deserialize_state_tree(&vec![2, -1, -1, -1, 0, 2, 0, 1, -1, -1, -1, 0, 0, 0]);
+    }
+
+    // A negative sub-node count is rejected rather than treated as no sub-nodes
+    #[test]
+    #[should_panic(expected = "Attempted to deserialize ill-formed serialized state tree.")]
+    fn should_panic_if_deserializing_negative_sub_node_count() {
+        deserialize_state_tree(&vec![2, -1, -1, -1, 0, -1, 0]);
+    }
+}
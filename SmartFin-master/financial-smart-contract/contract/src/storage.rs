@@ -28,7 +28,13 @@ impl ObsName {
 
 // Storage table, stores anything looked up while the contract is executing, clears upon exiting contract code
 pub struct Storage {
-    table: Vec<Entry>
+    table: Vec<Entry>,
+
+    // Whether or not writes are kept in the storage table only, rather than also persisted to contract storage
+    transient: bool,
+
+    // Whether or not contract storage is never accessed (values not in the storage table read as zero), for use off-chain
+    detached: bool
 }
 
 // The implementing struct can store values of the given type (passed/returned by value). These values must never change size.
@@ -79,7 +85,17 @@ pub trait StoresVariable<T> {
 impl Storage {
     // Initialise a new storage table
     pub fn new() -> Storage {
-        Storage { table: Vec::new() }
+        Storage { table: Vec::new(), transient: false, detached: false }
+    }
+
+    // Initialise a new storage table which never persists writes, for evaluating changes without committing them
+    pub fn new_transient() -> Storage {
+        Storage { table: Vec::new(), transient: true, detached: false }
+    }
+
+    // Initialise a new storage table which never accesses contract storage, for evaluating combinators off-chain
+    pub fn new_detached() -> Storage {
+        Storage { table: Vec::new(), transient: true, detached: true }
     }
     
     // Convert a stored value into an address
@@ -160,7 +176,7 @@ impl StoresFixed<[u8; 32]> for Storage {
             }
         }
 
-        let value = pwasm_ethereum::read(key);
+        let value = if self.detached { [0; 32] } else { pwasm_ethereum::read(key) };
         self.table.push(Entry {
             key: key.clone(),
             value: value.clone()
@@ -170,7 +186,9 @@ impl StoresFixed<[u8; 32]> for Storage {
 
     // Write a value to storage and store locally
     fn write(&mut self, key: &H256, value: [u8; 32]) -> H256 {
-        pwasm_ethereum::write(key, &value);
+        if !self.transient {
+            pwasm_ethereum::write(key, &value);
+        }
 
         for entry in &mut self.table {
             if entry.key == *key {
@@ -225,6 +243,21 @@ impl StoresFixed<u32> for Storage {
     }
 }
 
+// Times are stored as u64, in the same single i64 slot as u32 values, so values stored as u32 by older deployments read as the
+// same u64 values
+impl StoresFixed<u64> for Storage {
+    fn read(&mut self, key: &H256) -> (u64, H256) {
+        let (value, last_used): ([u8; 32], H256) = self.read(key);
+        (Storage::to_i64(&value) as u64, last_used)
+    }
+
+    fn write(&mut self, key: &H256, value: u64) -> H256 {
+        // Values above 2^63-1 are stored as negative i64s, which convert back to the same u64
+        self.write(key, Storage::from_i64(value as i64));
+        *key
+    }
+}
+
 impl StoresFixed<bool> for Storage {
     fn read(&mut self, key: &H256) -> (bool, H256) {
         let (value, last_used): ([u8; 32], H256) = self.read(key);
@@ -523,6 +556,33 @@ mod tests {
         assert_eq!(storage.read(&H256::zero()), (value, H256::zero()));
     }
 
+    // Transient storage reads back its own writes without persisting them
+    #[test]
+    fn transient_storage_does_not_persist_writes() {
+        let mut storage: Storage = Storage::new_transient();
+
+        let value: i64 = 14436934069;
+        storage.write(&H256::zero(), value);
+        assert_eq!(storage.read(&H256::zero()), (value, H256::zero()));
+
+        let mut persisted_storage: Storage = Storage::new();
+        assert_eq!(persisted_storage.read(&H256::zero()), (0_i64, H256::zero()));
+    }
+
+    // Detached storage reads zero for values it has not written, even if present in contract storage
+    #[test]
+    fn detached_storage_does_not_read_contract_storage() {
+        let mut persisted_storage: Storage = Storage::new();
+        persisted_storage.write(&H256::zero(), 5_i64);
+
+        let mut storage: Storage = Storage::new_detached();
+        assert_eq!(storage.read(&H256::zero()), (0_i64, H256::zero()));
+
+        storage.write(&H256::zero(), 7_i64);
+        assert_eq!(storage.read(&H256::zero()), (7_i64, H256::zero()));
+        assert_eq!(Storage::new().read(&H256::zero()), (5_i64, H256::zero()));
+    }
+
     // Storage of a u32 works correctly
     #[test]
     fn stores_and_retrieves_u32_correctly() {
@@ -533,6 +593,29 @@ mod tests {
         assert_eq!(storage.read(&H256::zero()), (value, H256::zero()));
     }
 
+    // Storage of a u64 works correctly
+    #[test]
+    fn stores_and_retrieves_u64_correctly() {
+        let mut storage: Storage = Storage::new();
+
+        let value: u64 = 5_000_000_000;
+        storage.write(&H256::zero(), value);
+        assert_eq!(storage.read(&H256::zero()), (value, H256::zero()));
+
+        storage.write(&H256::zero(), u64::max_value());
+        assert_eq!(storage.read(&H256::zero()), (u64::max_value(), H256::zero()));
+    }
+
+    // Times stored as u32 by older deployments are read as the same u64 times
+    #[test]
+    fn reads_u32_times_as_u64() {
+        let mut storage: Storage = Storage::new();
+
+        storage.write(&H256::zero(), (true, Some(1443693_u32)));
+        assert_eq!(<Storage as StoresFixed<u32>>::size(), <Storage as StoresFixed<u64>>::size());
+        assert_eq!(StoresFixed::<(bool, Option<u64>)>::read(&mut storage, &H256::zero()).0, (true, Some(1443693_u64)));
+    }
+
     // Storage of a bool works correctly
     #[test]
     fn stores_and_retrieves_bool_correctly() {
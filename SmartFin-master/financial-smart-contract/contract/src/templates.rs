@@ -0,0 +1,194 @@
+extern crate pwasm_std;
+
+use pwasm_std::Vec;
+use builder::*;
+
+// A zero-coupon bond: the holder pays the price on acquisition and receives the face value at maturity
+pub fn zero_coupon_bond(price: i64, face_value: i64, maturity: u64) -> Definition {
+    and(
+        give(scale(price, one())),
+        get(truncate(maturity, scale(face_value, one())))
+    )
+}
+
+// A coupon bond: the holder pays the price on acquisition, receives a coupon at the start of each of count periods from the
+// first coupon date, and receives the face value with the last coupon
+pub fn coupon_bond(price: i64, face_value: i64, coupon: i64, first_coupon: u64, period: u64, count: u64) -> Result<Definition, &'static str> {
+    if count == 0 {
+        return Err("A coupon bond must pay at least one coupon.");
+    }
+
+    let maturity = period_start(first_coupon, period, count - 1).ok_or("Coupon bond maturity overflows.")?;
+    Ok(and(
+        give(scale(price, one())),
+        and(
+            schedule(first_coupon, period, count, scale(coupon, one())),
+            get(truncate(maturity, scale(face_value, one())))
+        )
+    ))
+}
+
+// An amortising loan: the holder lends the principal on acquisition, and at the start of each of count periods from the first
+// repayment date receives an equal share of the principal (the remainder with the last repayment) plus interest on the principal
+// still outstanding, at the given rate in basis points per period
+pub fn amortising_loan(principal: i64, rate: i64, first_repayment: u64, period: u64, count: u64) -> Result<Definition, &'static str> {
+    if count == 0 {
+        return Err("A loan must have at least one repayment.");
+    }
+    if principal < 0 || rate < 0 {
+        return Err("Loan principal and rate must not be negative.");
+    }
+
+    // Build the repayments from the last period backwards, so the repayments appear in date order in the definition
+    let share = principal / count as i64;
+    let mut repayments: Option<Definition> = None;
+    for i in (0..count).rev() {
+        let outstanding = principal - share * i as i64;
+        let repaid = if i == count - 1 { outstanding } else { share };
+        let interest = outstanding.checked_mul(rate).ok_or("Loan interest overflows.")? / 10000;
+        let date = period_start(first_repayment, period, i).ok_or("Loan repayment date overflows.")?;
+
+        let repayment = get(truncate(date, scale(repaid + interest, one())));
+        repayments = Some(match repayments {
+            Some(later_repayments) => and(repayment, later_repayments),
+            None => repayment
+        });
+    }
+    Ok(and(give(scale(principal, one())), repayments.unwrap()))
+}
+
+// A cash-settled European call: the holder pays the premium on acquisition, and may choose at expiry to receive the underlying's
+// value (as provided by its observable) in exchange for the strike price
+pub fn european_call(premium: i64, strike: i64, expiry: u64, underlying: Observable) -> Definition {
+    and(
+        give(scale(premium, one())),
+        get(truncate(expiry, or(call_payoff(strike, underlying), zero())))
+    )
+}
+
+// A cash-settled European put: the holder pays the premium on acquisition, and may choose at expiry to receive the strike price
+// in exchange for the underlying's value (as provided by its observable)
+pub fn european_put(premium: i64, strike: i64, expiry: u64, underlying: Observable) -> Definition {
+    and(
+        give(scale(premium, one())),
+        get(truncate(expiry, or(give(call_payoff(strike, underlying)), zero())))
+    )
+}
+
+// A cash-settled American call: as a European call, but the holder may exercise at any time up to expiry
+pub fn american_call(premium: i64, strike: i64, expiry: u64, underlying: Observable) -> Definition {
+    and(
+        give(scale(premium, one())),
+        anytime(truncate(expiry, or(call_payoff(strike, underlying), zero())))
+    )
+}
+
+// A cash-settled American put: as a European put, but the holder may exercise at any time up to expiry
+pub fn american_put(premium: i64, strike: i64, expiry: u64, underlying: Observable) -> Definition {
+    and(
+        give(scale(premium, one())),
+        anytime(truncate(expiry, or(give(call_payoff(strike, underlying)), zero())))
+    )
+}
+
+// A cash-settled forward: at delivery the holder receives the underlying's value in exchange for the agreed price
+pub fn forward(price: i64, delivery: u64, underlying: Observable) -> Definition {
+    get(truncate(delivery, call_payoff(price, underlying)))
+}
+
+// A fixed/floating swap on the given notional: at the start of each period from the start date, the holder receives the fixed
+// rate and pays that period's floating rate (one observable per period, as observables are not shared between periods)
+pub fn fixed_floating_swap(notional: i64, fixed_rate: i64, start: u64, period: u64, floating_rates: Vec<Observable>) -> Definition {
+    if floating_rates.len() == 0 {
+        panic!("A swap must have at least one period.");
+    }
+
+    // Build the payments from the last period backwards, so the first period's observable comes first in the definition
+    let mut payments: Option<Definition> = None;
+    for (i, floating_rate) in floating_rates.into_iter().enumerate().rev() {
+        let payment = get(truncate(
+            start + period * i as u64,
+            and(scale(fixed_rate, one()), give(scale_by(floating_rate, one())))
+        ));
+        payments = Some(match payments {
+            Some(later_payments) => and(payment, later_payments),
+            None => payment
+        });
+    }
+    scale(notional, payments.unwrap())
+}
+
+// Gets the start of the period with the given index, or None if it overflows
+fn period_start(start: u64, period: u64, index: u64) -> Option<u64> {
+    period.checked_mul(index).and_then(|offset| start.checked_add(offset))
+}
+
+// Receiving the underlying's value in exchange for the given price
+fn call_payoff(price: i64, underlying: Observable) -> Definition {
+    and(scale_by(underlying, one()), give(scale(price, one())))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pwasm_std::{ vec, types::Address };
+
+    // A swap pays the fixed and floating rates on each period's date, in period order
+    #[test]
+    fn swap_definition_correct() {
+        let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let rate0 = observable(arbiter, "r0");
+        let rate1 = observable(arbiter, "r1");
+
+        let payment = |date, rate| get(truncate(date, and(scale(3, one()), give(scale_by(rate, one())))));
+        assert_eq!(
+            fixed_floating_swap(100, 3, 10, 5, vec![rate0.clone(), rate1.clone()]),
+            scale(100, and(payment(10, rate0), payment(15, rate1)))
+        );
+    }
+
+    // The face value of a coupon bond is paid with the last coupon
+    #[test]
+    fn coupon_bond_maturity_correct() {
+        assert_eq!(
+            coupon_bond(90, 100, 5, 10, 5, 3),
+            Ok(and(give(scale(90, one())), and(schedule(10, 5, 3, scale(5, one())), get(truncate(20, scale(100, one()))))))
+        );
+    }
+
+    // A coupon bond without coupons, or whose maturity overflows, is rejected
+    #[test]
+    fn invalid_coupon_bond_rejected() {
+        assert_eq!(coupon_bond(90, 100, 5, 10, 5, 0), Err("A coupon bond must pay at least one coupon."));
+        assert_eq!(coupon_bond(90, 100, 5, 10, u64::max_value(), 3), Err("Coupon bond maturity overflows."));
+        assert_eq!(coupon_bond(90, 100, 5, u64::max_value(), 1, 2), Err("Coupon bond maturity overflows."));
+    }
+
+    // Each loan repayment is a share of the principal plus interest on the principal outstanding, with the remainder repaid last
+    #[test]
+    fn amortising_loan_definition_correct() {
+        let repayment = |date, amount| get(truncate(date, scale(amount, one())));
+        assert_eq!(
+            amortising_loan(1000, 100, 10, 10, 3),
+            Ok(and(give(scale(1000, one())), and(repayment(10, 343), and(repayment(20, 339), repayment(30, 337)))))
+        );
+    }
+
+    // A loan without repayments, with a negative principal or rate, or whose interest or repayment dates overflow, is rejected
+    #[test]
+    fn invalid_amortising_loan_rejected() {
+        assert_eq!(amortising_loan(1000, 100, 10, 10, 0), Err("A loan must have at least one repayment."));
+        assert_eq!(amortising_loan(-1000, 100, 10, 10, 2), Err("Loan principal and rate must not be negative."));
+        assert_eq!(amortising_loan(1000, -100, 10, 10, 2), Err("Loan principal and rate must not be negative."));
+        assert_eq!(amortising_loan(i64::max_value(), 100, 10, 10, 1), Err("Loan interest overflows."));
+        assert_eq!(amortising_loan(1000, 100, 10, u64::max_value(), 2), Err("Loan repayment date overflows."));
+    }
+
+    // A swap without periods is not allowed
+    #[test]
+    #[should_panic(expected = "A swap must have at least one period.")]
+    fn should_panic_if_swap_has_no_periods() {
+        fixed_floating_swap(100, 3, 10, 5, vec![]);
+    }
+}
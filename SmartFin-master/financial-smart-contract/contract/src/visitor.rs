@@ -0,0 +1,353 @@
+extern crate pwasm_std;
+
+use pwasm_std::{ Box, Vec, vec };
+use combinators::{ Combinator, ContractCombinator };
+
+// A combinator's own parameters and sub-combinators (see CombinatorNode::new), for analysis passes
+pub enum CombinatorNode<'a> {
+    // A zero combinator
+    Zero,
+
+    // A one combinator, paying one unit of the asset with the given asset index (0 is Ether)
+    One {
+        asset: usize
+    },
+
+    // An and combinator
+    And {
+        sub_combinator0: &'a Box<ContractCombinator>,
+        sub_combinator1: &'a Box<ContractCombinator>
+    },
+
+    // An or combinator, with its or index
+    Or {
+        or_index: usize,
+        sub_combinator0: &'a Box<ContractCombinator>,
+        sub_combinator1: &'a Box<ContractCombinator>
+    },
+
+    // A truncate combinator, with the time it truncates to (None for relative truncations until the acquisition time is known)
+    Truncate {
+        time: Option<u64>,
+        sub_combinator: &'a Box<ContractCombinator>
+    },
+
+    // A scale combinator, with its provided scale value or the index of its observable
+    Scale {
+        scale_value: Option<i64>,
+        obs_index: Option<usize>,
+        sub_combinator: &'a Box<ContractCombinator>
+    },
+
+    // A give combinator
+    Give {
+        sub_combinator: &'a Box<ContractCombinator>
+    },
+
+    // A then combinator
+    Then {
+        sub_combinator0: &'a Box<ContractCombinator>,
+        sub_combinator1: &'a Box<ContractCombinator>
+    },
+
+    // A get combinator
+    Get {
+        sub_combinator: &'a Box<ContractCombinator>
+    },
+
+    // An anytime combinator, with its anytime index
+    Anytime {
+        anytime_index: usize,
+        sub_combinator: &'a Box<ContractCombinator>
+    },
+
+    // A schedule combinator, with its first period date (None for relative schedules until the acquisition time is known),
+    // period length and period count, and the sub-combinator of the current period
+    Schedule {
+        start: Option<u64>,
+        period: u64,
+        count: u64,
+        sub_combinator: &'a Box<ContractCombinator>
+    },
+
+    // A combinator registered by another crate, with its tag
+    Custom {
+        tag: i64,
+        sub_combinators: Vec<&'a Box<ContractCombinator>>
+    }
+}
+
+// Method implementation for combinator nodes
+impl<'a> CombinatorNode<'a> {
+    // Builds the node of the given combinator from its combinator number, index, parameters and sub-combinators, combinators
+    // registered by other crates having a custom node
+    pub fn new(combinator: &'a Box<ContractCombinator>) -> CombinatorNode<'a> {
+        let parameters = combinator.get_parameters();
+        let sub_combinators = combinator.get_sub_combinators();
+        match combinator.get_combinator_number() {
+            Combinator::ZERO => CombinatorNode::Zero,
+            Combinator::ONE | Combinator::ONE_ASSET => CombinatorNode::One { asset: parameters[0] as usize },
+            Combinator::AND => CombinatorNode::And { sub_combinator0: sub_combinators[0], sub_combinator1: sub_combinators[1] },
+            Combinator::OR => CombinatorNode::Or {
+                or_index: combinator.get_index().unwrap(),
+                sub_combinator0: sub_combinators[0],
+                sub_combinator1: sub_combinators[1]
+            },
+            Combinator::TRUNCATE | Combinator::TRUNCATE_RELATIVE => CombinatorNode::Truncate {
+                time: resolved_time(parameters[0]),
+                sub_combinator: sub_combinators[0]
+            },
+            Combinator::SCALE => CombinatorNode::Scale {
+                scale_value: parameters.first().cloned(),
+                obs_index: combinator.get_index(),
+                sub_combinator: sub_combinators[0]
+            },
+            Combinator::GIVE => CombinatorNode::Give { sub_combinator: sub_combinators[0] },
+            Combinator::THEN => CombinatorNode::Then { sub_combinator0: sub_combinators[0], sub_combinator1: sub_combinators[1] },
+            Combinator::GET => CombinatorNode::Get { sub_combinator: sub_combinators[0] },
+            Combinator::ANYTIME => CombinatorNode::Anytime { anytime_index: combinator.get_index().unwrap(), sub_combinator: sub_combinators[0] },
+            Combinator::SCHEDULE | Combinator::SCHEDULE_RELATIVE => CombinatorNode::Schedule {
+                start: resolved_time(parameters[0]),
+                period: parameters[1] as u64,
+                count: parameters[2] as u64,
+                sub_combinator: sub_combinators[0]
+            },
+            Combinator::CUSTOM(tag) => CombinatorNode::Custom { tag, sub_combinators }
+        }
+    }
+
+    // Returns the sub-combinators of the node, in order
+    pub fn get_sub_combinators(&self) -> Vec<&'a Box<ContractCombinator>> {
+        match *self {
+            CombinatorNode::Zero | CombinatorNode::One { .. } => Vec::new(),
+            CombinatorNode::And { sub_combinator0, sub_combinator1 }
+                | CombinatorNode::Or { sub_combinator0, sub_combinator1, .. }
+                | CombinatorNode::Then { sub_combinator0, sub_combinator1 } => vec![sub_combinator0, sub_combinator1],
+            CombinatorNode::Truncate { sub_combinator, .. }
+                | CombinatorNode::Scale { sub_combinator, .. }
+                | CombinatorNode::Give { sub_combinator }
+                | CombinatorNode::Get { sub_combinator }
+                | CombinatorNode::Anytime { sub_combinator, .. }
+                | CombinatorNode::Schedule { sub_combinator, .. } => vec![sub_combinator],
+            CombinatorNode::Custom { ref sub_combinators, .. } => sub_combinators.clone()
+        }
+    }
+}
+
+// An analysis pass over a combinator tree, see visit
+pub trait CombinatorVisitor {
+    // Visits a combinator before its sub-combinators, returns whether or not to visit its sub-combinators
+    fn enter(&mut self, combinator: &Box<ContractCombinator>, node: &CombinatorNode) -> bool;
+
+    // Visits a combinator after its sub-combinators (or straight after entering it, if they are skipped)
+    fn leave(&mut self, _combinator: &Box<ContractCombinator>, _node: &CombinatorNode) { }
+}
+
+// Visits the combinator tree depth-first, entering each combinator before and leaving it after its sub-combinators
+pub fn visit<V: CombinatorVisitor>(combinator: &Box<ContractCombinator>, visitor: &mut V) {
+    let node = CombinatorNode::new(combinator);
+    if visitor.enter(combinator, &node) {
+        for sub_combinator in node.get_sub_combinators() {
+            visit(sub_combinator, visitor);
+        }
+    }
+    visitor.leave(combinator, &node);
+}
+
+// Folds the combinator tree bottom-up, combining each combinator and its node with the results of its sub-combinators
+pub fn fold<T, F>(combinator: &Box<ContractCombinator>, f: &mut F) -> T
+    where F: FnMut(&Box<ContractCombinator>, &CombinatorNode, Vec<T>) -> T {
+    let node = CombinatorNode::new(combinator);
+    let mut results: Vec<T> = Vec::new();
+    for sub_combinator in node.get_sub_combinators() {
+        results.push(fold(sub_combinator, f));
+    }
+    f(combinator, &node, results)
+}
+
+// Converts a time parameter to a time, which is -1 until relative times are resolved
+fn resolved_time(time: i64) -> Option<u64> {
+    if time >= 0 { Some(time as u64) } else { None }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use builder::*;
+    use combinators::*;
+    use storage::Storage;
+    use FinancialScContract;
+
+    // Collects the node parameters of every combinator in pre-order, as [number, parameters...]
+    struct ParameterCollector {
+        nodes: Vec<Vec<i64>>,
+        depth: usize,
+        max_depth: usize,
+
+        // Sub-combinators of combinators at this depth are not visited
+        depth_limit: usize
+    }
+
+    // Combinator visitor implementation of the parameter collector
+    impl CombinatorVisitor for ParameterCollector {
+        fn enter(&mut self, combinator: &Box<ContractCombinator>, node: &CombinatorNode) -> bool {
+            let mut parameters = vec![i64::from(combinator.get_combinator_number())];
+            match *node {
+                CombinatorNode::One { asset } => parameters.push(asset as i64),
+                CombinatorNode::Or { or_index, .. } => parameters.push(or_index as i64),
+                CombinatorNode::Truncate { time, .. } => parameters.push(time.map(|t| t as i64).unwrap_or(-1)),
+                CombinatorNode::Scale { scale_value, obs_index, .. } => {
+                    parameters.push(scale_value.unwrap_or(-1));
+                    parameters.push(obs_index.map(|i| i as i64).unwrap_or(-1));
+                },
+                CombinatorNode::Anytime { anytime_index, .. } => parameters.push(anytime_index as i64),
+                CombinatorNode::Schedule { start, period, count, .. } => {
+                    parameters.push(start.map(|t| t as i64).unwrap_or(-1));
+                    parameters.push(period as i64);
+                    parameters.push(count as i64);
+                },
+                CombinatorNode::Custom { tag, .. } => parameters.push(tag),
+                _ => ()
+            }
+            self.nodes.push(parameters);
+            self.depth += 1;
+            if self.depth > self.max_depth {
+                self.max_depth = self.depth;
+            }
+            self.depth < self.depth_limit
+        }
+
+        fn leave(&mut self, _: &Box<ContractCombinator>, _: &CombinatorNode) {
+            self.depth -= 1;
+        }
+    }
+
+    // Method implementation of the parameter collector
+    impl ParameterCollector {
+        fn new(depth_limit: usize) -> ParameterCollector {
+            ParameterCollector { nodes: Vec::new(), depth: 0, max_depth: 0, depth_limit }
+        }
+    }
+
+    // Custom combinator without sub-combinators, which keeps the default node
+    struct CustomCombinator {
+        combinator_details: CombinatorDetails
+    }
+
+    // Contract combinator implementation of the custom combinator
+    impl ContractCombinator for CustomCombinator {
+        fn get_combinator_number(&self) -> Combinator {
+            Combinator::CUSTOM(100)
+        }
+
+        fn get_combinator_details(&self) -> &CombinatorDetails {
+            &self.combinator_details
+        }
+
+        fn acquire(&mut self, time: u64, _: &mut Storage) {
+            self.combinator_details.acquisition_time = Some(time);
+        }
+
+        fn update(&mut self, _: u64, _: &mut Storage) -> AssetAmounts {
+            AssetAmounts::zero()
+        }
+    }
+
+    // Visiting a contract gives the parameters of every combinator in pre-order
+    #[cfg(all(feature = "schedule", feature = "assets"))]
+    #[test]
+    fn visit_gives_node_parameters() {
+        let arbiter = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let token = "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap();
+        let definition = and(
+            or(truncate(10, scale(3, one())), scale_by(observable(arbiter, "rate"), give(one_of(token)))),
+            then(get(truncate_relative(5, one())), anytime(schedule(20, 10, 3, zero())))
+        );
+        let (combinator, _) = FinancialScContract::deserialize_definition(&definition.serialize());
+
+        let mut collector = ParameterCollector::new(100);
+        visit(&combinator, &mut collector);
+        assert_eq!(collector.nodes, vec![
+            vec![2],
+            vec![3, 0],
+            vec![4, 10],
+            vec![5, 3, -1],
+            vec![1, 0],
+            vec![5, -1, 0],
+            vec![6],
+            vec![13, 1],
+            vec![7],
+            vec![8],
+            vec![11, -1],
+            vec![1, 0],
+            vec![9, 0],
+            vec![10, 20, 10, 3],
+            vec![0]
+        ]);
+        assert_eq!(collector.depth, 0);
+        assert_eq!(collector.max_depth, 5);
+    }
+
+    // Relative times are given once resolved
+    #[cfg(feature = "schedule")]
+    #[test]
+    fn visit_gives_resolved_relative_times() {
+        let (mut combinator, _) = FinancialScContract::deserialize_definition(
+            &truncate_relative(5, schedule_relative(10, 5, 2, one())).serialize()
+        );
+        combinator.resolve_relative_times(100);
+
+        let mut collector = ParameterCollector::new(100);
+        visit(&combinator, &mut collector);
+        assert_eq!(collector.nodes, vec![vec![11, 105], vec![12, 110, 5, 2], vec![1, 0]]);
+    }
+
+    // Sub-combinators are skipped if the visitor does not enter them
+    #[test]
+    fn visit_skips_sub_combinators() {
+        let mut definition = one();
+        for _ in 0..10 {
+            definition = give(definition);
+        }
+        let (combinator, _) = FinancialScContract::deserialize_definition(&definition.serialize());
+
+        let mut collector = ParameterCollector::new(8);
+        visit(&combinator, &mut collector);
+        assert_eq!(collector.nodes.len(), 8);
+        assert_eq!(collector.depth, 0);
+    }
+
+    // Folding combines the results of sub-combinators bottom-up
+    #[test]
+    fn fold_combines_sub_combinator_results() {
+        let definition = and(scale(3, give(one())), or(scale(2, one()), truncate(5, scale(4, one()))));
+        let (combinator, _) = FinancialScContract::deserialize_definition(&definition.serialize());
+
+        // Largest amount the holder can be paid, if the holder chooses
+        let maximum = fold(&combinator, &mut |_, node, results: Vec<i64>| match *node {
+            CombinatorNode::One { .. } => 1,
+            CombinatorNode::Give { .. } => -results[0],
+            CombinatorNode::Scale { scale_value, .. } => scale_value.unwrap() * results[0],
+            CombinatorNode::Or { .. } => if results[0] > results[1] { results[0] } else { results[1] },
+            _ => results.iter().sum()
+        });
+        assert_eq!(maximum, 1);
+
+        let count = fold(&combinator, &mut |_, _, results: Vec<usize>| 1 + results.iter().sum::<usize>());
+        assert_eq!(count, 10);
+    }
+
+    // Combinators registered by other crates have a custom node
+    #[test]
+    fn custom_combinator_has_custom_node() {
+        let combinator: Box<ContractCombinator> = Box::new(CustomCombinator { combinator_details: CombinatorDetails::new() });
+        match CombinatorNode::new(&combinator) {
+            CombinatorNode::Custom { tag, sub_combinators } => {
+                assert_eq!(tag, 100);
+                assert_eq!(sub_combinators.len(), 0);
+            },
+            _ => panic!("Expected a custom node.")
+        }
+    }
+}
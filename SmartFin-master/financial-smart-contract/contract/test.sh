@@ -1,10 +1,7 @@
 #!/bin/bash
 
-# Generate test cargo manifest
-source ./generate-cargo.sh lib
+# Generate cargo manifest (just in case)
+source ./generate-cargo.sh
 
 # Run tests
-cargo test --features std $1
-
-# Replace build manifest
-source ./generate-cargo.sh cdylib
\ No newline at end of file
+cargo test --features std $1
\ No newline at end of file
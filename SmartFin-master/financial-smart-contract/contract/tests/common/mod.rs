@@ -2,11 +2,19 @@ extern crate pwasm_std;
 extern crate pwasm_test;
 extern crate financial_smart_contract;
 
-pub use self::financial_smart_contract::{ FinancialScContract, FinancialScInterface, storage::{ address_to_i64 } };
+pub use self::financial_smart_contract::{ FinancialScContract, FinancialScInterface, builder, simplify, templates, storage::{ address_to_i64 } };
 
+use std::sync::Once;
+use self::financial_smart_contract::{ AssetAmounts, Combinator, CombinatorDetails, ContractCombinator, storage::Storage };
+use self::financial_smart_contract::registry::{ CombinatorEntry, deserialize_combinator, register_combinator };
 use self::pwasm_std::{ types::Address };
 use self::pwasm_test::ext_reset;
 
+// The tag of the custom double combinator
+pub const DOUBLE_COMBINATOR: i64 = 100;
+
+static REGISTER_DOUBLE_COMBINATOR: Once = Once::new();
+
 // The details of a contract used for testing
 pub struct TestContractDetails {
     pub holder: Address,
@@ -40,7 +48,85 @@ pub fn setup_contract(deserialized_combinator: Vec<i64>) -> TestContractDetails
         .sender(sender)
         .timestamp(timestamp)
     );
-    contract.constructor(deserialized_combinator, holder, true);
+    contract.constructor(deserialized_combinator, holder, true, 2300);
 
     TestContractDetails::new(holder, sender, timestamp, contract)
-}
\ No newline at end of file
+}
+
+// A custom combinator which pays twice what its sub-combinator pays
+pub struct DoubleCombinator {
+    // The sub-combinator
+    sub_combinator: Box<ContractCombinator>,
+
+    // The common combinator details
+    combinator_details: CombinatorDetails
+}
+
+// Method implementation for the double combinator
+impl DoubleCombinator {
+    // Deserializes a double combinator from a contract definition
+    fn deserialize_definition(contract: &mut FinancialScContract, index: usize, serialized: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        let (index0, sub_combinator) = contract.deserialize_sub_combinator(index, serialized);
+        (index0, Box::new(DoubleCombinator { sub_combinator, combinator_details: CombinatorDetails::new() }))
+    }
+
+    // Deserializes a double combinator from its serialized state
+    fn deserialize_state(index: usize, serialized: &Vec<i64>) -> (usize, Box<ContractCombinator>) {
+        let (index0, sub_combinator) = deserialize_combinator(index + 2, serialized);
+        let combinator_details = CombinatorDetails::deserialize([serialized[index], serialized[index + 1]]);
+        (index0, Box::new(DoubleCombinator { sub_combinator, combinator_details }))
+    }
+}
+
+// Contract combinator implementation for the double combinator
+impl ContractCombinator for DoubleCombinator {
+    fn get_combinator_number(&self) -> Combinator {
+        Combinator::CUSTOM(DOUBLE_COMBINATOR)
+    }
+
+    fn get_horizon(&self) -> Option<u64> {
+        self.sub_combinator.get_horizon()
+    }
+
+    fn get_combinator_details(&self) -> &CombinatorDetails {
+        &self.combinator_details
+    }
+
+    fn get_sub_combinators(&self) -> Vec<&Box<ContractCombinator>> {
+        vec![&self.sub_combinator]
+    }
+
+    fn get_accrued(&self, storage: &mut Storage) -> AssetAmounts {
+        self.sub_combinator.get_accrued(storage) * 2
+    }
+
+    fn acquire(&mut self, time: u64, storage: &mut Storage) {
+        self.sub_combinator.acquire(time, storage);
+        self.combinator_details.acquisition_time = Some(time);
+    }
+
+    fn update(&mut self, time: u64, storage: &mut Storage) -> AssetAmounts {
+        if self.combinator_details.acquisition_time == None || self.combinator_details.fully_updated {
+            return AssetAmounts::zero();
+        }
+
+        let sub_value = self.sub_combinator.update(time, storage);
+        self.combinator_details.fully_updated = self.sub_combinator.get_combinator_details().fully_updated;
+        sub_value * 2
+    }
+
+    fn serialize(&self) -> Vec<i64> {
+        let mut serialized = self.serialize_details();
+        serialized.extend_from_slice(&self.sub_combinator.serialize());
+        serialized
+    }
+}
+
+// Registers the double combinator, once for all tests
+pub fn register_double_combinator() {
+    REGISTER_DOUBLE_COMBINATOR.call_once(|| register_combinator(CombinatorEntry::new(
+        DOUBLE_COMBINATOR,
+        DoubleCombinator::deserialize_definition,
+        DoubleCombinator::deserialize_state
+    )));
+}
@@ -1,11 +1,17 @@
+extern crate pwasm_ethereum;
 extern crate pwasm_std;
 extern crate pwasm_test;
 mod common;
 
+use std::{ cell::Cell, panic, rc::Rc };
+
 #[allow(unused_imports)]
 use self::pwasm_std::{ vec, types::{ Address, U256 } };
+use self::pwasm_ethereum::Error;
 use self::pwasm_test::{ ext_update };
-use self::common::{ setup_contract, FinancialScContract, FinancialScInterface, address_to_i64 };
+use self::common::{ setup_contract, register_double_combinator, FinancialScContract, FinancialScInterface, address_to_i64, DOUBLE_COMBINATOR };
+use self::common::builder::{ and, get, give, observable, one, or, scale, scale_by, truncate, zero };
+use self::common::{ simplify::simplify, templates };
 
 // The value of the contract is based on the given serialized combinator vector
 #[test]
@@ -325,6 +331,114 @@ fn anytime_has_correct_value_after_additional_acquisition() {
     assert_eq!(contract.get_balance(true), 1);
 }
 
+// The value of a schedule contract accumulates one sub-contract per elapsed period
+#[cfg(feature = "schedule")]
+#[test]
+fn schedule_has_correct_value() {
+    // Create contract schedule 1 2 3 one
+    let contract_details = setup_contract(vec![10, 1, 2, 3, 1]);
+
+    // Mock details
+    ext_update(|e| e
+        .timestamp(0)
+        .sender(contract_details.holder)
+    );
+
+    // Check value is 0 before the first period
+    let mut contract = contract_details.contract;
+    contract.acquire();
+    assert_eq!(contract.get_balance(true), 0);
+
+    // Check value is 2 after the second period
+    ext_update(|e| e.timestamp(3));
+    contract.update();
+    assert_eq!(contract.get_balance(true), 2);
+
+    // Check value is 3 after the last period, and the contract has concluded
+    ext_update(|e| e.timestamp(5));
+    contract.update();
+    assert_eq!(contract.get_balance(true), 3);
+    assert!(contract.get_concluded());
+}
+
+// The value of a schedule contract with a scaled sub-contract is correct
+#[cfg(feature = "schedule")]
+#[test]
+fn schedule_of_scaled_sub_contract_has_correct_value() {
+    // Create contract schedule 0 10 40 scale 25 one (a 40 period coupon)
+    let contract_details = setup_contract(vec![10, 0, 10, 40, 5, 1, 25, 1]);
+
+    ext_update(|e| e
+        .timestamp(0)
+        .sender(contract_details.holder)
+    );
+    let mut contract = contract_details.contract;
+    contract.acquire();
+    assert_eq!(contract.get_balance(true), 25);
+
+    ext_update(|e| e.timestamp(390));
+    contract.update();
+    assert_eq!(contract.get_balance(true), 25 * 40);
+}
+
+// A relative contract pays out relative to its acquisition time
+#[test]
+fn relative_get_has_correct_value() {
+    // Create contract get truncate +30 one
+    let contract_details = setup_contract(vec![8, 11, 30, 1]);
+
+    // Acquire at time = 100
+    ext_update(|e| e
+        .timestamp(100)
+        .sender(contract_details.holder)
+    );
+    let mut contract = contract_details.contract;
+    contract.acquire();
+    assert_eq!(contract.get_balance(true), 0);
+
+    // Check value is 0 before the relative horizon
+    ext_update(|e| e.timestamp(129));
+    contract.update();
+    assert_eq!(contract.get_balance(true), 0);
+
+    // Check value is 1 at the relative horizon
+    ext_update(|e| e.timestamp(130));
+    contract.update();
+    assert_eq!(contract.get_balance(true), 1);
+}
+
+// A relative schedule pays out periods relative to the acquisition time
+#[cfg(feature = "schedule")]
+#[test]
+fn relative_schedule_has_correct_value() {
+    // Create contract schedule +10 10 2 one
+    let contract_details = setup_contract(vec![12, 10, 10, 2, 1]);
+
+    // Acquire at time = 50
+    ext_update(|e| e
+        .timestamp(50)
+        .sender(contract_details.holder)
+    );
+    let mut contract = contract_details.contract;
+    contract.acquire();
+    assert_eq!(contract.get_balance(true), 0);
+
+    ext_update(|e| e.timestamp(70));
+    contract.update();
+    assert_eq!(contract.get_balance(true), 2);
+    assert!(contract.get_concluded());
+}
+
+// A relative contract which has not been acquired does not expire
+#[test]
+fn unacquired_relative_contract_not_concluded() {
+    // Create contract truncate +30 one
+    let mut contract_details = setup_contract(vec![11, 30, 1]);
+
+    ext_update(|e| e.timestamp(1000));
+    assert!(!contract_details.contract.get_concluded());
+}
+
 // An expired contract should be concluded.
 #[test]
 fn expired_contract_concluded() {
@@ -351,6 +465,314 @@ fn fully_updated_contract_concluded() {
     assert!(contract.get_concluded());
 }
 
+// Swap of two assets has the correct value for each asset
+#[cfg(feature = "assets")]
+#[test]
+fn asset_swap_has_correct_values() {
+    let token: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+    let token_serialized: [i64; 4] = address_to_i64(token);
+
+    // Create contract and (scale 100 one-of-token) (give scale 3 one)
+    let mut contract_details = setup_contract(vec![
+        2, 5, 1, 100, 13, token_serialized[0], token_serialized[1], token_serialized[2], token_serialized[3], 6, 5, 1, 3, 1
+    ]);
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.acquire();
+
+    assert_eq!(contract_details.contract.get_balance(true), -3);
+    assert_eq!(contract_details.contract.get_balance(false), 3);
+    assert_eq!(contract_details.contract.get_asset_balance(true, token), 100);
+    assert_eq!(contract_details.contract.get_asset_balance(false, token), -100);
+    assert!(contract_details.contract.get_concluded());
+}
+
+// A contract built with the builder has the same value as its hand-written definition
+#[test]
+fn built_contract_has_correct_value() {
+    let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+
+    // Create contract and (truncate 1 (or zero (scale 5 one))) (give (scale obs one))
+    let mut contract_details = setup_contract(and(
+        truncate(1, or(zero(), scale(5, one()))),
+        give(scale_by(observable(arbiter, "rate"), one()))
+    ).serialize());
+
+    ext_update(|e| e.sender(arbiter));
+    contract_details.contract.set_obs_value(0, 2);
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.set_or_choice(0, false);
+    contract_details.contract.acquire();
+
+    assert_eq!(contract_details.contract.get_balance(true), 3);
+}
+
+// A zero-coupon bond pays the face value at maturity
+#[test]
+fn zero_coupon_bond_template_has_correct_value() {
+    let mut contract_details = setup_contract(templates::zero_coupon_bond(90, 100, 10).serialize());
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.acquire();
+    assert_eq!(contract_details.contract.get_balance(true), -90);
+
+    ext_update(|e| e.timestamp(10));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), 10);
+    assert!(contract_details.contract.get_concluded());
+}
+
+// A coupon bond pays each coupon, then the face value with the last coupon
+#[cfg(feature = "schedule")]
+#[test]
+fn coupon_bond_template_has_correct_value() {
+    let mut contract_details = setup_contract(templates::coupon_bond(95, 100, 5, 10, 10, 3).unwrap().serialize());
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.acquire();
+    assert_eq!(contract_details.contract.get_balance(true), -95);
+
+    ext_update(|e| e.timestamp(10));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), -90);
+
+    ext_update(|e| e.timestamp(30));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), 20);
+    assert!(contract_details.contract.get_concluded());
+}
+
+// An amortising loan repays a share of the principal plus interest on the outstanding principal each period
+#[test]
+fn amortising_loan_template_has_correct_value() {
+    let mut contract_details = setup_contract(templates::amortising_loan(1000, 100, 10, 10, 2).unwrap().serialize());
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.acquire();
+    assert_eq!(contract_details.contract.get_balance(true), -1000);
+
+    ext_update(|e| e.timestamp(10));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), -490);
+    assert!(!contract_details.contract.get_concluded());
+
+    ext_update(|e| e.timestamp(20));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), 15);
+    assert!(contract_details.contract.get_concluded());
+}
+
+// An exercised European call pays the underlying's value less the strike at expiry
+#[test]
+fn european_call_template_has_correct_value() {
+    let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+    let mut contract_details = setup_contract(templates::european_call(2, 50, 10, observable(arbiter, "price")).serialize());
+
+    ext_update(|e| e.sender(arbiter));
+    contract_details.contract.set_obs_value(0, 60);
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.set_or_choice(0, true);
+    contract_details.contract.acquire();
+    assert_eq!(contract_details.contract.get_balance(true), -2);
+
+    ext_update(|e| e.timestamp(10));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), 8);
+}
+
+// An unexercised European put pays nothing at expiry
+#[test]
+fn unexercised_european_put_template_has_correct_value() {
+    let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+    let mut contract_details = setup_contract(templates::european_put(2, 50, 10, observable(arbiter, "price")).serialize());
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.set_or_choice(0, false);
+    contract_details.contract.acquire();
+
+    ext_update(|e| e.timestamp(10));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), -2);
+    assert!(contract_details.contract.get_concluded());
+}
+
+// An exercised European put pays the strike less the underlying's value at expiry
+#[test]
+fn european_put_template_has_correct_value() {
+    let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+    let mut contract_details = setup_contract(templates::european_put(2, 50, 10, observable(arbiter, "price")).serialize());
+
+    ext_update(|e| e.sender(arbiter));
+    contract_details.contract.set_obs_value(0, 40);
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.set_or_choice(0, true);
+    contract_details.contract.acquire();
+
+    ext_update(|e| e.timestamp(10));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), 8);
+}
+
+// An American call pays the underlying's value less the strike when exercised before expiry
+#[test]
+fn american_call_template_has_correct_value() {
+    let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+    let mut contract_details = setup_contract(templates::american_call(2, 50, 10, observable(arbiter, "price")).serialize());
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.acquire();
+
+    // Exercise at time 5
+    ext_update(|e| e.sender(arbiter).timestamp(5));
+    contract_details.contract.set_obs_value(0, 70);
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.set_or_choice(0, true);
+    contract_details.contract.acquire_anytime_sub_contract(0);
+
+    assert_eq!(contract_details.contract.get_balance(true), 18);
+}
+
+// An American put pays the strike less the underlying's value when exercised before expiry
+#[test]
+fn american_put_template_has_correct_value() {
+    let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+    let mut contract_details = setup_contract(templates::american_put(2, 50, 10, observable(arbiter, "price")).serialize());
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.acquire();
+
+    // Exercise at time 5
+    ext_update(|e| e.sender(arbiter).timestamp(5));
+    contract_details.contract.set_obs_value(0, 35);
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.set_or_choice(0, true);
+    contract_details.contract.acquire_anytime_sub_contract(0);
+
+    assert_eq!(contract_details.contract.get_balance(true), 13);
+}
+
+// A forward pays the underlying's value less the agreed price at delivery, even if negative
+#[test]
+fn forward_template_has_correct_value() {
+    let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+    let mut contract_details = setup_contract(templates::forward(50, 10, observable(arbiter, "price")).serialize());
+
+    ext_update(|e| e.sender(arbiter));
+    contract_details.contract.set_obs_value(0, 45);
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.acquire();
+
+    ext_update(|e| e.timestamp(10));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), -5);
+}
+
+// A fixed/floating swap pays the difference of the fixed and floating rates on the notional each period
+#[test]
+fn fixed_floating_swap_template_has_correct_value() {
+    let arbiter: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+    let mut contract_details = setup_contract(templates::fixed_floating_swap(
+        100, 3, 10, 5, vec![observable(arbiter, "rate0"), observable(arbiter, "rate1")]
+    ).serialize());
+
+    ext_update(|e| e.sender(arbiter));
+    contract_details.contract.set_obs_value(0, 2);
+    contract_details.contract.set_obs_value(1, 5);
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.acquire();
+
+    ext_update(|e| e.timestamp(10));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), 100);
+
+    ext_update(|e| e.timestamp(15));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), -100);
+    assert!(contract_details.contract.get_concluded());
+}
+
+// A simplified contract pays the same as the original contract
+#[test]
+fn simplified_contract_has_correct_value() {
+    let mut contract_details = setup_contract(simplify(and(
+        give(give(scale(1, give(scale(90, one()))))),
+        get(truncate(20, truncate(10, scale(100, one()))))
+    )).serialize());
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.acquire();
+    assert_eq!(contract_details.contract.get_balance(true), -90);
+
+    ext_update(|e| e.timestamp(10));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), 10);
+    assert!(contract_details.contract.get_concluded());
+}
+
+// A contract can use a combinator registered by another crate
+#[test]
+fn registered_combinator_has_correct_value() {
+    register_double_combinator();
+    let mut contract_details = setup_contract(vec![DOUBLE_COMBINATOR, 8, 4, 10, 1]);
+
+    ext_update(|e| e.sender(contract_details.holder));
+    contract_details.contract.acquire();
+    assert_eq!(contract_details.contract.get_balance(true), 0);
+
+    ext_update(|e| e.timestamp(10));
+    contract_details.contract.update();
+    assert_eq!(contract_details.contract.get_balance(true), 2);
+    assert!(contract_details.contract.get_concluded());
+}
+
+// Withdrawing to another recipient pays the recipient the full amount, the gas stipend is not deducted
+#[test]
+fn withdraw_to_pays_recipient() {
+    let recipient: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+    let received = Rc::new(Cell::new(U256::zero()));
+    let mut contract_details = setup_contract(vec![0]);
+
+    let recipient_received = received.clone();
+    ext_update(|e| e
+        .value(U256::from(10000))
+        .endpoint(recipient, Box::new(move |value: U256, _input: &[u8], _result: &mut [u8]| {
+            recipient_received.set(value);
+            Ok(())
+        }))
+    );
+    contract_details.contract.stake();
+    contract_details.contract.withdraw_to(Address::zero(), recipient, 1000);
+
+    assert_eq!(received.get(), U256::from(1000));
+    assert_eq!(contract_details.contract.get_balance(false), 9000);
+}
+
+// A failed payment rolls back the withdrawal, so the balance can be withdrawn again
+#[test]
+fn failed_payment_rolls_back_balance() {
+    let recipient: Address = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+    let mut contract_details = setup_contract(vec![0]);
+
+    ext_update(|e| e
+        .value(U256::from(10000))
+        .endpoint(recipient, Box::new(|_value: U256, _input: &[u8], _result: &mut [u8]| Err(Error)))
+    );
+    contract_details.contract.stake();
+
+    let contract = &mut contract_details.contract;
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| contract.withdraw_to(Address::zero(), recipient, 1000)));
+    assert!(result.is_err());
+    assert_eq!(contract.get_balance(false), 10000);
+
+    contract.withdraw(1000);
+    assert_eq!(contract.get_balance(false), 9000);
+}
+
 // Setting an or-choice twice is not allowed
 #[test]
 #[should_panic(expected="Or-choice has already been set.")]
@@ -0,0 +1,722 @@
+use financial_smart_contract::FinancialScContract;
+use financial_smart_contract::builder::{ Definition, ScaleValue, Observable };
+use ir::{ Type, Expr, Stmt, Function, StateVariable, Program };
+
+// A contract generated for a single contract definition
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct GeneratedContract {
+    // The name of the contract
+    pub name: String,
+
+    // The contract definition the contract was generated for (serialized)
+    pub definition: Vec<i64>,
+
+    // The state and functions of the contract's combinators
+    pub program: Program,
+
+    // The number of or combinators
+    pub or_count: usize,
+
+    // The observables, by observable index
+    pub observables: Vec<Observable>,
+
+    // The number of anytime combinators
+    pub anytime_count: usize
+}
+
+// Generates a contract with the given name for the given contract definition (serialized). Each combinator of the definition
+// becomes its own state variables and acquire and update functions, so the contract never deserializes the definition.
+// Or, observable and anytime indices are assigned in the same order as the financial smart contract assigns them. Asset,
+// quorum and disputable combinators cannot be generated, and give an error.
+pub fn generate(name: &str, contract_definition: &Vec<i64>) -> Result<GeneratedContract, &'static str> {
+    // Deserializing checks the definition as the constructor does, and gives the horizon before relative times are known
+    let (combinator, _) = FinancialScContract::deserialize_definition(contract_definition);
+    let definition = Definition::deserialize(contract_definition);
+
+    let mut generator = Generator::new();
+    generator.declare(Type::Uint64, "baseTime".to_string(), None);
+    generator.generate_node(&definition)?;
+
+    // Arrays are never empty, indices are checked against the counts instead
+    let or_length = Some(generator.or_count.max(1));
+    let obs_length = Some(generator.observables.len().max(1));
+    let anytime_length = Some(generator.anytime_count.max(1));
+    generator.declare(Type::Uint8, "orChoices".to_string(), or_length);
+    generator.declare(Type::Int64, "obsValues".to_string(), obs_length);
+    generator.declare(Type::Bool, "obsSet".to_string(), obs_length);
+    generator.declare(Type::Bool, "anytimeAcquired".to_string(), anytime_length);
+    generator.declare(Type::Bool, "anytimeTimeSet".to_string(), anytime_length);
+    generator.declare(Type::Uint64, "anytimeTimes".to_string(), anytime_length);
+
+    // The contract has concluded once fully updated, or if it expires before being acquired
+    let expired = match combinator.get_horizon() {
+        Some(horizon) => lt(Expr::Uint(horizon), Expr::Now),
+        None => Expr::Bool(false)
+    };
+    generator.functions.push(Function {
+        name: "isConcluded".to_string(),
+        comment: "Whether or not the contract has concluded".to_string(),
+        parameters: Vec::new(),
+        returns: Some(Type::Bool),
+        view: true,
+        body: vec![Stmt::Return(Some(or(var(&node_name("fullyUpdated", 0)), and(not(var(&node_name("acquired", 0))), expired))))]
+    });
+
+    Ok(GeneratedContract {
+        name: name.to_string(),
+        definition: contract_definition.clone(),
+        program: Program {
+            state_variables: generator.state_variables,
+            functions: generator.functions
+        },
+        or_count: generator.or_count,
+        observables: generator.observables,
+        anytime_count: generator.anytime_count
+    })
+}
+
+// A generated combinator
+struct Node {
+    // The index of the combinator, in pre-order
+    index: usize,
+
+    // The horizon once relative times are known (a call to the combinator's horizon function), or None if it has none
+    horizon: Option<Expr>,
+
+    // The indices of the combinator and its sub-combinators, with whether or not each is a schedule combinator
+    subtree: Vec<(usize, bool)>,
+
+    // The anytime indices of the combinator and its sub-combinators
+    anytimes: Vec<usize>
+}
+
+// The code generated for a combinator of a given kind, see Generator::generate_node
+struct NodeCode {
+    // The kind of combinator, as named in panic messages
+    kind: &'static str,
+
+    // The horizon once relative times are known, or None if it has none
+    horizon: Option<Expr>,
+
+    // Acquires the sub-combinators, after the acquisition is checked
+    acquire: Vec<Stmt>,
+
+    // Updates the sub-combinators and returns the balance, if acquired by the given time and not fully updated
+    update: Vec<Stmt>,
+
+    // A further condition under which updating does nothing, if any
+    idle: Option<Expr>,
+
+    // Any further functions of the combinator
+    functions: Vec<Function>,
+
+    // The sub-combinators
+    sub_nodes: Vec<Node>,
+
+    // Whether or not the combinator is a schedule combinator
+    schedule: bool,
+
+    // The anytime index, if the combinator is an anytime combinator
+    anytime: Option<usize>
+}
+
+// Method implementation for the code of a combinator
+impl NodeCode {
+    // Constructor
+    fn new(kind: &'static str, horizon: Option<Expr>) -> NodeCode {
+        NodeCode {
+            kind,
+            horizon,
+            acquire: Vec::new(),
+            update: Vec::new(),
+            idle: None,
+            functions: Vec::new(),
+            sub_nodes: Vec::new(),
+            schedule: false,
+            anytime: None
+        }
+    }
+}
+
+// Generates the program of a contract, combinator by combinator
+struct Generator {
+    // The number of combinators generated so far
+    node_count: usize,
+
+    // The number of or combinators generated so far
+    or_count: usize,
+
+    // The observables of the scale combinators generated so far
+    observables: Vec<Observable>,
+
+    // The number of anytime combinators generated so far
+    anytime_count: usize,
+
+    // The state variables declared so far
+    state_variables: Vec<StateVariable>,
+
+    // The functions generated so far
+    functions: Vec<Function>
+}
+
+// Method implementation for the generator
+impl Generator {
+    // Constructor
+    fn new() -> Generator {
+        Generator {
+            node_count: 0,
+            or_count: 0,
+            observables: Vec::new(),
+            anytime_count: 0,
+            state_variables: Vec::new(),
+            functions: Vec::new()
+        }
+    }
+
+    // Declares a state variable
+    fn declare(&mut self, ty: Type, name: String, length: Option<usize>) {
+        self.state_variables.push(StateVariable { ty, name, length });
+    }
+
+    // Generates the state and functions of the given combinator and its sub-combinators, the combinator's functions
+    // preceding those of its sub-combinators
+    fn generate_node(&mut self, definition: &Definition) -> Result<Node, &'static str> {
+        let index = self.node_count;
+        self.node_count += 1;
+        self.declare(Type::Bool, node_name("acquired", index), None);
+        self.declare(Type::Uint64, node_name("acquisitionTime", index), None);
+        self.declare(Type::Bool, node_name("fullyUpdated", index), None);
+        let position = self.functions.len();
+
+        let code = match *definition {
+            Definition::Zero => Ok(self.generate_leaf(index, "zero", 0)),
+            Definition::One => Ok(self.generate_leaf(index, "one", 1)),
+            Definition::OneAsset(_) => Err("Only Ether-denominated contracts can be generated."),
+            Definition::And(ref definition0, ref definition1) => self.generate_and(index, definition0, definition1),
+            Definition::Or(ref definition0, ref definition1) => self.generate_or(index, definition0, definition1),
+            Definition::Truncate(time, ref sub_definition) => self.generate_truncate(index, Expr::Uint(time), sub_definition),
+            Definition::TruncateRelative(offset, ref sub_definition) => self.generate_truncate(index, relative_time(offset), sub_definition),
+            Definition::Scale(ref scale_value, ref sub_definition) => self.generate_scale(index, scale_value, sub_definition),
+            Definition::Give(ref sub_definition) => self.generate_give(index, sub_definition),
+            Definition::Then(ref definition0, ref definition1) => self.generate_then(index, definition0, definition1),
+            Definition::Get(ref sub_definition) => self.generate_get(index, sub_definition),
+            Definition::Anytime(ref sub_definition) => self.generate_anytime(index, sub_definition),
+            Definition::Schedule(start, period, count, ref sub_definition) =>
+                self.generate_schedule(index, Expr::Uint(start), period, count, sub_definition),
+            Definition::ScheduleRelative(offset, period, count, ref sub_definition) =>
+                self.generate_schedule(index, relative_time(offset), period, count, sub_definition)
+        }?;
+
+        let mut functions: Vec<Function> = Vec::new();
+        let horizon = match code.horizon {
+            Some(horizon) => {
+                functions.push(Function {
+                    name: node_name("horizon", index),
+                    comment: format!("The horizon of the {} combinator {}", code.kind, index),
+                    parameters: Vec::new(),
+                    returns: Some(Type::Uint64),
+                    view: true,
+                    body: vec![Stmt::Return(Some(horizon))]
+                });
+                Some(call(&node_name("horizon", index), Vec::new()))
+            },
+            None => None
+        };
+
+        // Acquisition checks the horizon and that the combinator is not yet acquired
+        let mut acquire = require(not(past_horizon(&horizon, time())), "Cannot acquire an expired contract.");
+        acquire.extend(require(
+            not(var(&node_name("acquired", index))),
+            &format!("Acquiring a previously-acquired {} combinator is not allowed.", code.kind)
+        ));
+        acquire.extend(code.acquire);
+        acquire.push(Stmt::Assign(var(&node_name("acquired", index)), Expr::Bool(true)));
+        acquire.push(Stmt::Assign(var(&node_name("acquisitionTime", index)), time()));
+        functions.push(Function {
+            name: node_name("acquire", index),
+            comment: format!("Acquires the {} combinator {} and acquirable sub-combinators", code.kind, index),
+            parameters: vec![(Type::Uint64, "time".to_string())],
+            returns: None,
+            view: false,
+            body: acquire
+        });
+
+        // If not acquired yet or fully updated (no more pending balance), updating returns 0
+        let mut idle = or(
+            or(not(var(&node_name("acquired", index))), lt(time(), var(&node_name("acquisitionTime", index)))),
+            var(&node_name("fullyUpdated", index))
+        );
+        if let Some(condition) = code.idle {
+            idle = or(idle, condition);
+        }
+        let mut update = vec![Stmt::If(idle, vec![Stmt::Return(Some(Expr::Int(0)))], Vec::new())];
+        update.extend(code.update);
+        functions.push(Function {
+            name: node_name("update", index),
+            comment: format!("Updates the {} combinator {}, returning the balance to be paid from the counter-party to the holder", code.kind, index),
+            parameters: vec![(Type::Uint64, "time".to_string())],
+            returns: Some(Type::Int64),
+            view: false,
+            body: update
+        });
+
+        functions.extend(code.functions);
+        let sub_functions = self.functions.split_off(position);
+        self.functions.extend(functions);
+        self.functions.extend(sub_functions);
+
+        let mut subtree = vec![(index, code.schedule)];
+        let mut anytimes: Vec<usize> = code.anytime.into_iter().collect();
+        for sub_node in code.sub_nodes {
+            subtree.extend(sub_node.subtree);
+            anytimes.extend(sub_node.anytimes);
+        }
+        Ok(Node { index, horizon, subtree, anytimes })
+    }
+
+    // Generates a zero or one combinator, paying the given amount once acquired
+    fn generate_leaf(&mut self, index: usize, kind: &'static str, amount: i64) -> NodeCode {
+        let mut code = NodeCode::new(kind, None);
+        code.update = vec![
+            Stmt::Assign(var(&node_name("fullyUpdated", index)), Expr::Bool(true)),
+            Stmt::Return(Some(Expr::Int(amount)))
+        ];
+        code
+    }
+
+    // Generates an and combinator, acquiring and updating both sub-combinators
+    fn generate_and(&mut self, index: usize, definition0: &Definition, definition1: &Definition)
+        -> Result<NodeCode, &'static str> {
+        let sub_node0 = self.generate_node(definition0)?;
+        let sub_node1 = self.generate_node(definition1)?;
+
+        let mut code = NodeCode::new("and", latest_time(&sub_node0.horizon, &sub_node1.horizon));
+        code.acquire.extend(if_then(not(past_horizon(&sub_node0.horizon, time())), vec![acquire_call(sub_node0.index, time())]));
+        code.acquire.extend(if_then(not(past_horizon(&sub_node1.horizon, time())), vec![acquire_call(sub_node1.index, time())]));
+        code.update = vec![
+            Stmt::Let(Type::Int64, "value0".to_string(), update_call(sub_node0.index)),
+            Stmt::Let(Type::Int64, "value1".to_string(), update_call(sub_node1.index)),
+            Stmt::Assign(
+                var(&node_name("fullyUpdated", index)),
+                and(var(&node_name("fullyUpdated", sub_node0.index)), var(&node_name("fullyUpdated", sub_node1.index)))
+            ),
+            Stmt::Return(Some(Expr::Add(Box::new(var("value0")), Box::new(var("value1")))))
+        ];
+        code.sub_nodes = vec![sub_node0, sub_node1];
+        Ok(code)
+    }
+
+    // Generates an or combinator, acquiring the sub-combinator chosen by the holder (unless the other has expired)
+    fn generate_or(&mut self, index: usize, definition0: &Definition, definition1: &Definition)
+        -> Result<NodeCode, &'static str> {
+        let or_index = self.or_count;
+        self.or_count += 1;
+        let sub_node0 = self.generate_node(definition0)?;
+        let sub_node1 = self.generate_node(definition1)?;
+
+        let mut code = NodeCode::new("or", latest_time(&sub_node0.horizon, &sub_node1.horizon));
+
+        let mut choice = if_then(past_horizon(&sub_node0.horizon, time()), vec![Stmt::Return(Some(Expr::Uint(0)))]);
+        choice.extend(if_then(past_horizon(&sub_node1.horizon, time()), vec![Stmt::Return(Some(Expr::Uint(1)))]));
+        choice.push(Stmt::Return(Some(element("orChoices", or_index))));
+        code.functions.push(Function {
+            name: node_name("orChoice", index),
+            comment: format!("The choice of the or combinator {} at the given time, 1 for the first sub-combinator, 0 for the second and 2 if not yet chosen", index),
+            parameters: vec![(Type::Uint64, "time".to_string())],
+            returns: Some(Type::Uint8),
+            view: true,
+            body: choice
+        });
+
+        let acquisition_time = var(&node_name("acquisitionTime", index));
+        code.acquire = vec![
+            Stmt::Let(Type::Uint8, "choice".to_string(), call(&node_name("orChoice", index), vec![time()])),
+            Stmt::If(
+                eq(var("choice"), Expr::Uint(1)),
+                vec![acquire_call(sub_node0.index, time())],
+                vec![Stmt::If(eq(var("choice"), Expr::Uint(0)), vec![acquire_call(sub_node1.index, time())], Vec::new())]
+            )
+        ];
+        code.update = vec![
+            Stmt::Let(Type::Uint8, "choice".to_string(), call(&node_name("orChoice", index), vec![acquisition_time.clone()])),
+            Stmt::If(eq(var("choice"), Expr::Uint(2)), vec![Stmt::Return(Some(Expr::Int(0)))], Vec::new())
+        ];
+        let mut update0 = acquire_if_unacquired(sub_node0.index, acquisition_time.clone());
+        update0.extend(update_sub_node(index, sub_node0.index, "value0", |value| value));
+        code.update.push(Stmt::If(eq(var("choice"), Expr::Uint(1)), update0, Vec::new()));
+        code.update.extend(acquire_if_unacquired(sub_node1.index, acquisition_time));
+        code.update.extend(update_sub_node(index, sub_node1.index, "value1", |value| value));
+        code.sub_nodes = vec![sub_node0, sub_node1];
+        Ok(code)
+    }
+
+    // Generates a truncate combinator with the given truncated horizon
+    fn generate_truncate(&mut self, index: usize, truncated_horizon: Expr, sub_definition: &Definition)
+        -> Result<NodeCode, &'static str> {
+        let sub_node = self.generate_node(sub_definition)?;
+
+        let mut code = NodeCode::new("truncate", earliest_time(&sub_node.horizon, truncated_horizon));
+        code.acquire = vec![acquire_call(sub_node.index, time())];
+        code.update = update_sub_node(index, sub_node.index, "value", |value| value);
+        code.sub_nodes = vec![sub_node];
+        Ok(code)
+    }
+
+    // Generates a scale combinator, scaling by the provided value or by the observable once its value is set
+    fn generate_scale(&mut self, index: usize, scale_value: &ScaleValue, sub_definition: &Definition)
+        -> Result<NodeCode, &'static str> {
+        let (scale, idle) = match *scale_value {
+            ScaleValue::Provided(value) => (Expr::Int(value), None),
+            ScaleValue::Observable(ref observable) => {
+                let obs_index = self.observables.len();
+                self.observables.push(observable.clone());
+                (element("obsValues", obs_index), Some(not(element("obsSet", obs_index))))
+            },
+            ScaleValue::Quorum(_) => return Err("Quorum observables are not supported by generated contracts."),
+            ScaleValue::Disputable(..) => return Err("Disputable observables are not supported by generated contracts.")
+        };
+        let sub_node = self.generate_node(sub_definition)?;
+
+        let mut code = NodeCode::new("scale", sub_node.horizon.clone());
+        code.acquire = vec![acquire_call(sub_node.index, time())];
+        code.update = update_sub_node(index, sub_node.index, "value", |value| Expr::Mul(Box::new(value), Box::new(scale)));
+        code.idle = idle;
+        code.sub_nodes = vec![sub_node];
+        Ok(code)
+    }
+
+    // Generates a give combinator, negating the sub-combinator's balance
+    fn generate_give(&mut self, index: usize, sub_definition: &Definition) -> Result<NodeCode, &'static str> {
+        let sub_node = self.generate_node(sub_definition)?;
+
+        let mut code = NodeCode::new("give", sub_node.horizon.clone());
+        code.acquire = vec![acquire_call(sub_node.index, time())];
+        code.update = update_sub_node(index, sub_node.index, "value", |value| Expr::Neg(Box::new(value)));
+        code.sub_nodes = vec![sub_node];
+        Ok(code)
+    }
+
+    // Generates a then combinator, acquiring the first sub-combinator unless it has expired, otherwise the second
+    fn generate_then(&mut self, index: usize, definition0: &Definition, definition1: &Definition)
+        -> Result<NodeCode, &'static str> {
+        let sub_node0 = self.generate_node(definition0)?;
+        let sub_node1 = self.generate_node(definition1)?;
+
+        let mut code = NodeCode::new("then", latest_time(&sub_node0.horizon, &sub_node1.horizon));
+        code.acquire = if_else(
+            not(past_horizon(&sub_node0.horizon, time())),
+            vec![acquire_call(sub_node0.index, time())],
+            vec![acquire_call(sub_node1.index, time())]
+        );
+        code.update = if_else(
+            not(past_horizon(&sub_node0.horizon, var(&node_name("acquisitionTime", index)))),
+            update_sub_node(index, sub_node0.index, "value0", |value| value),
+            update_sub_node(index, sub_node1.index, "value1", |value| value)
+        );
+        code.sub_nodes = vec![sub_node0, sub_node1];
+        Ok(code)
+    }
+
+    // Generates a get combinator, acquiring the sub-combinator at its horizon
+    fn generate_get(&mut self, index: usize, sub_definition: &Definition) -> Result<NodeCode, &'static str> {
+        let sub_node = self.generate_node(sub_definition)?;
+
+        let mut code = NodeCode::new("get", sub_node.horizon.clone());
+        code.acquire = match sub_node.horizon {
+            Some(ref horizon) => vec![acquire_call(sub_node.index, horizon.clone())],
+            // Without a horizon the sub-combinator can never be acquired
+            None => vec![Stmt::Assign(var(&node_name("fullyUpdated", index)), Expr::Bool(true))]
+        };
+        code.update = update_sub_node(index, sub_node.index, "value", |value| value);
+        code.sub_nodes = vec![sub_node];
+        Ok(code)
+    }
+
+    // Generates an anytime combinator, acquiring the sub-combinator when the holder chooses (or at its horizon)
+    fn generate_anytime(&mut self, index: usize, sub_definition: &Definition) -> Result<NodeCode, &'static str> {
+        let anytime_index = self.anytime_count;
+        self.anytime_count += 1;
+        let sub_node = self.generate_node(sub_definition)?;
+
+        // The latest acquisition time of the sub-combinator, its horizon, which the holder may bring forward
+        let set_latest_time = vec![
+            Stmt::Assign(element("anytimeTimeSet", anytime_index), Expr::Bool(sub_node.horizon.is_some())),
+            Stmt::Assign(element("anytimeTimes", anytime_index), sub_node.horizon.clone().unwrap_or(Expr::Uint(0)))
+        ];
+
+        let mut code = NodeCode::new("anytime", sub_node.horizon.clone());
+        code.anytime = Some(anytime_index);
+        code.acquire = vec![Stmt::Assign(element("anytimeAcquired", anytime_index), Expr::Bool(false))];
+        code.acquire.extend(set_latest_time.clone());
+
+        let mut acquired = vec![Stmt::Assign(element("anytimeAcquired", anytime_index), Expr::Bool(true))];
+        acquired.extend(set_latest_time);
+        code.update = vec![Stmt::If(not(element("anytimeAcquired", anytime_index)), acquired, Vec::new())];
+
+        // Acquire the sub-combinator once its acquisition time has passed, no later than its horizon
+        let mut acquire_sub = vec![Stmt::Let(Type::Uint64, "subAcquisitionTime".to_string(), element("anytimeTimes", anytime_index))];
+        if let Some(ref horizon) = sub_node.horizon {
+            acquire_sub.push(Stmt::If(
+                lt(horizon.clone(), var("subAcquisitionTime")),
+                vec![Stmt::Assign(var("subAcquisitionTime"), horizon.clone())],
+                Vec::new()
+            ));
+        }
+        acquire_sub.push(Stmt::Require(
+            not(lt(var("subAcquisitionTime"), var(&node_name("acquisitionTime", index)))),
+            "Cannot acquire anytime sub-combinator before the anytime combinator is acquired.".to_string()
+        ));
+        acquire_sub.push(Stmt::If(
+            not(lt(time(), var("subAcquisitionTime"))),
+            vec![acquire_call(sub_node.index, var("subAcquisitionTime"))],
+            Vec::new()
+        ));
+        code.update.push(Stmt::If(
+            and(not(var(&node_name("acquired", sub_node.index))), element("anytimeTimeSet", anytime_index)),
+            acquire_sub,
+            Vec::new()
+        ));
+        code.update.extend(update_sub_node(index, sub_node.index, "value", |value| value));
+        code.sub_nodes = vec![sub_node];
+        Ok(code)
+    }
+
+    // Generates a schedule combinator with the given first period date, acquiring a fresh copy of the sub-combinator at the
+    // start of each period
+    fn generate_schedule(&mut self, index: usize, start: Expr, period: u64, count: u64, sub_definition: &Definition)
+        -> Result<NodeCode, &'static str> {
+        if count == 0 {
+            panic!("Schedule combinator must have at least one period.");
+        }
+        let current_period = node_name("currentPeriod", index);
+        self.declare(Type::Uint64, current_period.clone(), None);
+        let sub_node = self.generate_node(sub_definition)?;
+
+        let period_date = |period_index: Expr| saturating_add(start.clone(), saturating_mul(Expr::Uint(period), period_index));
+        let last_date = period_date(Expr::Uint(count - 1));
+
+        // The last period at which the sub-combinator can still be acquired
+        let horizon = match sub_node.horizon {
+            None => last_date,
+            Some(ref sub_horizon) => {
+                let last_acquirable_date = if period == 0 {
+                    sub_horizon.clone()
+                } else {
+                    cond(
+                        lt(sub_horizon.clone(), start.clone()),
+                        sub_horizon.clone(),
+                        period_date(Expr::Div(Box::new(Expr::Sub(Box::new(sub_horizon.clone()), Box::new(start.clone()))), Box::new(Expr::Uint(period))))
+                    )
+                };
+                cond(not(lt(sub_horizon.clone(), last_date.clone())), last_date, last_acquirable_date)
+            }
+        };
+
+        let mut code = NodeCode::new("schedule", Some(horizon));
+        code.schedule = true;
+
+        // Periods which started before the acquisition time are skipped
+        code.acquire = vec![Stmt::While(
+            and(lt(var(&current_period), Expr::Uint(count)), lt(period_date(var(&current_period)), time())),
+            vec![Stmt::Assign(var(&current_period), saturating_add(var(&current_period), Expr::Uint(1)))]
+        )];
+
+        // Periods are paid out in order, a period's sub-combinator is only acquired once the previous one is fully updated
+        let mut acquire_period = if_then(
+            past_horizon(&sub_node.horizon, var("date")),
+            vec![Stmt::Assign(var(&current_period), Expr::Uint(count)), Stmt::Break]
+        );
+        acquire_period.push(acquire_call(sub_node.index, var("date")));
+        let period_body = vec![
+            Stmt::Let(Type::Uint64, "date".to_string(), period_date(var(&current_period))),
+            Stmt::If(lt(time(), var("date")), vec![Stmt::Break], Vec::new()),
+            Stmt::If(not(var(&node_name("acquired", sub_node.index))), acquire_period, Vec::new()),
+            Stmt::Assign(var("value"), Expr::Add(Box::new(var("value")), Box::new(update_call(sub_node.index)))),
+            Stmt::If(not(var(&node_name("fullyUpdated", sub_node.index))), vec![Stmt::Break], Vec::new()),
+            Stmt::Assign(var(&current_period), saturating_add(var(&current_period), Expr::Uint(1))),
+            Stmt::Call(node_name("resetPeriod", index), Vec::new())
+        ];
+        code.update = vec![
+            Stmt::Let(Type::Int64, "value".to_string(), Expr::Int(0)),
+            Stmt::While(lt(var(&current_period), Expr::Uint(count)), period_body),
+            Stmt::Assign(var(&node_name("fullyUpdated", index)), not(lt(var(&current_period), Expr::Uint(count)))),
+            Stmt::Return(Some(var("value")))
+        ];
+
+        // The next period's sub-combinator starts unacquired
+        let mut reset: Vec<Stmt> = Vec::new();
+        for &(sub_index, schedule) in sub_node.subtree.iter() {
+            reset.push(Stmt::Assign(var(&node_name("acquired", sub_index)), Expr::Bool(false)));
+            reset.push(Stmt::Assign(var(&node_name("acquisitionTime", sub_index)), Expr::Uint(0)));
+            reset.push(Stmt::Assign(var(&node_name("fullyUpdated", sub_index)), Expr::Bool(false)));
+            if schedule {
+                reset.push(Stmt::Assign(var(&node_name("currentPeriod", sub_index)), Expr::Uint(0)));
+            }
+        }
+
+        // Anytime acquisition times are shared by all periods, those of the last period are kept
+        let mut reset_anytimes: Vec<Stmt> = Vec::new();
+        for &anytime_index in sub_node.anytimes.iter() {
+            reset_anytimes.push(Stmt::Assign(element("anytimeAcquired", anytime_index), Expr::Bool(false)));
+            reset_anytimes.push(Stmt::Assign(element("anytimeTimeSet", anytime_index), Expr::Bool(false)));
+            reset_anytimes.push(Stmt::Assign(element("anytimeTimes", anytime_index), Expr::Uint(0)));
+        }
+        if !reset_anytimes.is_empty() {
+            reset.push(Stmt::If(lt(var(&current_period), Expr::Uint(count)), reset_anytimes, Vec::new()));
+        }
+        code.functions.push(Function {
+            name: node_name("resetPeriod", index),
+            comment: format!("Resets the sub-combinators of the schedule combinator {} for the next period", index),
+            parameters: Vec::new(),
+            returns: None,
+            view: false,
+            body: reset
+        });
+        code.sub_nodes = vec![sub_node];
+        Ok(code)
+    }
+}
+
+// Gets the name of a combinator's state variable or function
+fn node_name(name: &str, index: usize) -> String {
+    format!("{}_{}", name, index)
+}
+
+// A variable
+fn var(name: &str) -> Expr {
+    Expr::Var(name.to_string())
+}
+
+// An element of a state array
+fn element(name: &str, index: usize) -> Expr {
+    Expr::Element(name.to_string(), Box::new(Expr::Uint(index as u64)))
+}
+
+// The time parameter of acquire and update functions
+fn time() -> Expr {
+    var("time")
+}
+
+// A time relative to the contract acquisition time
+fn relative_time(offset: u64) -> Expr {
+    saturating_add(var("baseTime"), Expr::Uint(offset))
+}
+
+// A call to a function of the program
+fn call(name: &str, arguments: Vec<Expr>) -> Expr {
+    Expr::Call(name.to_string(), arguments)
+}
+
+// Acquires the given combinator at the given time
+fn acquire_call(index: usize, time: Expr) -> Stmt {
+    Stmt::Call(node_name("acquire", index), vec![time])
+}
+
+// Updates the given combinator at the update time
+fn update_call(index: usize) -> Expr {
+    call(&node_name("update", index), vec![time()])
+}
+
+// Acquires the given combinator at the given time if not yet acquired
+fn acquire_if_unacquired(index: usize, time: Expr) -> Vec<Stmt> {
+    vec![Stmt::If(not(var(&node_name("acquired", index))), vec![acquire_call(index, time)], Vec::new())]
+}
+
+// Updates the given sub-combinator, the combinator being fully updated with it, and returns its mapped balance
+fn update_sub_node<F: FnOnce(Expr) -> Expr>(index: usize, sub_index: usize, value: &str, map: F) -> Vec<Stmt> {
+    vec![
+        Stmt::Let(Type::Int64, value.to_string(), update_call(sub_index)),
+        Stmt::Assign(var(&node_name("fullyUpdated", index)), var(&node_name("fullyUpdated", sub_index))),
+        Stmt::Return(Some(map(var(value))))
+    ]
+}
+
+// Whether or not the given time is past the given horizon
+fn past_horizon(horizon: &Option<Expr>, time: Expr) -> Expr {
+    match *horizon {
+        Some(ref horizon) => lt(horizon.clone(), time),
+        None => Expr::Bool(false)
+    }
+}
+
+// The later of two horizons, None if either has none
+fn latest_time(horizon0: &Option<Expr>, horizon1: &Option<Expr>) -> Option<Expr> {
+    match (horizon0, horizon1) {
+        (Some(h0), Some(h1)) => Some(cond(lt(h1.clone(), h0.clone()), h0.clone(), h1.clone())),
+        _ => None
+    }
+}
+
+// The earlier of a sub-combinator's horizon and a truncated horizon
+fn earliest_time(horizon0: &Option<Expr>, horizon1: Expr) -> Option<Expr> {
+    match *horizon0 {
+        Some(ref h0) => Some(cond(lt(h0.clone(), horizon1.clone()), h0.clone(), horizon1)),
+        None => Some(horizon1)
+    }
+}
+
+// Expressions and statements, folding constant conditions
+
+fn not(e: Expr) -> Expr {
+    match e {
+        Expr::Bool(b) => Expr::Bool(!b),
+        Expr::Not(e) => *e,
+        e => Expr::Not(Box::new(e))
+    }
+}
+
+fn and(a: Expr, b: Expr) -> Expr {
+    match (a, b) {
+        (Expr::Bool(false), _) | (_, Expr::Bool(false)) => Expr::Bool(false),
+        (Expr::Bool(true), e) | (e, Expr::Bool(true)) => e,
+        (a, b) => Expr::And(Box::new(a), Box::new(b))
+    }
+}
+
+fn or(a: Expr, b: Expr) -> Expr {
+    match (a, b) {
+        (Expr::Bool(true), _) | (_, Expr::Bool(true)) => Expr::Bool(true),
+        (Expr::Bool(false), e) | (e, Expr::Bool(false)) => e,
+        (a, b) => Expr::Or(Box::new(a), Box::new(b))
+    }
+}
+
+fn eq(a: Expr, b: Expr) -> Expr {
+    Expr::Eq(Box::new(a), Box::new(b))
+}
+
+fn lt(a: Expr, b: Expr) -> Expr {
+    Expr::Lt(Box::new(a), Box::new(b))
+}
+
+fn cond(c: Expr, a: Expr, b: Expr) -> Expr {
+    Expr::Cond(Box::new(c), Box::new(a), Box::new(b))
+}
+
+fn saturating_add(a: Expr, b: Expr) -> Expr {
+    Expr::SaturatingAdd(Box::new(a), Box::new(b))
+}
+
+fn saturating_mul(a: Expr, b: Expr) -> Expr {
+    Expr::SaturatingMul(Box::new(a), Box::new(b))
+}
+
+fn require(condition: Expr, message: &str) -> Vec<Stmt> {
+    match condition {
+        Expr::Bool(true) => Vec::new(),
+        condition => vec![Stmt::Require(condition, message.to_string())]
+    }
+}
+
+fn if_then(condition: Expr, statements: Vec<Stmt>) -> Vec<Stmt> {
+    if_else(condition, statements, Vec::new())
+}
+
+fn if_else(condition: Expr, statements: Vec<Stmt>, else_statements: Vec<Stmt>) -> Vec<Stmt> {
+    match condition {
+        Expr::Bool(true) => statements,
+        Expr::Bool(false) => else_statements,
+        condition => vec![Stmt::If(condition, statements, else_statements)]
+    }
+}
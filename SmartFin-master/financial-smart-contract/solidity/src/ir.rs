@@ -0,0 +1,153 @@
+// The intermediate representation of the combinator code of generated contracts, which is rendered as Solidity (see solidity)
+// and executed by the machine (see machine). Generated contracts only need the few constructs below.
+
+// The types of values
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Type {
+    Bool,
+    Uint8,
+    Uint64,
+    Int64
+}
+
+// An expression
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Expr {
+    // A boolean literal
+    Bool(bool),
+
+    // An unsigned integer literal
+    Uint(u64),
+
+    // A signed integer literal
+    Int(i64),
+
+    // A parameter, local variable or state variable
+    Var(String),
+
+    // An element of a state array
+    Element(String, Box<Expr>),
+
+    // The block timestamp, as a uint64
+    Now,
+
+    // Boolean operators (short-circuiting)
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+
+    // Comparison operators
+    Eq(Box<Expr>, Box<Expr>),
+    Lt(Box<Expr>, Box<Expr>),
+
+    // int64 arithmetic, wrapping on overflow as the EVM does
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
+
+    // uint64 arithmetic, the saturating operators are helper functions of the contract template
+    Sub(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    SaturatingAdd(Box<Expr>, Box<Expr>),
+    SaturatingMul(Box<Expr>, Box<Expr>),
+
+    // A conditional expression (condition, value if true, value if false)
+    Cond(Box<Expr>, Box<Expr>, Box<Expr>),
+
+    // A call to a function of the program
+    Call(String, Vec<Expr>)
+}
+
+// A statement
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Stmt {
+    // Declares a local variable
+    Let(Type, String, Expr),
+
+    // Assigns to a variable or array element
+    Assign(Expr, Expr),
+
+    // A conditional, with the statements if true and if false
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+
+    // A loop, while the condition holds
+    While(Expr, Vec<Stmt>),
+
+    // Exits the innermost loop
+    Break,
+
+    // Reverts with the given message unless the condition holds
+    Require(Expr, String),
+
+    // Returns from the function, with a value if it has a return type
+    Return(Option<Expr>),
+
+    // Calls a function of the program, discarding any result
+    Call(String, Vec<Expr>)
+}
+
+// A function of the program
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct Function {
+    // The function name
+    pub name: String,
+
+    // A comment describing the function
+    pub comment: String,
+
+    // The parameter types and names
+    pub parameters: Vec<(Type, String)>,
+
+    // The return type, if any
+    pub returns: Option<Type>,
+
+    // Whether or not the function only reads state
+    pub view: bool,
+
+    // The function body
+    pub body: Vec<Stmt>
+}
+
+// A state variable of the program, zero-initialised
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct StateVariable {
+    // The type of the variable, or of its elements
+    pub ty: Type,
+
+    // The variable name
+    pub name: String,
+
+    // The length of the variable, if it is a fixed-size array
+    pub length: Option<usize>
+}
+
+// The state and functions of a generated contract's combinators
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct Program {
+    // The state variables
+    pub state_variables: Vec<StateVariable>,
+
+    // The functions
+    pub functions: Vec<Function>
+}
+
+// Method implementation for programs
+impl Program {
+    // Gets the function with the given name
+    pub fn get_function(&self, name: &str) -> &Function {
+        match self.functions.iter().find(|f| f.name == name) {
+            Some(function) => function,
+            None => panic!("Function not defined by the program.")
+        }
+    }
+}
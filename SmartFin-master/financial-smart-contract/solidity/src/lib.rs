@@ -0,0 +1,16 @@
+// Generation of Solidity contracts specialized to a single financial smart contract definition, with the same ABI as the
+// financial smart contract, an off-chain machine which runs the generated code in place of the EVM, and their compilation with
+// solc
+extern crate financial_smart_contract;
+extern crate pwasm_std;
+
+pub mod ir;
+pub mod generator;
+pub mod solidity;
+pub mod machine;
+pub mod solc;
+
+pub use generator::{ generate, GeneratedContract };
+pub use solidity::to_solidity;
+pub use machine::Machine;
+pub use solc::{ compile, find_solc };
@@ -0,0 +1,861 @@
+use std::collections::{ HashMap, HashSet };
+use pwasm_std::types::{ Address, U256 };
+use financial_smart_contract::storage::address_to_i64;
+use generator::GeneratedContract;
+use ir::{ Expr, Stmt, Program };
+use solidity::obs_name_text;
+
+// The state of a generated contract, restored when a call reverts
+#[derive(Clone)]
+struct MachineState {
+    // The contract holder
+    holder: Address,
+
+    // The counter-party
+    counter_party: Address,
+
+    // The balances of the holder and counter-party
+    holder_balance: i64,
+    counter_party_balance: i64,
+
+    // Whether or not Ether payments forward the gas stipend
+    use_gas: bool,
+
+    // The gas stipend forwarded with Ether payments if using gas
+    gas_stipend: u64,
+
+    // The last-updated time
+    last_updated: i64,
+
+    // The permissions each party has approved for each operator, by party and operator
+    operators: HashMap<(Address, Address), u64>,
+
+    // The signed observable reports submitted so far, with their signers
+    submitted_reports: HashSet<Vec<u8>>,
+
+    // The state variables of the program, scalars having a single element
+    variables: HashMap<String, Vec<i128>>,
+
+    // The Ether paid out so far, by recipient
+    payments: Vec<(Address, u64)>
+}
+
+// Runs a generated contract off-chain in place of the EVM. The program's combinator functions are interpreted, and the ABI
+// functions of the contract template are mirrored. A call which reverts returns the revert message and leaves the state
+// unchanged. Payments are recorded rather than made, so recipients can never re-enter the contract.
+pub struct Machine {
+    // The generated contract
+    contract: GeneratedContract,
+
+    // The contract state
+    state: MachineState,
+
+    // The sender, timestamp and value of the next call
+    sender: Address,
+    timestamp: u64,
+    value: U256,
+
+    // The recipients whose payments fail
+    failing_recipients: Vec<Address>,
+
+    // Recovers the signers of observable reports in place of ecrecover
+    signature_recoverer: SignatureRecoverer
+}
+
+// Recovers the signer of an observable report from the report and its signature
+pub type SignatureRecoverer = Box<Fn(&[u8], &[u8]) -> Address>;
+
+// The largest value staked or used as a gas stipend
+static MAX_I64: i64 = 2_i64.pow(62) + (2_i64.pow(62) - 1);
+
+// The operator permissions, combined as bit flags
+static OPERATOR_CHOICES: u64 = 1;
+static OPERATOR_ACQUIRE: u64 = 2;
+static OPERATOR_WITHDRAW: u64 = 4;
+static OPERATOR_DISPUTE: u64 = 8;
+
+// How long after its timestamp a signed observable report can be submitted, in seconds
+static OBS_REPORT_VALIDITY: u64 = 60 * 60;
+
+// Method implementation for the machine
+impl Machine {
+    // Instantiates the machine for the given contract, which must be constructed before it is called
+    pub fn new(contract: &GeneratedContract) -> Machine {
+        let mut variables: HashMap<String, Vec<i128>> = HashMap::new();
+        for variable in contract.program.state_variables.iter() {
+            variables.insert(variable.name.clone(), vec![0; variable.length.unwrap_or(1)]);
+        }
+
+        Machine {
+            contract: contract.clone(),
+            state: MachineState {
+                holder: Address::zero(),
+                counter_party: Address::zero(),
+                holder_balance: 0,
+                counter_party_balance: 0,
+                use_gas: false,
+                gas_stipend: 0,
+                last_updated: 0,
+                operators: HashMap::new(),
+                submitted_reports: HashSet::new(),
+                variables,
+                payments: Vec::new()
+            },
+            sender: Address::zero(),
+            timestamp: 0,
+            value: U256::zero(),
+            failing_recipients: Vec::new(),
+            signature_recoverer: Box::new(|_, _| Address::zero())
+        }
+    }
+
+    // Sets the sender of the following calls
+    pub fn set_sender(&mut self, sender: Address) {
+        self.sender = sender;
+    }
+
+    // Sets the block timestamp of the following calls
+    pub fn set_timestamp(&mut self, timestamp: u64) {
+        self.timestamp = timestamp;
+    }
+
+    // Sets the value sent with the following calls
+    pub fn set_value(&mut self, value: U256) {
+        self.value = value;
+    }
+
+    // Makes payments to the given recipient fail
+    pub fn fail_payments_to(&mut self, recipient: Address) {
+        self.failing_recipients.push(recipient);
+    }
+
+    // Sets how the signers of observable reports are recovered in place of ecrecover, from the report (the UTF-8 name, value
+    // and timestamp, packed as signed off-chain but without the contract address) and the 65-byte signature. Invalid
+    // signatures recover the zero address, as they do by default.
+    pub fn set_signature_recoverer(&mut self, recoverer: SignatureRecoverer) {
+        self.signature_recoverer = recoverer;
+    }
+
+    // Gets the payments made so far, as recipient and amount
+    pub fn get_payments(&self) -> &Vec<(Address, u64)> {
+        &self.state.payments
+    }
+
+    // The contract constructor, takes the contract definition (which must be the definition the contract was generated for),
+    // the holder address, whether or not Ether payments forward the gas stipend and the gas stipend
+    pub fn constructor(&mut self, contract_definition: Vec<i64>, holder: Address, use_gas: bool, gas_stipend: u64) -> Result<(), String> {
+        self.transact(|machine| {
+            if holder == machine.sender {
+                return Err("Holder and counter-party must be different addresses.".to_string());
+            }
+            if contract_definition.len() == 0 {
+                return Err("Provided combinator contract not valid.".to_string());
+            }
+            if contract_definition != machine.contract.definition {
+                return Err("Provided combinator contract does not match the generated contract.".to_string());
+            }
+            if gas_stipend > MAX_I64 as u64 {
+                return Err("Given value is too large to be converted to i64.".to_string());
+            }
+
+            machine.state.holder = holder;
+            machine.state.counter_party = machine.sender;
+            machine.state.use_gas = use_gas;
+            machine.state.gas_stipend = gas_stipend;
+            machine.state.last_updated = machine.timestamp as i64;
+
+            // No or-choices are set initially
+            for or_index in 0..machine.contract.or_count {
+                machine.set_element("orChoices", or_index, 2);
+            }
+            Ok(())
+        })
+    }
+
+    // Gets the address of the holder
+    pub fn get_holder(&self) -> Address {
+        self.state.holder
+    }
+
+    // Gets the address of the counter-party
+    pub fn get_counter_party(&self) -> Address {
+        self.state.counter_party
+    }
+
+    // Gets the combinator contract definition (serialized)
+    pub fn get_contract_definition(&self) -> Vec<i64> {
+        self.contract.definition.clone()
+    }
+
+    // Gets the balance of one of the two parties
+    pub fn get_balance(&self, holder_balance: bool) -> i64 {
+        if holder_balance {
+            self.state.holder_balance
+        } else {
+            self.state.counter_party_balance
+        }
+    }
+
+    // Gets the balance of the given asset of one of the two parties, only Ether (the zero address) is used
+    pub fn get_asset_balance(&self, holder_balance: bool, asset: Address) -> Result<i64, String> {
+        if asset != Address::zero() {
+            return Err("Asset is not used by the contract.".to_string());
+        }
+        Ok(self.get_balance(holder_balance))
+    }
+
+    // Gets the assets of the contract indexed by asset index, only Ether (the zero address)
+    pub fn get_assets(&self) -> Vec<Address> {
+        vec![Address::zero()]
+    }
+
+    // Gets whether or not the contract has concluded
+    pub fn get_concluded(&mut self) -> bool {
+        match self.call_function("isConcluded", Vec::new()) {
+            Ok(concluded) => concluded != 0,
+            Err(message) => panic!("{}", message)
+        }
+    }
+
+    // Gets whether or not the contract allocates gas fees upon withdrawal
+    pub fn get_use_gas(&self) -> bool {
+        self.state.use_gas
+    }
+
+    // Gets the gas stipend forwarded with Ether payments
+    pub fn get_gas_stipend(&self) -> u64 {
+        self.state.gas_stipend
+    }
+
+    // Gets the last-updated time
+    pub fn get_last_updated(&self) -> i64 {
+        self.state.last_updated
+    }
+
+    // Generated contracts cannot project balances
+    pub fn get_projected_balances(&self, _: u64) -> Result<Vec<i64>, String> {
+        Err("Not supported by generated contracts.".to_string())
+    }
+
+    // Gets the contract acquisition times (top level acquisition time and anytime acquisition times, -1 if not set)
+    pub fn get_acquisition_times(&self) -> Vec<i64> {
+        let mut acquisition_times = vec![if self.variable("acquired_0") != 0 { self.variable("acquisitionTime_0") as i64 } else { -1 }];
+        for anytime_index in 0..self.contract.anytime_count {
+            acquisition_times.push(if self.element("anytimeTimeSet", anytime_index) != 0 {
+                self.element("anytimeTimes", anytime_index) as i64
+            } else {
+                -1
+            });
+        }
+        acquisition_times
+    }
+
+    // Gets the or choices (1 for the first sub-combinator, 0 for the second and 2 if not set)
+    pub fn get_or_choices(&self) -> Vec<u8> {
+        (0..self.contract.or_count).map(|or_index| self.element("orChoices", or_index) as u8).collect()
+    }
+
+    // Gets the observable entries, as the arbiter address, -1 or 0 followed by the value, and the name of each observable
+    pub fn get_obs_entries(&self) -> Vec<i64> {
+        let mut entries: Vec<i64> = Vec::new();
+        for (obs_index, observable) in self.contract.observables.iter().enumerate() {
+            entries.extend_from_slice(&address_to_i64(observable.arbiter));
+            if self.element("obsSet", obs_index) != 0 {
+                entries.push(0);
+                entries.push(self.element("obsValues", obs_index) as i64);
+            } else {
+                entries.push(-1);
+            }
+            entries.push(observable.name.len() as i64);
+            entries.extend_from_slice(&observable.name);
+        }
+        entries
+    }
+
+    // Gets the values submitted for the given observable, as its threshold and number of arbiters (both 1), followed by its
+    // arbiter's address and -1, or 0 followed by its value
+    pub fn get_obs_submissions(&self, obs_index: u64) -> Result<Vec<i64>, String> {
+        if obs_index >= self.contract.observables.len() as u64 {
+            return Err("Stored vector index out of bounds.".to_string());
+        }
+
+        let index = obs_index as usize;
+        let mut submissions = vec![1, 1];
+        submissions.extend_from_slice(&address_to_i64(self.contract.observables[index].arbiter));
+        if self.element("obsSet", index) != 0 {
+            submissions.push(0);
+            submissions.push(self.element("obsValues", index) as i64);
+        } else {
+            submissions.push(-1);
+        }
+        Ok(submissions)
+    }
+
+    // Gets the dispute window of the given observable, empty as generated contracts have no disputable observables
+    pub fn get_obs_dispute(&self, obs_index: u64) -> Result<Vec<i64>, String> {
+        if obs_index >= self.contract.observables.len() as u64 {
+            return Err("Stored vector index out of bounds.".to_string());
+        }
+        Ok(Vec::new())
+    }
+
+    // Generated contracts do not serialize the state of their combinators
+    pub fn get_state_tree(&self) -> Result<Vec<i64>, String> {
+        Err("Not supported by generated contracts.".to_string())
+    }
+
+    // Gets the permissions the given party has approved for the given operator (0 if none)
+    pub fn get_operator_permissions(&self, party: Address, operator: Address) -> u64 {
+        *self.state.operators.get(&(party, operator)).unwrap_or(&0)
+    }
+
+    // Sets the preference of the given or combinator's sub-combinators
+    pub fn set_or_choice(&mut self, or_index: u64, choice: bool) -> Result<(), String> {
+        self.transact(|machine| {
+            machine.assert_sender_is_holder(OPERATOR_CHOICES, "Only the contract holder may set or-choices.")?;
+            machine.write_or_choice(or_index, choice)
+        })
+    }
+
+    // Sets a value for the given observable
+    pub fn set_obs_value(&mut self, obs_index: u64, value: i64) -> Result<(), String> {
+        self.transact(|machine| {
+            let sender = machine.sender;
+            machine.write_obs_value(obs_index, value, sender)
+        })
+    }
+
+    // Sets the preferences of the given or combinators' sub-combinators, then updates the contract if update_contract is set
+    pub fn set_or_choices(&mut self, or_indices: Vec<u64>, choices: Vec<bool>, update_contract: bool) -> Result<(), String> {
+        self.transact(|machine| {
+            if or_indices.len() != choices.len() {
+                return Err("Index and value arrays must have the same length.".to_string());
+            }
+            machine.assert_sender_is_holder(OPERATOR_CHOICES, "Only the contract holder may set or-choices.")?;
+
+            for (or_index, choice) in or_indices.into_iter().zip(choices) {
+                machine.write_or_choice(or_index, choice)?;
+            }
+
+            if update_contract {
+                machine.update_contract()?;
+            }
+            Ok(())
+        })
+    }
+
+    // Sets values for the given observables, then updates the contract if update_contract is set
+    pub fn set_obs_values(&mut self, obs_indices: Vec<u64>, values: Vec<i64>, update_contract: bool) -> Result<(), String> {
+        self.transact(|machine| {
+            if obs_indices.len() != values.len() {
+                return Err("Index and value arrays must have the same length.".to_string());
+            }
+
+            let sender = machine.sender;
+            for (obs_index, value) in obs_indices.into_iter().zip(values) {
+                machine.write_obs_value(obs_index, value, sender)?;
+            }
+
+            if update_contract {
+                machine.update_contract()?;
+            }
+            Ok(())
+        })
+    }
+
+    // Sets a value for the given observable from a report signed by its arbiter, which anyone can submit within an hour of the
+    // report's timestamp
+    pub fn submit_obs_report(&mut self, obs_index: u64, value: i64, timestamp: u64, signature: Vec<u8>) -> Result<(), String> {
+        self.transact(|machine| {
+            if obs_index >= machine.contract.observables.len() as u64 {
+                return Err("Stored vector index out of bounds.".to_string());
+            }
+            if timestamp > machine.timestamp {
+                return Err("Observable report timestamp is in the future.".to_string());
+            }
+            if machine.timestamp - timestamp > OBS_REPORT_VALIDITY {
+                return Err("Observable report has expired.".to_string());
+            }
+
+            let mut report = obs_name_text(&machine.contract.observables[obs_index as usize].name)?.into_bytes();
+            for number in [obs_index, value as u64, timestamp].iter() {
+                report.extend((0..8).map(|i| (number >> (56 - 8 * i)) as u8));
+            }
+            if signature.len() != 65 {
+                return Err("Observable report signature must be 65 bytes.".to_string());
+            }
+
+            let signer = (machine.signature_recoverer)(&report, &signature);
+            if signer == Address::zero() {
+                return Err("Observable report signature is not valid.".to_string());
+            }
+
+            // Reports are recorded by signer, as the arbiters of a quorum observable may sign the same report
+            let mut signed_report = report;
+            signed_report.extend_from_slice(&signer.0);
+            if machine.state.submitted_reports.contains(&signed_report) {
+                return Err("Observable report has already been submitted.".to_string());
+            }
+
+            machine.state.submitted_reports.insert(signed_report);
+            machine.write_obs_value(obs_index, value, signer)
+        })
+    }
+
+    // Flags the value of the given disputable observable, which generated contracts do not have
+    pub fn flag_obs_value(&mut self, obs_index: u64) -> Result<(), String> {
+        if obs_index >= self.contract.observables.len() as u64 {
+            return Err("Stored vector index out of bounds.".to_string());
+        }
+        Err("Observable is not disputable.".to_string())
+    }
+
+    // Confirms or overrides the flagged value of the given disputable observable, which generated contracts do not have
+    pub fn resolve_obs_value(&mut self, obs_index: u64, _value: i64) -> Result<(), String> {
+        if obs_index >= self.contract.observables.len() as u64 {
+            return Err("Stored vector index out of bounds.".to_string());
+        }
+        Err("Observable is not disputable.".to_string())
+    }
+
+    // Acquires the combinator contract at the current block-time
+    pub fn acquire(&mut self) -> Result<(), String> {
+        self.transact(|machine| {
+            machine.assert_sender_is_holder(OPERATOR_ACQUIRE, "Only the contract holder may acquire the combinator contract.")?;
+            if machine.variable("acquired_0") != 0 {
+                return Err("The combinator contract cannot be acquired more than once.".to_string());
+            }
+
+            // Times relative to the acquisition time become known now
+            let time = machine.timestamp as i128;
+            machine.state.variables.get_mut("baseTime").unwrap()[0] = time;
+            machine.call_function("acquire_0", vec![time])?;
+
+            machine.update_contract()
+        })
+    }
+
+    // Updates the balances of the holder and counter-party
+    pub fn update(&mut self) -> Result<(), String> {
+        self.transact(|machine| machine.update_contract())
+    }
+
+    // Acquires an anytime combinator's sub-contract
+    pub fn acquire_anytime_sub_contract(&mut self, anytime_index: u64) -> Result<(), String> {
+        self.transact(|machine| {
+            if anytime_index >= machine.contract.anytime_count as u64 {
+                return Err("Stored vector index out of bounds.".to_string());
+            }
+            let index = anytime_index as usize;
+            if machine.element("anytimeAcquired", index) == 0 {
+                return Err("Given anytime combinator has not been acquired.".to_string());
+            }
+            machine.assert_sender_is_holder(OPERATOR_ACQUIRE, "Only the contract holder may acquire the combinator contract.")?;
+
+            let time = machine.timestamp as i128;
+            if machine.element("anytimeTimeSet", index) != 0 && machine.element("anytimeTimes", index) <= time {
+                return Err("Cannot acquire a sub-combinator contract which has already been acquired.".to_string());
+            }
+
+            machine.set_element("anytimeTimeSet", index, 1);
+            machine.set_element("anytimeTimes", index, time);
+
+            machine.update_contract()
+        })
+    }
+
+    // Stakes the value sent with the contract, returns the caller's total balance
+    pub fn stake(&mut self) -> Result<i64, String> {
+        self.transact(|machine| {
+            if machine.value > U256::from(MAX_I64) {
+                return Err("Given value is too large to be converted to i64.".to_string());
+            }
+
+            let stake = machine.value.low_u64() as i64;
+            if machine.sender == machine.state.holder {
+                machine.state.holder_balance = Machine::safe_add(machine.state.holder_balance, stake)?;
+                Ok(machine.state.holder_balance)
+            } else if machine.sender == machine.state.counter_party {
+                machine.state.counter_party_balance = Machine::safe_add(machine.state.counter_party_balance, stake)?;
+                Ok(machine.state.counter_party_balance)
+            } else {
+                Err("Only the contract holder or the counter-party may stake Ether in the contract.".to_string())
+            }
+        })
+    }
+
+    // Withdraws positive Ether balance up to the given amount from the contract
+    pub fn withdraw(&mut self, amount: u64) -> Result<(), String> {
+        let party = self.principal(OPERATOR_WITHDRAW);
+        self.transact(|machine| machine.withdraw_ether(party, party, amount))
+    }
+
+    // Withdraws positive balance of the given asset up to the given amount, paying it to the given recipient
+    pub fn withdraw_to(&mut self, asset: Address, recipient: Address, amount: u64) -> Result<(), String> {
+        self.transact(|machine| {
+            let party = machine.principal(OPERATOR_WITHDRAW);
+            if party != machine.sender && party != Address::zero() && recipient != party {
+                return Err("Operators may only withdraw to the party they act for.".to_string());
+            }
+            if asset != Address::zero() {
+                return Err("Asset is not used by the contract.".to_string());
+            }
+            machine.withdraw_ether(party, recipient, amount)
+        })
+    }
+
+    // Only Ether is used by generated contracts, which is staked by sending value to stake
+    pub fn stake_asset(&mut self, asset: Address, amount: u64) -> Result<i64, String> {
+        if amount > MAX_I64 as u64 {
+            Err("Given value is too large to be converted to i64.".to_string())
+        } else if asset != Address::zero() {
+            Err("Asset is not used by the contract.".to_string())
+        } else {
+            Err("Ether must be staked by sending value to stake.".to_string())
+        }
+    }
+
+    // Withdraws positive balance of the given asset up to the given amount from the contract
+    pub fn withdraw_asset(&mut self, asset: Address, amount: u64) -> Result<(), String> {
+        let party = self.principal(OPERATOR_WITHDRAW);
+        self.withdraw_to(asset, party, amount)
+    }
+
+    // Approves the given operator to act for the caller (holder or counter-party) with the given permissions
+    pub fn approve_operator(&mut self, operator: Address, permissions: u64) -> Result<(), String> {
+        self.transact(|machine| {
+            let (party, holder, counter_party) = (machine.sender, machine.state.holder, machine.state.counter_party);
+            if party != holder && party != counter_party {
+                return Err("Only the contract holder or the counter-party may approve operators.".to_string());
+            }
+            if operator == holder || operator == counter_party {
+                return Err("The contract holder and counter-party cannot be operators.".to_string());
+            }
+            if permissions == 0 || permissions & !(OPERATOR_CHOICES | OPERATOR_ACQUIRE | OPERATOR_WITHDRAW | OPERATOR_DISPUTE) != 0 {
+                return Err("Operator permissions must be a non-empty combination of choices, acquire, withdraw and dispute.".to_string());
+            }
+            let other_party = if party == holder { counter_party } else { holder };
+            if machine.get_operator_permissions(other_party, operator) != 0 {
+                return Err("Operator already acts for the other party.".to_string());
+            }
+
+            machine.state.operators.insert((party, operator), permissions);
+            Ok(())
+        })
+    }
+
+    // Revokes the given operator's approval to act for the caller
+    pub fn revoke_operator(&mut self, operator: Address) -> Result<(), String> {
+        self.transact(|machine| {
+            let party = machine.sender;
+            if machine.get_operator_permissions(party, operator) == 0 {
+                return Err("Operator has not been approved by the sender.".to_string());
+            }
+
+            machine.state.operators.insert((party, operator), 0);
+            Ok(())
+        })
+    }
+
+    // Runs a call, restoring the state if it reverts
+    fn transact<T, F: FnOnce(&mut Machine) -> Result<T, String>>(&mut self, call: F) -> Result<T, String> {
+        let state = self.state.clone();
+        let result = call(self);
+        if result.is_err() {
+            self.state = state;
+        }
+        result
+    }
+
+    // Updates the contract, adjusting the balances by the amount paid to the holder
+    fn update_contract(&mut self) -> Result<(), String> {
+        if self.get_concluded() {
+            return Err("Contract has concluded, nothing more to update.".to_string());
+        }
+
+        self.state.last_updated = self.timestamp as i64;
+        let time = self.timestamp as i128;
+        let difference = self.call_function("update_0", vec![time])? as i64;
+        if difference != 0 {
+            self.state.holder_balance = Machine::safe_add(self.state.holder_balance, difference)?;
+            self.state.counter_party_balance = Machine::safe_add(self.state.counter_party_balance, difference.wrapping_neg())?;
+        }
+        Ok(())
+    }
+
+    // Checks that the sender is the holder, or an operator approved by the holder with the given permission
+    fn assert_sender_is_holder(&self, permission: u64, message: &str) -> Result<(), String> {
+        if self.principal(permission) != self.state.holder {
+            return Err(message.to_string());
+        }
+        Ok(())
+    }
+
+    // Returns the party the sender acts for with the given operator permission (the zero address if none)
+    fn principal(&self, permission: u64) -> Address {
+        let (holder, counter_party) = (self.state.holder, self.state.counter_party);
+        if self.sender == holder || self.sender == counter_party {
+            self.sender
+        } else if self.get_operator_permissions(holder, self.sender) & permission != 0 {
+            holder
+        } else if self.get_operator_permissions(counter_party, self.sender) & permission != 0 {
+            counter_party
+        } else {
+            Address::zero()
+        }
+    }
+
+    // Sets the given or combinator's preference if not yet set (the sender must already be checked)
+    fn write_or_choice(&mut self, or_index: u64, choice: bool) -> Result<(), String> {
+        if or_index >= self.contract.or_count as u64 {
+            return Err("Stored vector index out of bounds.".to_string());
+        }
+        if self.element("orChoices", or_index as usize) != 2 {
+            return Err("Or-choice has already been set.".to_string());
+        }
+
+        self.set_element("orChoices", or_index as usize, if choice { 1 } else { 0 });
+        Ok(())
+    }
+
+    // Sets the given observable's value if not yet set and the setter is its arbiter
+    fn write_obs_value(&mut self, obs_index: u64, value: i64, setter: Address) -> Result<(), String> {
+        if obs_index >= self.contract.observables.len() as u64 {
+            return Err("Stored vector index out of bounds.".to_string());
+        }
+        let index = obs_index as usize;
+        if self.element("obsSet", index) != 0 {
+            return Err("Observable has already been set.".to_string());
+        }
+        if setter != self.contract.observables[index].arbiter {
+            return Err("Sender cannot set value for given observable-index.".to_string());
+        }
+
+        self.set_element("obsSet", index, 1);
+        self.set_element("obsValues", index, value as i128);
+        Ok(())
+    }
+
+    // Withdraws positive Ether balance of the given party up to the given amount, paying it to the recipient
+    fn withdraw_ether(&mut self, party: Address, recipient: Address, amount: u64) -> Result<(), String> {
+        let holder = party == self.state.holder;
+        if !holder && party != self.state.counter_party {
+            return Err("Only the contract holder or the counter-party may withdraw Ether from the contract.".to_string());
+        }
+
+        // Get the amount to send (clamp at balance amount)
+        let original_balance = if holder { self.state.holder_balance } else { self.state.counter_party_balance };
+        let funds = self.state.holder_balance.wrapping_add(self.state.counter_party_balance);
+        let withdraw_amount = Machine::get_withdrawal_amount(amount, original_balance, funds);
+        if withdraw_amount <= 0 {
+            return Err("Not enough funds to withdraw.".to_string());
+        }
+        if holder {
+            self.state.holder_balance = original_balance.wrapping_sub(withdraw_amount);
+        } else {
+            self.state.counter_party_balance = original_balance.wrapping_sub(withdraw_amount);
+        }
+
+        if self.failing_recipients.contains(&recipient) {
+            return Err("Payment failed".to_string());
+        }
+        self.state.payments.push((recipient, withdraw_amount as u64));
+        Ok(())
+    }
+
+    // Returns the amount to withdraw, clamped at the balance and funds
+    fn get_withdrawal_amount(amount: u64, balance: i64, funds: i64) -> i64 {
+        let mut final_amount = amount as i64;
+        if balance < final_amount {
+            final_amount = balance;
+        }
+        if funds < final_amount {
+            final_amount = funds;
+        }
+        final_amount
+    }
+
+    // Add two signed integers if no overflow or underflow can occur
+    fn safe_add(x: i64, y: i64) -> Result<i64, String> {
+        if y > 0 && x > MAX_I64 - y {
+            Err("Integer overflow.".to_string())
+        } else if y < 0 && x < -MAX_I64 - 1 - y {
+            Err("Integer underflow.".to_string())
+        } else {
+            Ok(x + y)
+        }
+    }
+
+    // Gets a scalar state variable of the program
+    fn variable(&self, name: &str) -> i128 {
+        self.element(name, 0)
+    }
+
+    // Gets an element of a state array of the program
+    fn element(&self, name: &str, index: usize) -> i128 {
+        self.state.variables[name][index]
+    }
+
+    // Sets an element of a state array of the program
+    fn set_element(&mut self, name: &str, index: usize, value: i128) {
+        self.state.variables.get_mut(name).unwrap()[index] = value;
+    }
+
+    // Calls a function of the program at the current block-time
+    fn call_function(&mut self, name: &str, arguments: Vec<i128>) -> Result<i128, String> {
+        let mut interpreter = Interpreter {
+            program: &self.contract.program,
+            variables: &mut self.state.variables,
+            now: self.timestamp as i128
+        };
+        interpreter.call(name, arguments)
+    }
+}
+
+// How execution continues after a statement
+enum Flow {
+    Next,
+    Break,
+    Return(i128)
+}
+
+// Interprets the functions of a program over its state variables. All values are held as i128, booleans as 1 or 0, and
+// arithmetic wraps to the width of its type as the EVM does.
+struct Interpreter<'a> {
+    // The program
+    program: &'a Program,
+
+    // The state variables of the program
+    variables: &'a mut HashMap<String, Vec<i128>>,
+
+    // The block timestamp
+    now: i128
+}
+
+// Method implementation for the interpreter
+impl<'a> Interpreter<'a> {
+    // Calls the given function, returns its result (0 if it returns nothing) or the revert message
+    fn call(&mut self, name: &str, arguments: Vec<i128>) -> Result<i128, String> {
+        let program = self.program;
+        let function = program.get_function(name);
+        let mut locals: HashMap<String, i128> = function.parameters.iter().map(|p| p.1.clone()).zip(arguments).collect();
+        match self.execute(&function.body, &mut locals)? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(0)
+        }
+    }
+
+    // Executes the given statements
+    fn execute(&mut self, statements: &Vec<Stmt>, locals: &mut HashMap<String, i128>) -> Result<Flow, String> {
+        for statement in statements {
+            match *statement {
+                Stmt::Let(_, ref name, ref value) => {
+                    let value = self.evaluate(value, locals)?;
+                    locals.insert(name.clone(), value);
+                },
+                Stmt::Assign(ref target, ref value) => {
+                    let value = self.evaluate(value, locals)?;
+                    match *target {
+                        Expr::Var(ref name) if locals.contains_key(name) => { locals.insert(name.clone(), value); },
+                        Expr::Var(ref name) => self.variables.get_mut(name).unwrap()[0] = value,
+                        Expr::Element(ref name, ref index) => {
+                            let index = self.evaluate(index, locals)? as usize;
+                            self.variables.get_mut(name).unwrap()[index] = value;
+                        },
+                        _ => panic!("Only variables and array elements can be assigned.")
+                    }
+                },
+                Stmt::If(ref condition, ref statements, ref else_statements) => {
+                    let flow = if self.evaluate(condition, locals)? != 0 {
+                        self.execute(statements, locals)?
+                    } else {
+                        self.execute(else_statements, locals)?
+                    };
+                    match flow {
+                        Flow::Next => (),
+                        flow => return Ok(flow)
+                    }
+                },
+                Stmt::While(ref condition, ref statements) => {
+                    while self.evaluate(condition, locals)? != 0 {
+                        match self.execute(statements, locals)? {
+                            Flow::Next => (),
+                            Flow::Break => break,
+                            flow => return Ok(flow)
+                        }
+                    }
+                },
+                Stmt::Break => return Ok(Flow::Break),
+                Stmt::Require(ref condition, ref message) => {
+                    if self.evaluate(condition, locals)? == 0 {
+                        return Err(message.clone());
+                    }
+                },
+                Stmt::Return(None) => return Ok(Flow::Return(0)),
+                Stmt::Return(Some(ref value)) => return Ok(Flow::Return(self.evaluate(value, locals)?)),
+                Stmt::Call(ref name, ref arguments) => {
+                    let arguments = self.evaluate_all(arguments, locals)?;
+                    self.call(name, arguments)?;
+                }
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    // Evaluates the given expressions in order
+    fn evaluate_all(&mut self, exprs: &Vec<Expr>, locals: &HashMap<String, i128>) -> Result<Vec<i128>, String> {
+        let mut values: Vec<i128> = Vec::new();
+        for expr in exprs {
+            values.push(self.evaluate(expr, locals)?);
+        }
+        Ok(values)
+    }
+
+    // Evaluates the given expression
+    fn evaluate(&mut self, expr: &Expr, locals: &HashMap<String, i128>) -> Result<i128, String> {
+        Ok(match *expr {
+            Expr::Bool(value) => value as i128,
+            Expr::Uint(value) => value as i128,
+            Expr::Int(value) => value as i128,
+            Expr::Var(ref name) => match locals.get(name) {
+                Some(value) => *value,
+                None => self.variables[name][0]
+            },
+            Expr::Element(ref name, ref index) => {
+                let index = self.evaluate(index, locals)? as usize;
+                self.variables[name][index]
+            },
+            Expr::Now => self.now,
+            Expr::Not(ref e) => (self.evaluate(e, locals)? == 0) as i128,
+            Expr::And(ref a, ref b) => (self.evaluate(a, locals)? != 0 && self.evaluate(b, locals)? != 0) as i128,
+            Expr::Or(ref a, ref b) => (self.evaluate(a, locals)? != 0 || self.evaluate(b, locals)? != 0) as i128,
+            Expr::Eq(ref a, ref b) => (self.evaluate(a, locals)? == self.evaluate(b, locals)?) as i128,
+            Expr::Lt(ref a, ref b) => (self.evaluate(a, locals)? < self.evaluate(b, locals)?) as i128,
+            Expr::Add(ref a, ref b) => (self.evaluate(a, locals)? + self.evaluate(b, locals)?) as i64 as i128,
+            Expr::Mul(ref a, ref b) => (self.evaluate(a, locals)? * self.evaluate(b, locals)?) as i64 as i128,
+            Expr::Neg(ref e) => (-self.evaluate(e, locals)?) as i64 as i128,
+            Expr::Sub(ref a, ref b) => (self.evaluate(a, locals)? - self.evaluate(b, locals)?) as u64 as i128,
+            Expr::Div(ref a, ref b) => {
+                let (a, b) = (self.evaluate(a, locals)?, self.evaluate(b, locals)?);
+                if b == 0 {
+                    return Err("Division by zero.".to_string());
+                }
+                a / b
+            },
+            Expr::SaturatingAdd(ref a, ref b) => (self.evaluate(a, locals)? + self.evaluate(b, locals)?).min(u64::max_value() as i128),
+            Expr::SaturatingMul(ref a, ref b) => {
+                let (a, b) = (self.evaluate(a, locals)?, self.evaluate(b, locals)?);
+                a.checked_mul(b).unwrap_or(i128::max_value()).min(u64::max_value() as i128)
+            },
+            Expr::Cond(ref c, ref a, ref b) => if self.evaluate(c, locals)? != 0 {
+                self.evaluate(a, locals)?
+            } else {
+                self.evaluate(b, locals)?
+            },
+            Expr::Call(ref name, ref arguments) => {
+                let arguments = self.evaluate_all(arguments, locals)?;
+                self.call(name, arguments)?
+            }
+        })
+    }
+}
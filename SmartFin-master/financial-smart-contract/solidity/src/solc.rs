@@ -0,0 +1,80 @@
+use generator::GeneratedContract;
+use solidity::to_solidity;
+use std::env;
+use std::fs;
+use std::process::{ self, Command };
+use std::sync::atomic::{ AtomicUsize, Ordering };
+
+// The EVM version the generated contracts are compiled for, the latest supported by the development blockchain
+static EVM_VERSION: &str = "byzantium";
+
+// The number of contracts compiled, so each is written to its own source file
+static COMPILED: AtomicUsize = AtomicUsize::new(0);
+
+// Finds the solc 0.5 compiler, given by the SOLC environment variable or installed as solc, or None if neither is available
+pub fn find_solc() -> Option<String> {
+    if let Ok(solc) = env::var("SOLC") {
+        return Some(solc);
+    }
+    match Command::new("solc").arg("--version").output() {
+        Ok(ref output) if output.status.success() => Some("solc".to_string()),
+        _ => None
+    }
+}
+
+// Compiles the generated contract with the given solc compiler, returning its deployment bytecode
+pub fn compile(solc: &str, contract: &GeneratedContract) -> Result<Vec<u8>, String> {
+    let directory = env::temp_dir().join("financial-smart-contract-solidity");
+    fs::create_dir_all(&directory).map_err(|error| error.to_string())?;
+    let number = COMPILED.fetch_add(1, Ordering::SeqCst);
+    let path = directory.join(format!("{}-{}-{}.sol", contract.name, process::id(), number));
+    fs::write(&path, to_solidity(contract)).map_err(|error| error.to_string())?;
+
+    let output = Command::new(solc)
+        .arg("--bin")
+        .arg("--evm-version")
+        .arg(EVM_VERSION)
+        .arg(&path)
+        .output()
+        .map_err(|error| format!("Could not run {}: {}", solc, error))?;
+    if !output.status.success() {
+        return Err(format!("solc rejected {}: {}", path.display(), String::from_utf8_lossy(&output.stderr)));
+    }
+
+    // The bytecode follows the "Binary:" line of the contract
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines().skip_while(|line| line.trim() != "Binary:").skip(1);
+    match lines.next() {
+        Some(line) => from_hex(line.trim()),
+        None => Err(format!("solc gave no bytecode for {}.", path.display()))
+    }
+}
+
+// Decodes a hexadecimal string
+fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    let invalid = || format!("Invalid bytecode: {}", text);
+    if text.is_empty() {
+        return Err(invalid());
+    }
+    text.as_bytes().chunks(2)
+        .map(|pair| match pair.len() {
+            2 => u8::from_str_radix(&String::from_utf8_lossy(pair), 16).map_err(|_| invalid()),
+            _ => Err(invalid())
+        })
+        .collect()
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Bytecode is decoded from hexadecimal
+    #[test]
+    fn bytecode_decoded() {
+        assert_eq!(from_hex("6080ff00"), Ok(vec![0x60, 0x80, 0xff, 0x00]));
+        assert!(from_hex("608").is_err());
+        assert!(from_hex("60zz").is_err());
+        assert!(from_hex("").is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use financial_smart_contract::storage::address_to_i64;
+use generator::GeneratedContract;
+use ir::{ Type, Expr, Stmt, Function, StateVariable };
+
+// The contract template, holding the ABI functions shared by all generated contracts
+static TEMPLATE: &str = include_str!("template.sol");
+
+// Renders the generated contract as Solidity source
+pub fn to_solidity(contract: &GeneratedContract) -> String {
+    let definition: Vec<String> = contract.definition.iter().map(|e| e.to_string()).collect();
+    let state_variables: Vec<String> = contract.program.state_variables.iter().map(render_state_variable).collect();
+    let functions: Vec<String> = contract.program.functions.iter().map(render_function).collect();
+
+    // The observables' arbiters and names are set by the constructor, their entries serialized as the financial smart contract
+    // does
+    let mut obs_arbiters = String::new();
+    let mut obs_entries = String::new();
+    let mut obs_entries_length = 0;
+    let mut obs_submissions = String::new();
+    for (obs_index, observable) in contract.observables.iter().enumerate() {
+        let arbiter: String = observable.arbiter.0.iter().map(|byte| format!("{:02x}", byte)).collect();
+        obs_arbiters.push_str(&format!("        obsArbiters[{}] = address(bytes20(hex\"{}\"));\n", obs_index, arbiter));
+        let name: String = obs_name_text(&observable.name).unwrap_or_else(|message| panic!("{}", message)).bytes().map(|byte| format!("{:02x}", byte)).collect();
+        obs_arbiters.push_str(&format!("        obsNames[{}] = hex\"{}\";\n", obs_index, name));
+
+        obs_submissions.push_str(&format!("        if (obs_index == {}) {{\n", obs_index));
+        for (i, part) in address_to_i64(observable.arbiter).iter().enumerate() {
+            obs_submissions.push_str(&format!("            submissions[{}] = {};\n", 2 + i, part));
+        }
+        obs_submissions.push_str("        }\n");
+
+        let mut entries: Vec<String> = address_to_i64(observable.arbiter).iter().map(|e| e.to_string()).collect();
+        entries.push(observable.name.len().to_string());
+        entries.extend(observable.name.iter().map(|e| e.to_string()));
+        obs_entries_length += entries.len();
+
+        let value_index = 4;
+        for (i, entry) in entries.iter().enumerate() {
+            if i == value_index {
+                obs_entries.push_str(&format!("        if (obsSet[{0}]) {{\n            entries[j++] = 0;\n            entries[j++] = obsValues[{0}];\n", obs_index));
+                obs_entries.push_str("        } else {\n            entries[j++] = -1;\n        }\n");
+            }
+            obs_entries.push_str(&format!("        entries[j++] = {};\n", entry));
+        }
+    }
+
+    TEMPLATE
+        .replace("{{NAME}}", &contract.name)
+        .replace("{{DEFINITION}}", &format!("[{}]", definition.join(", ")))
+        .replace("{{DEFINITION_ARRAY}}", &format!("[int64({})]", definition.join("), int64(")))
+        .replace("{{DEFINITION_LENGTH}}", &definition.len().to_string())
+        .replace("{{OR_COUNT}}", &contract.or_count.to_string())
+        .replace("{{OBS_COUNT}}", &contract.observables.len().to_string())
+        .replace("{{OBS_LENGTH}}", &contract.observables.len().max(1).to_string())
+        .replace("{{ANYTIME_COUNT}}", &contract.anytime_count.to_string())
+        .replace("{{STATE_VARIABLES}}", &state_variables.join("\n"))
+        .replace("{{OBS_ARBITERS}}", obs_arbiters.trim_end_matches('\n'))
+        .replace("{{OBS_ENTRIES_LENGTH}}", &obs_entries_length.to_string())
+        .replace("{{OBS_ENTRIES}}", obs_entries.trim_end_matches('\n'))
+        .replace("{{OBS_SUBMISSIONS}}", obs_submissions.trim_end_matches('\n'))
+        .replace("{{FUNCTIONS}}", functions.join("\n").trim_end_matches('\n'))
+}
+
+// Decodes an observable name from its character codes, as signed in observable reports
+pub fn obs_name_text(name: &Vec<i64>) -> Result<String, String> {
+    name.iter().map(|code| {
+        match ::std::char::from_u32(*code as u32) {
+            Some(character) if *code >= 0 => Ok(character),
+            _ => Err("Observable name is not valid text.".to_string())
+        }
+    }).collect()
+}
+
+// Renders a type
+fn render_type(ty: Type) -> &'static str {
+    match ty {
+        Type::Bool => "bool",
+        Type::Uint8 => "uint8",
+        Type::Uint64 => "uint64",
+        Type::Int64 => "int64"
+    }
+}
+
+// Renders a state variable declaration
+fn render_state_variable(variable: &StateVariable) -> String {
+    match variable.length {
+        Some(length) => format!("    {}[{}] {};", render_type(variable.ty), length, variable.name),
+        None => format!("    {} {};", render_type(variable.ty), variable.name)
+    }
+}
+
+// Renders a private function
+fn render_function(function: &Function) -> String {
+    let parameters: Vec<String> = function.parameters.iter().map(|&(ty, ref name)| format!("{} {}", render_type(ty), name)).collect();
+    let mut rendered = format!("    // {}\n    function {}({}) private", function.comment, function.name, parameters.join(", "));
+    if function.view {
+        rendered.push_str(" view");
+    }
+    if let Some(ty) = function.returns {
+        rendered.push_str(&format!(" returns ({})", render_type(ty)));
+    }
+    rendered.push_str(" {\n");
+    render_statements(&function.body, 2, &mut rendered);
+    rendered.push_str("    }\n");
+    rendered
+}
+
+// Renders statements at the given indentation level
+fn render_statements(statements: &Vec<Stmt>, level: usize, rendered: &mut String) {
+    let indent = "    ".repeat(level);
+    for statement in statements {
+        match *statement {
+            Stmt::Let(ty, ref name, ref value) => rendered.push_str(&format!("{}{} {} = {};\n", indent, render_type(ty), name, render_expr(value))),
+            Stmt::Assign(ref target, ref value) => rendered.push_str(&format!("{}{} = {};\n", indent, render_expr(target), render_expr(value))),
+            Stmt::If(ref condition, ref statements, ref else_statements) => {
+                rendered.push_str(&indent);
+                render_if(condition, statements, else_statements, level, rendered);
+            },
+            Stmt::While(ref condition, ref statements) => {
+                rendered.push_str(&format!("{}while ({}) {{\n", indent, render_expr(condition)));
+                render_statements(statements, level + 1, rendered);
+                rendered.push_str(&format!("{}}}\n", indent));
+            },
+            Stmt::Break => rendered.push_str(&format!("{}break;\n", indent)),
+            Stmt::Require(ref condition, ref message) => rendered.push_str(&format!(
+                "{0}require(\n{0}    {1},\n{0}    \"{2}\"\n{0});\n", indent, render_expr(condition), message
+            )),
+            Stmt::Return(None) => rendered.push_str(&format!("{}return;\n", indent)),
+            Stmt::Return(Some(ref value)) => rendered.push_str(&format!("{}return {};\n", indent, render_expr(value))),
+            Stmt::Call(ref name, ref arguments) => rendered.push_str(&format!("{}{}({});\n", indent, name, render_arguments(arguments)))
+        }
+    }
+}
+
+// Renders a conditional (after its indentation), chaining conditionals in the else branch
+fn render_if(condition: &Expr, statements: &Vec<Stmt>, else_statements: &Vec<Stmt>, level: usize, rendered: &mut String) {
+    let indent = "    ".repeat(level);
+    rendered.push_str(&format!("if ({}) {{\n", render_expr(condition)));
+    render_statements(statements, level + 1, rendered);
+    if else_statements.len() == 1 {
+        if let Stmt::If(ref condition, ref statements, ref else_statements) = else_statements[0] {
+            rendered.push_str(&format!("{}}} else ", indent));
+            render_if(condition, statements, else_statements, level, rendered);
+            return;
+        }
+    }
+    if !else_statements.is_empty() {
+        rendered.push_str(&format!("{}}} else {{\n", indent));
+        render_statements(else_statements, level + 1, rendered);
+    }
+    rendered.push_str(&format!("{}}}\n", indent));
+}
+
+// Renders an expression
+fn render_expr(expr: &Expr) -> String {
+    match *expr {
+        Expr::Bool(value) => value.to_string(),
+        Expr::Uint(value) => value.to_string(),
+        Expr::Int(value) => value.to_string(),
+        Expr::Var(ref name) => name.clone(),
+        Expr::Element(ref name, ref index) => format!("{}[{}]", name, render_expr(index)),
+        Expr::Now => "uint64(now)".to_string(),
+        Expr::Not(ref e) => format!("!{}", render_operand(e)),
+        Expr::And(ref a, ref b) => match **a {
+            Expr::And(_, _) => format!("{} && {}", render_expr(a), render_operand(b)),
+            _ => format!("{} && {}", render_operand(a), render_operand(b))
+        },
+        Expr::Or(ref a, ref b) => match **a {
+            Expr::Or(_, _) => format!("{} || {}", render_expr(a), render_operand(b)),
+            _ => format!("{} || {}", render_operand(a), render_operand(b))
+        },
+        Expr::Eq(ref a, ref b) => format!("{} == {}", render_operand(a), render_operand(b)),
+        Expr::Lt(ref a, ref b) => format!("{} < {}", render_operand(a), render_operand(b)),
+        Expr::Add(ref a, ref b) => format!("{} + {}", render_operand(a), render_operand(b)),
+        Expr::Mul(ref a, ref b) => format!("{} * {}", render_operand(a), render_operand(b)),
+        Expr::Neg(ref e) => format!("-{}", render_operand(e)),
+        Expr::Sub(ref a, ref b) => format!("{} - {}", render_operand(a), render_operand(b)),
+        Expr::Div(ref a, ref b) => format!("{} / {}", render_operand(a), render_operand(b)),
+        Expr::SaturatingAdd(ref a, ref b) => format!("saturatingAdd({}, {})", render_expr(a), render_expr(b)),
+        Expr::SaturatingMul(ref a, ref b) => format!("saturatingMul({}, {})", render_expr(a), render_expr(b)),
+
+        // Literal values are typed, as both values must have the same type
+        Expr::Cond(ref c, ref a, ref b) => format!("{} ? {} : {}", render_operand(c), render_typed_operand(a), render_typed_operand(b)),
+        Expr::Call(ref name, ref arguments) => format!("{}({})", name, render_arguments(arguments))
+    }
+}
+
+// Renders an operand of an operator, in parentheses unless it is atomic
+fn render_operand(expr: &Expr) -> String {
+    match *expr {
+        Expr::Bool(_) | Expr::Uint(_) | Expr::Var(_) | Expr::Not(_) | Expr::Element(_, _) | Expr::Now | Expr::Call(_, _)
+            | Expr::SaturatingAdd(_, _) | Expr::SaturatingMul(_, _) => render_expr(expr),
+        Expr::Int(value) if value >= 0 => render_expr(expr),
+        _ => format!("({})", render_expr(expr))
+    }
+}
+
+// Renders a value of a conditional expression, converting literals to their type
+fn render_typed_operand(expr: &Expr) -> String {
+    match *expr {
+        Expr::Uint(value) => format!("uint64({})", value),
+        Expr::Int(value) => format!("int64({})", value),
+        _ => render_operand(expr)
+    }
+}
+
+// Renders the arguments of a call
+fn render_arguments(arguments: &Vec<Expr>) -> String {
+    let arguments: Vec<String> = arguments.iter().map(render_expr).collect();
+    arguments.join(", ")
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use generator::generate;
+    use financial_smart_contract::builder::*;
+
+    // Every function of the financial smart contract interface is part of the generated contract
+    #[test]
+    fn generated_contract_has_interface_functions() {
+        let arbiter = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let definition = and(
+            or(truncate(10, one()), anytime(scale_by(observable(arbiter, "x"), give(one())))),
+            schedule_relative(5, 10, 3, get(truncate_relative(20, scale(2, one()))))
+        );
+        let solidity = to_solidity(&generate("Contract", &definition.serialize()).unwrap());
+
+        let signatures = [
+            "constructor(int64[] memory contract_definition, address contractHolder, bool use_gas, uint64 gas_stipend) public",
+            "function get_holder() public view returns (address)",
+            "function get_counter_party() public view returns (address)",
+            "function get_contract_definition() public pure returns (int64[] memory)",
+            "function get_balance(bool holderBalance_) public view returns (int64)",
+            "function get_asset_balance(bool holderBalance_, address asset) public view returns (int64)",
+            "function get_assets() public pure returns (address[] memory)",
+            "function get_concluded() public view returns (bool)",
+            "function get_use_gas() public view returns (bool)",
+            "function get_gas_stipend() public view returns (uint64)",
+            "function get_last_updated() public view returns (int64)",
+            "function get_projected_balances(uint64) public pure returns (int64[] memory)",
+            "function get_acquisition_times() public view returns (int64[] memory)",
+            "function get_or_choices() public view returns (bytes memory)",
+            "function get_obs_entries() public view returns (int64[] memory)",
+            "function get_obs_submissions(uint64 obs_index) public view returns (int64[] memory)",
+            "function get_obs_dispute(uint64 obs_index) public pure returns (int64[] memory)",
+            "function get_state_tree() public pure returns (int64[] memory)",
+            "function set_or_choice(uint64 or_index, bool choice) public",
+            "function set_obs_value(uint64 obs_index, int64 value) public",
+            "function set_or_choices(uint64[] memory or_indices, bool[] memory choices, bool update_contract) public",
+            "function set_obs_values(uint64[] memory obs_indices, int64[] memory values, bool update_contract) public",
+            "function submit_obs_report(uint64 obs_index, int64 value, uint64 timestamp, bytes memory signature) public",
+            "function flag_obs_value(uint64 obs_index) public",
+            "function resolve_obs_value(uint64 obs_index, int64 value) public",
+            "function acquire() public",
+            "function update() public",
+            "function acquire_anytime_sub_contract(uint64 anytime_index) public",
+            "function stake() public payable returns (int64)",
+            "function withdraw(uint64 amount) public",
+            "function withdraw_to(address asset, address payable recipient, uint64 amount) public",
+            "function stake_asset(address asset, uint64 amount) public returns (int64)",
+            "function withdraw_asset(address asset, uint64 amount) public"
+        ];
+        for signature in signatures.iter() {
+            assert!(solidity.contains(signature), "Missing {}", signature);
+        }
+        assert!(!solidity.contains("{{"));
+    }
+
+    // Each combinator gets its own state and functions, the constructor checking the definition and setting the arbiters
+    #[test]
+    fn generated_contract_has_combinator_code() {
+        let arbiter = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let definition = or(truncate(10, one()), scale_by(observable(arbiter, "x"), give(one())));
+        let solidity = to_solidity(&generate("Contract", &definition.serialize()).unwrap());
+
+        assert!(solidity.contains("contract Contract {"));
+        assert!(solidity.contains("int64[14] memory definition = [int64(3), int64(4), int64(10), int64(1), int64(5), int64(-1), "));
+        assert!(solidity.contains("obsArbiters[0] = address(bytes20(hex\"3d04e16e08e4c1c7fa8fc5a386237669341eaace\"));"));
+        assert!(solidity.contains("obsNames[0] = hex\"78\";"));
+        for index in 0..6 {
+            assert!(solidity.contains(&format!("    bool acquired_{};", index)));
+            assert!(solidity.contains(&format!("function acquire_{}(uint64 time) private {{", index)));
+            assert!(solidity.contains(&format!("function update_{}(uint64 time) private returns (int64) {{", index)));
+        }
+        assert!(solidity.contains("function horizon_1() private view returns (uint64) {\n        return 10;\n    }"));
+        assert!(solidity.contains("function orChoice_0(uint64 time) private view returns (uint8) {"));
+        assert!(solidity.contains("        return fullyUpdated_0;"));
+    }
+
+    // Asset, quorum and disputable combinators are not supported by generated contracts
+    #[test]
+    fn unsupported_combinators_not_generated() {
+        let arbiter = "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap();
+        let arbiters = vec![arbiter, "0e6D2ABbe3C0d1e0C2f4Bb1aa4B9bC8b5D1f4a2E".parse().unwrap()];
+
+        let asset = and(one(), one_of(arbiter));
+        assert_eq!(generate("Contract", &asset.serialize()).unwrap_err(), "Only Ether-denominated contracts can be generated.");
+        let quorum = give(scale_by_quorum(quorum(arbiters, 1, "x"), one()));
+        assert_eq!(generate("Contract", &quorum.serialize()).unwrap_err(), "Quorum observables are not supported by generated contracts.");
+        let disputable = scale_by_disputable(dispute(60, arbiter), ScaleValue::Observable(observable(arbiter, "x")), one());
+        assert_eq!(
+            generate("Contract", &disputable.serialize()).unwrap_err(),
+            "Disputable observables are not supported by generated contracts."
+        );
+    }
+}
@@ -0,0 +1,644 @@
+pragma solidity >=0.5.0 <0.6.0;
+
+// Generated for the contract definition {{DEFINITION}}, with the same ABI as the financial smart contract
+contract {{NAME}} {
+    // Static values
+    int64 constant MAX_INT64 = 2**63 - 1;
+    int64 constant MIN_INT64 = -2**63;
+    uint64 constant MAX_UINT64 = 2**64 - 1;
+
+    // The operator permissions, combined as bit flags
+    uint64 constant OPERATOR_CHOICES = 1;
+    uint64 constant OPERATOR_ACQUIRE = 2;
+    uint64 constant OPERATOR_WITHDRAW = 4;
+    uint64 constant OPERATOR_DISPUTE = 8;
+
+    // How long after its timestamp a signed observable report can be submitted, in seconds
+    uint64 constant OBS_REPORT_VALIDITY = 60 * 60;
+
+    // The contract holder
+    address holder;
+
+    // The counter-party
+    address counterParty;
+
+    // The balances of the holder and counter-party
+    int64 holderBalance;
+    int64 counterPartyBalance;
+
+    // Whether or not Ether payments forward the gas stipend
+    bool useGas;
+
+    // The gas stipend forwarded with Ether payments if using gas (not deducted from the payment)
+    uint64 gasStipend;
+
+    // The last-updated time
+    int64 lastUpdated;
+
+    // Whether or not a withdrawal is being paid out
+    bool withdrawing;
+
+    // The permissions each party has approved for each operator
+    mapping(address => mapping(address => uint64)) operatorPermissions;
+
+    // The arbiters of the observables
+    address[{{OBS_LENGTH}}] obsArbiters;
+
+    // The names of the observables, as UTF-8 text
+    bytes[{{OBS_LENGTH}}] obsNames;
+
+    // The hashes of the signed observable reports submitted so far, with their signers
+    mapping(bytes32 => bool) submittedReports;
+
+    // The state of the combinators
+{{STATE_VARIABLES}}
+
+    // Logged when a party approves an operator
+    event OperatorApproved(address indexed party, address indexed operator, uint64 permissions);
+
+    // Logged when a party revokes an operator
+    event OperatorRevoked(address indexed party, address indexed operator);
+
+    // Constructor, takes the contract definition (which must be the definition the contract was generated for), the contract
+    // holder address, whether or not Ether payments forward the gas stipend and the gas stipend
+    constructor(int64[] memory contract_definition, address contractHolder, bool use_gas, uint64 gas_stipend) public {
+        require(
+            contractHolder != msg.sender,
+            "Holder and counter-party must be different addresses."
+        );
+        require(
+            contract_definition.length != 0,
+            "Provided combinator contract not valid."
+        );
+
+        int64[{{DEFINITION_LENGTH}}] memory definition = {{DEFINITION_ARRAY}};
+        require(
+            contract_definition.length == definition.length,
+            "Provided combinator contract does not match the generated contract."
+        );
+        for (uint i = 0; i < definition.length; i++) {
+            require(
+                contract_definition[i] == definition[i],
+                "Provided combinator contract does not match the generated contract."
+            );
+        }
+        require(
+            gas_stipend <= uint64(MAX_INT64),
+            "Given value is too large to be converted to i64."
+        );
+
+        // Set the holder and counter-party
+        holder = contractHolder;
+        counterParty = msg.sender;
+        useGas = use_gas;
+        gasStipend = gas_stipend;
+        lastUpdated = int64(now);
+
+        // No or-choices are set initially
+        for (uint i = 0; i < {{OR_COUNT}}; i++) {
+            orChoices[i] = 2;
+        }
+{{OBS_ARBITERS}}
+    }
+
+    // Only allows the holder (or an operator approved by the holder with the given permission) to call a function
+    modifier onlyHolder(uint64 permission, string memory message) {
+        require(
+            principal(permission) == holder,
+            message
+        );
+
+        _;
+    }
+
+    // Gets the address of the holder
+    function get_holder() public view returns (address) {
+        return holder;
+    }
+
+    // Gets the address of the counter-party
+    function get_counter_party() public view returns (address) {
+        return counterParty;
+    }
+
+    // Gets the combinator contract definition (serialized)
+    function get_contract_definition() public pure returns (int64[] memory) {
+        int64[{{DEFINITION_LENGTH}}] memory definition = {{DEFINITION_ARRAY}};
+        int64[] memory result = new int64[](definition.length);
+        for (uint i = 0; i < definition.length; i++) {
+            result[i] = definition[i];
+        }
+        return result;
+    }
+
+    // Returns the balance of one of the two parties
+    function get_balance(bool holderBalance_) public view returns (int64) {
+        if (holderBalance_) {
+            return holderBalance;
+        } else {
+            return counterPartyBalance;
+        }
+    }
+
+    // Returns the balance of the given asset of one of the two parties, only Ether (the zero address) is used
+    function get_asset_balance(bool holderBalance_, address asset) public view returns (int64) {
+        require(
+            asset == address(0),
+            "Asset is not used by the contract."
+        );
+
+        return get_balance(holderBalance_);
+    }
+
+    // Gets the assets of the contract indexed by asset index, only Ether (the zero address)
+    function get_assets() public pure returns (address[] memory) {
+        return new address[](1);
+    }
+
+    // Gets whether or not the contract has concluded
+    function get_concluded() public view returns (bool) {
+        return isConcluded();
+    }
+
+    // Gets whether or not the contract allocates gas fees upon withdrawal
+    function get_use_gas() public view returns (bool) {
+        return useGas;
+    }
+
+    // Gets the gas stipend forwarded with Ether payments
+    function get_gas_stipend() public view returns (uint64) {
+        return gasStipend;
+    }
+
+    // Gets the last-updated time
+    function get_last_updated() public view returns (int64) {
+        return lastUpdated;
+    }
+
+    // Projected balances need the combinators to be updated without persisting them, which generated contracts cannot do
+    function get_projected_balances(uint64) public pure returns (int64[] memory) {
+        revert("Not supported by generated contracts.");
+    }
+
+    // Gets the contract acquisition times (top level acquisition time and anytime acquisition times, -1 if not set)
+    function get_acquisition_times() public view returns (int64[] memory) {
+        int64[] memory times = new int64[](1 + {{ANYTIME_COUNT}});
+        times[0] = acquired_0 ? int64(acquisitionTime_0) : -1;
+        for (uint i = 0; i < {{ANYTIME_COUNT}}; i++) {
+            times[i + 1] = anytimeTimeSet[i] ? int64(anytimeTimes[i]) : -1;
+        }
+        return times;
+    }
+
+    // Gets the or choices (1 for the first sub-combinator, 0 for the second and 2 if not set)
+    function get_or_choices() public view returns (bytes memory) {
+        bytes memory choices = new bytes({{OR_COUNT}});
+        for (uint i = 0; i < {{OR_COUNT}}; i++) {
+            choices[i] = byte(orChoices[i]);
+        }
+        return choices;
+    }
+
+    // Gets the observable entries, as the arbiter address, -1 or 0 followed by the value, and the name of each observable
+    function get_obs_entries() public view returns (int64[] memory) {
+        uint length = {{OBS_ENTRIES_LENGTH}};
+        for (uint i = 0; i < {{OBS_COUNT}}; i++) {
+            length += obsSet[i] ? 2 : 1;
+        }
+
+        int64[] memory entries = new int64[](length);
+        uint j = 0;
+{{OBS_ENTRIES}}
+        return entries;
+    }
+
+    // Gets the values submitted for the given observable, as its threshold and number of arbiters, followed by its arbiter's
+    // address and -1, or 0 followed by the value it submitted (generated contracts have no quorum observables, so each
+    // observable has a single arbiter and a threshold of 1)
+    function get_obs_submissions(uint64 obs_index) public view returns (int64[] memory) {
+        require(
+            obs_index < {{OBS_COUNT}},
+            "Stored vector index out of bounds."
+        );
+
+        int64[] memory submissions = new int64[](obsSet[obs_index] ? 8 : 7);
+        submissions[0] = 1;
+        submissions[1] = 1;
+{{OBS_SUBMISSIONS}}
+        if (obsSet[obs_index]) {
+            submissions[6] = 0;
+            submissions[7] = obsValues[obs_index];
+        } else {
+            submissions[6] = -1;
+        }
+        return submissions;
+    }
+
+    // Gets the dispute window of the given observable (generated contracts have no disputable observables, so this is empty)
+    function get_obs_dispute(uint64 obs_index) public pure returns (int64[] memory) {
+        require(
+            obs_index < {{OBS_COUNT}},
+            "Stored vector index out of bounds."
+        );
+        return new int64[](0);
+    }
+
+    // The state of each combinator is held in its own state variables, which are not serialized
+    function get_state_tree() public pure returns (int64[] memory) {
+        revert("Not supported by generated contracts.");
+    }
+
+    // Gets the permissions the given party has approved for the given operator (0 if none)
+    function get_operator_permissions(address party, address operator) public view returns (uint64) {
+        return operatorPermissions[party][operator];
+    }
+
+    // Sets the preference of the given or combinator's sub-combinators
+    function set_or_choice(uint64 or_index, bool choice) public onlyHolder(OPERATOR_CHOICES, "Only the contract holder may set or-choices.") {
+        writeOrChoice(or_index, choice);
+    }
+
+    // Sets a value for the given observable
+    function set_obs_value(uint64 obs_index, int64 value) public {
+        writeObsValue(obs_index, value, msg.sender);
+    }
+
+    // Sets the preferences of the given or combinators' sub-combinators, then updates the contract if update_contract is set
+    function set_or_choices(uint64[] memory or_indices, bool[] memory choices, bool update_contract) public {
+        require(
+            or_indices.length == choices.length,
+            "Index and value arrays must have the same length."
+        );
+        require(
+            principal(OPERATOR_CHOICES) == holder,
+            "Only the contract holder may set or-choices."
+        );
+
+        for (uint i = 0; i < or_indices.length; i++) {
+            writeOrChoice(or_indices[i], choices[i]);
+        }
+
+        if (update_contract) {
+            update();
+        }
+    }
+
+    // Sets values for the given observables, then updates the contract if update_contract is set
+    function set_obs_values(uint64[] memory obs_indices, int64[] memory values, bool update_contract) public {
+        require(
+            obs_indices.length == values.length,
+            "Index and value arrays must have the same length."
+        );
+
+        for (uint i = 0; i < obs_indices.length; i++) {
+            writeObsValue(obs_indices[i], values[i], msg.sender);
+        }
+
+        if (update_contract) {
+            update();
+        }
+    }
+
+    // Sets a value for the given observable from a report signed by its arbiter, which anyone can submit within an hour of the
+    // report's timestamp. The signature is the eth_sign signature of
+    // keccak256(abi.encodePacked(name, obs_index, value, timestamp, address(this))).
+    function submit_obs_report(uint64 obs_index, int64 value, uint64 timestamp, bytes memory signature) public {
+        require(
+            obs_index < {{OBS_COUNT}},
+            "Stored vector index out of bounds."
+        );
+        require(
+            timestamp <= uint64(now),
+            "Observable report timestamp is in the future."
+        );
+        require(
+            uint64(now) - timestamp <= OBS_REPORT_VALIDITY,
+            "Observable report has expired."
+        );
+
+        bytes32 report = keccak256(abi.encodePacked(obsNames[obs_index], obs_index, value, timestamp, address(this)));
+        bytes32 hash = keccak256(abi.encodePacked("\x19Ethereum Signed Message:\n32", report));
+        require(
+            signature.length == 65,
+            "Observable report signature must be 65 bytes."
+        );
+
+        // The signature is r, s and v
+        bytes32 r;
+        bytes32 s;
+        uint8 v;
+        assembly {
+            r := mload(add(signature, 32))
+            s := mload(add(signature, 64))
+            v := byte(0, mload(add(signature, 96)))
+        }
+        if (v < 27) {
+            v += 27;
+        }
+
+        address signer = ecrecover(hash, v, r, s);
+        require(
+            signer != address(0),
+            "Observable report signature is not valid."
+        );
+
+        // Reports are recorded by signer, as the arbiters of a quorum observable may sign the same report
+        bytes32 signedReport = keccak256(abi.encodePacked(hash, signer));
+        require(
+            !submittedReports[signedReport],
+            "Observable report has already been submitted."
+        );
+
+        submittedReports[signedReport] = true;
+        writeObsValue(obs_index, value, signer);
+    }
+
+    // Flags the value of the given disputable observable (generated contracts have no disputable observables)
+    function flag_obs_value(uint64 obs_index) public {
+        require(
+            obs_index < {{OBS_COUNT}},
+            "Stored vector index out of bounds."
+        );
+        revert("Observable is not disputable.");
+    }
+
+    // Confirms or overrides the flagged value of the given disputable observable (generated contracts have no disputable
+    // observables)
+    function resolve_obs_value(uint64 obs_index, int64 value) public {
+        require(
+            obs_index < {{OBS_COUNT}},
+            "Stored vector index out of bounds."
+        );
+        revert("Observable is not disputable.");
+    }
+
+    // Acquires the combinator contract at the current block-time
+    function acquire() public onlyHolder(OPERATOR_ACQUIRE, "Only the contract holder may acquire the combinator contract.") {
+        require(
+            !acquired_0,
+            "The combinator contract cannot be acquired more than once."
+        );
+
+        // Times relative to the acquisition time become known now
+        baseTime = uint64(now);
+        acquire_0(uint64(now));
+
+        update();
+    }
+
+    // Updates the balances of the holder and counter-party
+    function update() public {
+        require(
+            !isConcluded(),
+            "Contract has concluded, nothing more to update."
+        );
+
+        lastUpdated = int64(now);
+        int64 difference = update_0(uint64(now));
+        if (difference != 0) {
+            holderBalance = safeAddSigned(holderBalance, difference);
+            counterPartyBalance = safeAddSigned(counterPartyBalance, -difference);
+        }
+    }
+
+    // Acquires an anytime combinator's sub-contract
+    function acquire_anytime_sub_contract(uint64 anytime_index) public {
+        require(
+            anytime_index < {{ANYTIME_COUNT}},
+            "Stored vector index out of bounds."
+        );
+        require(
+            anytimeAcquired[anytime_index],
+            "Given anytime combinator has not been acquired."
+        );
+        require(
+            principal(OPERATOR_ACQUIRE) == holder,
+            "Only the contract holder may acquire the combinator contract."
+        );
+        require(
+            !anytimeTimeSet[anytime_index] || anytimeTimes[anytime_index] > uint64(now),
+            "Cannot acquire a sub-combinator contract which has already been acquired."
+        );
+
+        anytimeTimeSet[anytime_index] = true;
+        anytimeTimes[anytime_index] = uint64(now);
+
+        update();
+    }
+
+    // Stakes Ether with the contract, returns the caller's total balance
+    function stake() public payable returns (int64) {
+        require(
+            msg.value <= uint64(MAX_INT64),
+            "Given value is too large to be converted to i64."
+        );
+
+        if (msg.sender == holder) {
+            holderBalance = safeAddSigned(holderBalance, int64(msg.value));
+            return holderBalance;
+        } else if (msg.sender == counterParty) {
+            counterPartyBalance = safeAddSigned(counterPartyBalance, int64(msg.value));
+            return counterPartyBalance;
+        }
+        revert("Only the contract holder or the counter-party may stake Ether in the contract.");
+    }
+
+    // Withdraws positive Ether balance up to the given amount from the contract
+    function withdraw(uint64 amount) public {
+        address payable party = address(uint160(principal(OPERATOR_WITHDRAW)));
+        withdrawEther(party, party, amount);
+    }
+
+    // Withdraws positive balance of the given asset up to the given amount, paying it to the given recipient
+    function withdraw_to(address asset, address payable recipient, uint64 amount) public {
+        address party = principal(OPERATOR_WITHDRAW);
+        require(
+            party == msg.sender || party == address(0) || recipient == party,
+            "Operators may only withdraw to the party they act for."
+        );
+        require(
+            asset == address(0),
+            "Asset is not used by the contract."
+        );
+
+        withdrawEther(party, recipient, amount);
+    }
+
+    // Only Ether is used by generated contracts, which is staked by sending value to stake
+    function stake_asset(address asset, uint64 amount) public returns (int64) {
+        require(
+            amount <= uint64(MAX_INT64),
+            "Given value is too large to be converted to i64."
+        );
+        require(
+            asset == address(0),
+            "Asset is not used by the contract."
+        );
+
+        revert("Ether must be staked by sending value to stake.");
+    }
+
+    // Withdraws positive balance of the given asset up to the given amount from the contract
+    function withdraw_asset(address asset, uint64 amount) public {
+        withdraw_to(asset, address(uint160(principal(OPERATOR_WITHDRAW))), amount);
+    }
+
+    // Approves the given operator to act for the caller (holder or counter-party) with the given permissions, replacing any
+    // previous approval
+    function approve_operator(address operator, uint64 permissions) public {
+        require(
+            msg.sender == holder || msg.sender == counterParty,
+            "Only the contract holder or the counter-party may approve operators."
+        );
+        require(
+            operator != holder && operator != counterParty,
+            "The contract holder and counter-party cannot be operators."
+        );
+        require(
+            permissions != 0 && (permissions & ~(OPERATOR_CHOICES | OPERATOR_ACQUIRE | OPERATOR_WITHDRAW | OPERATOR_DISPUTE)) == 0,
+            "Operator permissions must be a non-empty combination of choices, acquire, withdraw and dispute."
+        );
+        require(
+            operatorPermissions[msg.sender == holder ? counterParty : holder][operator] == 0,
+            "Operator already acts for the other party."
+        );
+
+        operatorPermissions[msg.sender][operator] = permissions;
+        emit OperatorApproved(msg.sender, operator, permissions);
+    }
+
+    // Revokes the given operator's approval to act for the caller
+    function revoke_operator(address operator) public {
+        require(
+            operatorPermissions[msg.sender][operator] != 0,
+            "Operator has not been approved by the sender."
+        );
+
+        operatorPermissions[msg.sender][operator] = 0;
+        emit OperatorRevoked(msg.sender, operator);
+    }
+
+    // Returns the party the sender acts for with the given operator permission: the sender itself if it is the holder or
+    // counter-party, otherwise the party which approved the sender with the permission (the zero address if none)
+    function principal(uint64 permission) private view returns (address) {
+        if (msg.sender == holder || msg.sender == counterParty) {
+            return msg.sender;
+        } else if ((operatorPermissions[holder][msg.sender] & permission) != 0) {
+            return holder;
+        } else if ((operatorPermissions[counterParty][msg.sender] & permission) != 0) {
+            return counterParty;
+        }
+        return address(0);
+    }
+
+    // Sets the given or combinator's preference if not yet set (the sender must already be checked)
+    function writeOrChoice(uint64 or_index, bool choice) private {
+        require(
+            or_index < {{OR_COUNT}},
+            "Stored vector index out of bounds."
+        );
+        require(
+            orChoices[or_index] == 2,
+            "Or-choice has already been set."
+        );
+
+        orChoices[or_index] = choice ? 1 : 0;
+    }
+
+    // Sets the given observable's value if not yet set and the setter is its arbiter
+    function writeObsValue(uint64 obs_index, int64 value, address setter) private {
+        require(
+            obs_index < {{OBS_COUNT}},
+            "Stored vector index out of bounds."
+        );
+        require(
+            !obsSet[obs_index],
+            "Observable has already been set."
+        );
+        require(
+            setter == obsArbiters[obs_index],
+            "Sender cannot set value for given observable-index."
+        );
+
+        obsSet[obs_index] = true;
+        obsValues[obs_index] = value;
+    }
+
+    // Withdraws positive Ether balance of the given party up to the given amount, paying it to the recipient
+    function withdrawEther(address party, address payable recipient, uint64 amount) private {
+        require(
+            !withdrawing,
+            "Cannot withdraw while another withdrawal is being paid out."
+        );
+        require(
+            party == holder || party == counterParty,
+            "Only the contract holder or the counter-party may withdraw Ether from the contract."
+        );
+
+        // Get the amount to send (clamp at balance amount)
+        int64 originalBalance = party == holder ? holderBalance : counterPartyBalance;
+        int64 withdrawAmount = withdrawalAmount(amount, originalBalance, holderBalance + counterPartyBalance);
+        require(
+            withdrawAmount > 0,
+            "Not enough funds to withdraw."
+        );
+
+        // Adjust balance first, a failed payment reverts it
+        if (party == holder) {
+            holderBalance = originalBalance - withdrawAmount;
+        } else {
+            counterPartyBalance = originalBalance - withdrawAmount;
+        }
+
+        // The gas stipend is forwarded on top of the payment, the withdrawer's transaction pays for it
+        withdrawing = true;
+        (bool success, ) = recipient.call.value(uint64(withdrawAmount)).gas(useGas ? gasStipend : 0)("");
+        withdrawing = false;
+        require(
+            success,
+            "Payment failed"
+        );
+    }
+
+    // Returns the amount to withdraw, clamped at the balance and funds
+    function withdrawalAmount(uint64 amount, int64 balance, int64 funds) private pure returns (int64) {
+        int64 finalAmount = int64(amount);
+        if (balance < finalAmount) {
+            finalAmount = balance;
+        }
+        if (funds < finalAmount) {
+            finalAmount = funds;
+        }
+        return finalAmount;
+    }
+
+    // Add two signed integers if no overflow or underflow can occur
+    function safeAddSigned(int64 a, int64 b) private pure returns (int64) {
+        require(
+            !(b > 0 && a > MAX_INT64 - b),
+            "Integer overflow."
+        );
+        require(
+            !(b < 0 && a < MIN_INT64 - b),
+            "Integer underflow."
+        );
+
+        return a + b;
+    }
+
+    // Add two times, saturating at the largest time
+    function saturatingAdd(uint64 a, uint64 b) private pure returns (uint64) {
+        uint128 result = uint128(a) + uint128(b);
+        return result > MAX_UINT64 ? MAX_UINT64 : uint64(result);
+    }
+
+    // Multiply two times, saturating at the largest time
+    function saturatingMul(uint64 a, uint64 b) private pure returns (uint64) {
+        uint128 result = uint128(a) * uint128(b);
+        return result > MAX_UINT64 ? MAX_UINT64 : uint64(result);
+    }
+
+    // The combinators, each with its horizon (if any) and its acquire and update functions
+
+{{FUNCTIONS}}
+}
@@ -0,0 +1,411 @@
+extern crate financial_smart_contract;
+extern crate financial_smart_contract_solidity;
+extern crate pwasm_std;
+extern crate pwasm_test;
+
+use std::panic;
+use pwasm_std::types::{ Address, U256 };
+use pwasm_test::{ ext_reset, ext_update };
+use financial_smart_contract::{ FinancialScContract, FinancialScInterface, templates };
+use financial_smart_contract::builder::{ Definition, Observable, and, anytime, get, give, observable, one, or, scale, scale_by,
+    schedule, schedule_relative, then, truncate, truncate_relative, zero };
+use financial_smart_contract_solidity::{ compile, find_solc, generate, Machine };
+
+// The number of steps in each scenario
+const SCENARIO_STEPS: usize = 30;
+
+// A deterministic pseudo-random number generator (xorshift), so failing scenarios can be reproduced
+struct Random {
+    state: u64
+}
+
+// Method implementation for the random number generator
+impl Random {
+    // Instantiates the generator with the given seed
+    fn new(seed: u64) -> Random {
+        Random { state: seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407) | 1 }
+    }
+
+    // Returns a number in the range [0, bound)
+    fn below(&mut self, bound: u64) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state % bound
+    }
+
+    // Returns a number in the range [low, high]
+    fn between(&mut self, low: i64, high: i64) -> i64 {
+        low + self.below((high - low + 1) as u64) as i64
+    }
+
+    // Returns true with the given percentage chance
+    fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    // Returns an index below the given count, or rarely the (out of bounds) count itself
+    fn index(&mut self, count: u64) -> u64 {
+        if count > 0 && self.chance(95) {
+            self.below(count)
+        } else {
+            count
+        }
+    }
+}
+
+// The parties of a scenario
+struct Parties {
+    holder: Address,
+    counter_party: Address,
+    arbiter: Address,
+    operator: Address,
+    other: Address
+}
+
+// Gets the parties used in every scenario
+fn parties() -> Parties {
+    Parties {
+        holder: "25248F6f32B37f69A92dAf05d5647981b58Aaec4".parse().unwrap(),
+        counter_party: "1818909b947a9FA7f5Fe42b0DD1b2f9E9a4F903f".parse().unwrap(),
+        arbiter: "3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap(),
+        operator: "7E5F4552091A69125d5DfCb7b8C2659029395Bdf".parse().unwrap(),
+        other: "0e6D2ABbe3C0d1e0C2f4Bb1aa4B9bC8b5D1f4a2E".parse().unwrap()
+    }
+}
+
+// Signs an observable report as the given signer for the mock ecrecover, which recovers the first 20 bytes of the signature
+fn mock_signature(signer: Address) -> Vec<u8> {
+    let mut signature = signer.0.to_vec();
+    signature.resize(64, 0);
+    signature.push(27);
+    signature
+}
+
+// Calls the financial smart contract, returning the panic message if it panics
+fn call_contract<T, F: FnOnce(&mut FinancialScContract) -> T>(contract: &mut FinancialScContract, call: F) -> Result<T, String> {
+    panic::catch_unwind(panic::AssertUnwindSafe(|| call(contract))).map_err(|error| {
+        match error.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => error.downcast_ref::<String>().cloned().unwrap_or(String::new())
+        }
+    })
+}
+
+// Builds a random Ether-only definition of at most the given depth
+fn random_definition(random: &mut Random, observables: &Vec<Observable>, depth: usize) -> Definition {
+    if depth == 0 || random.chance(20) {
+        return if random.chance(20) { zero() } else { one() };
+    }
+
+    let time = random.between(0, 60) as u64;
+    match random.below(13) {
+        0 => and(random_definition(random, observables, depth - 1), random_definition(random, observables, depth - 1)),
+        1 => or(random_definition(random, observables, depth - 1), random_definition(random, observables, depth - 1)),
+        2 => truncate(time, random_definition(random, observables, depth - 1)),
+        3 => scale(random.between(-3, 3), random_definition(random, observables, depth - 1)),
+        4 => {
+            let obs = observables[random.below(observables.len() as u64) as usize].clone();
+            scale_by(obs, random_definition(random, observables, depth - 1))
+        },
+        5 => give(random_definition(random, observables, depth - 1)),
+        6 => then(random_definition(random, observables, depth - 1), random_definition(random, observables, depth - 1)),
+        7 => get(random_definition(random, observables, depth - 1)),
+        8 => anytime(random_definition(random, observables, depth - 1)),
+        9 => schedule(time, random.between(0, 10) as u64, random.between(1, 4) as u64, random_definition(random, observables, depth - 1)),
+        10 => truncate_relative(time, random_definition(random, observables, depth - 1)),
+        11 => schedule_relative(time, random.between(0, 10) as u64, random.between(1, 4) as u64,
+            random_definition(random, observables, depth - 1)),
+        _ => get(truncate(time, random_definition(random, observables, depth - 1)))
+    }
+}
+
+// Runs a random scenario against both the financial smart contract and the machine running the generated contract, checking
+// after each step that both returned the same result (or revert message) and are in the same state. The scenario stops at the
+// first revert, as the financial smart contract's storage is not rolled back by the test environment.
+fn run_scenario(definition: &Definition, seed: u64) {
+    let serialized = definition.serialize();
+    let generated = match panic::catch_unwind(|| generate("Contract", &serialized)) {
+        Ok(Ok(generated)) => generated,
+        // The definition is rejected by the financial smart contract too, or is not supported by generated contracts
+        _ => return
+    };
+
+    let parties = parties();
+    let mut random = Random::new(seed);
+    let mut timestamp = random.below(20);
+    let use_gas = random.chance(50);
+
+    // Construct both contracts
+    let mut contract = FinancialScContract::new();
+    ext_reset(|e| e
+        .sender(parties.counter_party)
+        .timestamp(timestamp)
+        .endpoint("0000000000000000000000000000000000000001".parse().unwrap(), Box::new(|_value: U256, input: &[u8], result: &mut [u8]| {
+            result[12..32].copy_from_slice(&input[64..84]);
+            Ok(())
+        }))
+    );
+    let mut machine = Machine::new(&generated);
+    machine.set_sender(parties.counter_party);
+    machine.set_timestamp(timestamp);
+    machine.set_signature_recoverer(Box::new(|_report: &[u8], signature: &[u8]| Address::from_slice(&signature[0..20])));
+
+    let expected = call_contract(&mut contract, |c| c.constructor(serialized.clone(), parties.holder, use_gas, 5));
+    let actual = machine.constructor(serialized.clone(), parties.holder, use_gas, 5);
+    assert_eq!(expected, actual, "Constructor differs for {:?} (seed {})", serialized, seed);
+    if expected.is_err() {
+        return;
+    }
+
+    let or_count = machine.get_or_choices().len() as u64;
+    let obs_count = generated.observables.len() as u64;
+    let anytime_count = generated.anytime_count as u64;
+    for step in 0..SCENARIO_STEPS {
+        timestamp += random.below(8);
+
+        // Mostly use the party allowed to make each call
+        let sender = match random.below(20) {
+            0 => parties.other,
+            1 | 2 => parties.counter_party,
+            3 | 4 => parties.operator,
+            _ => parties.holder
+        };
+        let value = random.below(100);
+        ext_update(|e| e
+            .sender(sender)
+            .timestamp(timestamp)
+            .value(U256::from(value))
+        );
+        machine.set_sender(sender);
+        machine.set_timestamp(timestamp);
+        machine.set_value(U256::from(value));
+
+        // Calls using an index are only made when there is something to index
+        let action = match random.below(16) {
+            3 if or_count == 0 => 1,
+            4 | 14 | 15 if obs_count == 0 => 1,
+            5 if anytime_count == 0 => 1,
+            action => action
+        };
+        let (expected, actual) = match action {
+            0 => (call_contract(&mut contract, |c| c.acquire()), machine.acquire()),
+            1 | 2 | 8 | 9 => (call_contract(&mut contract, |c| c.update()), machine.update()),
+            3 => {
+                let or_index = random.index(or_count);
+                let choice = random.chance(50);
+                (call_contract(&mut contract, |c| c.set_or_choice(or_index, choice)), machine.set_or_choice(or_index, choice))
+            },
+            4 => {
+                let obs_index = random.index(obs_count);
+                let value = random.between(-5, 5);
+                if random.chance(90) {
+                    ext_update(|e| e.sender(parties.arbiter));
+                    machine.set_sender(parties.arbiter);
+                }
+                (call_contract(&mut contract, |c| c.set_obs_value(obs_index, value)), machine.set_obs_value(obs_index, value))
+            },
+            5 => {
+                let anytime_index = random.index(anytime_count);
+                (
+                    call_contract(&mut contract, |c| c.acquire_anytime_sub_contract(anytime_index)),
+                    machine.acquire_anytime_sub_contract(anytime_index)
+                )
+            },
+            6 => {
+                let or_indices: Vec<u64> = (0..or_count).filter(|_| random.chance(50)).collect();
+                let choices: Vec<bool> = or_indices.iter().map(|_| random.chance(50)).collect();
+                let update_contract = random.chance(50);
+                (
+                    call_contract(&mut contract, |c| c.set_or_choices(or_indices.clone(), choices.clone(), update_contract)),
+                    machine.set_or_choices(or_indices.clone(), choices.clone(), update_contract)
+                )
+            },
+            7 | 10 => {
+                let expected = call_contract(&mut contract, |c| c.stake());
+                (expected.map(|_| ()), machine.stake().map(|_| ()))
+            },
+            12 => {
+                let operator = if random.chance(90) { parties.operator } else { parties.other };
+                let permissions = if random.chance(95) { 1 + random.below(15) } else { 16 };
+                (
+                    call_contract(&mut contract, |c| c.approve_operator(operator, permissions)),
+                    machine.approve_operator(operator, permissions)
+                )
+            },
+            13 => {
+                let operator = parties.operator;
+                (call_contract(&mut contract, |c| c.revoke_operator(operator)), machine.revoke_operator(operator))
+            },
+            14 => {
+                let obs_index = random.index(obs_count);
+                let value = random.between(-5, 5);
+                let report_time = if random.chance(95) { timestamp - random.below(timestamp + 1) } else { timestamp + 1 };
+                let mut signature = mock_signature(if random.chance(90) { parties.arbiter } else { parties.other });
+                if random.chance(5) {
+                    signature.pop();
+                }
+                (
+                    call_contract(&mut contract, |c| c.submit_obs_report(obs_index, value, report_time, signature.clone())),
+                    machine.submit_obs_report(obs_index, value, report_time, signature.clone())
+                )
+            },
+            15 => {
+                let obs_index = random.index(obs_count);
+                if random.chance(50) {
+                    (call_contract(&mut contract, |c| c.flag_obs_value(obs_index)), machine.flag_obs_value(obs_index))
+                } else {
+                    let value = random.between(-5, 5);
+                    (call_contract(&mut contract, |c| c.resolve_obs_value(obs_index, value)), machine.resolve_obs_value(obs_index, value))
+                }
+            },
+            _ => {
+                let amount = random.below(20);
+                (call_contract(&mut contract, |c| c.withdraw(amount)), machine.withdraw(amount))
+            }
+        };
+        assert_eq!(expected, actual, "Action {} at step {} differs for {:?} (seed {})", action, step, serialized, seed);
+        if expected.is_err() {
+            return;
+        }
+
+        // Check that the contracts are in the same state
+        let context = format!("after action {} at step {} for {:?} (seed {})", action, step, serialized, seed);
+        assert_eq!(contract.get_balance(true), machine.get_balance(true), "Holder balance differs {}", context);
+        assert_eq!(contract.get_balance(false), machine.get_balance(false), "Counter-party balance differs {}", context);
+        assert_eq!(contract.get_concluded(), machine.get_concluded(), "Concluded differs {}", context);
+        assert_eq!(contract.get_last_updated(), machine.get_last_updated(), "Last-updated time differs {}", context);
+        assert_eq!(contract.get_acquisition_times(), machine.get_acquisition_times(), "Acquisition times differ {}", context);
+        assert_eq!(contract.get_or_choices(), machine.get_or_choices(), "Or-choices differ {}", context);
+        assert_eq!(contract.get_obs_entries(), machine.get_obs_entries(), "Observable entries differ {}", context);
+        for obs_index in 0..obs_count {
+            assert_eq!(
+                Ok(contract.get_obs_submissions(obs_index)),
+                machine.get_obs_submissions(obs_index),
+                "Observable submissions differ {}", context
+            );
+            assert_eq!(Ok(contract.get_obs_dispute(obs_index)), machine.get_obs_dispute(obs_index), "Observable disputes differ {}", context);
+        }
+        for party in [parties.holder, parties.counter_party].iter() {
+            assert_eq!(
+                contract.get_operator_permissions(*party, parties.operator),
+                machine.get_operator_permissions(*party, parties.operator),
+                "Operator permissions differ {}", context
+            );
+        }
+    }
+}
+
+// Gets the definitions of the templates
+fn template_definitions() -> Vec<Definition> {
+    let arbiter = parties().arbiter;
+    vec![
+        templates::zero_coupon_bond(95, 100, 20),
+        templates::coupon_bond(95, 100, 5, 10, 10, 3).unwrap(),
+        templates::european_call(2, 10, 20, observable(arbiter, "price")),
+        templates::european_put(2, 10, 20, observable(arbiter, "price")),
+        templates::american_call(2, 10, 20, observable(arbiter, "price")),
+        templates::american_put(2, 10, 20, observable(arbiter, "price")),
+        templates::forward(10, 20, observable(arbiter, "price")),
+        templates::fixed_floating_swap(10, 3, 10, 10, vec![observable(arbiter, "rate0"), observable(arbiter, "rate1")])
+    ]
+}
+
+// Gets hand-written definitions, covering each combinator
+fn combinator_definitions() -> Vec<Definition> {
+    let arbiter = parties().arbiter;
+    vec![
+        zero(),
+        one(),
+        and(one(), give(scale(2, one()))),
+        or(truncate(10, one()), scale_by(observable(arbiter, "x"), give(one()))),
+        then(truncate(15, scale(3, one())), give(one())),
+        get(truncate(20, one())),
+        anytime(truncate(30, scale(4, one()))),
+        schedule(5, 10, 3, scale(2, one())),
+        schedule(5, 10, 3, or(one(), give(one()))),
+        schedule(5, 10, 2, anytime(truncate_relative(8, one()))),
+        schedule_relative(5, 10, 3, get(truncate_relative(20, scale(2, one())))),
+        and(
+            or(truncate(10, one()), anytime(scale_by(observable(arbiter, "x"), give(one())))),
+            schedule_relative(5, 10, 3, get(truncate_relative(20, scale(2, one()))))
+        )
+    ]
+}
+
+// Gets the random definitions of the scenarios
+fn random_definitions() -> Vec<Definition> {
+    let arbiter = parties().arbiter;
+    let observables = vec![observable(arbiter, "x"), observable(arbiter, "y")];
+
+    let mut random = Random::new(42);
+    (0..400).map(|_| random_definition(&mut random, &observables, 4)).collect()
+}
+
+// The generated contracts of the templates behave as the financial smart contract
+#[test]
+fn generated_contracts_match_templates() {
+    for (i, definition) in template_definitions().iter().enumerate() {
+        for seed in 0..40 {
+            run_scenario(definition, (i * 1000) as u64 + seed);
+        }
+    }
+}
+
+// The generated contracts of hand-written definitions, covering each combinator, behave as the financial smart contract
+#[test]
+fn generated_contracts_match_combinators() {
+    for (i, definition) in combinator_definitions().iter().enumerate() {
+        for seed in 0..40 {
+            run_scenario(definition, (i * 1000) as u64 + seed);
+        }
+    }
+}
+
+// The generated contracts of random definitions behave as the financial smart contract
+#[test]
+fn generated_contracts_match_random_definitions() {
+    for (seed, definition) in random_definitions().iter().enumerate() {
+        run_scenario(definition, seed as u64);
+    }
+}
+
+// A generated contract can only be constructed with the definition it was generated for
+#[test]
+fn generated_contract_rejects_other_definitions() {
+    let parties = parties();
+    let mut machine = Machine::new(&generate("Contract", &one().serialize()).unwrap());
+    machine.set_sender(parties.counter_party);
+
+    assert_eq!(
+        machine.constructor(zero().serialize(), parties.holder, true, 2300),
+        Err("Provided combinator contract does not match the generated contract.".to_string())
+    );
+    assert_eq!(machine.constructor(one().serialize(), parties.holder, true, 2300), Ok(()));
+}
+
+// Every generated contract is compiled to bytecode by solc 0.5, if installed (or given by the SOLC environment variable)
+#[test]
+fn generated_contracts_compile_with_solc() {
+    let solc = match find_solc() {
+        Some(solc) => solc,
+        None => {
+            println!("solc 0.5 is not installed, so the generated contracts are not compiled.");
+            return;
+        }
+    };
+
+    let definitions = template_definitions().into_iter().chain(combinator_definitions()).chain(random_definitions());
+    for definition in definitions {
+        // Definitions rejected by the financial smart contract have no generated contract
+        let serialized = definition.serialize();
+        let generated = match panic::catch_unwind(|| generate("Contract", &serialized)) {
+            Ok(Ok(generated)) => generated,
+            _ => continue
+        };
+
+        if let Err(message) = compile(&solc, &generated) {
+            panic!("Could not compile the contract generated for {:?}: {}", serialized, message);
+        }
+    }
+}
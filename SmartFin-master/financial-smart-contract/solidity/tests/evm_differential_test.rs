@@ -0,0 +1,243 @@
+// Differential tests of the generated contracts on a real EVM: the development blockchain (run-node.sh), which must be reachable
+// at localhost:8545 and have the financial smart contract built (build.sh). The generated contracts are compiled with solc 0.5,
+// which must be installed (or given by the SOLC environment variable). Run with cargo test -- --ignored.
+extern crate financial_smart_contract;
+extern crate financial_smart_contract_cli;
+extern crate financial_smart_contract_solidity;
+extern crate pwasm_std;
+
+use std::env;
+use std::fs;
+use std::time::{ SystemTime, UNIX_EPOCH };
+use pwasm_std::types::Address;
+use financial_smart_contract::templates;
+use financial_smart_contract::random::Random;
+use financial_smart_contract::builder::{ Definition, and, anytime, get, give, observable, one, or, scale, scale_by, schedule,
+    schedule_relative, then, truncate, truncate_relative, zero };
+use financial_smart_contract_cli::FinancialScClient;
+use financial_smart_contract_cli::abi::{ parse_address, Token };
+use financial_smart_contract_solidity::{ compile, find_solc, generate };
+
+// The JSON-RPC URL of the development blockchain
+const URL: &str = "http://localhost:8545";
+
+// The password of the development blockchain's accounts
+const PASSWORD: &str = "test";
+
+// The accounts of the development blockchain (the same as the JS client's tests)
+const HOLDER: &str = "0x057E231DaB35A789F5999056c8Ec775512609CBb";
+const COUNTER_PARTY: &str = "0x1e00c1c4f7c9C878e863E9B2acC374F0C2a0F742";
+const UNINVOLVED: &str = "0x37aC31b396F68051e2a5D148CaF2198Af45ac918";
+
+// The number of steps in each scenario, fewer than off-chain as each step is mined
+const SCENARIO_STEPS: usize = 12;
+
+// A day in seconds, keeping the definitions' times clear of the time taken by a scenario
+const DAY: u64 = 86400;
+
+// The development blockchain and its accounts, the counter-party also being the arbiter of every observable and the
+// uninvolved account the operator (it has no Ether to send transactions)
+struct Chain {
+    client: FinancialScClient,
+    holder: Address,
+    counter_party: Address,
+    uninvolved: Address,
+    wasm: Vec<u8>,
+    solc: String
+}
+
+// Connects to the development blockchain and unlocks the accounts
+fn connect() -> Chain {
+    let client = FinancialScClient::new(URL).unwrap();
+    let holder = parse_address(HOLDER).unwrap();
+    let counter_party = parse_address(COUNTER_PARTY).unwrap();
+    let uninvolved = parse_address(UNINVOLVED).unwrap();
+    for account in [holder, counter_party, uninvolved].iter() {
+        client.unlock_account(account, PASSWORD).unwrap();
+    }
+
+    let wasm = env::var("CONTRACT_WASM").unwrap_or_else(|_| "../contract/target/financial_smart_contract.wasm".to_string());
+    let wasm = fs::read(&wasm).expect("Contract must be built with build.sh.");
+    let solc = find_solc().expect("solc 0.5 must be installed.");
+    Chain { client, holder, counter_party, uninvolved, wasm, solc }
+}
+
+// Deploys the given code from the counter-party
+fn deploy(chain: &Chain, code: &[u8], definition: &[i64], use_gas: bool) -> Address {
+    // First deployment may fail
+    match chain.client.deploy(&chain.counter_party, code, definition, &chain.holder, use_gas, 2300) {
+        Ok((contract, _)) => contract,
+        Err(_) => chain.client.deploy(&chain.counter_party, code, definition, &chain.holder, use_gas, 2300).unwrap().0
+    }
+}
+
+// Gets the current UNIX time
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+// The state of a deployed contract compared after each step. Acquisition times are only compared as set or not, as the two
+// contracts' transactions are mined in different blocks.
+#[derive(Debug)]
+#[derive(PartialEq)]
+struct ContractState {
+    holder_balance: i64,
+    counter_party_balance: i64,
+    concluded: bool,
+    acquired: Vec<bool>,
+    or_choices: Vec<u8>,
+    obs_entries: Vec<i64>,
+    obs_submissions: Vec<Vec<i64>>,
+    operator_permissions: Vec<u64>
+}
+
+// Gets the state of the given contract
+fn contract_state(chain: &Chain, contract: &Address, obs_count: u64) -> ContractState {
+    let client = &chain.client;
+    let caller = &chain.holder;
+    ContractState {
+        holder_balance: client.get_balance(contract, caller, true).unwrap(),
+        counter_party_balance: client.get_balance(contract, caller, false).unwrap(),
+        concluded: client.get_concluded(contract, caller).unwrap(),
+        acquired: client.get_acquisition_times(contract, caller).unwrap().iter().map(|time| *time >= 0).collect(),
+        or_choices: client.get_or_choices(contract, caller).unwrap(),
+        obs_entries: client.get_obs_entries(contract, caller).unwrap(),
+        obs_submissions: (0..obs_count).map(|obs_index| client.get_obs_submissions(contract, caller, obs_index).unwrap()).collect(),
+        operator_permissions: [chain.holder, chain.counter_party].iter()
+            .map(|party| client.get_operator_permissions(contract, caller, party, &chain.uninvolved).unwrap())
+            .collect()
+    }
+}
+
+// Returns true with the given percentage chance
+fn chance(random: &mut Random, percent: u64) -> bool {
+    random.next_below(100) < percent
+}
+
+// Returns an index below the given count, or rarely the (out of bounds) count itself
+fn index(random: &mut Random, count: u64) -> u64 {
+    if count > 0 && chance(random, 90) {
+        random.next_below(count)
+    } else {
+        count
+    }
+}
+
+// Returns a value in the range [-5, 5]
+fn value(random: &mut Random) -> i64 {
+    random.next_below(11) as i64 - 5
+}
+
+// Deploys the financial smart contract and the generated contract compiled by solc for the given definition, and runs a random
+// scenario against both, checking after each step that both accepted (or rejected) the same transaction and are in the same
+// state. Rejected transactions are not mined, so the scenario carries on after them.
+fn run_scenario(chain: &Chain, definition: &Definition, seed: u64) {
+    let serialized = definition.serialize();
+    let generated = generate("Contract", &serialized).unwrap();
+    let bytecode = compile(&chain.solc, &generated).unwrap();
+
+    let mut random = Random::new(seed);
+    let use_gas = chance(&mut random, 50);
+    let contracts = [deploy(chain, &chain.wasm, &serialized, use_gas), deploy(chain, &bytecode, &serialized, use_gas)];
+
+    let or_count = generated.or_count as u64;
+    let obs_count = generated.observables.len() as u64;
+    let anytime_count = generated.anytime_count as u64;
+    for step in 0..SCENARIO_STEPS {
+        // Mostly use the party allowed to make each call
+        let sender = if chance(&mut random, 15) { chain.counter_party } else { chain.holder };
+        let mut value_sent = 0;
+        let (name, args) = match random.next_below(11) {
+            0 | 1 => ("acquire", Vec::new()),
+            2 | 3 => ("update", Vec::new()),
+            4 => ("set_or_choice", vec![Token::Uint(index(&mut random, or_count)), Token::Bool(chance(&mut random, 50))]),
+            5 => {
+                let or_indices: Vec<u64> = (0..or_count).filter(|_| chance(&mut random, 50)).collect();
+                let choices: Vec<Token> = or_indices.iter().map(|_| Token::Bool(chance(&mut random, 50))).collect();
+                let or_indices = or_indices.into_iter().map(Token::Uint).collect();
+                ("set_or_choices", vec![Token::Array(or_indices), Token::Array(choices), Token::Bool(chance(&mut random, 50))])
+            },
+            6 => ("set_obs_value", vec![Token::Uint(index(&mut random, obs_count)), Token::Int(value(&mut random))]),
+            7 => ("acquire_anytime_sub_contract", vec![Token::Uint(index(&mut random, anytime_count))]),
+            8 => {
+                value_sent = random.next_below(100);
+                ("stake", Vec::new())
+            },
+            9 => ("withdraw", vec![Token::Uint(random.next_below(20))]),
+            _ => if chance(&mut random, 80) {
+                ("approve_operator", vec![Token::Address(chain.uninvolved), Token::Uint(1 + random.next_below(15))])
+            } else {
+                ("revoke_operator", vec![Token::Address(chain.uninvolved)])
+            }
+        };
+        // Observable values are mostly set by their arbiter
+        let sender = if name == "set_obs_value" && chance(&mut random, 90) { chain.counter_party } else { sender };
+
+        let context = format!("after {} at step {} for {:?} (seed {})", name, step, serialized, seed);
+        let accepted: Vec<bool> = contracts.iter()
+            .map(|contract| chain.client.transact(contract, &sender, name, &args, value_sent).is_ok())
+            .collect();
+        assert_eq!(accepted[0], accepted[1], "Acceptance differs {}", context);
+        assert_eq!(
+            contract_state(chain, &contracts[0], obs_count),
+            contract_state(chain, &contracts[1], obs_count),
+            "State differs {}", context
+        );
+    }
+}
+
+// Gets the definitions of the templates, dated around the given time
+fn template_definitions(now: u64) -> Vec<Definition> {
+    let arbiter = parse_address(COUNTER_PARTY).unwrap();
+    vec![
+        templates::zero_coupon_bond(95, 100, now + DAY),
+        templates::coupon_bond(95, 100, 5, now - 60, DAY, 3).unwrap(),
+        templates::amortising_loan(1000, 100, now - 60, DAY, 2).unwrap(),
+        templates::european_call(2, 10, now + DAY, observable(arbiter, "price")),
+        templates::american_put(2, 10, now + DAY, observable(arbiter, "price")),
+        templates::forward(10, now + DAY, observable(arbiter, "price")),
+        templates::fixed_floating_swap(10, 3, now - 60, DAY, vec![observable(arbiter, "rate0"), observable(arbiter, "rate1")])
+    ]
+}
+
+// Gets hand-written definitions covering each combinator, dated around the given time
+fn combinator_definitions(now: u64) -> Vec<Definition> {
+    let arbiter = parse_address(COUNTER_PARTY).unwrap();
+    vec![
+        zero(),
+        one(),
+        and(one(), give(scale(2, one()))),
+        or(truncate(now + DAY, one()), scale_by(observable(arbiter, "x"), give(one()))),
+        then(truncate(now - 60, scale(3, one())), give(one())),
+        get(truncate(now + DAY, one())),
+        anytime(truncate(now + DAY, scale(4, one()))),
+        schedule(now - 60, DAY, 3, or(one(), give(one()))),
+        schedule(now - 60, 0, 2, anytime(truncate_relative(DAY, one()))),
+        schedule_relative(0, DAY, 3, and(scale(2, one()), anytime(truncate_relative(DAY, give(one())))))
+    ]
+}
+
+// The generated contracts of the templates behave as the financial smart contract on the EVM
+#[test]
+#[ignore]
+fn generated_contracts_match_templates_on_chain() {
+    let chain = connect();
+    for (i, definition) in template_definitions(unix_time()).iter().enumerate() {
+        for seed in 0..2 {
+            run_scenario(&chain, definition, (i * 1000) as u64 + seed);
+        }
+    }
+}
+
+// The generated contracts of hand-written definitions, covering each combinator, behave as the financial smart contract on
+// the EVM
+#[test]
+#[ignore]
+fn generated_contracts_match_combinators_on_chain() {
+    let chain = connect();
+    for (i, definition) in combinator_definitions(unix_time()).iter().enumerate() {
+        for seed in 0..2 {
+            run_scenario(&chain, definition, (i * 1000) as u64 + seed);
+        }
+    }
+}
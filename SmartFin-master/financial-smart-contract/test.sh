@@ -13,10 +13,32 @@ test-js() {
     cd -
 }
 
+test-cli() {
+    "./build.sh"
+    cd cli
+
+    # Unit tests, then the integration tests against the node
+    cargo test
+    cargo test -- --ignored
+    cd -
+}
+
+test-sol() {
+    "./build.sh"
+    cd solidity
+
+    # Differential tests against the off-chain machine, then against the EVM on the node
+    cargo test
+    cargo test -- --ignored
+    cd -
+}
+
 cd "$(dirname "$0")"
 
 RS_TEST=0
 JS_TEST=0
+CLI_TEST=0
+SOL_TEST=0
 NC_TEST=0
 
 while test $# -gt 0
@@ -26,6 +48,10 @@ do
             ;;
         -js) JS_TEST=1
             ;;
+        -cli) CLI_TEST=1
+            ;;
+        -sol) SOL_TEST=1
+            ;;
         -nc) NC_TEST=1
             ;;
     esac
@@ -47,9 +73,17 @@ else
     if [ $JS_TEST -eq 1 ]
     then
         test-js
+    elif [ $CLI_TEST -eq 1 ]
+    then
+        test-cli
+    elif [ $SOL_TEST -eq 1 ]
+    then
+        test-sol
     else
         test-rs
         test-js
+        test-cli
+        test-sol
     fi
 
     # Kill blockchain node if required
@@ -0,0 +1,364 @@
+use std::collections::BTreeSet;
+use pwasm_std::types::Address;
+use financial_smart_contract::{ FinancialScContract, Combinator, ContractCombinator, AssetAmounts,
+    or_choices_key, obs_values_key, anytime_acquisition_times_key };
+use financial_smart_contract::storage::{ Storage, StoresFixedVec };
+
+// A scenario for a contract: when it is acquired, the or choices and observable values provided, and when anytime
+// combinators' sub-combinators are acquired. Choices and values not given are never provided, as on-chain.
+pub struct Scenario {
+    // The time the contract is acquired
+    pub acquisition_time: u64,
+
+    // The time up to which the contract is updated
+    pub end_time: u64,
+
+    // The or choice of each or combinator, indexed by or index (true for the first sub-combinator)
+    pub or_choices: Vec<Option<bool>>,
+
+    // The value of each observable, indexed by observable index
+    pub obs_values: Vec<Option<i64>>,
+
+    // The time each anytime combinator's sub-combinator is acquired, indexed by anytime index (by default at its horizon)
+    pub anytime_acquisition_times: Vec<Option<u64>>
+}
+
+// A party to the contract
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Party {
+    Holder,
+    CounterParty
+}
+
+// A payment made by the contract
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct CashFlow {
+    // The time the payment is made
+    pub date: u64,
+
+    // The party paying
+    pub payer: Party,
+
+    // The party paid
+    pub payee: Party,
+
+    // The asset paid, 0 for Ether and otherwise one more than the token's index in the contract's assets
+    pub asset: usize,
+
+    // The amount paid (in wei for Ether)
+    pub amount: i64,
+
+    // The pre-order index of the combinator causing the payment in the contract, a one combinator or (for payments within
+    // a schedule's periods) a schedule combinator
+    pub node: usize,
+
+    // The type of the combinator causing the payment
+    pub combinator: Combinator
+}
+
+// Method implementation of parties
+impl Party {
+    // Gets the name of the party
+    pub fn name(&self) -> &'static str {
+        match self {
+            Party::Holder => "holder",
+            Party::CounterParty => "counter-party"
+        }
+    }
+}
+
+// Gets the payments made by the given contract definition in the given scenario, in order. The contract is acquired and
+// updated at every date where a payment may be made, exactly as the contract does on-chain.
+pub fn get_cash_flows(contract_definition: &Vec<i64>, scenario: &Scenario) -> Vec<CashFlow> {
+    let (mut combinator, mut storage) = FinancialScContract::deserialize_definition(contract_definition);
+    provide_scenario(scenario, &mut storage);
+
+    combinator.resolve_relative_times(scenario.acquisition_time);
+    combinator.acquire(scenario.acquisition_time, &mut storage);
+
+    let mut dates: BTreeSet<u64> = BTreeSet::new();
+    dates.insert(scenario.acquisition_time);
+    collect_dates(&combinator, &mut dates);
+    for acquisition_time in &scenario.anytime_acquisition_times {
+        if let Some(acquisition_time) = acquisition_time {
+            dates.insert(*acquisition_time);
+        }
+    }
+
+    let mut cash_flows: Vec<CashFlow> = Vec::new();
+    let mut accrued = get_accrued_by_node(&combinator, scenario, &mut storage);
+    for date in dates.range(scenario.acquisition_time..(scenario.end_time.saturating_add(1))).cloned() {
+        let mut difference = combinator.update(date, &mut storage);
+        if acquire_anytime_sub_combinators(scenario, date, &mut storage) {
+            difference += combinator.update(date, &mut storage);
+        }
+        let new_accrued = get_accrued_by_node(&combinator, scenario, &mut storage);
+
+        let mut total = AssetAmounts::zero();
+        for ((node, combinator_number, node_accrued), (_, _, previous_accrued)) in new_accrued.into_iter().zip(accrued.iter()) {
+            let node_difference = node_accrued.clone() + -previous_accrued.clone();
+            for (asset, amount) in node_difference.get_amounts().iter().enumerate() {
+                if *amount == 0 {
+                    continue;
+                }
+                let (payer, payee) = if *amount > 0 { (Party::CounterParty, Party::Holder) } else { (Party::Holder, Party::CounterParty) };
+                cash_flows.push(CashFlow {
+                    date,
+                    payer,
+                    payee,
+                    asset,
+                    amount: amount.abs(),
+                    node,
+                    combinator: Combinator::from(combinator_number)
+                });
+            }
+            total += node_difference;
+        }
+        if total != difference {
+            panic!("Cash flows do not match the balance changes of the contract.");
+        }
+
+        accrued = get_accrued_by_node(&combinator, scenario, &mut storage);
+    }
+    cash_flows
+}
+
+// Exports cash flows as CSV, with a header row
+pub fn to_csv(cash_flows: &Vec<CashFlow>) -> String {
+    let mut csv = String::from("date,payer,payee,asset,amount,node,combinator\n");
+    for cash_flow in cash_flows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{:?}\n",
+            cash_flow.date,
+            cash_flow.payer.name(),
+            cash_flow.payee.name(),
+            cash_flow.asset,
+            cash_flow.amount,
+            cash_flow.node,
+            cash_flow.combinator
+        ));
+    }
+    csv
+}
+
+// Exports cash flows as a JSON array of objects
+pub fn to_json(cash_flows: &Vec<CashFlow>) -> String {
+    let entries: Vec<String> = cash_flows.iter().map(|cash_flow| format!(
+        "{{\"date\":{},\"payer\":\"{}\",\"payee\":\"{}\",\"asset\":{},\"amount\":{},\"node\":{},\"combinator\":\"{:?}\"}}",
+        cash_flow.date,
+        cash_flow.payer.name(),
+        cash_flow.payee.name(),
+        cash_flow.asset,
+        cash_flow.amount,
+        cash_flow.node,
+        cash_flow.combinator
+    )).collect();
+    format!("[{}]", entries.join(","))
+}
+
+// Provides the scenario's or choices and observable values to the contract's storage, checking every choice and value given
+// (including anytime acquisition times) belongs to a combinator in the contract
+fn provide_scenario(scenario: &Scenario, storage: &mut Storage) {
+    for (or_index, or_choice) in scenario.or_choices.iter().enumerate() {
+        if or_index >= StoresFixedVec::<Option<bool>>::length(storage, &or_choices_key()) {
+            panic!("Or choice given for non-existent or combinator {}.", or_index);
+        }
+        storage.set(&or_choices_key(), or_index, *or_choice);
+    }
+
+    for (obs_index, obs_value) in scenario.obs_values.iter().enumerate() {
+        if obs_index >= StoresFixedVec::<(Address, Option<i64>)>::length(storage, &obs_values_key()) {
+            panic!("Value given for non-existent observable {}.", obs_index);
+        }
+        let (arbiter, _) = StoresFixedVec::<(Address, Option<i64>)>::get(storage, &obs_values_key(), obs_index);
+        storage.set(&obs_values_key(), obs_index, (arbiter, *obs_value));
+    }
+
+    for anytime_index in 0..scenario.anytime_acquisition_times.len() {
+        if anytime_index >= StoresFixedVec::<(bool, Option<u64>)>::length(storage, &anytime_acquisition_times_key()) {
+            panic!("Acquisition time given for non-existent anytime combinator {}.", anytime_index);
+        }
+    }
+}
+
+// Acquires the sub-combinators of the anytime combinators the scenario acquires at the given date, as the holder does through
+// the contract, returns whether any were acquired
+fn acquire_anytime_sub_combinators(scenario: &Scenario, date: u64, storage: &mut Storage) -> bool {
+    let mut acquired = false;
+    for (anytime_index, acquisition_time) in scenario.anytime_acquisition_times.iter().enumerate() {
+        if *acquisition_time != Some(date) {
+            continue;
+        }
+
+        let anytime_state: (bool, Option<u64>) = storage.get(&anytime_acquisition_times_key(), anytime_index);
+        if !anytime_state.0 {
+            panic!("Anytime combinator {} has not been acquired by {}.", anytime_index, date);
+        }
+        if anytime_state.1 != None && anytime_state.1.unwrap() <= date {
+            panic!("Anytime combinator {} has already acquired its sub-combinator by {}.", anytime_index, date);
+        }
+
+        storage.set(&anytime_acquisition_times_key(), anytime_index, (true, Some(date)));
+        acquired = true;
+    }
+    acquired
+}
+
+// Gets the value paid out so far by each one and schedule combinator (from the holder's point of view), in pre-order
+fn get_accrued_by_node(combinator: &Box<ContractCombinator>, scenario: &Scenario, storage: &mut Storage) -> Vec<(usize, i64, AssetAmounts)> {
+    let mut accrued: Vec<(usize, i64, AssetAmounts)> = Vec::new();
+    let mut node = 0;
+    collect_accrued(combinator, 1, true, scenario, storage, &mut node, &mut accrued);
+    accrued
+}
+
+// Collects the value paid out so far by each one and schedule combinator in the given combinator, multiplied by the factor
+// from its ancestors. Combinators within a schedule are counted but not collected, as the schedule accrues their payments.
+fn collect_accrued(combinator: &Box<ContractCombinator>, factor: i64, collect: bool, scenario: &Scenario, storage: &mut Storage,
+    node: &mut usize, accrued: &mut Vec<(usize, i64, AssetAmounts)>) {
+    let index = *node;
+    *node += 1;
+
+    let (sub_factor, sub_collect) = match combinator.get_combinator_number() {
+        Combinator::ONE | Combinator::ONE_ASSET | Combinator::SCHEDULE | Combinator::SCHEDULE_RELATIVE => {
+            if collect {
+                accrued.push((index, i64::from(combinator.get_combinator_number()), combinator.get_accrued(storage) * factor));
+            }
+            (factor, false)
+        },
+        Combinator::GIVE => (-factor, collect),
+        Combinator::SCALE => {
+            let scale_value = match combinator.get_index() {
+                Some(obs_index) => scenario.obs_values.get(obs_index).cloned().unwrap_or(None).unwrap_or(0),
+                None => combinator.get_parameters()[0]
+            };
+            (factor * scale_value, collect)
+        },
+        _ => (factor, collect)
+    };
+
+    for sub_combinator in combinator.get_sub_combinators() {
+        collect_accrued(sub_combinator, sub_factor, sub_collect, scenario, storage, node, accrued);
+    }
+}
+
+// Collects every date at which a payment may be made: each horizon (when get and anytime combinators acquire their
+// sub-combinators) and each schedule period date
+fn collect_dates(combinator: &Box<ContractCombinator>, dates: &mut BTreeSet<u64>) {
+    if let Some(horizon) = combinator.get_horizon() {
+        dates.insert(horizon);
+    }
+    match combinator.get_combinator_number() {
+        Combinator::SCHEDULE | Combinator::SCHEDULE_RELATIVE => {
+            let parameters = combinator.get_parameters();
+            let (start, period, count) = (parameters[0] as u64, parameters[1] as u64, parameters[2] as u64);
+            for period_index in 0..count {
+                dates.insert(start.saturating_add(period.saturating_mul(period_index)));
+            }
+        },
+        _ => ()
+    }
+    for sub_combinator in combinator.get_sub_combinators() {
+        collect_dates(sub_combinator, dates);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use financial_smart_contract::builder::*;
+    use financial_smart_contract::templates;
+
+    fn scenario(acquisition_time: u64, end_time: u64) -> Scenario {
+        Scenario {
+            acquisition_time,
+            end_time,
+            or_choices: vec![],
+            obs_values: vec![],
+            anytime_acquisition_times: vec![]
+        }
+    }
+
+    fn cash_flow(date: u64, payer: Party, amount: i64, node: usize, combinator: Combinator) -> CashFlow {
+        let payee = if payer == Party::Holder { Party::CounterParty } else { Party::Holder };
+        CashFlow { date, payer, payee, asset: 0, amount, node, combinator }
+    }
+
+    // A bond pays the price on acquisition and the face value at maturity, each attributed to its one combinator
+    #[test]
+    fn zero_coupon_bond_cash_flows_correct() {
+        let cash_flows = get_cash_flows(&templates::zero_coupon_bond(90, 100, 50).serialize(), &scenario(10, 100));
+        assert_eq!(cash_flows, vec![
+            cash_flow(10, Party::Holder, 90, 3, Combinator::ONE),
+            cash_flow(50, Party::CounterParty, 100, 7, Combinator::ONE)
+        ]);
+    }
+
+    // Cash flows stop at the end time
+    #[test]
+    fn cash_flows_end_at_end_time() {
+        let cash_flows = get_cash_flows(&templates::zero_coupon_bond(90, 100, 50).serialize(), &scenario(10, 49));
+        assert_eq!(cash_flows.len(), 1);
+    }
+
+    // Payments within a schedule's periods are attributed to the schedule
+    #[test]
+    fn schedule_cash_flows_correct() {
+        let cash_flows = get_cash_flows(&schedule(0, 10, 3, scale(2, one())).serialize(), &scenario(5, 100));
+        assert_eq!(cash_flows, vec![
+            cash_flow(10, Party::CounterParty, 2, 0, Combinator::SCHEDULE),
+            cash_flow(20, Party::CounterParty, 2, 0, Combinator::SCHEDULE)
+        ]);
+    }
+
+    // Or choices, observable values and anytime acquisition times follow the scenario
+    #[test]
+    fn scenario_choices_followed() {
+        let price = observable("3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap(), "price");
+        let definition = and(
+            anytime(truncate(50, or(scale_by(price, one()), give(scale(3, one()))))),
+            get(truncate(60, one()))
+        ).serialize();
+
+        let mut choices = scenario(0, 100);
+        choices.or_choices = vec![Some(false)];
+        choices.obs_values = vec![Some(7)];
+        choices.anytime_acquisition_times = vec![Some(20)];
+        assert_eq!(get_cash_flows(&definition, &choices), vec![
+            cash_flow(20, Party::Holder, 3, 8, Combinator::ONE),
+            cash_flow(60, Party::CounterParty, 1, 11, Combinator::ONE)
+        ]);
+
+        choices.or_choices = vec![Some(true)];
+        assert_eq!(get_cash_flows(&definition, &choices)[0], cash_flow(20, Party::CounterParty, 7, 5, Combinator::ONE));
+    }
+
+    // Without an or choice, the or combinator pays nothing
+    #[test]
+    fn missing_or_choice_pays_nothing() {
+        let cash_flows = get_cash_flows(&or(one(), give(one())).serialize(), &scenario(0, 100));
+        assert_eq!(cash_flows, vec![]);
+    }
+
+    // Cash flows are exported with one row per payment
+    #[test]
+    fn export_correct() {
+        let cash_flows = get_cash_flows(&templates::zero_coupon_bond(90, 100, 50).serialize(), &scenario(10, 100));
+        assert_eq!(
+            to_csv(&cash_flows),
+            "date,payer,payee,asset,amount,node,combinator\n10,holder,counter-party,0,90,3,ONE\n50,counter-party,holder,0,100,7,ONE\n"
+        );
+        assert_eq!(
+            to_json(&cash_flows),
+            concat!(
+                "[{\"date\":10,\"payer\":\"holder\",\"payee\":\"counter-party\",\"asset\":0,\"amount\":90,\"node\":3,\"combinator\":\"ONE\"},",
+                "{\"date\":50,\"payer\":\"counter-party\",\"payee\":\"holder\",\"asset\":0,\"amount\":100,\"node\":7,\"combinator\":\"ONE\"}]"
+            )
+        );
+    }
+}
@@ -0,0 +1,51 @@
+// Closed-form Black-Scholes prices of European options, for checking numerical valuations
+
+// The price of a European call with the given spot, strike, annual interest rate, annual volatility and years to expiry
+pub fn black_scholes_call(spot: f64, strike: f64, interest_rate: f64, volatility: f64, years: f64) -> f64 {
+    let (d1, d2) = black_scholes_d(spot, strike, interest_rate, volatility, years);
+    spot * normal_cdf(d1) - strike * (-interest_rate * years).exp() * normal_cdf(d2)
+}
+
+// The price of a European put with the given spot, strike, annual interest rate, annual volatility and years to expiry
+pub fn black_scholes_put(spot: f64, strike: f64, interest_rate: f64, volatility: f64, years: f64) -> f64 {
+    let (d1, d2) = black_scholes_d(spot, strike, interest_rate, volatility, years);
+    strike * (-interest_rate * years).exp() * normal_cdf(-d2) - spot * normal_cdf(-d1)
+}
+
+// The d1 and d2 terms of the Black-Scholes formula
+fn black_scholes_d(spot: f64, strike: f64, interest_rate: f64, volatility: f64, years: f64) -> (f64, f64) {
+    let d1 = ((spot / strike).ln() + (interest_rate + 0.5 * volatility * volatility) * years) / (volatility * years.sqrt());
+    (d1, d1 - volatility * years.sqrt())
+}
+
+// The standard normal cumulative distribution function (Abramowitz and Stegun 26.2.17, absolute error below 7.5e-8)
+pub fn normal_cdf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.231_641_9 * x.abs());
+    let polynomial = t * (0.319_381_530 + t * (-0.356_563_782 + t * (1.781_477_937 + t * (-1.821_255_978 + t * 1.330_274_429))));
+    let tail = (-0.5 * x * x).exp() / (2.0 * ::std::f64::consts::PI).sqrt() * polynomial;
+    if x >= 0.0 { 1.0 - tail } else { tail }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The normal distribution function matches known values
+    #[test]
+    fn normal_cdf_correct() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975_002).abs() < 1e-6);
+        assert!((normal_cdf(-1.0) - 0.158_655).abs() < 1e-6);
+    }
+
+    // Prices match a known value, and satisfy put-call parity
+    #[test]
+    fn black_scholes_correct() {
+        let call = black_scholes_call(100.0, 100.0, 0.05, 0.2, 1.0);
+        let put = black_scholes_put(100.0, 100.0, 0.05, 0.2, 1.0);
+
+        assert!((call - 10.4506).abs() < 1e-4, "Call price is {}", call);
+        assert!((call - put - (100.0 - 100.0 * (-0.05_f64).exp())).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::BTreeSet;
+use financial_smart_contract::{ FinancialScContract, Combinator, ContractCombinator, ETHER_ASSET };
+
+// The most observables whose range corners are searched, each observable doubles the search
+const MAX_OBSERVABLES: usize = 16;
+
+// The bounds on the total payout to each party of a contract acquired at a given time. Bounds are taken over every choice
+// either party could make (or choices and anytime exercise times) and every observable value within the declared ranges.
+#[derive(Debug)]
+pub struct Exposure {
+    // The total payout bounds of the holder
+    pub holder: Bounds,
+
+    // The total payout bounds of the counter-party
+    pub counter_party: Bounds
+}
+
+// The minimum and maximum total payout to a party, each with a path achieving it
+#[derive(Debug)]
+pub struct Bounds {
+    pub minimum: Outcome,
+    pub maximum: Outcome
+}
+
+// A total payout to a party (in wei, negative when the party pays out), and the path leading to it
+#[derive(Debug)]
+pub struct Outcome {
+    pub payout: i64,
+    pub path: Path
+}
+
+// The observable values and choices leading to an outcome
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Path {
+    // The value of each observable, indexed by observable index
+    pub observables: Vec<i64>,
+
+    // The choices made, in the order the combinators acquiring them appear in the contract
+    pub decisions: Vec<Decision>
+}
+
+// A choice made by the holder
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Decision {
+    // The or combinator with the given or index, acquired at the given time, chose its first sub-combinator or not
+    Or { or_index: usize, time: u64, first: bool },
+
+    // The anytime combinator with the given anytime index, acquired at the given time, acquired its sub-combinator at the
+    // exercise time
+    Anytime { anytime_index: usize, time: u64, exercise_time: u64 }
+}
+
+// Analyses the bounds on the total payout to each party if the given contract definition is acquired at the given time, with
+// each observable's value between the given (minimum, maximum) range. The payout is exact when each or and anytime combinator
+// is acquired once; or choices inside a schedule are made per period, so the bounds are conservative if an optimal choice
+// differs between periods.
+pub fn analyse_exposure(contract_definition: &Vec<i64>, acquisition_time: u64, ranges: &Vec<(i64, i64)>) -> Exposure {
+    let (mut combinator, _) = FinancialScContract::deserialize_definition(contract_definition);
+    combinator.resolve_relative_times(acquisition_time);
+    if combinator.past_horizon(acquisition_time) {
+        panic!("Cannot acquire an expired contract.");
+    }
+
+    let observable_count = get_observable_count(&combinator);
+    if observable_count > MAX_OBSERVABLES {
+        panic!("Cannot analyse the exposure of a contract with more than {} observables.", MAX_OBSERVABLES);
+    }
+    for obs_index in 0..observable_count {
+        if obs_index >= ranges.len() {
+            panic!("No range given for observable {}.", obs_index);
+        }
+        if ranges[obs_index].0 > ranges[obs_index].1 {
+            panic!("Range of observable {} is empty.", obs_index);
+        }
+    }
+
+    // Every change in behaviour happens at or just after a contract date, so these are the only exercise times worth trying
+    let mut dates: BTreeSet<u64> = BTreeSet::new();
+    dates.insert(acquisition_time);
+    collect_dates(&combinator, &mut dates);
+
+    // The payout is linear in each observable for any fixed choices, so each bound is achieved at a corner of the ranges
+    let mut minimum: Option<Outcome> = None;
+    let mut maximum: Option<Outcome> = None;
+    for corner in 0..(1usize << observable_count) {
+        let observables: Vec<i64> = (0..observable_count)
+            .map(|obs_index| if corner & (1 << obs_index) == 0 { ranges[obs_index].0 } else { ranges[obs_index].1 })
+            .collect();
+
+        for maximise in vec![false, true] {
+            let mut decisions: Vec<Decision> = Vec::new();
+            let payout = bound(&combinator, acquisition_time, &observables, maximise, &dates, &mut decisions);
+            let best = if maximise { &mut maximum } else { &mut minimum };
+            let improves = match best {
+                Some(outcome) => if maximise { payout > outcome.payout } else { payout < outcome.payout },
+                None => true
+            };
+            if improves {
+                *best = Some(Outcome {
+                    payout,
+                    path: Path {
+                        observables: observables.clone(),
+                        decisions
+                    }
+                });
+            }
+        }
+    }
+
+    let (minimum, maximum) = (minimum.unwrap(), maximum.unwrap());
+    Exposure {
+        counter_party: Bounds {
+            minimum: Outcome { payout: -maximum.payout, path: maximum.path.clone() },
+            maximum: Outcome { payout: -minimum.payout, path: minimum.path.clone() }
+        },
+        holder: Bounds {
+            minimum,
+            maximum
+        }
+    }
+}
+
+// Gets the bound on the holder's total payout from the given combinator acquired at the given time, with the given observable
+// values. Pushes the decisions achieving the bound.
+fn bound(combinator: &Box<ContractCombinator>, time: u64, observables: &Vec<i64>, maximise: bool, dates: &BTreeSet<u64>,
+    decisions: &mut Vec<Decision>) -> i64 {
+    // Expired combinators cannot be acquired, so pay nothing
+    if combinator.past_horizon(time) {
+        return 0;
+    }
+
+    let sub_combinators = combinator.get_sub_combinators();
+    match combinator.get_combinator_number() {
+        Combinator::ZERO => 0,
+        Combinator::ONE | Combinator::ONE_ASSET => {
+            if combinator.get_parameters()[0] != ETHER_ASSET as i64 {
+                panic!("Only Ether-denominated contracts can be analysed.");
+            }
+            1
+        },
+        Combinator::AND => {
+            let payout0 = bound(sub_combinators[0], time, observables, maximise, dates, decisions);
+            let payout1 = bound(sub_combinators[1], time, observables, maximise, dates, decisions);
+            checked(payout0.checked_add(payout1))
+        },
+        Combinator::OR => {
+            // An expired branch cannot be chosen
+            if sub_combinators[0].past_horizon(time) {
+                return bound(sub_combinators[1], time, observables, maximise, dates, decisions);
+            }
+            if sub_combinators[1].past_horizon(time) {
+                return bound(sub_combinators[0], time, observables, maximise, dates, decisions);
+            }
+
+            let mut decisions0: Vec<Decision> = Vec::new();
+            let mut decisions1: Vec<Decision> = Vec::new();
+            let payout0 = bound(sub_combinators[0], time, observables, maximise, dates, &mut decisions0);
+            let payout1 = bound(sub_combinators[1], time, observables, maximise, dates, &mut decisions1);
+            let first = if maximise { payout0 >= payout1 } else { payout0 <= payout1 };
+
+            decisions.push(Decision::Or { or_index: combinator.get_index().unwrap(), time, first });
+            decisions.append(if first { &mut decisions0 } else { &mut decisions1 });
+            if first { payout0 } else { payout1 }
+        },
+        Combinator::TRUNCATE | Combinator::TRUNCATE_RELATIVE => bound(sub_combinators[0], time, observables, maximise, dates, decisions),
+        Combinator::SCALE => {
+            let scale_value = match combinator.get_index() {
+                Some(obs_index) => observables[obs_index],
+                None => combinator.get_parameters()[0]
+            };
+            // Scaling by a negative value swaps the sub-combinator's bounds
+            let sub_maximise = if scale_value < 0 { !maximise } else { maximise };
+            checked(scale_value.checked_mul(bound(sub_combinators[0], time, observables, sub_maximise, dates, decisions)))
+        },
+        Combinator::GIVE => {
+            // The holder's best payout from a given combinator is the worst payout of its sub-combinator, and vice versa
+            checked(bound(sub_combinators[0], time, observables, !maximise, dates, decisions).checked_neg())
+        },
+        Combinator::THEN => {
+            let sub_combinator = if !sub_combinators[0].past_horizon(time) { sub_combinators[0] } else { sub_combinators[1] };
+            bound(sub_combinator, time, observables, maximise, dates, decisions)
+        },
+        Combinator::GET => match sub_combinators[0].get_horizon() {
+            Some(horizon) => bound(sub_combinators[0], horizon, observables, maximise, dates, decisions),
+            None => 0
+        },
+        Combinator::ANYTIME => {
+            // The sub-combinator is acquired at its horizon at the latest
+            let horizon = sub_combinators[0].get_horizon();
+            let mut best: Option<(i64, u64, Vec<Decision>)> = None;
+            for exercise_time in dates.range(time..).cloned() {
+                if horizon.map_or(false, |horizon| exercise_time > horizon) {
+                    break;
+                }
+
+                let mut exercise_decisions: Vec<Decision> = Vec::new();
+                let payout = bound(sub_combinators[0], exercise_time, observables, maximise, dates, &mut exercise_decisions);
+                let improves = match best {
+                    Some((best_payout, _, _)) => if maximise { payout > best_payout } else { payout < best_payout },
+                    None => true
+                };
+                if improves {
+                    best = Some((payout, exercise_time, exercise_decisions));
+                }
+            }
+
+            let (payout, exercise_time, mut exercise_decisions) = best.unwrap();
+            decisions.push(Decision::Anytime { anytime_index: combinator.get_index().unwrap(), time, exercise_time });
+            decisions.append(&mut exercise_decisions);
+            payout
+        },
+        Combinator::SCHEDULE | Combinator::SCHEDULE_RELATIVE => {
+            let mut payout: i64 = 0;
+            for date in get_period_dates(combinator) {
+                if date < time {
+                    continue;
+                }
+                if sub_combinators[0].past_horizon(date) {
+                    break;
+                }
+                payout = checked(payout.checked_add(bound(sub_combinators[0], date, observables, maximise, dates, decisions)));
+            }
+            payout
+        },
+        Combinator::CUSTOM(_) => panic!("Custom combinators cannot be analysed.")
+    }
+}
+
+// Collects every time at which a combinator's behaviour can change: each horizon and schedule period date, and just after
+fn collect_dates(combinator: &Box<ContractCombinator>, dates: &mut BTreeSet<u64>) {
+    let mut combinator_dates: Vec<u64> = Vec::new();
+    if let Some(horizon) = combinator.get_horizon() {
+        combinator_dates.push(horizon);
+    }
+    match combinator.get_combinator_number() {
+        Combinator::SCHEDULE | Combinator::SCHEDULE_RELATIVE => combinator_dates.append(&mut get_period_dates(combinator)),
+        _ => ()
+    }
+    for date in combinator_dates {
+        dates.insert(date);
+        dates.insert(date.saturating_add(1));
+    }
+
+    for sub_combinator in combinator.get_sub_combinators() {
+        collect_dates(sub_combinator, dates);
+    }
+}
+
+// Gets the number of observables in the combinator (one more than the largest observable index)
+fn get_observable_count(combinator: &Box<ContractCombinator>) -> usize {
+    let mut count = match (combinator.get_combinator_number(), combinator.get_index()) {
+        (Combinator::SCALE, Some(obs_index)) => obs_index + 1,
+        _ => 0
+    };
+    for sub_combinator in combinator.get_sub_combinators() {
+        count = count.max(get_observable_count(sub_combinator));
+    }
+    count
+}
+
+// Gets the period dates of a schedule combinator
+fn get_period_dates(combinator: &Box<ContractCombinator>) -> Vec<u64> {
+    let parameters = combinator.get_parameters();
+    let (start, period, count) = (parameters[0] as u64, parameters[1] as u64, parameters[2] as u64);
+    (0..count).map(|period_index| start.saturating_add(period.saturating_mul(period_index))).collect()
+}
+
+// Unwraps the result of checked payout arithmetic
+fn checked(payout: Option<i64>) -> i64 {
+    match payout {
+        Some(payout) => payout,
+        None => panic!("Payout overflows.")
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use financial_smart_contract::builder::*;
+    use financial_smart_contract::templates;
+    use test_utils::underlying;
+
+    // A contract without choices or observables has a single payout
+    #[test]
+    fn fixed_contract_exposure_correct() {
+        let exposure = analyse_exposure(&templates::zero_coupon_bond(90, 100, 50).serialize(), 0, &vec![]);
+        assert_eq!(exposure.holder.minimum.payout, 10);
+        assert_eq!(exposure.holder.maximum.payout, 10);
+        assert_eq!(exposure.counter_party.minimum.payout, -10);
+        assert_eq!(exposure.counter_party.maximum.payout, -10);
+    }
+
+    // Each bound takes the or choice which is worst for the other party
+    #[test]
+    fn or_choices_adversarial() {
+        let exposure = analyse_exposure(&or(scale(5, one()), give(scale(3, one()))).serialize(), 0, &vec![]);
+        assert_eq!(exposure.holder.maximum.payout, 5);
+        assert_eq!(exposure.holder.maximum.path.decisions, vec![Decision::Or { or_index: 0, time: 0, first: true }]);
+        assert_eq!(exposure.holder.minimum.payout, -3);
+        assert_eq!(exposure.counter_party.maximum.payout, 3);
+        assert_eq!(exposure.counter_party.maximum.path.decisions, vec![Decision::Or { or_index: 0, time: 0, first: false }]);
+    }
+
+    // Observables take the value in their range which is worst for the other party
+    #[test]
+    fn observable_ranges_respected() {
+        let definition = templates::european_call(2, 100, 50, underlying()).serialize();
+        let exposure = analyse_exposure(&definition, 0, &vec![(80, 120)]);
+
+        assert_eq!(exposure.holder.maximum.payout, 18);
+        assert_eq!(exposure.holder.maximum.path.observables, vec![120]);
+        assert_eq!(exposure.counter_party.maximum.payout, 22);
+        assert_eq!(exposure.counter_party.maximum.path, Path {
+            observables: vec![80],
+            decisions: vec![Decision::Or { or_index: 0, time: 50, first: true }]
+        });
+    }
+
+    // Each observable takes its own value, which the path reports
+    #[test]
+    fn observables_chosen_independently() {
+        let definition = and(scale_by(underlying(), one()), give(scale_by(underlying(), one()))).serialize();
+        let exposure = analyse_exposure(&definition, 0, &vec![(-10, 10), (-10, 10)]);
+
+        assert_eq!(exposure.holder.maximum.payout, 20);
+        assert_eq!(exposure.holder.maximum.path.observables, vec![10, -10]);
+        assert_eq!(exposure.holder.minimum.payout, -20);
+        assert_eq!(exposure.holder.minimum.path.observables, vec![-10, 10]);
+    }
+
+    // Scaling by a negative observable value swaps which choice is best
+    #[test]
+    fn negative_scale_swaps_bounds() {
+        let definition = scale_by(underlying(), or(scale(2, one()), give(one()))).serialize();
+        let exposure = analyse_exposure(&definition, 0, &vec![(-3, 1)]);
+
+        assert_eq!(exposure.holder.maximum.payout, 3);
+        assert_eq!(exposure.holder.maximum.path, Path {
+            observables: vec![-3],
+            decisions: vec![Decision::Or { or_index: 0, time: 0, first: false }]
+        });
+        assert_eq!(exposure.holder.minimum.payout, -6);
+    }
+
+    // Anytime combinators are exercised at the time worst for the other party
+    #[test]
+    fn anytime_exercise_time_adversarial() {
+        let definition = anytime(truncate(50, then(truncate(10, scale(2, one())), one()))).serialize();
+        let exposure = analyse_exposure(&definition, 0, &vec![]);
+
+        assert_eq!(exposure.holder.maximum.payout, 2);
+        assert_eq!(exposure.holder.maximum.path.decisions, vec![Decision::Anytime { anytime_index: 0, time: 0, exercise_time: 0 }]);
+        assert_eq!(exposure.holder.minimum.payout, 1);
+        assert_eq!(exposure.holder.minimum.path.decisions, vec![Decision::Anytime { anytime_index: 0, time: 0, exercise_time: 11 }]);
+    }
+
+    // A schedule pays every period from the acquisition time
+    #[test]
+    fn schedule_exposure_correct() {
+        let exposure = analyse_exposure(&schedule(0, 10, 4, scale(2, one())).serialize(), 15, &vec![]);
+        assert_eq!(exposure.holder.maximum.payout, 4);
+    }
+
+    // Every observable must be given a range
+    #[test]
+    #[should_panic(expected = "No range given for observable 0.")]
+    fn should_panic_if_observable_has_no_range() {
+        analyse_exposure(&scale_by(underlying(), one()).serialize(), 0, &vec![]);
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::BTreeSet;
+use financial_smart_contract::{ FinancialScContract, Combinator, ContractCombinator, ETHER_ASSET };
+use models::{ Market, ObservableModel, SECONDS_PER_YEAR };
+
+// A value process: the value of a combinator (in money at that date) if acquired at each node, indexed by level and node
+type Process = Vec<Vec<f64>>;
+
+// Binomial lattice valuation of a combinator contract, following the value processes of Peyton Jones, Eber and Seward. The
+// holder takes the more valuable branch of each or combinator, and exercises each anytime combinator optimally.
+// At most one observable may be stochastic (geometric Brownian motion), any others must be fixed curves.
+pub struct Lattice {
+    // The time between two consecutive lattice dates. Contract dates between lattice dates are moved back to the previous one.
+    pub step: u64
+}
+
+// The lattice dates and the value of each observable at each node
+struct Tree {
+    // The first lattice date (the valuation time)
+    valuation_time: u64,
+
+    // The time between two consecutive lattice dates
+    step: u64,
+
+    // The index of the last lattice date
+    levels: usize,
+
+    // The factor discounting a value from one lattice date to the previous one
+    discount: f64,
+
+    // The value of each observable at each node, indexed by observable index, level and node
+    observables: Vec<Process>
+}
+
+// Method implementation of lattice valuation
+impl Lattice {
+    // Instantiates a new lattice valuation
+    pub fn new(step: u64) -> Lattice {
+        if step == 0 {
+            panic!("Lattice step must be positive.");
+        }
+
+        Lattice {
+            step
+        }
+    }
+
+    // Values the given contract definition if acquired at the market's valuation time
+    pub fn value(&self, contract_definition: &Vec<i64>, market: &Market) -> f64 {
+        let (mut combinator, _) = FinancialScContract::deserialize_definition(contract_definition);
+        combinator.resolve_relative_times(market.valuation_time);
+        if combinator.past_horizon(market.valuation_time) {
+            panic!("Cannot acquire an expired contract.");
+        }
+
+        let mut dates: BTreeSet<u64> = BTreeSet::new();
+        Lattice::collect_dates(&combinator, &mut dates);
+        let last_date = dates.range(market.valuation_time..).next_back().cloned().unwrap_or(market.valuation_time);
+
+        let tree = Tree::new(market, self.step, ((last_date - market.valuation_time) / self.step) as usize);
+        Lattice::value_combinator(&combinator, &tree)[0][0]
+    }
+
+    // Collects every horizon and schedule period date in the combinator, which together bound the lattice
+    fn collect_dates(combinator: &Box<ContractCombinator>, dates: &mut BTreeSet<u64>) {
+        if let Some(horizon) = combinator.get_horizon() {
+            dates.insert(horizon);
+        }
+        match combinator.get_combinator_number() {
+            Combinator::SCHEDULE | Combinator::SCHEDULE_RELATIVE => for date in Lattice::get_period_dates(combinator) {
+                dates.insert(date);
+            },
+            _ => ()
+        }
+        for sub_combinator in combinator.get_sub_combinators() {
+            Lattice::collect_dates(sub_combinator, dates);
+        }
+    }
+
+    // Gets the value process of the given combinator, which is zero at every date past its horizon
+    fn value_combinator(combinator: &Box<ContractCombinator>, tree: &Tree) -> Process {
+        let sub_combinators = combinator.get_sub_combinators();
+        let mut process = match combinator.get_combinator_number() {
+            Combinator::ZERO => tree.constant(0.0),
+            Combinator::ONE | Combinator::ONE_ASSET => {
+                if combinator.get_parameters()[0] != ETHER_ASSET as i64 {
+                    panic!("Only Ether-denominated contracts can be valued.");
+                }
+                tree.constant(1.0)
+            },
+            Combinator::AND => {
+                let process0 = Lattice::value_combinator(sub_combinators[0], tree);
+                let process1 = Lattice::value_combinator(sub_combinators[1], tree);
+                tree.map(|level, node| process0[level][node] + process1[level][node])
+            },
+            Combinator::OR => {
+                // An expired branch cannot be chosen, so only take the maximum where both branches can be acquired
+                let process0 = Lattice::value_combinator(sub_combinators[0], tree);
+                let process1 = Lattice::value_combinator(sub_combinators[1], tree);
+                tree.map(|level, node| {
+                    let time = tree.get_time(level);
+                    match (sub_combinators[0].past_horizon(time), sub_combinators[1].past_horizon(time)) {
+                        (false, false) => process0[level][node].max(process1[level][node]),
+                        (false, true) => process0[level][node],
+                        _ => process1[level][node]
+                    }
+                })
+            },
+            Combinator::TRUNCATE | Combinator::TRUNCATE_RELATIVE => Lattice::value_combinator(sub_combinators[0], tree),
+            Combinator::SCALE => {
+                let process = Lattice::value_combinator(sub_combinators[0], tree);
+                match combinator.get_index() {
+                    Some(obs_index) => {
+                        let observable = tree.get_observable(obs_index);
+                        tree.map(|level, node| process[level][node] * observable[level][node])
+                    },
+                    None => {
+                        let scale_value = combinator.get_parameters()[0] as f64;
+                        tree.map(|level, node| process[level][node] * scale_value)
+                    }
+                }
+            },
+            Combinator::GIVE => {
+                let process = Lattice::value_combinator(sub_combinators[0], tree);
+                tree.map(|level, node| -process[level][node])
+            },
+            Combinator::THEN => {
+                let process0 = Lattice::value_combinator(sub_combinators[0], tree);
+                let process1 = Lattice::value_combinator(sub_combinators[1], tree);
+                tree.map(|level, node| {
+                    if !sub_combinators[0].past_horizon(tree.get_time(level)) { process0[level][node] } else { process1[level][node] }
+                })
+            },
+            Combinator::GET => match sub_combinators[0].get_horizon() {
+                Some(horizon) => {
+                    let process = Lattice::value_combinator(sub_combinators[0], tree);
+                    let level = tree.get_level(horizon);
+                    tree.roll_back(process[level].clone(), level, None)
+                },
+                None => tree.constant(0.0)
+            },
+            Combinator::ANYTIME => match sub_combinators[0].get_horizon() {
+                // Optimal stopping: at each date take the better of acquiring the sub-combinator now and waiting
+                Some(horizon) => {
+                    let process = Lattice::value_combinator(sub_combinators[0], tree);
+                    let level = tree.get_level(horizon);
+                    tree.roll_back(process[level].clone(), level, Some(&process))
+                },
+                None => panic!("Anytime combinators without a horizon cannot be valued.")
+            },
+            Combinator::SCHEDULE | Combinator::SCHEDULE_RELATIVE => {
+                // Each period acts as a get combinator on the period date, acquired only at or before that date
+                let process = Lattice::value_combinator(sub_combinators[0], tree);
+                let mut total = tree.constant(0.0);
+                for date in Lattice::get_period_dates(combinator) {
+                    if sub_combinators[0].past_horizon(date) {
+                        break;
+                    }
+                    if date < tree.valuation_time {
+                        continue;
+                    }
+
+                    let level = tree.get_level(date);
+                    let period_process = tree.roll_back(process[level].clone(), level, None);
+                    total = tree.map(|level, node| total[level][node] + period_process[level][node]);
+                }
+                total
+            },
+            Combinator::CUSTOM(_) => panic!("Custom combinators cannot be valued.")
+        };
+
+        // Expired combinators cannot be acquired, so are worth nothing
+        for level in 0..(tree.levels + 1) {
+            if combinator.past_horizon(tree.get_time(level)) {
+                process[level] = vec![0.0; level + 1];
+            }
+        }
+        process
+    }
+
+    // Gets the period dates of a schedule combinator, empty until relative times are resolved
+    fn get_period_dates(combinator: &Box<ContractCombinator>) -> Vec<u64> {
+        let parameters = combinator.get_parameters();
+        if parameters[0] < 0 {
+            return Vec::new();
+        }
+
+        let (start, period, count) = (parameters[0] as u64, parameters[1] as u64, parameters[2] as u64);
+        (0..count).map(|period_index| start.saturating_add(period.saturating_mul(period_index))).collect()
+    }
+}
+
+// Method implementation of the lattice tree
+impl Tree {
+    // Builds a tree of the given number of levels for the given market, using an equal-probability (Jarrow-Rudd) lattice
+    fn new(market: &Market, step: u64, levels: usize) -> Tree {
+        let years = step as f64 / SECONDS_PER_YEAR;
+        let mut stochastic_count = 0;
+
+        let mut observables: Vec<Process> = Vec::new();
+        for model in &market.observables {
+            let mut process: Process = Vec::new();
+            for level in 0..(levels + 1) {
+                process.push(match model {
+                    ObservableModel::GeometricBrownianMotion { initial, drift, volatility } => {
+                        let log_drift = (drift - 0.5 * volatility * volatility) * years;
+                        let log_spread = volatility * years.sqrt();
+                        (0..(level + 1)).map(|node| {
+                            let ups = node as f64;
+                            let downs = (level - node) as f64;
+                            initial * ((ups + downs) * log_drift + (ups - downs) * log_spread).exp()
+                        }).collect()
+                    },
+                    ObservableModel::Curve(points) => {
+                        let value = ObservableModel::curve_value(points, market.valuation_time.saturating_add(step.saturating_mul(level as u64)));
+                        vec![value; level + 1]
+                    }
+                });
+            }
+            if model.is_stochastic() {
+                stochastic_count += 1;
+            }
+            observables.push(process);
+        }
+        if stochastic_count > 1 {
+            panic!("Lattice valuation supports at most one stochastic observable.");
+        }
+
+        Tree {
+            valuation_time: market.valuation_time,
+            step,
+            levels,
+            discount: (-market.interest_rate * years).exp(),
+            observables
+        }
+    }
+
+    // Gets the date of the given level
+    fn get_time(&self, level: usize) -> u64 {
+        self.valuation_time.saturating_add(self.step.saturating_mul(level as u64))
+    }
+
+    // Gets the level of the last lattice date not after the given date
+    fn get_level(&self, time: u64) -> usize {
+        ((time.saturating_sub(self.valuation_time) / self.step) as usize).min(self.levels)
+    }
+
+    // Gets the value process of the given observable
+    fn get_observable(&self, obs_index: usize) -> &Process {
+        if obs_index >= self.observables.len() {
+            panic!("No model given for observable {}.", obs_index);
+        }
+        &self.observables[obs_index]
+    }
+
+    // Gets a process with the same value at every node
+    fn constant(&self, value: f64) -> Process {
+        (0..(self.levels + 1)).map(|level| vec![value; level + 1]).collect()
+    }
+
+    // Gets a process from the value at each node
+    fn map<F: Fn(usize, usize) -> f64>(&self, value: F) -> Process {
+        (0..(self.levels + 1)).map(|level| (0..(level + 1)).map(|node| value(level, node)).collect()).collect()
+    }
+
+    // Discounts the expected values at the given level back to every earlier level, zero after the given level. If an
+    // exercise process is given, each node takes the better of the exercise value and the discounted expected value.
+    fn roll_back(&self, values: Vec<f64>, level: usize, exercise: Option<&Process>) -> Process {
+        let mut process = self.constant(0.0);
+        process[level] = values;
+        for earlier_level in (0..level).rev() {
+            for node in 0..(earlier_level + 1) {
+                let expected = 0.5 * self.discount * (process[earlier_level + 1][node] + process[earlier_level + 1][node + 1]);
+                process[earlier_level][node] = match exercise {
+                    Some(exercise) => expected.max(exercise[earlier_level][node]),
+                    None => expected
+                };
+            }
+        }
+        process
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use closed_form::{ black_scholes_call, black_scholes_put };
+    use financial_smart_contract::builder::*;
+    use financial_smart_contract::templates;
+    use test_utils::{ setup_market, underlying };
+
+    const YEAR: u64 = SECONDS_PER_YEAR as u64;
+
+    // A zero-coupon bond is worth the discounted face value less the price
+    #[test]
+    fn zero_coupon_bond_value_correct() {
+        let value = Lattice::new(YEAR / 10).value(&templates::zero_coupon_bond(90, 100, YEAR).serialize(), &setup_market(100.0, 0.05, 0.2));
+        assert!((value - (100.0 * (-0.05_f64).exp() - 90.0)).abs() < 1e-9);
+    }
+
+    // European options are worth their Black-Scholes prices
+    #[test]
+    fn european_options_match_black_scholes() {
+        let market = setup_market(100.0, 0.05, 0.2);
+        let lattice = Lattice::new(YEAR / 500);
+
+        let call = lattice.value(&templates::european_call(0, 100, YEAR, underlying()).serialize(), &market);
+        let expected_call = black_scholes_call(100.0, 100.0, 0.05, 0.2, 1.0);
+        assert!((call - expected_call).abs() < 0.05, "{} vs {}", call, expected_call);
+
+        let put = lattice.value(&templates::european_put(0, 110, YEAR, underlying()).serialize(), &market);
+        let expected_put = black_scholes_put(100.0, 110.0, 0.05, 0.2, 1.0);
+        assert!((put - expected_put).abs() < 0.05, "{} vs {}", put, expected_put);
+    }
+
+    // Early exercise of a call is never optimal, but early exercise of a put can be
+    #[test]
+    fn american_options_value_correct() {
+        let market = setup_market(100.0, 0.05, 0.2);
+        let lattice = Lattice::new(YEAR / 500);
+
+        let american_call = lattice.value(&templates::american_call(0, 100, YEAR, underlying()).serialize(), &market);
+        let european_call = lattice.value(&templates::european_call(0, 100, YEAR, underlying()).serialize(), &market);
+        assert!((american_call - european_call).abs() < 1e-6, "{} vs {}", american_call, european_call);
+
+        let american_put = lattice.value(&templates::american_put(0, 100, YEAR, underlying()).serialize(), &market);
+        assert!((american_put - 6.09).abs() < 0.05, "{}", american_put);
+        assert!(american_put > black_scholes_put(100.0, 100.0, 0.05, 0.2, 1.0));
+    }
+
+    // The holder cannot choose an or branch which has expired
+    #[test]
+    fn or_ignores_expired_branch() {
+        let mut market = setup_market(100.0, 0.0, 0.2);
+        let definition = or(truncate(10, scale(5, one())), scale(3, one())).serialize();
+        assert_eq!(Lattice::new(10).value(&definition, &market), 5.0);
+
+        market.valuation_time = 20;
+        assert_eq!(Lattice::new(10).value(&definition, &market), 3.0);
+    }
+
+    // A then combinator acquires its second sub-combinator once the first has expired
+    #[test]
+    fn then_sequencing_correct() {
+        let mut market = setup_market(100.0, 0.05, 0.2);
+        let definition = then(get(truncate(YEAR / 2, scale(2, one()))), get(truncate(YEAR, one()))).serialize();
+        assert!((Lattice::new(YEAR / 10).value(&definition, &market) - 2.0 * (-0.025_f64).exp()).abs() < 1e-9);
+
+        market.valuation_time = YEAR / 10 * 6;
+        assert!((Lattice::new(YEAR / 10).value(&definition, &market) - (-0.02_f64).exp()).abs() < 1e-9);
+    }
+
+    // A schedule pays every period from the acquisition time
+    #[test]
+    fn schedule_value_correct() {
+        let mut market = setup_market(100.0, 0.0, 0.2);
+        let definition = schedule(0, 10, 4, scale(2, one())).serialize();
+        assert_eq!(Lattice::new(5).value(&definition, &market), 8.0);
+
+        market.valuation_time = 15;
+        assert_eq!(Lattice::new(5).value(&definition, &market), 4.0);
+    }
+
+    // Lattices with more than one stochastic observable are not supported
+    #[test]
+    #[should_panic(expected = "Lattice valuation supports at most one stochastic observable.")]
+    fn should_panic_if_multiple_stochastic_observables() {
+        let mut market = setup_market(100.0, 0.0, 0.2);
+        market.observables.push(market.observables[0].clone());
+        Lattice::new(10).value(&or(one(), zero()).serialize(), &market);
+    }
+}
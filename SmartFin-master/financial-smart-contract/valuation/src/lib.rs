@@ -0,0 +1,20 @@
+// Off-chain valuation and analysis of financial smart contracts, walking the same combinator tree the contract deserializes on-chain
+extern crate financial_smart_contract;
+extern crate pwasm_std;
+
+pub mod models;
+pub mod cash_flows;
+pub mod closed_form;
+pub mod exposure;
+pub mod lattice;
+pub mod monte_carlo;
+mod random;
+mod regression;
+#[cfg(test)]
+mod test_utils;
+
+pub use models::{ Market, ObservableModel };
+pub use monte_carlo::{ MonteCarlo, Valuation };
+pub use lattice::Lattice;
+pub use exposure::{ analyse_exposure, Exposure };
+pub use cash_flows::{ get_cash_flows, CashFlow, Scenario };
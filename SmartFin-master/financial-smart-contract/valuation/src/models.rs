@@ -0,0 +1,216 @@
+use std::collections::BTreeSet;
+use random::Random;
+
+// The number of seconds in a year, rates and volatilities are annual while contract times are in seconds
+pub const SECONDS_PER_YEAR: f64 = 31_536_000.0;
+
+// A model for the value of an observable over time
+#[derive(Clone)]
+#[derive(Debug)]
+pub enum ObservableModel {
+    // Geometric Brownian motion from the initial value at the valuation time, with the given annual drift and volatility
+    // (use the interest rate as the drift for risk-neutral valuation)
+    GeometricBrownianMotion { initial: f64, drift: f64, volatility: f64 },
+
+    // A fixed curve of (time, value) points, linearly interpolated between points and flat beyond either end
+    Curve(Vec<(u64, f64)>)
+}
+
+// The market a contract is valued in
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct Market {
+    // The time the contract is acquired and cash flows are discounted to
+    pub valuation_time: u64,
+
+    // The continuously compounded annual interest rate
+    pub interest_rate: f64,
+
+    // The models of the contract's observables, indexed by observable index
+    pub observables: Vec<ObservableModel>
+}
+
+// Simulated observable values at each of a set of times, for each path
+pub struct Scenarios {
+    // The simulated times, in increasing order
+    times: Vec<u64>,
+
+    // The observable values, indexed by observable index, path and time index
+    values: Vec<Vec<Vec<f64>>>,
+
+    // The indices of the stochastic observables
+    stochastic: Vec<usize>
+}
+
+// Method implementation of observable models
+impl ObservableModel {
+    // Returns whether or not the observable's value is random
+    pub fn is_stochastic(&self) -> bool {
+        match self {
+            ObservableModel::GeometricBrownianMotion { .. } => true,
+            ObservableModel::Curve(_) => false
+        }
+    }
+
+    // Gets the value of a fixed curve at the given time
+    pub fn curve_value(points: &Vec<(u64, f64)>, time: u64) -> f64 {
+        if points.len() == 0 {
+            panic!("Curve must have at least one point.");
+        }
+
+        match points.iter().position(|point| point.0 >= time) {
+            Some(0) => points[0].1,
+            Some(i) => {
+                let (time0, value0) = points[i - 1];
+                let (time1, value1) = points[i];
+                value0 + (value1 - value0) * (time - time0) as f64 / (time1 - time0) as f64
+            },
+            None => points[points.len() - 1].1
+        }
+    }
+}
+
+// Method implementation of markets
+impl Market {
+    // Gets the factor discounting a cash flow at the given time to the valuation time
+    pub fn discount_factor(&self, time: u64) -> f64 {
+        (-self.interest_rate * self.years_since_valuation(time)).exp()
+    }
+
+    // Gets the time in years from the valuation time to the given time
+    pub fn years_since_valuation(&self, time: u64) -> f64 {
+        (time as f64 - self.valuation_time as f64) / SECONDS_PER_YEAR
+    }
+
+    // Gets the model of the given observable
+    pub fn get_model(&self, obs_index: usize) -> &ObservableModel {
+        if obs_index >= self.observables.len() {
+            panic!("No model given for observable {}.", obs_index);
+        }
+        &self.observables[obs_index]
+    }
+
+    // Simulates the given number of paths of every observable at the given times (which must not precede the valuation time)
+    pub fn simulate(&self, times: &BTreeSet<u64>, paths: usize, seed: u64) -> Scenarios {
+        let times: Vec<u64> = times.iter().cloned().collect();
+        if times.len() > 0 && times[0] < self.valuation_time {
+            panic!("Cannot simulate observables before the valuation time.");
+        }
+
+        let mut random = Random::new(seed);
+        let mut values: Vec<Vec<Vec<f64>>> = Vec::new();
+        for model in &self.observables {
+            let mut model_values: Vec<Vec<f64>> = Vec::new();
+            for _ in 0..paths {
+                let path_values = match model {
+                    ObservableModel::GeometricBrownianMotion { initial, drift, volatility } => {
+                        let mut value = *initial;
+                        let mut previous_time = self.valuation_time;
+                        times.iter().map(|time| {
+                            let dt = (time - previous_time) as f64 / SECONDS_PER_YEAR;
+                            value *= ((drift - 0.5 * volatility * volatility) * dt + volatility * dt.sqrt() * random.next_normal()).exp();
+                            previous_time = *time;
+                            value
+                        }).collect()
+                    },
+                    ObservableModel::Curve(points) => times.iter().map(|time| ObservableModel::curve_value(points, *time)).collect()
+                };
+                model_values.push(path_values);
+            }
+            values.push(model_values);
+        }
+
+        Scenarios {
+            times,
+            values,
+            stochastic: (0..self.observables.len()).filter(|i| self.observables[*i].is_stochastic()).collect()
+        }
+    }
+}
+
+// Method implementation of scenarios
+impl Scenarios {
+    // Gets the value of the given observable on the given path at the given (simulated) time
+    pub fn get_value(&self, obs_index: usize, path: usize, time: u64) -> f64 {
+        self.values[obs_index][path][self.time_index(time)]
+    }
+
+    // Gets the values of every stochastic observable on the given path at the given (simulated) time
+    pub fn get_state(&self, path: usize, time: u64) -> Vec<f64> {
+        let time_index = self.time_index(time);
+        self.stochastic.iter().map(|obs_index| self.values[*obs_index][path][time_index]).collect()
+    }
+
+    // Gets the index of the given simulated time
+    fn time_index(&self, time: u64) -> usize {
+        match self.times.binary_search(&time) {
+            Ok(index) => index,
+            Err(_) => panic!("Time {} was not simulated.", time)
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Curves are interpolated linearly between points, and flat beyond either end
+    #[test]
+    fn curve_interpolation_correct() {
+        let points = vec![(10, 1.0), (20, 3.0)];
+        assert_eq!(ObservableModel::curve_value(&points, 0), 1.0);
+        assert_eq!(ObservableModel::curve_value(&points, 15), 2.0);
+        assert_eq!(ObservableModel::curve_value(&points, 20), 3.0);
+        assert_eq!(ObservableModel::curve_value(&points, 30), 3.0);
+    }
+
+    // Cash flows are discounted at the continuously compounded interest rate
+    #[test]
+    fn discount_factor_correct() {
+        let market = Market { valuation_time: 100, interest_rate: 0.05, observables: vec![] };
+        assert_eq!(market.discount_factor(100), 1.0);
+        assert!((market.discount_factor(100 + SECONDS_PER_YEAR as u64) - (-0.05_f64).exp()).abs() < 1e-12);
+    }
+
+    // The mean of a geometric Brownian motion grows at the drift
+    #[test]
+    fn geometric_brownian_motion_mean_correct() {
+        let market = Market {
+            valuation_time: 0,
+            interest_rate: 0.0,
+            observables: vec![ObservableModel::GeometricBrownianMotion { initial: 100.0, drift: 0.1, volatility: 0.2 }]
+        };
+        let year = SECONDS_PER_YEAR as u64;
+        let times: BTreeSet<u64> = vec![year / 2, year].into_iter().collect();
+        let scenarios = market.simulate(&times, 20000, 1);
+
+        let mean = (0..20000).map(|path| scenarios.get_value(0, path, year)).sum::<f64>() / 20000.0;
+        assert!((mean - 100.0 * 0.1_f64.exp()).abs() < 1.0, "Mean of GBM at one year is {}", mean);
+        assert_eq!(scenarios.get_state(0, year), vec![scenarios.get_value(0, 0, year)]);
+    }
+
+    // Only stochastic observables make up the state
+    #[test]
+    fn state_excludes_curves() {
+        let market = Market {
+            valuation_time: 0,
+            interest_rate: 0.0,
+            observables: vec![
+                ObservableModel::Curve(vec![(0, 1.0)]),
+                ObservableModel::GeometricBrownianMotion { initial: 100.0, drift: 0.0, volatility: 0.2 }
+            ]
+        };
+        let times: BTreeSet<u64> = vec![0].into_iter().collect();
+        let scenarios = market.simulate(&times, 1, 1);
+        assert_eq!(scenarios.get_state(0, 0), vec![100.0]);
+    }
+
+    // Using an observable without a model is not allowed
+    #[test]
+    #[should_panic(expected = "No model given for observable 1.")]
+    fn should_panic_if_observable_has_no_model() {
+        let market = Market { valuation_time: 0, interest_rate: 0.0, observables: vec![ObservableModel::Curve(vec![(0, 1.0)])] };
+        market.get_model(1);
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::BTreeSet;
+use financial_smart_contract::{ FinancialScContract, Combinator, ContractCombinator, ETHER_ASSET };
+use models::{ Market, Scenarios };
+use regression::fit;
+
+// Monte Carlo valuation of a combinator contract. Or-choices and anytime exercise follow a least-squares regression policy:
+// the holder picks the branch (or exercise date) whose value, regressed on the stochastic observables' values, is highest.
+pub struct MonteCarlo {
+    // The number of simulated paths
+    pub paths: usize,
+
+    // The seed of the random number generator
+    pub seed: u64,
+
+    // The time between two dates at which an anytime combinator's sub-combinator may be acquired
+    pub exercise_period: u64
+}
+
+// The result of a Monte Carlo valuation, from the holder's point of view
+#[derive(Debug)]
+pub struct Valuation {
+    // The mean present value over all paths
+    pub present_value: f64,
+
+    // The standard error of the present value
+    pub standard_error: f64,
+
+    // The 95% confidence interval of the present value
+    pub confidence_interval: (f64, f64)
+}
+
+// Method implementation of Monte Carlo valuation
+impl MonteCarlo {
+    // Instantiates a new Monte Carlo valuation
+    pub fn new(paths: usize, seed: u64, exercise_period: u64) -> MonteCarlo {
+        if paths < 2 {
+            panic!("Monte Carlo valuation requires at least two paths.");
+        }
+        if exercise_period == 0 {
+            panic!("Exercise period must be positive.");
+        }
+
+        MonteCarlo {
+            paths,
+            seed,
+            exercise_period
+        }
+    }
+
+    // Values the given contract definition if acquired at the market's valuation time
+    pub fn value(&self, contract_definition: &Vec<i64>, market: &Market) -> Valuation {
+        let (mut combinator, _) = FinancialScContract::deserialize_definition(contract_definition);
+        combinator.resolve_relative_times(market.valuation_time);
+        if combinator.past_horizon(market.valuation_time) {
+            panic!("Cannot acquire an expired contract.");
+        }
+
+        let mut times: BTreeSet<u64> = BTreeSet::new();
+        self.collect_times(&combinator, market.valuation_time, &mut times);
+        let scenarios = market.simulate(&times, self.paths, self.seed);
+
+        let values = self.value_combinator(&combinator, market.valuation_time, market, &scenarios);
+        let paths = self.paths as f64;
+        let mean = values.iter().sum::<f64>() / paths;
+        let variance = values.iter().map(|value| (value - mean) * (value - mean)).sum::<f64>() / (paths - 1.0);
+        let standard_error = (variance / paths).sqrt();
+
+        Valuation {
+            present_value: mean,
+            standard_error,
+            confidence_interval: (mean - 1.96 * standard_error, mean + 1.96 * standard_error)
+        }
+    }
+
+    // Collects every time at which a combinator may be acquired (and so at which observables are needed)
+    fn collect_times(&self, combinator: &Box<ContractCombinator>, time: u64, times: &mut BTreeSet<u64>) {
+        if combinator.past_horizon(time) {
+            return;
+        }
+        times.insert(time);
+
+        let sub_combinators = combinator.get_sub_combinators();
+        match combinator.get_combinator_number() {
+            Combinator::GET => if let Some(horizon) = sub_combinators[0].get_horizon() {
+                self.collect_times(sub_combinators[0], horizon, times);
+            },
+            Combinator::THEN => self.collect_times(MonteCarlo::then_sub_combinator(combinator, time), time, times),
+            Combinator::ANYTIME => for date in self.get_exercise_dates(sub_combinators[0], time) {
+                self.collect_times(sub_combinators[0], date, times);
+            },
+            Combinator::SCHEDULE | Combinator::SCHEDULE_RELATIVE => for date in MonteCarlo::get_period_dates(combinator, time) {
+                self.collect_times(sub_combinators[0], date, times);
+            },
+            _ => for sub_combinator in sub_combinators {
+                self.collect_times(sub_combinator, time, times);
+            }
+        }
+    }
+
+    // Values the given combinator acquired at the given time, returns the present value on each path
+    fn value_combinator(&self, combinator: &Box<ContractCombinator>, time: u64, market: &Market, scenarios: &Scenarios) -> Vec<f64> {
+        // Expired combinators cannot be acquired, so are worth nothing
+        if combinator.past_horizon(time) {
+            return vec![0.0; self.paths];
+        }
+
+        let sub_combinators = combinator.get_sub_combinators();
+        match combinator.get_combinator_number() {
+            Combinator::ZERO => vec![0.0; self.paths],
+            Combinator::ONE | Combinator::ONE_ASSET => {
+                if combinator.get_parameters()[0] != ETHER_ASSET as i64 {
+                    panic!("Only Ether-denominated contracts can be valued.");
+                }
+                vec![market.discount_factor(time); self.paths]
+            },
+            Combinator::AND => {
+                let values0 = self.value_combinator(sub_combinators[0], time, market, scenarios);
+                let values1 = self.value_combinator(sub_combinators[1], time, market, scenarios);
+                values0.iter().zip(values1.iter()).map(|(value0, value1)| value0 + value1).collect()
+            },
+            Combinator::OR => {
+                let values0 = self.value_combinator(sub_combinators[0], time, market, scenarios);
+                let values1 = self.value_combinator(sub_combinators[1], time, market, scenarios);
+                let basis = self.get_basis(time, scenarios);
+                let expected0 = fit(&basis, &values0);
+                let expected1 = fit(&basis, &values1);
+                (0..self.paths).map(|path| if expected0[path] >= expected1[path] { values0[path] } else { values1[path] }).collect()
+            },
+            Combinator::TRUNCATE | Combinator::TRUNCATE_RELATIVE => self.value_combinator(sub_combinators[0], time, market, scenarios),
+            Combinator::SCALE => {
+                let values = self.value_combinator(sub_combinators[0], time, market, scenarios);
+                match combinator.get_index() {
+                    Some(obs_index) => {
+                        market.get_model(obs_index);
+                        values.iter().enumerate().map(|(path, value)| value * scenarios.get_value(obs_index, path, time)).collect()
+                    },
+                    None => {
+                        let scale_value = combinator.get_parameters()[0] as f64;
+                        values.iter().map(|value| value * scale_value).collect()
+                    }
+                }
+            },
+            Combinator::GIVE => self.value_combinator(sub_combinators[0], time, market, scenarios).iter().map(|value| -value).collect(),
+            Combinator::THEN => self.value_combinator(MonteCarlo::then_sub_combinator(combinator, time), time, market, scenarios),
+            Combinator::GET => match sub_combinators[0].get_horizon() {
+                Some(horizon) => self.value_combinator(sub_combinators[0], horizon, market, scenarios),
+                None => vec![0.0; self.paths]
+            },
+            Combinator::ANYTIME => {
+                // Work backwards from the last exercise date (the sub-horizon, where acquisition is forced)
+                let dates = self.get_exercise_dates(sub_combinators[0], time);
+                let mut values = self.value_combinator(sub_combinators[0], dates[dates.len() - 1], market, scenarios);
+                for date in dates.iter().rev().skip(1) {
+                    let exercise_values = self.value_combinator(sub_combinators[0], *date, market, scenarios);
+                    let continuation_values = fit(&self.get_basis(*date, scenarios), &values);
+                    for path in 0..self.paths {
+                        if exercise_values[path] > continuation_values[path] {
+                            values[path] = exercise_values[path];
+                        }
+                    }
+                }
+                values
+            },
+            Combinator::SCHEDULE | Combinator::SCHEDULE_RELATIVE => {
+                let mut values = vec![0.0; self.paths];
+                for date in MonteCarlo::get_period_dates(combinator, time) {
+                    let period_values = self.value_combinator(sub_combinators[0], date, market, scenarios);
+                    for path in 0..self.paths {
+                        values[path] += period_values[path];
+                    }
+                }
+                values
+            },
+            Combinator::CUSTOM(_) => panic!("Custom combinators cannot be valued.")
+        }
+    }
+
+    // Gets the sub-combinator a then combinator acquires at the given time
+    fn then_sub_combinator(combinator: &Box<ContractCombinator>, time: u64) -> &Box<ContractCombinator> {
+        let sub_combinators = combinator.get_sub_combinators();
+        if !sub_combinators[0].past_horizon(time) { sub_combinators[0] } else { sub_combinators[1] }
+    }
+
+    // Gets the dates at which an anytime combinator acquired at the given time may acquire its sub-combinator
+    fn get_exercise_dates(&self, sub_combinator: &Box<ContractCombinator>, time: u64) -> Vec<u64> {
+        let horizon = match sub_combinator.get_horizon() {
+            Some(horizon) => horizon,
+            None => panic!("Anytime combinators without a horizon cannot be valued.")
+        };
+
+        let mut dates: Vec<u64> = Vec::new();
+        let mut date = time;
+        while date < horizon {
+            dates.push(date);
+            date = date.saturating_add(self.exercise_period);
+        }
+        dates.push(horizon);
+        dates
+    }
+
+    // Gets the dates at which a schedule combinator acquired at the given time acquires a copy of its sub-combinator
+    fn get_period_dates(combinator: &Box<ContractCombinator>, time: u64) -> Vec<u64> {
+        let parameters = combinator.get_parameters();
+        let sub_combinator = combinator.get_sub_combinators()[0];
+        let (start, period, count) = (parameters[0] as u64, parameters[1] as u64, parameters[2] as u64);
+
+        (0..count)
+            .map(|period_index| start.saturating_add(period.saturating_mul(period_index)))
+            .filter(|date| *date >= time)
+            .take_while(|date| !sub_combinator.past_horizon(*date))
+            .collect()
+    }
+
+    // Gets the regression basis functions on each path at the given time (1, x and x^2 for each stochastic observable x)
+    fn get_basis(&self, time: u64, scenarios: &Scenarios) -> Vec<Vec<f64>> {
+        (0..self.paths).map(|path| {
+            let mut basis = vec![1.0];
+            for value in scenarios.get_state(path, time) {
+                basis.push(value);
+                basis.push(value * value);
+            }
+            basis
+        }).collect()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::SECONDS_PER_YEAR;
+    use closed_form::{ black_scholes_call, black_scholes_put };
+    use financial_smart_contract::builder::*;
+    use financial_smart_contract::templates;
+    use test_utils::{ setup_market, underlying };
+
+    const YEAR: u64 = SECONDS_PER_YEAR as u64;
+
+    // A zero-coupon bond is worth the discounted face value less the price, with no uncertainty
+    #[test]
+    fn zero_coupon_bond_value_correct() {
+        let market = setup_market(100.0, 0.05, 0.2);
+        let valuation = MonteCarlo::new(10, 1, YEAR).value(&templates::zero_coupon_bond(90, 100, YEAR).serialize(), &market);
+
+        assert!((valuation.present_value - (100.0 * (-0.05_f64).exp() - 90.0)).abs() < 1e-9);
+        assert!(valuation.standard_error < 1e-9);
+    }
+
+    // The holder takes the more valuable branch of an or combinator
+    #[test]
+    fn or_takes_more_valuable_branch() {
+        let market = setup_market(100.0, 0.0, 0.2);
+        let valuation = MonteCarlo::new(10, 1, YEAR).value(&or(scale(3, one()), scale(5, one())).serialize(), &market);
+
+        assert!((valuation.present_value - 5.0).abs() < 1e-9);
+    }
+
+    // A forward on the underlying is worth the spot less the discounted price
+    #[test]
+    fn forward_value_correct() {
+        let market = setup_market(100.0, 0.05, 0.2);
+        let valuation = MonteCarlo::new(20000, 1, YEAR).value(&templates::forward(100, YEAR, underlying()).serialize(), &market);
+
+        let expected = 100.0 - 100.0 * (-0.05_f64).exp();
+        assert!(valuation.confidence_interval.0 < expected && expected < valuation.confidence_interval.1, "{:?}", valuation);
+    }
+
+    // A European call is worth its Black-Scholes price
+    #[test]
+    fn european_call_matches_black_scholes() {
+        let market = setup_market(100.0, 0.05, 0.2);
+        let valuation = MonteCarlo::new(20000, 2, YEAR).value(&templates::european_call(0, 100, YEAR, underlying()).serialize(), &market);
+
+        let expected = black_scholes_call(100.0, 100.0, 0.05, 0.2, 1.0);
+        assert!((valuation.present_value - expected).abs() < 3.0 * valuation.standard_error, "{:?} vs {}", valuation, expected);
+    }
+
+    // An American put is worth at least its European equivalent, as early exercise can be optimal
+    #[test]
+    fn american_put_worth_more_than_european_put() {
+        let market = setup_market(100.0, 0.05, 0.2);
+        let monte_carlo = MonteCarlo::new(20000, 3, YEAR / 10);
+        let american = monte_carlo.value(&templates::american_put(0, 100, YEAR, underlying()).serialize(), &market);
+        let european = black_scholes_put(100.0, 100.0, 0.05, 0.2, 1.0);
+
+        assert!(american.present_value > european, "{:?} vs {}", american, european);
+        assert!(american.present_value < european + 1.0, "{:?} vs {}", american, european);
+    }
+
+    // Relative horizons are resolved from the valuation time
+    #[test]
+    fn relative_times_resolved_from_valuation_time() {
+        let mut market = setup_market(100.0, 0.05, 0.2);
+        market.valuation_time = 1000;
+        let valuation = MonteCarlo::new(10, 1, YEAR).value(&get(truncate_relative(YEAR, one())).serialize(), &market);
+
+        assert!((valuation.present_value - (-0.05_f64).exp()).abs() < 1e-9);
+    }
+
+    // A schedule pays every period from the acquisition time
+    #[test]
+    fn schedule_value_correct() {
+        let market = setup_market(100.0, 0.0, 0.2);
+        let valuation = MonteCarlo::new(10, 1, YEAR).value(&schedule(0, 10, 4, scale(2, one())).serialize(), &market);
+
+        assert!((valuation.present_value - 8.0).abs() < 1e-9);
+    }
+
+    // Valuing a token-denominated contract is not allowed
+    #[test]
+    #[should_panic(expected = "Only Ether-denominated contracts can be valued.")]
+    fn should_panic_if_valuing_token_contract() {
+        let market = setup_market(100.0, 0.0, 0.2);
+        MonteCarlo::new(10, 1, YEAR).value(&one_of("3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap()).serialize(), &market);
+    }
+}
@@ -0,0 +1,65 @@
+use financial_smart_contract::random::Random as UniformRandom;
+
+// A seeded pseudo-random number generator, so valuations are reproducible
+pub struct Random {
+    uniform: UniformRandom,
+
+    // The second normal sample of the last Box-Muller transform, if not yet used
+    spare_normal: Option<f64>
+}
+
+// Method implementation of the random number generator
+impl Random {
+    // Instantiates a new generator from the given seed
+    pub fn new(seed: u64) -> Random {
+        Random {
+            uniform: UniformRandom::new(seed),
+            spare_normal: None
+        }
+    }
+
+    // Gets a uniformly distributed sample in (0, 1)
+    pub fn next_uniform(&mut self) -> f64 {
+        self.uniform.next_uniform()
+    }
+
+    // Gets a standard normally distributed sample
+    pub fn next_normal(&mut self) -> f64 {
+        if let Some(normal) = self.spare_normal.take() {
+            return normal;
+        }
+
+        let radius = (-2.0 * self.next_uniform().ln()).sqrt();
+        let angle = 2.0 * ::std::f64::consts::PI * self.next_uniform();
+        self.spare_normal = Some(radius * angle.sin());
+        radius * angle.cos()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::Random;
+
+    // Normal samples have mean 0 and variance 1
+    #[test]
+    fn normal_samples_standard() {
+        let mut random = Random::new(7);
+        let samples: Vec<f64> = (0..100000).map(|_| random.next_normal()).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance = samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / samples.len() as f64;
+
+        assert!(mean.abs() < 0.02, "Mean of normal samples is {}", mean);
+        assert!((variance - 1.0).abs() < 0.02, "Variance of normal samples is {}", variance);
+    }
+
+    // The same seed gives the same samples
+    #[test]
+    fn samples_reproducible() {
+        let mut random0 = Random::new(3);
+        let mut random1 = Random::new(3);
+        for _ in 0..10 {
+            assert_eq!(random0.next_uniform(), random1.next_uniform());
+        }
+    }
+}
@@ -0,0 +1,82 @@
+// Fits a least-squares linear combination of the given basis function values (one row per sample) to the given values,
+// returns the fitted values. Basis functions which are (nearly) linearly dependent on earlier ones are ignored.
+pub fn fit(basis: &Vec<Vec<f64>>, values: &Vec<f64>) -> Vec<f64> {
+    if basis.len() != values.len() {
+        panic!("Basis and value arrays must have the same length.");
+    }
+    if basis.len() == 0 {
+        return Vec::new();
+    }
+    let size = basis[0].len();
+
+    // Form the normal equations (B^T B) c = B^T v
+    let mut matrix: Vec<Vec<f64>> = vec![vec![0.0; size + 1]; size];
+    for (row, value) in basis.iter().zip(values.iter()) {
+        for i in 0..size {
+            for j in 0..size {
+                matrix[i][j] += row[i] * row[j];
+            }
+            matrix[i][size] += row[i] * value;
+        }
+    }
+
+    // Solve by Gaussian elimination, skipping columns without a usable pivot
+    let mut pivot_rows: Vec<Option<usize>> = vec![None; size];
+    let mut next_row = 0;
+    let scale = (0..size).map(|i| matrix[i][i].abs()).fold(0.0, f64::max);
+    for column in 0..size {
+        let pivot = (next_row..size).max_by(|a, b| matrix[*a][column].abs().partial_cmp(&matrix[*b][column].abs()).unwrap());
+        let pivot = match pivot {
+            Some(pivot) if matrix[pivot][column].abs() > 1e-10 * scale => pivot,
+            _ => continue
+        };
+        matrix.swap(next_row, pivot);
+
+        for row in 0..size {
+            if row != next_row {
+                let factor = matrix[row][column] / matrix[next_row][column];
+                for j in column..(size + 1) {
+                    matrix[row][j] -= factor * matrix[next_row][j];
+                }
+            }
+        }
+        pivot_rows[column] = Some(next_row);
+        next_row += 1;
+    }
+
+    let coefficients: Vec<f64> = (0..size).map(|column| match pivot_rows[column] {
+        Some(row) => matrix[row][size] / matrix[row][column],
+        None => 0.0
+    }).collect();
+
+    basis.iter().map(|row| row.iter().zip(coefficients.iter()).map(|(x, c)| x * c).sum()).collect()
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::fit;
+
+    // Values which are a combination of the basis functions are fitted exactly
+    #[test]
+    fn exact_fit_correct() {
+        let xs = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let basis: Vec<Vec<f64>> = xs.iter().map(|x| vec![1.0, *x, x * x]).collect();
+        let values: Vec<f64> = xs.iter().map(|x| 2.0 - x + 0.5 * x * x).collect();
+
+        for (fitted, value) in fit(&basis, &values).iter().zip(values.iter()) {
+            assert!((fitted - value).abs() < 1e-9);
+        }
+    }
+
+    // Dependent basis functions (e.g. a constant state) fit the mean
+    #[test]
+    fn dependent_basis_fits_mean() {
+        let basis = vec![vec![1.0, 3.0], vec![1.0, 3.0], vec![1.0, 3.0]];
+        let values = vec![1.0, 2.0, 6.0];
+
+        for fitted in fit(&basis, &values) {
+            assert!((fitted - 3.0).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+// Fixtures shared by the valuation unit tests
+use financial_smart_contract::builder::{ observable, Observable };
+use models::{ Market, ObservableModel };
+
+// A market with a single geometric Brownian motion observable, drifting at the interest rate
+pub fn setup_market(spot: f64, interest_rate: f64, volatility: f64) -> Market {
+    Market {
+        valuation_time: 0,
+        interest_rate,
+        observables: vec![ObservableModel::GeometricBrownianMotion { initial: spot, drift: interest_rate, volatility }]
+    }
+}
+
+// The observable modelled by the market
+pub fn underlying() -> Observable {
+    observable("3D04E16e08E4c1c7fa8fC5A386237669341EaAcE".parse().unwrap(), "price")
+}